@@ -0,0 +1,189 @@
+use crate::{
+    core::{
+        pool::Handle,
+        math::{
+            vec2::Vec2,
+            Rect,
+        },
+    },
+    widget::{
+        Widget,
+        WidgetBuilder,
+    },
+    UserInterface,
+    UINode,
+    Control,
+    ControlTemplate,
+    UINodeContainer,
+    Builder,
+};
+use std::collections::HashMap;
+
+/// Defines position of a child of anchor panel relative to panel bounds.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Anchor {
+    /// Point in parent bounds in normalized coordinates, (0, 0) is top left corner and
+    /// (1, 1) is bottom right.
+    pub point: Vec2,
+    /// Point in child bounds which will be placed at anchor point, in normalized coordinates.
+    pub pivot: Vec2,
+    /// Offset in pixels from anchor point.
+    pub offset: Vec2,
+}
+
+impl Default for Anchor {
+    fn default() -> Self {
+        Self::top_left()
+    }
+}
+
+impl Anchor {
+    /// Creates anchor which pivot is at the same relative point as anchor itself, so
+    /// child will stay inside parent bounds with zero offset.
+    pub fn new(x: f32, y: f32) -> Self {
+        Self {
+            point: Vec2::new(x, y),
+            pivot: Vec2::new(x, y),
+            offset: Vec2::ZERO,
+        }
+    }
+
+    pub fn top_left() -> Self {
+        Self::new(0.0, 0.0)
+    }
+
+    pub fn top_center() -> Self {
+        Self::new(0.5, 0.0)
+    }
+
+    pub fn top_right() -> Self {
+        Self::new(1.0, 0.0)
+    }
+
+    pub fn center_left() -> Self {
+        Self::new(0.0, 0.5)
+    }
+
+    pub fn center() -> Self {
+        Self::new(0.5, 0.5)
+    }
+
+    pub fn center_right() -> Self {
+        Self::new(1.0, 0.5)
+    }
+
+    pub fn bottom_left() -> Self {
+        Self::new(0.0, 1.0)
+    }
+
+    pub fn bottom_center() -> Self {
+        Self::new(0.5, 1.0)
+    }
+
+    pub fn bottom_right() -> Self {
+        Self::new(1.0, 1.0)
+    }
+
+    pub fn with_offset(mut self, offset: Vec2) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn with_pivot(mut self, pivot: Vec2) -> Self {
+        self.pivot = pivot;
+        self
+    }
+
+    /// Returns position of top left corner of a child with given size in parent bounds.
+    pub fn position(&self, parent_size: Vec2, child_size: Vec2) -> Vec2 {
+        Vec2::new(
+            self.point.x * parent_size.x - self.pivot.x * child_size.x + self.offset.x,
+            self.point.y * parent_size.y - self.pivot.y * child_size.y + self.offset.y,
+        )
+    }
+}
+
+/// Places children relative to its edges or center using `AnchorPanel::ANCHOR` attached
+/// property, for example `Anchor::bottom_center().with_offset(Vec2::new(0.0, -40.0))`.
+/// Children keep their positions relative to anchors when panel is resized, this is
+/// useful for game HUDs which must adapt to screen size. Children without anchor are
+/// placed at top left corner.
+pub struct AnchorPanel {
+    widget: Widget,
+}
+
+impl AnchorPanel {
+    /// Name of attached property of type `Anchor`.
+    pub const ANCHOR: &'static str = "Anchor";
+
+    pub fn new(widget: Widget) -> Self {
+        Self {
+            widget
+        }
+    }
+}
+
+impl Control for AnchorPanel {
+    fn widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    fn widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    fn raw_copy(&self) -> Box<dyn Control> {
+        Box::new(Self {
+            widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
+        })
+    }
+
+    fn resolve(&mut self, _: &ControlTemplate, _: &HashMap<Handle<UINode>, Handle<UINode>>) {}
+
+    fn measure_override(&self, ui: &UserInterface, available_size: Vec2) -> Vec2 {
+        for child_handle in self.widget.children.iter() {
+            ui.node(*child_handle).measure(ui, available_size);
+        }
+
+        // Panel takes all available space, since positions of children depend on its size.
+        Vec2::new(
+            if available_size.x.is_finite() { available_size.x } else { 0.0 },
+            if available_size.y.is_finite() { available_size.y } else { 0.0 },
+        )
+    }
+
+    fn arrange_override(&self, ui: &UserInterface, final_size: Vec2) -> Vec2 {
+        for child_handle in self.widget.children.iter() {
+            let child = ui.node(*child_handle);
+            let anchor = child.widget()
+                .attached_property::<Anchor>(Self::ANCHOR)
+                .cloned()
+                .unwrap_or_default();
+            let size = child.widget().desired_size.get();
+            let position = anchor.position(final_size, size);
+            child.arrange(ui, &Rect::new(position.x, position.y, size.x, size.y));
+        }
+
+        final_size
+    }
+}
+
+pub struct AnchorPanelBuilder {
+    widget_builder: WidgetBuilder,
+}
+
+impl AnchorPanelBuilder {
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder
+        }
+    }
+}
+
+impl Builder for AnchorPanelBuilder {
+    fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
+        ui.add_node(Box::new(AnchorPanel {
+            widget: self.widget_builder.build()
+        }))
+    }
+}
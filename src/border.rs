@@ -131,7 +131,7 @@ impl Control for Border {
 
     fn draw(&self, drawing_context: &mut DrawingContext) {
         let bounds = self.widget.get_screen_bounds();
-        drawing_context.push_rect_filled(&bounds, None, self.widget.background());
+        drawing_context.push_brush_rect(&bounds, self.widget.background_texture(), self.widget.background());
         drawing_context.push_rect_vary(&bounds, self.stroke_thickness, self.widget.foreground());
         drawing_context.commit(CommandKind::Geometry, CommandTexture::None);
     }
@@ -141,14 +141,14 @@ impl Control for Border {
             Self::STROKE_THICKNESS => if let Some(value) = value.downcast_ref() {
                 self.stroke_thickness = *value;
             },
-            _ => ()
+            _ => self.widget.set_property(name, value)
         }
     }
 
     fn get_property(&self, name: &str) -> Option<&dyn Any> {
         match name {
             Self::STROKE_THICKNESS => Some(&self.stroke_thickness),
-            _ => None
+            _ => self.widget.get_property(name)
         }
     }
 }
@@ -13,10 +13,21 @@ use crate::{
     VerticalAlignment,
     Thickness,
     text::TextBuilder,
-    border::BorderBuilder,
+    image::ImageBuilder,
+    stack_panel::StackPanelBuilder,
+    scroll_bar::Orientation,
+    decorator::{
+        Decorator,
+        DecoratorBuilder,
+    },
+    draw::{
+        Texture,
+        TextureBrush,
+    },
     event::{
         UIEvent,
         UIEventKind,
+        TimerId,
     },
     Control,
     ControlTemplate,
@@ -36,25 +47,110 @@ use std::{
 ///
 /// # Events
 ///
-/// [`Click`] - spawned when user click button.
+/// [`Click`] - spawned when user click button. In repeat mode it is spawned when button
+/// is pressed and then repeatedly while it is held.
+/// [`Checked`] - spawned by button in toggle mode when its toggled state changes.
 pub struct Button {
     widget: Widget,
     body: Handle<UINode>,
     content: Handle<UINode>,
+    repeat_mode: bool,
+    /// Time in seconds between press and first repeated click.
+    repeat_delay: f32,
+    /// Time in seconds between repeated clicks.
+    repeat_interval: f32,
+    repeat_timer: Option<TimerId>,
+    is_repeating: bool,
+    toggle_mode: bool,
+    toggled: bool,
 }
 
 impl Button {
+    /// Size of icon created by `ButtonBuilder::with_icon_and_text`.
+    pub const ICON_SIZE: f32 = 16.0;
+
     pub fn new(widget: Widget, body: Handle<UINode>, content: Handle<UINode>) -> Self {
         Self {
             widget,
             body,
             content,
+            repeat_mode: false,
+            repeat_delay: 0.5,
+            repeat_interval: 0.05,
+            repeat_timer: None,
+            is_repeating: false,
+            toggle_mode: false,
+            toggled: false,
         }
     }
 
     pub fn content(&self) -> Handle<UINode> {
         self.content
     }
+
+    /// Returns handle of decorator which shows hover and press state of the button.
+    pub fn body(&self) -> Handle<UINode> {
+        self.body
+    }
+
+    pub fn set_repeat_mode(&mut self, repeat_mode: bool) -> &mut Self {
+        self.repeat_mode = repeat_mode;
+        self
+    }
+
+    pub fn is_repeat_mode(&self) -> bool {
+        self.repeat_mode
+    }
+
+    /// Sets delay before first repeated click and interval between next ones, in seconds.
+    pub fn set_repeat_timings(&mut self, delay: f32, interval: f32) -> &mut Self {
+        self.repeat_delay = delay;
+        self.repeat_interval = interval;
+        self
+    }
+
+    pub fn repeat_delay(&self) -> f32 {
+        self.repeat_delay
+    }
+
+    pub fn repeat_interval(&self) -> f32 {
+        self.repeat_interval
+    }
+
+    pub fn set_toggle_mode(&mut self, toggle_mode: bool) -> &mut Self {
+        self.toggle_mode = toggle_mode;
+        self
+    }
+
+    pub fn is_toggle_mode(&self) -> bool {
+        self.toggle_mode
+    }
+
+    pub fn set_toggled(&mut self, toggled: bool) -> &mut Self {
+        if self.toggled != toggled {
+            self.toggled = toggled;
+            self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::Checked(Some(toggled))));
+        }
+        self
+    }
+
+    pub fn is_toggled(&self) -> bool {
+        self.toggled
+    }
+
+    fn click(&self) {
+        self.widget
+            .events
+            .borrow_mut()
+            .push_back(UIEvent::new(UIEventKind::Click));
+    }
+
+    fn stop_repeat(&mut self, ui: &mut UserInterface) {
+        if let Some(timer) = self.repeat_timer.take() {
+            ui.kill_timer(timer);
+        }
+        self.is_repeating = false;
+    }
 }
 
 impl Control for Button {
@@ -71,6 +167,13 @@ impl Control for Button {
             widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
             body: self.body,
             content: self.content,
+            repeat_mode: self.repeat_mode,
+            repeat_delay: self.repeat_delay,
+            repeat_interval: self.repeat_interval,
+            repeat_timer: None,
+            is_repeating: false,
+            toggle_mode: self.toggle_mode,
+            toggled: self.toggled,
         })
     }
 
@@ -80,45 +183,41 @@ impl Control for Button {
     }
 
     fn handle_event(&mut self, self_handle: Handle<UINode>, ui: &mut UserInterface, evt: &mut UIEvent) {
-        let normal_color = Color::opaque(120, 120, 120);
-        let pressed_color = Color::opaque(100, 100, 100);
-        let hover_color = Color::opaque(160, 160, 160);
-
         if evt.source == self_handle || self.widget().has_descendant(evt.source, ui) {
             match evt.kind {
                 UIEventKind::MouseUp { .. } => {
-                    // Generate Click event
-                    self.widget_mut()
-                        .events
-                        .borrow_mut()
-                        .push_back(UIEvent::new(UIEventKind::Click));
+                    if self.repeat_mode {
+                        // Clicks were already generated while button was held.
+                        self.stop_repeat(ui);
+                    } else {
+                        self.click();
+                    }
+                    if self.toggle_mode {
+                        let toggled = !self.toggled;
+                        self.set_toggled(toggled);
+                    }
                     ui.release_mouse_capture();
                 }
                 UIEventKind::MouseDown { .. } => {
                     ui.capture_mouse(evt.source);
-                }
-                _ => ()
-            }
-        }
-
-        if evt.source == self.body || ui.is_node_child_of(evt.source, self.body) {
-            let back = ui.nodes.borrow_mut(self.body).widget_mut();
-            match evt.kind {
-                UIEventKind::MouseDown { .. } => {
-                    back.set_background(pressed_color);
-                }
-                UIEventKind::MouseUp { .. } => {
-                    if back.is_mouse_over {
-                        back.set_background(hover_color);
-                    } else {
-                        back.set_background(normal_color);
+                    if self.repeat_mode {
+                        self.click();
+                        self.stop_repeat(ui);
+                        self.repeat_timer = Some(ui.set_timer(self_handle, self.repeat_delay, false));
                     }
                 }
-                UIEventKind::MouseLeave => {
-                    back.set_background(normal_color);
+                UIEventKind::Timer(id) if evt.source == self_handle && self.repeat_timer == Some(id) => {
+                    self.click();
+                    if !self.is_repeating {
+                        self.is_repeating = true;
+                        self.repeat_timer = Some(ui.set_timer(self_handle, self.repeat_interval, true));
+                    }
                 }
-                UIEventKind::MouseEnter => {
-                    back.set_background(hover_color);
+                UIEventKind::Checked(_) if evt.source == self_handle => {
+                    // Toggled button stays pressed.
+                    if let Some(decorator) = ui.node_mut(self.body).downcast_mut::<Decorator>() {
+                        decorator.set_selected(self.toggled);
+                    }
                 }
                 _ => ()
             }
@@ -129,12 +228,20 @@ impl Control for Button {
 pub enum ButtonContent {
     Text(String),
     Node(Handle<UINode>),
+    /// Icon followed by text in a row.
+    IconText {
+        icon: TextureBrush,
+        text: String,
+    },
 }
 
 pub struct ButtonBuilder {
     widget_builder: WidgetBuilder,
     content: Option<ButtonContent>,
     font: Option<Arc<Mutex<Font>>>,
+    repeat: Option<(f32, f32)>,
+    toggle_mode: bool,
+    toggled: bool,
 }
 
 impl ButtonBuilder {
@@ -143,9 +250,29 @@ impl ButtonBuilder {
             widget_builder,
             content: None,
             font: None,
+            repeat: None,
+            toggle_mode: false,
+            toggled: false,
         }
     }
 
+    /// Enables repeat mode, `delay` is time before first repeated click and `interval`
+    /// is time between next ones, both in seconds.
+    pub fn with_repeat(mut self, delay: f32, interval: f32) -> Self {
+        self.repeat = Some((delay, interval));
+        self
+    }
+
+    pub fn with_toggle_mode(mut self, toggle_mode: bool) -> Self {
+        self.toggle_mode = toggle_mode;
+        self
+    }
+
+    pub fn with_toggled(mut self, toggled: bool) -> Self {
+        self.toggled = toggled;
+        self
+    }
+
     pub fn with_text(mut self, text: &str) -> Self {
         self.content = Some(ButtonContent::Text(text.to_owned()));
         self
@@ -156,14 +283,30 @@ impl ButtonBuilder {
         self
     }
 
+    pub fn with_icon_and_text(mut self, icon: Arc<Texture>, text: &str) -> Self {
+        self.content = Some(ButtonContent::IconText {
+            icon: TextureBrush::new(icon),
+            text: text.to_owned(),
+        });
+        self
+    }
+
+    /// Same as `with_icon_and_text`, but icon is a region of texture, for example sprite
+    /// from `SpriteRegistry`.
+    pub fn with_sprite_and_text(mut self, icon: TextureBrush, text: &str) -> Self {
+        self.content = Some(ButtonContent::IconText {
+            icon,
+            text: text.to_owned(),
+        });
+        self
+    }
+
     pub fn with_font(mut self, font: Arc<Mutex<Font>>) -> Self {
         self.font = Some(font);
         self
     }
 
     pub fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
-        let normal_color = Color::opaque(120, 120, 120);
-
         let content = if let Some(content) = self.content {
             match content {
                 ButtonContent::Text(txt) => {
@@ -174,25 +317,55 @@ impl ButtonBuilder {
                         .with_vertical_text_alignment(VerticalAlignment::Center)
                         .build(ui)
                 }
-                ButtonContent::Node(node) => node
+                ButtonContent::Node(node) => node,
+                ButtonContent::IconText { icon, text } => {
+                    let icon = ImageBuilder::new(WidgetBuilder::new()
+                        .with_width(Button::ICON_SIZE)
+                        .with_height(Button::ICON_SIZE)
+                        .with_vertical_alignment(VerticalAlignment::Center)
+                        .with_margin(Thickness::right(4.0)))
+                        .with_brush(icon)
+                        .build(ui);
+                    let text = TextBuilder::new(WidgetBuilder::new())
+                        .with_text(text.as_str())
+                        .with_opt_font(self.font)
+                        .with_vertical_text_alignment(VerticalAlignment::Center)
+                        .build(ui);
+                    StackPanelBuilder::new(WidgetBuilder::new()
+                        .with_horizontal_alignment(HorizontalAlignment::Center)
+                        .with_child(icon)
+                        .with_child(text))
+                        .with_orientation(Orientation::Horizontal)
+                        .build(ui)
+                }
             }
         } else {
             Handle::NONE
         };
 
-        let body = BorderBuilder::new(WidgetBuilder::new()
-            .with_background(normal_color)
+        let toggled = self.toggle_mode && self.toggled;
+
+        let body = DecoratorBuilder::new(WidgetBuilder::new()
             .with_foreground(Color::opaque(200, 200, 200))
             .with_child(content))
             .with_stroke_thickness(Thickness { left: 1.0, right: 1.0, top: 1.0, bottom: 1.0 })
+            .with_selected(toggled)
             .build(ui);
 
         let button = Button {
             widget: self.widget_builder
+                .with_focusable(true)
                 .with_child(body)
                 .build(),
             body,
             content,
+            repeat_mode: self.repeat.is_some(),
+            repeat_delay: self.repeat.map_or(0.5, |(delay, _)| delay),
+            repeat_interval: self.repeat.map_or(0.05, |(_, interval)| interval),
+            repeat_timer: None,
+            is_repeating: false,
+            toggle_mode: self.toggle_mode,
+            toggled,
         };
         ui.add_node(Box::new(button))
     }
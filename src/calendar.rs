@@ -0,0 +1,653 @@
+use crate::{
+    core::{
+        pool::Handle,
+        math::vec2::Vec2,
+    },
+    widget::{
+        Widget,
+        WidgetBuilder,
+    },
+    button::{
+        Button,
+        ButtonBuilder,
+    },
+    text::{
+        Text,
+        TextBuilder,
+    },
+    text_box::TextBox,
+    masked_text_box::{
+        MaskedTextBox,
+        MaskedTextBoxBuilder,
+    },
+    border::BorderBuilder,
+    grid::{
+        GridBuilder,
+        Column,
+        Row,
+    },
+    color_ext::{
+        ColorExt,
+        palette,
+    },
+    event::{
+        UIEvent,
+        UIEventKind,
+    },
+    UserInterface,
+    UINode,
+    Thickness,
+    Visibility,
+    HorizontalAlignment,
+    VerticalAlignment,
+    Control,
+    ControlTemplate,
+    UINodeContainer,
+    Builder,
+};
+use std::{
+    collections::HashMap,
+    fmt,
+};
+
+/// Date of proleptic Gregorian calendar, month and day start from 1.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    /// Creates date, returns None if there is no such day.
+    pub fn new(year: i32, month: u32, day: u32) -> Option<Self> {
+        if (1..=12).contains(&month) && day >= 1 && day <= Self::days_in_month(year, month) {
+            Some(Self { year, month, day })
+        } else {
+            None
+        }
+    }
+
+    /// Returns current date in UTC.
+    pub fn today() -> Self {
+        let seconds = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+        Self::from_days((seconds / 86400) as i64)
+    }
+
+    /// Creates date from number of days since 1970-01-01.
+    pub fn from_days(days: i64) -> Self {
+        // Algorithm by Howard Hinnant, see "chrono-Compatible Low-Level Date Algorithms".
+        let z = days + 719_468;
+        let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
+        let doe = z - era * 146_097;
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = (yoe + era * 400) as i32 + if month <= 2 { 1 } else { 0 };
+        Self { year, month, day }
+    }
+
+    /// Returns number of days since 1970-01-01.
+    pub fn to_days(&self) -> i64 {
+        let year = self.year as i64 - if self.month <= 2 { 1 } else { 0 };
+        let era = if year >= 0 { year } else { year - 399 } / 400;
+        let yoe = year - era * 400;
+        let month = self.month as i64;
+        let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + self.day as i64 - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146_097 + doe - 719_468
+    }
+
+    pub fn is_leap_year(year: i32) -> bool {
+        (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+    }
+
+    pub fn days_in_month(year: i32, month: u32) -> u32 {
+        match month {
+            2 => if Self::is_leap_year(year) { 29 } else { 28 },
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        }
+    }
+
+    /// Returns day of week, 0 is Monday.
+    pub fn weekday(&self) -> u32 {
+        // 1970-01-01 was Thursday.
+        ((self.to_days() + 3).rem_euclid(7)) as u32
+    }
+
+    pub fn add_days(&self, days: i64) -> Self {
+        Self::from_days(self.to_days() + days)
+    }
+
+    /// Moves date by given amount of months, day is clamped to length of new month.
+    pub fn add_months(&self, months: i32) -> Self {
+        let index = self.year * 12 + self.month as i32 - 1 + months;
+        let year = index.div_euclid(12);
+        let month = index.rem_euclid(12) as u32 + 1;
+        Self {
+            year,
+            month,
+            day: self.day.min(Self::days_in_month(year, month)),
+        }
+    }
+
+    /// Parses date in `YYYY-MM-DD` format.
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut parts = text.trim().split('-');
+        let year = parts.next()?.parse().ok()?;
+        let month = parts.next()?.parse().ok()?;
+        let day = parts.next()?.parse().ok()?;
+        if parts.next().is_some() {
+            return None;
+        }
+        Self::new(year, month, day)
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+const MONTH_NAMES: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June",
+    "July", "August", "September", "October", "November", "December"
+];
+
+/// Displayed month, selected date, title and day buttons of calendar.
+type DaysState = (Date, Option<Date>, Handle<UINode>, Vec<Handle<UINode>>);
+
+const DAY_NAMES: [&str; 7] = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"];
+
+/// Month grid with navigation between months and years. Days of previous and next month
+/// are shown dimmed, click on them moves to their month. Today is highlighted.
+///
+/// # Events
+///
+/// [`DateSelected`] - sent when user clicks a day.
+pub struct Calendar {
+    widget: Widget,
+    /// First day of displayed month.
+    displayed: Date,
+    selected: Option<Date>,
+    title: Handle<UINode>,
+    prev_year: Handle<UINode>,
+    prev_month: Handle<UINode>,
+    next_month: Handle<UINode>,
+    next_year: Handle<UINode>,
+    /// Six weeks of day buttons starting from Monday.
+    day_buttons: Vec<Handle<UINode>>,
+}
+
+impl Calendar {
+    pub const WEEKS: usize = 6;
+
+    pub fn selected_date(&self) -> Option<Date> {
+        self.selected
+    }
+
+    /// Returns year and month which is shown now.
+    pub fn displayed_month(&self) -> (i32, u32) {
+        (self.displayed.year, self.displayed.month)
+    }
+
+    /// Selects date and shows its month. Unlike click on a day, this does not send
+    /// `DateSelected`.
+    pub fn set_selected_date(ui: &mut UserInterface, calendar: Handle<UINode>, date: Option<Date>) {
+        let state = match ui.node_mut(calendar).downcast_mut::<Calendar>() {
+            Some(calendar) => {
+                calendar.select(date);
+                calendar.state()
+            }
+            None => return,
+        };
+        Self::update_days(ui, state);
+    }
+
+    /// Shows given month, selection is kept.
+    pub fn show_month(ui: &mut UserInterface, calendar: Handle<UINode>, year: i32, month: u32) {
+        let state = match ui.node_mut(calendar).downcast_mut::<Calendar>() {
+            Some(calendar) => {
+                if let Some(date) = Date::new(year, month, 1) {
+                    calendar.displayed = date;
+                }
+                calendar.state()
+            }
+            None => return,
+        };
+        Self::update_days(ui, state);
+    }
+
+    fn select(&mut self, date: Option<Date>) {
+        self.selected = date;
+        if let Some(date) = date {
+            self.displayed = Date { year: date.year, month: date.month, day: 1 };
+        }
+    }
+
+    fn state(&self) -> DaysState {
+        (self.displayed, self.selected, self.title, self.day_buttons.clone())
+    }
+
+    /// Date shown in cell with given index of month grid.
+    fn cell_date(displayed: Date, index: usize) -> Date {
+        displayed.add_days(index as i64 - displayed.weekday() as i64)
+    }
+
+    fn update_days(ui: &mut UserInterface, state: DaysState) {
+        let (displayed, selected, title, day_buttons) = state;
+        if let Some(title) = ui.node_mut(title).downcast_mut::<Text>() {
+            title.set_text(format!("{} {}", MONTH_NAMES[displayed.month as usize - 1], displayed.year));
+        }
+        let today = Date::today();
+        for (i, button_handle) in day_buttons.into_iter().enumerate() {
+            let date = Self::cell_date(displayed, i);
+            let content = match ui.node_mut(button_handle).downcast_mut::<Button>() {
+                Some(button) => {
+                    button.set_toggled(selected == Some(date));
+                    button.content()
+                }
+                None => continue,
+            };
+            let color = if date == today {
+                palette::current().accent
+            } else if date.month != displayed.month {
+                palette::current().text.darken(0.5)
+            } else {
+                palette::current().text
+            };
+            if let Some(text) = ui.node_mut(content).downcast_mut::<Text>() {
+                text.set_text(date.day.to_string());
+                text.widget_mut().set_foreground(color);
+            }
+        }
+    }
+}
+
+impl Control for Calendar {
+    fn widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    fn widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    fn raw_copy(&self) -> Box<dyn Control> {
+        Box::new(Self {
+            widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
+            displayed: self.displayed,
+            selected: self.selected,
+            title: self.title,
+            prev_year: self.prev_year,
+            prev_month: self.prev_month,
+            next_month: self.next_month,
+            next_year: self.next_year,
+            day_buttons: self.day_buttons.clone(),
+        })
+    }
+
+    fn resolve(&mut self, _: &ControlTemplate, node_map: &HashMap<Handle<UINode>, Handle<UINode>>) {
+        self.title = *node_map.get(&self.title).unwrap();
+        self.prev_year = *node_map.get(&self.prev_year).unwrap();
+        self.prev_month = *node_map.get(&self.prev_month).unwrap();
+        self.next_month = *node_map.get(&self.next_month).unwrap();
+        self.next_year = *node_map.get(&self.next_year).unwrap();
+        for button in self.day_buttons.iter_mut() {
+            *button = *node_map.get(button).unwrap();
+        }
+    }
+
+    fn handle_event(&mut self, _self_handle: Handle<UINode>, ui: &mut UserInterface, evt: &mut UIEvent) {
+        if let UIEventKind::Click = evt.kind {
+            let months = if evt.source == self.prev_year {
+                -12
+            } else if evt.source == self.prev_month {
+                -1
+            } else if evt.source == self.next_month {
+                1
+            } else if evt.source == self.next_year {
+                12
+            } else {
+                0
+            };
+            if months != 0 {
+                self.displayed = self.displayed.add_months(months);
+                Self::update_days(ui, self.state());
+            } else if let Some(index) = self.day_buttons.iter().position(|button| *button == evt.source) {
+                let date = Self::cell_date(self.displayed, index);
+                self.select(Some(date));
+                Self::update_days(ui, self.state());
+                self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::DateSelected(date)));
+            }
+        }
+    }
+
+    fn on_attached_to_ui(&mut self, _self_handle: Handle<UINode>, ui: &mut UserInterface) {
+        Self::update_days(ui, self.state());
+    }
+}
+
+pub struct CalendarBuilder {
+    widget_builder: WidgetBuilder,
+    selected: Option<Date>,
+}
+
+impl CalendarBuilder {
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            selected: None,
+        }
+    }
+
+    /// Sets selected date, calendar will show its month. By default calendar shows
+    /// current month.
+    pub fn with_selected_date(mut self, date: Date) -> Self {
+        self.selected = Some(date);
+        self
+    }
+}
+
+fn make_nav_button(ui: &mut dyn UINodeContainer, column: usize, text: &str) -> Handle<UINode> {
+    ButtonBuilder::new(WidgetBuilder::new()
+        .on_column(column)
+        .with_margin(Thickness::uniform(1.0)))
+        .with_text(text)
+        .build(ui)
+}
+
+impl Builder for CalendarBuilder {
+    fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
+        let prev_year = make_nav_button(ui, 0, "<<");
+        let prev_month = make_nav_button(ui, 1, "<");
+        let title = TextBuilder::new(WidgetBuilder::new()
+            .on_column(2))
+            .with_horizontal_text_alignment(HorizontalAlignment::Center)
+            .with_vertical_text_alignment(VerticalAlignment::Center)
+            .build(ui);
+        let next_month = make_nav_button(ui, 3, ">");
+        let next_year = make_nav_button(ui, 4, ">>");
+
+        let header = GridBuilder::new(WidgetBuilder::new()
+            .on_row(0)
+            .with_child(prev_year)
+            .with_child(prev_month)
+            .with_child(title)
+            .with_child(next_month)
+            .with_child(next_year))
+            .add_column(Column::strict(24.0))
+            .add_column(Column::strict(24.0))
+            .add_column(Column::stretch())
+            .add_column(Column::strict(24.0))
+            .add_column(Column::strict(24.0))
+            .add_row(Row::strict(24.0))
+            .build(ui);
+
+        let mut days_builder = WidgetBuilder::new().on_row(1);
+        for (column, name) in DAY_NAMES.iter().enumerate() {
+            let text = TextBuilder::new(WidgetBuilder::new()
+                .on_row(0)
+                .on_column(column))
+                .with_text(name)
+                .with_horizontal_text_alignment(HorizontalAlignment::Center)
+                .with_vertical_text_alignment(VerticalAlignment::Center)
+                .build(ui);
+            days_builder = days_builder.with_child(text);
+        }
+        let mut day_buttons = Vec::with_capacity(7 * Calendar::WEEKS);
+        for i in 0..7 * Calendar::WEEKS {
+            let button = ButtonBuilder::new(WidgetBuilder::new()
+                .on_row(1 + i / 7)
+                .on_column(i % 7)
+                .with_margin(Thickness::uniform(1.0)))
+                .with_text("")
+                .build(ui);
+            days_builder = days_builder.with_child(button);
+            day_buttons.push(button);
+        }
+        let days = GridBuilder::new(days_builder)
+            .add_columns((0..7).map(|_| Column::stretch()).collect())
+            .add_rows((0..=Calendar::WEEKS).map(|_| Row::strict(24.0)).collect())
+            .build(ui);
+
+        let today = Date::today();
+        let displayed = self.selected.unwrap_or(today);
+
+        let calendar = Calendar {
+            widget: self.widget_builder
+                .with_child(GridBuilder::new(WidgetBuilder::new()
+                    .with_child(header)
+                    .with_child(days))
+                    .add_column(Column::stretch())
+                    .add_row(Row::auto())
+                    .add_row(Row::stretch())
+                    .build(ui))
+                .build(),
+            displayed: Date { year: displayed.year, month: displayed.month, day: 1 },
+            selected: self.selected,
+            title,
+            prev_year,
+            prev_month,
+            next_month,
+            next_year,
+            day_buttons,
+        };
+
+        ui.add_node(Box::new(calendar))
+    }
+}
+
+/// Masked date field with a button which opens calendar in a popup. Date can be typed in
+/// `YYYY-MM-DD` format or picked in calendar.
+///
+/// # Events
+///
+/// [`DateSelected`] - sent when valid date was typed or picked.
+pub struct DatePicker {
+    widget: Widget,
+    field: Handle<UINode>,
+    /// Text box inside of masked field.
+    text_field: Handle<UINode>,
+    open_button: Handle<UINode>,
+    /// Root-level node which hosts calendar.
+    popup: Handle<UINode>,
+    calendar: Handle<UINode>,
+    date: Option<Date>,
+    popup_open: bool,
+}
+
+impl DatePicker {
+    pub fn date(&self) -> Option<Date> {
+        self.date
+    }
+
+    pub fn calendar(&self) -> Handle<UINode> {
+        self.calendar
+    }
+
+    pub fn is_popup_open(&self) -> bool {
+        self.popup_open
+    }
+
+    pub fn set_date(&mut self, ui: &mut UserInterface, date: Option<Date>) {
+        if self.date == date {
+            return;
+        }
+        self.date = date;
+        if let Some(date) = date {
+            self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::DateSelected(date)));
+        }
+        let text = date.map_or(String::new(), |date| date.to_string());
+        if let Some(text_box) = ui.node_mut(self.text_field).downcast_mut::<TextBox>() {
+            if text_box.text() != text {
+                text_box.set_text(text);
+            }
+        }
+        Calendar::set_selected_date(ui, self.calendar, date);
+    }
+
+    fn open_popup(&mut self, ui: &mut UserInterface) {
+        self.popup_open = true;
+        let position = Vec2::new(
+            self.widget.screen_position.x,
+            self.widget.screen_position.y + self.widget.actual_size().y,
+        );
+        // Popup must be drawn on top of everything else.
+        let root = ui.root();
+        ui.node_mut(root).widget_mut().move_child(self.popup, std::usize::MAX);
+        ui.node_mut(self.popup)
+            .widget_mut()
+            .set_desired_local_position(position)
+            .set_visibility(Visibility::Visible);
+    }
+
+    fn close_popup(&mut self, ui: &mut UserInterface) {
+        self.popup_open = false;
+        ui.node_mut(self.popup)
+            .widget_mut()
+            .set_visibility(Visibility::Collapsed);
+    }
+}
+
+impl Control for DatePicker {
+    fn widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    fn widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    fn raw_copy(&self) -> Box<dyn Control> {
+        Box::new(Self {
+            widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
+            field: self.field,
+            text_field: self.text_field,
+            open_button: self.open_button,
+            popup: self.popup,
+            calendar: self.calendar,
+            date: self.date,
+            popup_open: false,
+        })
+    }
+
+    fn resolve(&mut self, _: &ControlTemplate, node_map: &HashMap<Handle<UINode>, Handle<UINode>>) {
+        self.field = *node_map.get(&self.field).unwrap();
+        self.text_field = *node_map.get(&self.text_field).unwrap();
+        self.open_button = *node_map.get(&self.open_button).unwrap();
+        self.popup = *node_map.get(&self.popup).unwrap();
+        self.calendar = *node_map.get(&self.calendar).unwrap();
+    }
+
+    fn handle_event(&mut self, _self_handle: Handle<UINode>, ui: &mut UserInterface, evt: &mut UIEvent) {
+        match evt.kind {
+            UIEventKind::Click if evt.source == self.open_button => {
+                if self.popup_open {
+                    self.close_popup(ui);
+                } else {
+                    self.open_popup(ui);
+                }
+            }
+            UIEventKind::DateSelected(date) if evt.source == self.calendar => {
+                self.set_date(ui, Some(date));
+                self.close_popup(ui);
+            }
+            UIEventKind::TextChanged if evt.source == self.text_field => {
+                let text = ui.node(self.text_field)
+                    .downcast_ref::<TextBox>()
+                    .map(|text_box| text_box.text())
+                    .unwrap_or_default();
+                if let Some(date) = Date::parse(&text) {
+                    self.set_date(ui, Some(date));
+                }
+            }
+            _ => ()
+        }
+    }
+
+    fn on_removed(&mut self, _self_handle: Handle<UINode>, ui: &mut UserInterface) {
+        ui.remove_node(self.popup);
+    }
+}
+
+pub struct DatePickerBuilder {
+    widget_builder: WidgetBuilder,
+    date: Option<Date>,
+}
+
+impl DatePickerBuilder {
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            date: None,
+        }
+    }
+
+    pub fn with_date(mut self, date: Date) -> Self {
+        self.date = Some(date);
+        self
+    }
+}
+
+impl Builder for DatePickerBuilder {
+    fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
+        let field = MaskedTextBoxBuilder::new(WidgetBuilder::new()
+            .on_column(0))
+            .with_mask("0000-00-00")
+            .with_text(&self.date.map_or(String::new(), |date| date.to_string()))
+            .build(ui);
+        let text_field = ui.node(field)
+            .downcast_ref::<MaskedTextBox>()
+            .map_or(Handle::NONE, |masked| masked.field());
+
+        let open_button = ButtonBuilder::new(WidgetBuilder::new()
+            .on_column(1)
+            .with_margin(Thickness::left(1.0)))
+            .with_text("...")
+            .build(ui);
+
+        let mut calendar_builder = CalendarBuilder::new(WidgetBuilder::new()
+            .with_width(200.0)
+            .with_height(200.0));
+        if let Some(date) = self.date {
+            calendar_builder = calendar_builder.with_selected_date(date);
+        }
+        let calendar = calendar_builder.build(ui);
+
+        let popup = BorderBuilder::new(WidgetBuilder::new()
+            .with_visibility(Visibility::Collapsed)
+            .with_background(palette::current().panel)
+            .with_foreground(palette::current().frame)
+            .with_child(calendar))
+            .with_stroke_thickness(Thickness::uniform(1.0))
+            .build(ui);
+
+        let date_picker = DatePicker {
+            widget: self.widget_builder
+                .with_child(GridBuilder::new(WidgetBuilder::new()
+                    .with_child(field)
+                    .with_child(open_button))
+                    .add_column(Column::stretch())
+                    .add_column(Column::strict(24.0))
+                    .add_row(Row::stretch())
+                    .build(ui))
+                .build(),
+            field,
+            text_field,
+            open_button,
+            popup,
+            calendar,
+            date: self.date,
+            popup_open: false,
+        };
+
+        ui.add_node(Box::new(date_picker))
+    }
+}
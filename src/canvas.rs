@@ -12,18 +12,60 @@ use crate::{
             WidgetBuilder,
         },
         UserInterface,
-        draw::DrawingContext,
+        draw::{
+            DrawingContext,
+            CommandKind,
+            CommandTexture,
+        },
+        event::{
+            UIEvent,
+            UIEventKind,
+        },
+        color_ext::palette,
         Control,
         ControlTemplate,
         UINodeContainer,
-        Builder
+        Builder,
+        Visibility,
 
 };
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    cell::RefCell,
+};
+
+/// Settings of snapping of canvas children, see `Canvas::snap`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CanvasSnapping {
+    /// Step of grid, position is rounded to nearest grid node. None disables grid snapping.
+    pub grid_step: Option<f32>,
+    /// Snap edges and centers to edges and centers of other children of canvas.
+    pub snap_to_siblings: bool,
+    /// Maximal distance in pixels at which edges are attracted to each other.
+    pub threshold: f32,
+    /// Draw alignment lines through matched edges while snapping.
+    pub show_alignment_lines: bool,
+}
+
+impl Default for CanvasSnapping {
+    fn default() -> Self {
+        Self {
+            grid_step: None,
+            snap_to_siblings: true,
+            threshold: 6.0,
+            show_alignment_lines: true,
+        }
+    }
+}
 
 /// Allows user to directly set position and size of a node
 pub struct Canvas {
     widget: Widget,
+    /// Layer canvases are direct children of root canvas that always cover whole screen.
+    pub(in crate) is_layer: bool,
+    snapping: Option<CanvasSnapping>,
+    /// Alignment lines found by last snap, in local coordinates of canvas.
+    alignment_lines: RefCell<Vec<(Vec2, Vec2)>>,
 }
 
 impl Control for Canvas {
@@ -38,6 +80,9 @@ impl Control for Canvas {
     fn raw_copy(&self) -> Box<dyn Control> {
         Box::new(Self {
             widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
+            is_layer: self.is_layer,
+            snapping: self.snapping,
+            alignment_lines: Default::default(),
         })
     }
 
@@ -45,14 +90,22 @@ impl Control for Canvas {
 
     }
 
-    fn measure_override(&self, ui: &UserInterface, _available_size: Vec2) -> Vec2 {
+    fn measure_override(&self, ui: &UserInterface, available_size: Vec2) -> Vec2 {
         let size_for_child = Vec2::new(
             std::f32::INFINITY,
             std::f32::INFINITY,
         );
 
         for child_handle in self.widget.children.iter() {
-            ui.node(*child_handle).measure(ui, size_for_child);
+            let child = ui.node(*child_handle);
+            if self.is_root() && child.widget().use_safe_area {
+                let safe_area = ui.safe_area(available_size);
+                child.measure(ui, Vec2::new(safe_area.w, safe_area.h));
+            } else if child.downcast_ref::<Canvas>().map_or(false, |canvas| canvas.is_layer) {
+                child.measure(ui, available_size);
+            } else {
+                child.measure(ui, size_for_child);
+            }
         }
 
         Vec2::ZERO
@@ -61,11 +114,17 @@ impl Control for Canvas {
     fn arrange_override(&self, ui: &UserInterface, final_size: Vec2) -> Vec2 {
         for child_handle in self.widget.children.iter() {
             let child = ui.nodes.borrow(*child_handle);
-            child.arrange(ui, &Rect::new(
-                child.widget().desired_local_position.get().x,
-                child.widget().desired_local_position.get().y,
-                child.widget().desired_size.get().x,
-                child.widget().desired_size.get().y));
+            if self.is_root() && child.widget().use_safe_area {
+                child.arrange(ui, &ui.safe_area(final_size));
+            } else if child.downcast_ref::<Canvas>().map_or(false, |canvas| canvas.is_layer) {
+                child.arrange(ui, &Rect::new(0.0, 0.0, final_size.x, final_size.y));
+            } else {
+                child.arrange(ui, &Rect::new(
+                    child.widget().desired_local_position.get().x,
+                    child.widget().desired_local_position.get().y,
+                    child.widget().desired_size.get().x,
+                    child.widget().desired_size.get().y));
+            }
         }
 
         final_size
@@ -75,6 +134,26 @@ impl Control for Canvas {
         self.widget.draw(drawing_context)
     }
 
+    fn post_draw(&self, drawing_context: &mut DrawingContext) {
+        let lines = self.alignment_lines.borrow();
+        if lines.is_empty() {
+            return;
+        }
+        let origin = self.widget.screen_position;
+        for (begin, end) in lines.iter() {
+            drawing_context.push_line(origin + *begin, origin + *end, 1.0, palette::current().accent);
+        }
+        drawing_context.commit(CommandKind::Geometry, CommandTexture::None);
+    }
+
+    fn handle_event(&mut self, _: Handle<UINode>, _: &mut UserInterface, evt: &mut UIEvent) {
+        // Any finished drag ends snapping session, so stale lines do not stay on screen.
+        match evt.kind {
+            UIEventKind::TransformCompleted(..) | UIEventKind::MouseUp { .. } => self.clear_alignment_lines(),
+            _ => (),
+        }
+    }
+
     fn update(&mut self, dt: f32) {
         self.widget.update(dt)
     }
@@ -83,27 +162,160 @@ impl Control for Canvas {
 impl Canvas {
     pub fn new(widget: Widget) -> Self {
         Self {
-            widget
+            widget,
+            is_layer: false,
+            snapping: None,
+            alignment_lines: Default::default(),
+        }
+    }
+
+    /// Root canvas of user interface (the only one without parent) and layer canvases
+    /// respect safe area.
+    fn is_root(&self) -> bool {
+        self.widget.parent.is_none() || self.is_layer
+    }
+
+    pub fn is_layer(&self) -> bool {
+        self.is_layer
+    }
+
+    pub fn set_snapping(&mut self, snapping: Option<CanvasSnapping>) -> &mut Self {
+        self.snapping = snapping;
+        if snapping.is_none() {
+            self.clear_alignment_lines();
+        }
+        self
+    }
+
+    pub fn snapping(&self) -> Option<CanvasSnapping> {
+        self.snapping
+    }
+
+    /// Removes alignment lines of last snap. Called automatically when drag of gizmo
+    /// handle or mouse button release happens.
+    pub fn clear_alignment_lines(&self) {
+        self.alignment_lines.borrow_mut().clear();
+    }
+
+    /// Snaps proposed local position of a child which is dragged over canvas, returns
+    /// position which should be applied to child. Left, center and right of the child are
+    /// matched against left, center and right of other visible children (same for vertical
+    /// axis), nearest match within threshold wins; when nothing matched on an axis, grid
+    /// snapping is used for that axis. Matched edges are shown as alignment lines until next
+    /// snap or end of drag.
+    ///
+    /// Returns position unchanged if snapping is disabled.
+    pub fn snap(&self, ui: &UserInterface, child: Handle<UINode>, position: Vec2) -> Vec2 {
+        let mut lines = self.alignment_lines.borrow_mut();
+        lines.clear();
+
+        let snapping = match self.snapping {
+            Some(snapping) => snapping,
+            None => return position,
+        };
+
+        let size = ui.node(child).widget().actual_size();
+        let rect = Rect::new(position.x, position.y, size.x, size.y);
+
+        let siblings = if snapping.snap_to_siblings {
+            self.widget.children
+                .iter()
+                .filter(|handle| **handle != child)
+                .map(|handle| ui.node(*handle))
+                .filter(|node| node.widget().get_visibility() == Visibility::Visible
+                    && node.downcast_ref::<Canvas>().map_or(true, |canvas| !canvas.is_layer))
+                .map(|node| {
+                    let position = node.widget().desired_local_position();
+                    let size = node.widget().actual_size();
+                    Rect::new(position.x, position.y, size.x, size.y)
+                })
+                .collect::<Vec<_>>()
+        } else {
+            Vec::new()
+        };
+
+        let horizontal = Self::snap_axis(&siblings, snapping, rect.x, rect.w, |r| (r.x, r.w));
+        let vertical = Self::snap_axis(&siblings, snapping, rect.y, rect.h, |r| (r.y, r.h));
+
+        let snapped = Rect::new(
+            horizontal.map_or_else(|| Self::snap_to_grid(snapping, rect.x), |(offset, _, _)| rect.x + offset),
+            vertical.map_or_else(|| Self::snap_to_grid(snapping, rect.y), |(offset, _, _)| rect.y + offset),
+            rect.w,
+            rect.h);
+
+        if snapping.show_alignment_lines {
+            if let Some((_, x, sibling)) = horizontal {
+                let top = snapped.y.min(sibling.y);
+                let bottom = (snapped.y + snapped.h).max(sibling.y + sibling.h);
+                lines.push((Vec2::new(x, top), Vec2::new(x, bottom)));
+            }
+            if let Some((_, y, sibling)) = vertical {
+                let left = snapped.x.min(sibling.x);
+                let right = (snapped.x + snapped.w).max(sibling.x + sibling.w);
+                lines.push((Vec2::new(left, y), Vec2::new(right, y)));
+            }
+        }
+
+        Vec2::new(snapped.x, snapped.y)
+    }
+
+    /// Finds best match of begin, center or end of a segment among segments of siblings
+    /// along one axis. Returns offset which should be added to segment, matched coordinate
+    /// and rect of matched sibling.
+    fn snap_axis<F>(siblings: &[Rect<f32>], snapping: CanvasSnapping, begin: f32, length: f32, axis: F) -> Option<(f32, f32, Rect<f32>)>
+        where F: Fn(&Rect<f32>) -> (f32, f32) {
+        let points = [begin, begin + length * 0.5, begin + length];
+        let mut best: Option<(f32, f32, Rect<f32>)> = None;
+        for sibling in siblings {
+            let (sibling_begin, sibling_length) = axis(sibling);
+            let targets = [sibling_begin, sibling_begin + sibling_length * 0.5, sibling_begin + sibling_length];
+            for point in points.iter() {
+                for target in targets.iter() {
+                    let offset = target - point;
+                    if offset.abs() <= snapping.threshold && best.map_or(true, |(best, _, _)| offset.abs() < best.abs()) {
+                        best = Some((offset, *target, *sibling));
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    fn snap_to_grid(snapping: CanvasSnapping, value: f32) -> f32 {
+        match snapping.grid_step {
+            Some(step) if step > 0.0 => (value / step).round() * step,
+            _ => value,
         }
     }
 }
 
 pub struct CanvasBuilder {
     widget_builder: WidgetBuilder,
+    snapping: Option<CanvasSnapping>,
 }
 
 impl CanvasBuilder {
     pub fn new(widget_builder: WidgetBuilder) -> Self {
         Self {
             widget_builder,
+            snapping: None,
         }
     }
+
+    /// Enables snapping of children, see `Canvas::snap`.
+    pub fn with_snapping(mut self, snapping: CanvasSnapping) -> Self {
+        self.snapping = Some(snapping);
+        self
+    }
 }
 
 impl Builder for CanvasBuilder {
     fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
         ui.add_node(Box::new(Canvas {
-            widget: self.widget_builder.build()
+            widget: self.widget_builder.build(),
+            is_layer: false,
+            snapping: self.snapping,
+            alignment_lines: Default::default(),
         }))
     }
 }
\ No newline at end of file
@@ -0,0 +1,355 @@
+use crate::{
+    core::{
+        color::Color,
+        pool::Handle,
+        math::vec2::Vec2,
+    },
+    widget::{
+        Widget,
+        WidgetBuilder,
+    },
+    text::TextBuilder,
+    text_box::{
+        TextBox,
+        TextBoxBuilder,
+    },
+    scroll_viewer::{
+        ScrollViewer,
+        ScrollViewerBuilder,
+    },
+    scroll_bar::{
+        ScrollBar,
+        Orientation,
+    },
+    stack_panel::StackPanelBuilder,
+    grid::{
+        GridBuilder,
+        Column,
+        Row,
+    },
+    color_ext::palette,
+    event::{
+        UIEvent,
+        UIEventKind,
+    },
+    keymap::WidgetAction,
+    UserInterface,
+    UINode,
+    Thickness,
+    Control,
+    ControlTemplate,
+    UINodeContainer,
+    Builder,
+};
+use std::collections::{
+    HashMap,
+    VecDeque,
+};
+
+/// Part of chat line drawn with its own color, for example name of sender.
+#[derive(Clone, Debug)]
+pub struct ChatSegment {
+    pub text: String,
+    pub color: Color,
+}
+
+impl ChatSegment {
+    pub fn new(text: &str, color: Color) -> Self {
+        Self {
+            text: text.to_owned(),
+            color,
+        }
+    }
+}
+
+/// In-game chat: scrollback of colored lines above an input line. Scrollback keeps limited
+/// amount of lines, oldest lines are removed. New lines keep view scrolled to the bottom
+/// until user scrolls up, auto-scroll resumes when user scrolls back to the bottom.
+/// Up and Down keys in input line recall previously submitted messages.
+///
+/// # Events
+///
+/// [`ChatMessageSubmitted`] - sent when user presses Enter with non-empty input.
+pub struct ChatBox {
+    widget: Widget,
+    scroll_viewer: Handle<UINode>,
+    lines_panel: Handle<UINode>,
+    input: Handle<UINode>,
+    lines: VecDeque<Handle<UINode>>,
+    max_lines: usize,
+    history: VecDeque<String>,
+    history_limit: usize,
+    /// Index of recalled message in history, None if user types new message.
+    history_index: Option<usize>,
+    /// Text typed before history recall started, restored after last recalled message.
+    draft: String,
+    auto_scroll: bool,
+}
+
+impl ChatBox {
+    /// Adds line made of colored segments to the end of scrollback.
+    pub fn add_line(ui: &mut UserInterface, chat_box: Handle<UINode>, segments: &[ChatSegment]) {
+        let (lines_panel, scroll_viewer, auto_scroll) = match ui.node(chat_box).downcast_ref::<ChatBox>() {
+            Some(chat_box) => (chat_box.lines_panel, chat_box.scroll_viewer, chat_box.auto_scroll),
+            None => return,
+        };
+
+        let mut line_builder = WidgetBuilder::new();
+        for segment in segments {
+            let text = TextBuilder::new(WidgetBuilder::new()
+                .with_foreground(segment.color))
+                .with_text(&segment.text)
+                .build(ui);
+            line_builder = line_builder.with_child(text);
+        }
+        let line = StackPanelBuilder::new(line_builder)
+            .with_orientation(Orientation::Horizontal)
+            .build(ui);
+        ui.link_nodes(line, lines_panel);
+
+        let removed = match ui.node_mut(chat_box).downcast_mut::<ChatBox>() {
+            Some(chat_box) => {
+                chat_box.lines.push_back(line);
+                let excess = chat_box.lines.len().saturating_sub(chat_box.max_lines);
+                chat_box.lines.drain(..excess).collect::<Vec<_>>()
+            }
+            None => Vec::new(),
+        };
+        for line in removed {
+            ui.remove_node(line);
+        }
+
+        if auto_scroll {
+            ScrollViewer::scroll_to(ui, scroll_viewer, Vec2::new(0.0, std::f32::MAX));
+        }
+    }
+
+    /// Adds single-colored line.
+    pub fn add_text(ui: &mut UserInterface, chat_box: Handle<UINode>, text: &str, color: Color) {
+        Self::add_line(ui, chat_box, &[ChatSegment::new(text, color)]);
+    }
+
+    /// Removes all lines of scrollback, history of input is kept.
+    pub fn clear(ui: &mut UserInterface, chat_box: Handle<UINode>) {
+        let lines = match ui.node_mut(chat_box).downcast_mut::<ChatBox>() {
+            Some(chat_box) => chat_box.lines.drain(..).collect::<Vec<_>>(),
+            None => return,
+        };
+        for line in lines {
+            ui.remove_node(line);
+        }
+    }
+
+    pub fn input(&self) -> Handle<UINode> {
+        self.input
+    }
+
+    pub fn scroll_viewer(&self) -> Handle<UINode> {
+        self.scroll_viewer
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn max_lines(&self) -> usize {
+        self.max_lines
+    }
+
+    /// Returns submitted messages, oldest first.
+    pub fn history(&self) -> &VecDeque<String> {
+        &self.history
+    }
+
+    pub fn is_auto_scroll(&self) -> bool {
+        self.auto_scroll
+    }
+
+    fn set_input_text(&self, ui: &mut UserInterface, text: &str) {
+        if let Some(text_box) = ui.node_mut(self.input).downcast_mut::<TextBox>() {
+            text_box.set_text(text);
+            text_box.set_caret_position(0, text.chars().count());
+        }
+    }
+
+    fn submit(&mut self, ui: &mut UserInterface) {
+        let text = ui.node(self.input)
+            .downcast_ref::<TextBox>()
+            .map_or(String::new(), |text_box| text_box.text());
+        self.history_index = None;
+        self.draft.clear();
+        if text.trim().is_empty() {
+            return;
+        }
+        if self.history.back() != Some(&text) {
+            self.history.push_back(text.clone());
+            if self.history.len() > self.history_limit {
+                self.history.pop_front();
+            }
+        }
+        self.set_input_text(ui, "");
+        self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::ChatMessageSubmitted(text)));
+    }
+
+    /// Moves through history, negative direction recalls older messages.
+    fn recall(&mut self, ui: &mut UserInterface, direction: isize) {
+        if self.history.is_empty() {
+            return;
+        }
+        let index = match (self.history_index, direction < 0) {
+            (None, true) => {
+                self.draft = ui.node(self.input)
+                    .downcast_ref::<TextBox>()
+                    .map_or(String::new(), |text_box| text_box.text());
+                Some(self.history.len() - 1)
+            }
+            (None, false) => return,
+            (Some(index), true) => Some(index.saturating_sub(1)),
+            (Some(index), false) => if index + 1 < self.history.len() { Some(index + 1) } else { None },
+        };
+        self.history_index = index;
+        let text = match index {
+            Some(index) => self.history[index].clone(),
+            None => std::mem::replace(&mut self.draft, String::new()),
+        };
+        self.set_input_text(ui, &text);
+    }
+
+    fn on_scroll_changed(&mut self, ui: &mut UserInterface, vertical: f32) {
+        let v_scroll_bar = match ui.node(self.scroll_viewer).downcast_ref::<ScrollViewer>() {
+            Some(scroll_viewer) => scroll_viewer.v_scroll_bar(),
+            None => return,
+        };
+        let max = ui.node(v_scroll_bar)
+            .downcast_ref::<ScrollBar>()
+            .map_or(0.0, |scroll_bar| scroll_bar.max_value());
+        let at_bottom = vertical >= max - 1.0;
+        if at_bottom && !self.auto_scroll {
+            self.auto_scroll = true;
+            ScrollViewer::scroll_to(ui, self.scroll_viewer, Vec2::new(0.0, std::f32::MAX));
+        } else if !at_bottom && self.auto_scroll {
+            self.auto_scroll = false;
+            // Replaces pending scroll to the bottom, so new lines do not move view.
+            ScrollViewer::scroll_to(ui, self.scroll_viewer, Vec2::new(0.0, vertical));
+        }
+    }
+}
+
+impl Control for ChatBox {
+    fn widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    fn widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    fn raw_copy(&self) -> Box<dyn Control> {
+        Box::new(Self {
+            widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
+            scroll_viewer: self.scroll_viewer,
+            lines_panel: self.lines_panel,
+            input: self.input,
+            lines: VecDeque::new(),
+            max_lines: self.max_lines,
+            history: VecDeque::new(),
+            history_limit: self.history_limit,
+            history_index: None,
+            draft: String::new(),
+            auto_scroll: true,
+        })
+    }
+
+    fn resolve(&mut self, _: &ControlTemplate, node_map: &HashMap<Handle<UINode>, Handle<UINode>>) {
+        self.scroll_viewer = *node_map.get(&self.scroll_viewer).unwrap();
+        self.lines_panel = *node_map.get(&self.lines_panel).unwrap();
+        self.input = *node_map.get(&self.input).unwrap();
+    }
+
+    fn handle_event(&mut self, _self_handle: Handle<UINode>, ui: &mut UserInterface, evt: &mut UIEvent) {
+        if evt.source == self.input {
+            if let UIEventKind::KeyDown { code } = evt.kind {
+                match ui.key_action(code) {
+                    Some(WidgetAction::Commit) => self.submit(ui),
+                    Some(WidgetAction::CaretUp) => self.recall(ui, -1),
+                    Some(WidgetAction::CaretDown) => self.recall(ui, 1),
+                    _ => ()
+                }
+            }
+        } else if evt.source == self.scroll_viewer {
+            if let UIEventKind::ScrollChanged { vertical, .. } = evt.kind {
+                self.on_scroll_changed(ui, vertical);
+            }
+        }
+    }
+}
+
+pub struct ChatBoxBuilder {
+    widget_builder: WidgetBuilder,
+    max_lines: usize,
+    history_limit: usize,
+}
+
+impl ChatBoxBuilder {
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            max_lines: 200,
+            history_limit: 50,
+        }
+    }
+
+    /// Sets maximum amount of lines in scrollback.
+    pub fn with_max_lines(mut self, max_lines: usize) -> Self {
+        self.max_lines = max_lines.max(1);
+        self
+    }
+
+    /// Sets maximum amount of submitted messages that can be recalled.
+    pub fn with_history_limit(mut self, limit: usize) -> Self {
+        self.history_limit = limit;
+        self
+    }
+}
+
+impl Builder for ChatBoxBuilder {
+    fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
+        let lines_panel = StackPanelBuilder::new(WidgetBuilder::new())
+            .build(ui);
+
+        let scroll_viewer = ScrollViewerBuilder::new(WidgetBuilder::new()
+            .on_row(0))
+            .with_content(lines_panel)
+            .build(ui);
+
+        let input = TextBoxBuilder::new(WidgetBuilder::new()
+            .on_row(1)
+            .with_margin(Thickness::top(2.0))
+            .with_background(palette::current().field))
+            .build(ui);
+
+        let chat_box = ChatBox {
+            widget: self.widget_builder
+                .with_child(GridBuilder::new(WidgetBuilder::new()
+                    .with_child(scroll_viewer)
+                    .with_child(input))
+                    .add_column(Column::stretch())
+                    .add_row(Row::stretch())
+                    .add_row(Row::strict(24.0))
+                    .build(ui))
+                .build(),
+            scroll_viewer,
+            lines_panel,
+            input,
+            lines: VecDeque::new(),
+            max_lines: self.max_lines,
+            history: VecDeque::new(),
+            history_limit: self.history_limit,
+            history_index: None,
+            draft: String::new(),
+            auto_scroll: true,
+        };
+
+        ui.add_node(Box::new(chat_box))
+    }
+}
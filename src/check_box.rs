@@ -11,8 +11,14 @@ use crate::{
         },
         Thickness,
         Visibility,
+        VerticalAlignment,
         bool_to_visibility,
         border::BorderBuilder,
+        grid::{
+            GridBuilder,
+            Column,
+            Row,
+        },
         Control,
         ControlTemplate,
         UINodeContainer,
@@ -29,6 +35,9 @@ pub struct CheckBox {
     widget: Widget,
     checked: Option<bool>,
     check_mark: Handle<UINode>,
+    content: Handle<UINode>,
+    /// States through which check box goes when user clicks it, in order.
+    cycle: Vec<Option<bool>>,
 }
 
 impl Control for CheckBox {
@@ -45,16 +54,19 @@ impl Control for CheckBox {
             widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
             checked: self.checked,
             check_mark: self.check_mark,
+            content: self.content,
+            cycle: self.cycle.clone(),
         })
     }
 
     fn resolve(&mut self, _: &ControlTemplate, node_map: &HashMap<Handle<UINode>, Handle<UINode>>) {
         self.check_mark = *node_map.get(&self.check_mark).unwrap();
+        if self.content.is_some() {
+            self.content = *node_map.get(&self.content).unwrap();
+        }
     }
 
     fn handle_event(&mut self, self_handle: Handle<UINode>, ui: &mut UserInterface, evt: &mut UIEvent) {
-        let check_mark_color = Color::opaque(200, 200, 200);
-
         match evt.kind {
             UIEventKind::MouseDown { .. } => {
                 if evt.source == self_handle || self.widget.has_descendant(evt.source, ui) {
@@ -65,43 +77,43 @@ impl Control for CheckBox {
                 if evt.source == self_handle || self.widget.has_descendant(evt.source, ui) {
                     ui.release_mouse_capture();
 
-                    if let Some(value) = self.checked {
-                        // Invert state if it is defined.
-                        self.set_checked(Some(!value));
-                    } else {
-                        // Switch from undefined state to checked.
-                        self.set_checked(Some(true));
-                    }
+                    let next = self.next_state();
+                    self.set_checked(next);
                 }
             }
             UIEventKind::Checked(value) if evt.source == self_handle => {
                 let check_mark = ui.node_mut(self.check_mark).widget_mut();
-                match value {
-                    None => {
-                        check_mark.set_background(Color::opaque(30, 30, 80));
-                    }
-                    Some(value) => {
-                        check_mark.set_background(check_mark_color);
-                        check_mark.set_visibility(
-                            if value {
-                                Visibility::Visible
-                            } else {
-                                Visibility::Collapsed
-                            });
-                    }
-                }
+                apply_check_mark_state(check_mark, value);
             }
             _ => {}
         }
     }
 }
 
+fn apply_check_mark_state(check_mark: &mut Widget, value: Option<bool>) {
+    match value {
+        None => {
+            check_mark.set_background(Color::opaque(30, 30, 80));
+            check_mark.set_visibility(Visibility::Visible);
+        }
+        Some(value) => {
+            check_mark.set_background(Color::opaque(200, 200, 200));
+            check_mark.set_visibility(bool_to_visibility(value));
+        }
+    }
+}
+
 impl CheckBox {
+    /// Size of check mark box when check box has content.
+    pub const BOX_SIZE: f32 = 16.0;
+
     pub fn new(widget: Widget, check_mark: Handle<UINode>) -> Self {
         Self {
             widget,
             check_mark,
+            content: Handle::NONE,
             checked: None,
+            cycle: vec![Some(false), Some(true)],
         }
     }
 
@@ -110,16 +122,62 @@ impl CheckBox {
     /// 2) Some(true) - checked
     /// 3) Some(false) - unchecked
     pub fn set_checked(&mut self, value: Option<bool>) -> &mut Self {
-        self.checked = value;
-        self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::Checked(value)));
+        if self.checked != value {
+            self.checked = value;
+            self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::Checked(value)));
+        }
         self
     }
+
+    pub fn checked(&self) -> Option<bool> {
+        self.checked
+    }
+
+    /// Sets order of states through which check box goes on each click. Empty cycle
+    /// makes check box read-only for user.
+    pub fn set_cycle(&mut self, cycle: Vec<Option<bool>>) -> &mut Self {
+        self.cycle = cycle;
+        self
+    }
+
+    pub fn cycle(&self) -> &[Option<bool>] {
+        &self.cycle
+    }
+
+    /// Enables or disables indeterminate state in click cycle: unchecked -> checked ->
+    /// indeterminate -> unchecked.
+    pub fn set_three_state(&mut self, three_state: bool) -> &mut Self {
+        self.set_cycle(make_cycle(three_state))
+    }
+
+    pub fn content(&self) -> Handle<UINode> {
+        self.content
+    }
+
+    /// Returns state that follows current one in click cycle. If current state is not in
+    /// cycle, first state of cycle is returned.
+    fn next_state(&self) -> Option<bool> {
+        match self.cycle.iter().position(|state| *state == self.checked) {
+            Some(i) => self.cycle[(i + 1) % self.cycle.len()],
+            None => self.cycle.first().cloned().unwrap_or(self.checked),
+        }
+    }
+}
+
+fn make_cycle(three_state: bool) -> Vec<Option<bool>> {
+    if three_state {
+        vec![Some(false), Some(true), None]
+    } else {
+        vec![Some(false), Some(true)]
+    }
 }
 
 pub struct CheckBoxBuilder {
     widget_builder: WidgetBuilder,
     checked: Option<bool>,
     check_mark: Option<Handle<UINode>>,
+    content: Handle<UINode>,
+    cycle: Option<Vec<Option<bool>>>,
 }
 
 impl CheckBoxBuilder {
@@ -128,6 +186,8 @@ impl CheckBoxBuilder {
             widget_builder,
             checked: Some(false),
             check_mark: None,
+            content: Handle::NONE,
+            cycle: None,
         }
     }
 
@@ -140,42 +200,81 @@ impl CheckBoxBuilder {
         self.check_mark = Some(check_mark);
         self
     }
+
+    /// Sets arbitrary content which will be placed right to the check mark.
+    pub fn with_content(mut self, content: Handle<UINode>) -> Self {
+        self.content = content;
+        self
+    }
+
+    pub fn with_three_state(mut self, three_state: bool) -> Self {
+        self.cycle = Some(make_cycle(three_state));
+        self
+    }
+
+    pub fn with_cycle(mut self, cycle: Vec<Option<bool>>) -> Self {
+        self.cycle = Some(cycle);
+        self
+    }
 }
 
 impl Builder for CheckBoxBuilder {
     fn build(self, container: &mut dyn UINodeContainer) -> Handle<UINode> {
-        let check_mark_color = Color::opaque(200, 200, 200);
-
         let check_mark = self.check_mark.unwrap_or_else(|| {
             BorderBuilder::new(WidgetBuilder::new()
-                .with_background(check_mark_color)
+                .with_background(Color::opaque(200, 200, 200))
                 .with_margin(Thickness::uniform(1.0)))
                 .with_stroke_thickness(Thickness::uniform(0.0))
                 .build(container)
         });
 
-        let visibility = if let Some(value) = self.checked {
-            bool_to_visibility(value)
+        apply_check_mark_state(container.node_mut(check_mark).widget_mut(), self.checked);
+
+        let mut box_builder = WidgetBuilder::new()
+            .with_background(Color::opaque(60, 60, 60))
+            .with_foreground(Color::WHITE)
+            .with_child(check_mark);
+        if self.content.is_some() {
+            // Check mark box has fixed size when it is shown together with content.
+            box_builder = box_builder
+                .with_width(CheckBox::BOX_SIZE)
+                .with_height(CheckBox::BOX_SIZE)
+                .with_vertical_alignment(VerticalAlignment::Center)
+                .with_margin(Thickness::right(4.0));
+        }
+        let check_box_border = BorderBuilder::new(box_builder)
+            .with_stroke_thickness(Thickness::uniform(1.0))
+            .build(container);
+
+        let widget = if self.content.is_some() {
+            container.node_mut(self.content)
+                .widget_mut()
+                .set_column(1);
+            self.widget_builder
+                .with_focusable(true)
+                .with_child(GridBuilder::new(WidgetBuilder::new()
+                    .with_child(check_box_border)
+                    .with_child(self.content))
+                    .add_column(Column::auto())
+                    .add_column(Column::stretch())
+                    .add_row(Row::stretch())
+                    .build(container))
+                .build()
         } else {
-            Visibility::Visible
+            self.widget_builder
+                .with_focusable(true)
+                .with_child(check_box_border)
+                .build()
         };
-        container.node_mut(check_mark)
-            .widget_mut()
-            .set_visibility(visibility);
 
         let check_box = CheckBox {
-            widget: self.widget_builder
-                .with_child(BorderBuilder::new(WidgetBuilder::new()
-                    .with_background(Color::opaque(60, 60, 60))
-                    .with_foreground(Color::WHITE)
-                    .with_child(check_mark))
-                    .with_stroke_thickness(Thickness::uniform(1.0))
-                    .build(container))
-                .build(),
+            widget,
             checked: self.checked,
             check_mark,
+            content: self.content,
+            cycle: self.cycle.unwrap_or_else(|| make_cycle(false)),
         };
 
         container.add_node(Box::new(check_box))
     }
-}
\ No newline at end of file
+}
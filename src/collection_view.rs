@@ -0,0 +1,303 @@
+use crate::{
+    core::pool::Handle,
+    list_box::{
+        ListBox,
+        KeyedItems,
+    },
+    item_templates::ItemTemplates,
+    text::{
+        Text,
+        TextBuilder,
+    },
+    widget::WidgetBuilder,
+    event::{
+        UIEvent,
+        UIEventKind,
+    },
+    UserInterface,
+    UINode,
+    Thickness,
+    Builder,
+};
+use std::{
+    cmp::Ordering,
+    collections::{
+        HashMap,
+        HashSet,
+    },
+};
+
+/// Identifier of item of collection view, it does not change when item is updated.
+pub type ItemKey = usize;
+
+/// Line of list box that is shown by collection view.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum ViewEntry {
+    Group(String),
+    Item(ItemKey),
+}
+
+/// View layer between collection of items and list box. It shows items that pass filter,
+/// in order defined by comparator, optionally split into groups with collapsible headers.
+/// Widgets of items are created by template.
+///
+/// Collection view is not a widget, it changes items of list box through its methods:
+/// when source items or view settings change, list box is updated by [`KeyedItems`], so
+/// only items that have actually changed are created, removed or moved. Application
+/// should pass events to [`handle_event`](Self::handle_event), so clicks on group headers
+/// collapse and expand groups.
+pub struct CollectionView<T> {
+    items: Vec<(ItemKey, T)>,
+    next_key: ItemKey,
+    template: Box<dyn Fn(&mut UserInterface, &T) -> Handle<UINode>>,
+    filter: Option<Box<dyn Fn(&T) -> bool>>,
+    comparator: Option<Box<dyn Fn(&T, &T) -> Ordering>>,
+    group_by: Option<Box<dyn Fn(&T) -> String>>,
+    collapsed: HashSet<String>,
+    /// Entries in order of items of list box.
+    entries: KeyedItems<ViewEntry>,
+    /// Text nodes of group headers.
+    headers: HashMap<String, Handle<UINode>>,
+}
+
+impl<T> CollectionView<T> {
+    /// Creates empty view for list box, list box should have no items.
+    pub fn new<F>(list_box: Handle<UINode>, template: F) -> Self
+        where F: Fn(&mut UserInterface, &T) -> Handle<UINode> + 'static {
+        Self {
+            items: Vec::new(),
+            next_key: 0,
+            template: Box::new(template),
+            filter: None,
+            comparator: None,
+            group_by: None,
+            collapsed: HashSet::new(),
+            entries: KeyedItems::new(list_box),
+            headers: HashMap::new(),
+        }
+    }
+
+    /// Creates empty view for list box, widget of each item is created by template selected
+    /// for the item.
+    pub fn with_templates(list_box: Handle<UINode>, templates: ItemTemplates<T>) -> Self
+        where T: 'static {
+        Self::new(list_box, move |ui, item| templates.create(ui, item))
+    }
+
+    pub fn list_box(&self) -> Handle<UINode> {
+        self.entries.list_box()
+    }
+
+    /// Returns all source items with their keys, including filtered out ones.
+    pub fn items(&self) -> impl Iterator<Item=(ItemKey, &T)> {
+        self.items.iter().map(|(key, item)| (*key, item))
+    }
+
+    pub fn item(&self, key: ItemKey) -> Option<&T> {
+        self.items
+            .iter()
+            .find(|(item_key, _)| *item_key == key)
+            .map(|(_, item)| item)
+    }
+
+    /// Returns key of item shown at given index of list box, None if there is group header
+    /// at the index.
+    pub fn key_at(&self, index: usize) -> Option<ItemKey> {
+        match self.entries.keys().get(index) {
+            Some(ViewEntry::Item(key)) => Some(*key),
+            _ => None,
+        }
+    }
+
+    /// Returns index of list box item that shows given item, None if item is filtered out
+    /// or its group is collapsed.
+    pub fn index_of(&self, key: ItemKey) -> Option<usize> {
+        self.entries.position(&ViewEntry::Item(key))
+    }
+
+    /// Adds item to source collection, returns key of the item.
+    pub fn insert(&mut self, ui: &mut UserInterface, item: T) -> ItemKey {
+        let key = self.next_key;
+        self.next_key += 1;
+        self.items.push((key, item));
+        self.refresh(ui);
+        key
+    }
+
+    pub fn remove(&mut self, ui: &mut UserInterface, key: ItemKey) -> Option<T> {
+        let position = self.items.iter().position(|(item_key, _)| *item_key == key)?;
+        let (_, item) = self.items.remove(position);
+        self.refresh(ui);
+        Some(item)
+    }
+
+    /// Replaces item, its widget is created again.
+    pub fn update(&mut self, ui: &mut UserInterface, key: ItemKey, item: T) {
+        if let Some((_, old)) = self.items.iter_mut().find(|(item_key, _)| *item_key == key) {
+            *old = item;
+            self.entries.remove(ui, &ViewEntry::Item(key));
+            self.refresh(ui);
+        }
+    }
+
+    /// Replaces all source items, widgets of all items are created again.
+    pub fn set_items(&mut self, ui: &mut UserInterface, items: Vec<T>) -> Vec<ItemKey> {
+        self.items.clear();
+        let keys = items
+            .into_iter()
+            .map(|item| {
+                let key = self.next_key;
+                self.next_key += 1;
+                self.items.push((key, item));
+                key
+            })
+            .collect();
+        self.refresh(ui);
+        keys
+    }
+
+    /// Shows only items for which predicate returns true.
+    pub fn set_filter<F>(&mut self, ui: &mut UserInterface, filter: F)
+        where F: Fn(&T) -> bool + 'static {
+        self.filter = Some(Box::new(filter));
+        self.refresh(ui);
+    }
+
+    pub fn clear_filter(&mut self, ui: &mut UserInterface) {
+        self.filter = None;
+        self.refresh(ui);
+    }
+
+    /// Sorts shown items with comparator, sorting is stable so equal items keep order of
+    /// source collection.
+    pub fn set_sort<F>(&mut self, ui: &mut UserInterface, comparator: F)
+        where F: Fn(&T, &T) -> Ordering + 'static {
+        self.comparator = Some(Box::new(comparator));
+        self.refresh(ui);
+    }
+
+    pub fn clear_sort(&mut self, ui: &mut UserInterface) {
+        self.comparator = None;
+        self.refresh(ui);
+    }
+
+    /// Splits shown items into groups by name returned by given function. Groups follow
+    /// in order of their first items.
+    pub fn set_grouping<F>(&mut self, ui: &mut UserInterface, group_by: F)
+        where F: Fn(&T) -> String + 'static {
+        self.group_by = Some(Box::new(group_by));
+        self.refresh(ui);
+    }
+
+    pub fn clear_grouping(&mut self, ui: &mut UserInterface) {
+        self.group_by = None;
+        self.refresh(ui);
+    }
+
+    pub fn is_group_collapsed(&self, group: &str) -> bool {
+        self.collapsed.contains(group)
+    }
+
+    /// Hides or shows items of group, header of the group stays visible.
+    pub fn set_group_collapsed(&mut self, ui: &mut UserInterface, group: &str, collapsed: bool) {
+        if collapsed {
+            self.collapsed.insert(group.to_owned());
+        } else {
+            self.collapsed.remove(group);
+        }
+        if let Some(header) = self.headers.get(group) {
+            if let Some(header) = ui.node_mut(*header).downcast_mut::<Text>() {
+                header.set_text(header_text(group, collapsed));
+            }
+        }
+        self.refresh(ui);
+    }
+
+    /// Toggles group when its header was clicked, should be called for every event polled
+    /// from user interface.
+    pub fn handle_event(&mut self, ui: &mut UserInterface, evt: &UIEvent) {
+        if let UIEventKind::MouseDown { .. } = evt.kind {
+            let items = match ui.node(self.entries.list_box()).downcast_ref::<ListBox>() {
+                Some(list_box) => list_box.get_items().to_vec(),
+                None => return,
+            };
+            let clicked = self.entries
+                .keys()
+                .iter()
+                .zip(items.iter())
+                .find_map(|(entry, item)| match entry {
+                    ViewEntry::Group(group) if evt.source == *item || ui.is_node_child_of(evt.source, *item) => {
+                        Some(group.clone())
+                    }
+                    _ => None,
+                });
+            if let Some(group) = clicked {
+                let collapsed = !self.is_group_collapsed(&group);
+                self.set_group_collapsed(ui, &group, collapsed);
+            }
+        }
+    }
+
+    /// Applies filter, sorting and grouping and updates items of list box. Should be called
+    /// when data used by filter or comparator has changed.
+    pub fn refresh(&mut self, ui: &mut UserInterface) {
+        let entries = self.build_entries();
+        let mut keyed = std::mem::replace(&mut self.entries, KeyedItems::new(Handle::NONE));
+        keyed.sync(ui, &entries, |entry| entry.clone(), |ui, entry| self.make_entry(ui, entry), |_, _, _| ());
+        self.entries = keyed;
+        self.headers.retain(|group, _| entries.contains(&ViewEntry::Group(group.clone())));
+    }
+
+    fn build_entries(&self) -> Vec<ViewEntry> {
+        let mut visible = self.items
+            .iter()
+            .filter(|(_, item)| self.filter.as_ref().map_or(true, |filter| filter(item)))
+            .collect::<Vec<_>>();
+        if let Some(comparator) = self.comparator.as_ref() {
+            visible.sort_by(|(_, a), (_, b)| comparator(a, b));
+        }
+
+        let group_by = match self.group_by.as_ref() {
+            Some(group_by) => group_by,
+            None => return visible.iter().map(|(key, _)| ViewEntry::Item(*key)).collect(),
+        };
+        let mut groups: Vec<(String, Vec<ItemKey>)> = Vec::new();
+        for (key, item) in visible {
+            let group = group_by(item);
+            match groups.iter_mut().find(|(name, _)| *name == group) {
+                Some((_, keys)) => keys.push(*key),
+                None => groups.push((group, vec![*key])),
+            }
+        }
+        let mut entries = Vec::new();
+        for (group, keys) in groups {
+            let collapsed = self.collapsed.contains(&group);
+            entries.push(ViewEntry::Group(group));
+            if !collapsed {
+                entries.extend(keys.into_iter().map(ViewEntry::Item));
+            }
+        }
+        entries
+    }
+
+    fn make_entry(&mut self, ui: &mut UserInterface, entry: &ViewEntry) -> Handle<UINode> {
+        match entry {
+            ViewEntry::Group(group) => {
+                let header = TextBuilder::new(WidgetBuilder::new()
+                    .with_margin(Thickness::uniform(2.0)))
+                    .with_text(&header_text(group, self.collapsed.contains(group)))
+                    .build(ui);
+                self.headers.insert(group.clone(), header);
+                header
+            }
+            ViewEntry::Item(key) => match self.items.iter().find(|(item_key, _)| item_key == key) {
+                Some((_, item)) => (self.template)(ui, item),
+                None => Handle::NONE,
+            },
+        }
+    }
+}
+
+fn header_text(group: &str, collapsed: bool) -> String {
+    format!("{} {}", if collapsed { "+" } else { "-" }, group)
+}
@@ -0,0 +1,271 @@
+use crate::core::color::Color;
+
+/// Colors of built-in widgets. Widgets use current palette instead of literal colors, so
+/// look of the library can be adjusted in one place. Constants are colors of default dark
+/// palette, other built-in palettes are accessibility variants of it.
+pub mod palette {
+    use crate::core::color::Color;
+    use std::sync::RwLock;
+
+    const fn rgb(r: u8, g: u8, b: u8) -> Color {
+        Color { r, g, b, a: 255 }
+    }
+
+    /// Background of panels (status bar, toolbar, check box).
+    pub const PANEL: Color = rgb(60, 60, 60);
+    /// Background of input fields.
+    pub const FIELD: Color = rgb(80, 80, 80);
+    /// Window frame and pressed or selected controls.
+    pub const FRAME: Color = rgb(100, 100, 100);
+    /// Normal state of controls (buttons, headers).
+    pub const CONTROL: Color = rgb(120, 120, 120);
+    /// Hovered state of controls.
+    pub const CONTROL_HOVER: Color = rgb(160, 160, 160);
+    /// Text and glyphs.
+    pub const TEXT: Color = rgb(200, 200, 200);
+    /// Text selection in text box.
+    pub const TEXT_SELECTION: Color = rgb(65, 65, 90);
+    /// Text selection in text box when it spans multiple lines.
+    pub const TEXT_SELECTION_MULTILINE: Color = rgb(90, 90, 120);
+    /// Accent color, used for marquee selection and check marks.
+    pub const ACCENT: Color = rgb(80, 120, 200);
+
+    /// Set of colors of built-in widgets, see constants of this module for meaning of
+    /// each color.
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    pub struct Palette {
+        pub panel: Color,
+        pub field: Color,
+        pub frame: Color,
+        pub control: Color,
+        pub control_hover: Color,
+        pub text: Color,
+        pub text_selection: Color,
+        pub text_selection_multiline: Color,
+        pub accent: Color,
+    }
+
+    impl Default for Palette {
+        fn default() -> Self {
+            Self::dark()
+        }
+    }
+
+    impl Palette {
+        /// Default palette.
+        pub const fn dark() -> Self {
+            Self {
+                panel: PANEL,
+                field: FIELD,
+                frame: FRAME,
+                control: CONTROL,
+                control_hover: CONTROL_HOVER,
+                text: TEXT,
+                text_selection: TEXT_SELECTION,
+                text_selection_multiline: TEXT_SELECTION_MULTILINE,
+                accent: ACCENT,
+            }
+        }
+
+        /// Black backgrounds, white text and frames and yellow accent, every pair of
+        /// foreground and background colors has contrast ratio above 7:1.
+        pub const fn high_contrast() -> Self {
+            Self {
+                panel: rgb(0, 0, 0),
+                field: rgb(0, 0, 0),
+                frame: rgb(255, 255, 255),
+                control: rgb(30, 30, 30),
+                control_hover: rgb(0, 70, 140),
+                text: rgb(255, 255, 255),
+                text_selection: rgb(0, 90, 200),
+                text_selection_multiline: rgb(0, 90, 200),
+                accent: rgb(255, 255, 0),
+            }
+        }
+
+        /// Dark palette with accents that stay distinguishable for red-green color
+        /// deficiency of deuteranopia type (blue/orange pair of Okabe-Ito palette).
+        pub const fn deuteranopia() -> Self {
+            Self {
+                text_selection: rgb(0, 70, 120),
+                text_selection_multiline: rgb(0, 90, 150),
+                accent: rgb(230, 159, 0),
+                ..Self::dark()
+            }
+        }
+
+        /// Dark palette with accents tuned for protanopia, red is avoided completely since
+        /// it is perceived as very dark.
+        pub const fn protanopia() -> Self {
+            Self {
+                text_selection: rgb(0, 70, 120),
+                text_selection_multiline: rgb(0, 90, 150),
+                accent: rgb(86, 180, 233),
+                ..Self::dark()
+            }
+        }
+
+        /// Returns all built-in palettes with their names, useful for settings screens.
+        pub fn built_in() -> Vec<(&'static str, Palette)> {
+            vec![
+                ("Dark", Self::dark()),
+                ("High Contrast", Self::high_contrast()),
+                ("Deuteranopia", Self::deuteranopia()),
+                ("Protanopia", Self::protanopia()),
+            ]
+        }
+    }
+
+    lazy_static! {
+        static ref CURRENT: RwLock<Palette> = RwLock::new(Palette::dark());
+    }
+
+    /// Returns palette used by built-in widgets.
+    pub fn current() -> Palette {
+        *CURRENT.read().unwrap()
+    }
+
+    /// Changes palette of built-in widgets. Widgets take most of their colors when they are
+    /// built, so interface should be rebuilt after palette has changed.
+    pub fn set_current(palette: Palette) {
+        *CURRENT.write().unwrap() = palette;
+    }
+}
+
+/// Color in HSV space, hue is in degrees [0; 360), saturation and value are in [0; 1].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Hsv {
+    pub hue: f32,
+    pub saturation: f32,
+    pub value: f32,
+}
+
+/// Color in HSL space, hue is in degrees [0; 360), saturation and lightness are in [0; 1].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Hsl {
+    pub hue: f32,
+    pub saturation: f32,
+    pub lightness: f32,
+}
+
+/// Color math used by widgets for derived colors (hover states, disabled look, etc.).
+pub trait ColorExt {
+    /// Moves color towards white by given amount in [0; 1], alpha is kept.
+    fn lighten(self, amount: f32) -> Color;
+
+    /// Moves color towards black by given amount in [0; 1], alpha is kept.
+    fn darken(self, amount: f32) -> Color;
+
+    /// Linear interpolation of all channels including alpha.
+    fn lerp(self, other: Color, t: f32) -> Color;
+
+    fn with_alpha(self, alpha: u8) -> Color;
+
+    fn to_hsv(self) -> Hsv;
+
+    fn from_hsv(hsv: Hsv, alpha: u8) -> Color;
+
+    fn to_hsl(self) -> Hsl;
+
+    fn from_hsl(hsl: Hsl, alpha: u8) -> Color;
+}
+
+fn to_byte(v: f32) -> u8 {
+    (v.max(0.0).min(1.0) * 255.0).round() as u8
+}
+
+fn lerp_byte(a: u8, b: u8, t: f32) -> u8 {
+    (a as f32 + (b as f32 - a as f32) * t).round().max(0.0).min(255.0) as u8
+}
+
+/// Returns hue in degrees, max and min channels of color in [0; 1].
+fn hue_max_min(color: Color) -> (f32, f32, f32) {
+    let r = color.r as f32 / 255.0;
+    let g = color.g as f32 / 255.0;
+    let b = color.b as f32 / 255.0;
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+    let hue = if delta <= std::f32::EPSILON {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / delta)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    (if hue < 0.0 { hue + 360.0 } else { hue }, max, min)
+}
+
+/// Builds color from hue and chroma, `m` is added to every channel.
+fn from_hue_chroma(hue: f32, chroma: f32, m: f32, alpha: u8) -> Color {
+    let h = (hue % 360.0 + 360.0) % 360.0 / 60.0;
+    let x = chroma * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    Color::from_rgba(to_byte(r + m), to_byte(g + m), to_byte(b + m), alpha)
+}
+
+impl ColorExt for Color {
+    fn lighten(self, amount: f32) -> Color {
+        self.lerp(Color::WHITE.with_alpha(self.a), amount)
+    }
+
+    fn darken(self, amount: f32) -> Color {
+        self.lerp(Color::BLACK.with_alpha(self.a), amount)
+    }
+
+    fn lerp(self, other: Color, t: f32) -> Color {
+        Color::from_rgba(
+            lerp_byte(self.r, other.r, t),
+            lerp_byte(self.g, other.g, t),
+            lerp_byte(self.b, other.b, t),
+            lerp_byte(self.a, other.a, t),
+        )
+    }
+
+    fn with_alpha(self, alpha: u8) -> Color {
+        Color::from_rgba(self.r, self.g, self.b, alpha)
+    }
+
+    fn to_hsv(self) -> Hsv {
+        let (hue, max, min) = hue_max_min(self);
+        Hsv {
+            hue,
+            saturation: if max <= std::f32::EPSILON { 0.0 } else { (max - min) / max },
+            value: max,
+        }
+    }
+
+    fn from_hsv(hsv: Hsv, alpha: u8) -> Color {
+        let chroma = hsv.value * hsv.saturation;
+        from_hue_chroma(hsv.hue, chroma, hsv.value - chroma, alpha)
+    }
+
+    fn to_hsl(self) -> Hsl {
+        let (hue, max, min) = hue_max_min(self);
+        let lightness = (max + min) * 0.5;
+        let delta = max - min;
+        Hsl {
+            hue,
+            saturation: if delta <= std::f32::EPSILON {
+                0.0
+            } else {
+                delta / (1.0 - (2.0 * lightness - 1.0).abs())
+            },
+            lightness,
+        }
+    }
+
+    fn from_hsl(hsl: Hsl, alpha: u8) -> Color {
+        let chroma = (1.0 - (2.0 * hsl.lightness - 1.0).abs()) * hsl.saturation;
+        from_hue_chroma(hsl.hue, chroma, hsl.lightness - chroma * 0.5, alpha)
+    }
+}
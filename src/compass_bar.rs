@@ -0,0 +1,285 @@
+use crate::{
+    core::{
+        color::Color,
+        pool::Handle,
+        math::vec2::Vec2,
+    },
+    widget::{
+        Widget,
+        WidgetBuilder,
+    },
+    draw::{
+        DrawingContext,
+        CommandKind,
+        CommandTexture,
+    },
+    formatted_text::{
+        FormattedText,
+        FormattedTextBuilder,
+    },
+    color_ext::palette,
+    UINode,
+    HorizontalAlignment,
+    VerticalAlignment,
+    Control,
+    ControlTemplate,
+    UINodeContainer,
+    Builder,
+};
+use std::collections::HashMap;
+
+pub type CompassMarkerId = usize;
+
+#[derive(Clone)]
+struct CompassMarker {
+    id: CompassMarkerId,
+    /// Position on world plane, X points to east and Y to north.
+    position: Vec2,
+    color: Color,
+}
+
+/// Wraps angle in degrees into [-180; 180).
+fn wrap_degrees(angle: f32) -> f32 {
+    (angle + 180.0).rem_euclid(360.0) - 180.0
+}
+
+/// Horizontal heading strip for HUD. Shows ticks and cardinal directions around current
+/// yaw and markers of tracked points. Ticks that do not fit are cut by bounds of widget,
+/// markers which are out of field of view stay at the nearest edge.
+///
+/// Yaw and bearings are in degrees, 0 is north and angles grow clockwise (east is 90).
+pub struct CompassBar {
+    widget: Widget,
+    yaw: f32,
+    /// Range of headings visible across width of widget, in degrees.
+    field_of_view: f32,
+    /// Angle between minor ticks, in degrees.
+    tick_step: f32,
+    observer: Vec2,
+    markers: Vec<CompassMarker>,
+    next_marker_id: CompassMarkerId,
+    /// Labels of N, NE, E, SE, S, SW, W, NW headings.
+    labels: Vec<FormattedText>,
+}
+
+impl CompassBar {
+    const LABELS: [&'static str; 8] = ["N", "NE", "E", "SE", "S", "SW", "W", "NW"];
+    const LABEL_AREA: Vec2 = Vec2 { x: 32.0, y: 16.0 };
+    const MARKER_SIZE: f32 = 8.0;
+
+    pub fn set_yaw(&mut self, yaw: f32) -> &mut Self {
+        self.yaw = yaw;
+        self
+    }
+
+    pub fn yaw(&self) -> f32 {
+        self.yaw
+    }
+
+    pub fn set_field_of_view(&mut self, field_of_view: f32) -> &mut Self {
+        self.field_of_view = field_of_view.max(1.0).min(360.0);
+        self
+    }
+
+    pub fn field_of_view(&self) -> f32 {
+        self.field_of_view
+    }
+
+    pub fn set_tick_step(&mut self, step: f32) -> &mut Self {
+        self.tick_step = step.max(1.0);
+        self
+    }
+
+    pub fn tick_step(&self) -> f32 {
+        self.tick_step
+    }
+
+    /// Sets position of observer on world plane, bearings of markers are measured from it.
+    pub fn set_observer_position(&mut self, position: Vec2) -> &mut Self {
+        self.observer = position;
+        self
+    }
+
+    pub fn observer_position(&self) -> Vec2 {
+        self.observer
+    }
+
+    /// Adds tracked point, returns id which can be used to move or remove it.
+    pub fn add_marker(&mut self, position: Vec2, color: Color) -> CompassMarkerId {
+        let id = self.next_marker_id;
+        self.next_marker_id += 1;
+        self.markers.push(CompassMarker { id, position, color });
+        id
+    }
+
+    pub fn set_marker_position(&mut self, id: CompassMarkerId, position: Vec2) {
+        if let Some(marker) = self.markers.iter_mut().find(|marker| marker.id == id) {
+            marker.position = position;
+        }
+    }
+
+    pub fn remove_marker(&mut self, id: CompassMarkerId) {
+        self.markers.retain(|marker| marker.id != id);
+    }
+
+    pub fn clear_markers(&mut self) {
+        self.markers.clear();
+    }
+
+    /// Returns bearing of marker from observer in degrees, None if there is no such marker.
+    pub fn marker_bearing(&self, id: CompassMarkerId) -> Option<f32> {
+        self.markers
+            .iter()
+            .find(|marker| marker.id == id)
+            .map(|marker| self.bearing(marker.position))
+    }
+
+    fn bearing(&self, position: Vec2) -> f32 {
+        let d = position - self.observer;
+        d.x.atan2(d.y).to_degrees()
+    }
+
+    /// Returns horizontal offset from center of widget for given heading.
+    fn heading_offset(&self, heading: f32, width: f32) -> f32 {
+        wrap_degrees(heading - self.yaw) / self.field_of_view * width
+    }
+
+    fn make_labels() -> Vec<FormattedText> {
+        Self::LABELS
+            .iter()
+            .map(|label| {
+                let mut text = FormattedTextBuilder::new()
+                    .with_font(crate::DEFAULT_FONT.clone())
+                    .with_text(label.to_string())
+                    .with_size(Self::LABEL_AREA)
+                    .with_color(palette::current().text)
+                    .with_horizontal_alignment(HorizontalAlignment::Center)
+                    .with_vertical_alignment(VerticalAlignment::Center)
+                    .build();
+                text.build();
+                text
+            })
+            .collect()
+    }
+}
+
+impl Control for CompassBar {
+    fn widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    fn widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    fn raw_copy(&self) -> Box<dyn Control> {
+        Box::new(Self {
+            widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
+            yaw: self.yaw,
+            field_of_view: self.field_of_view,
+            tick_step: self.tick_step,
+            observer: self.observer,
+            markers: self.markers.clone(),
+            next_marker_id: self.next_marker_id,
+            labels: Self::make_labels(),
+        })
+    }
+
+    fn resolve(&mut self, _: &ControlTemplate, _: &HashMap<Handle<UINode>, Handle<UINode>>) {}
+
+    fn draw(&self, drawing_context: &mut DrawingContext) {
+        let bounds = self.widget.get_screen_bounds();
+        let center_x = bounds.x + bounds.w * 0.5;
+        let color = self.widget.foreground();
+
+        drawing_context.push_rect_filled(&bounds, None, self.widget.background());
+
+        // Ticks are generated from both sides of yaw, ticks beyond bounds are clipped.
+        let half_fov = self.field_of_view * 0.5;
+        let first = ((self.yaw - half_fov) / self.tick_step).floor() as i32;
+        let last = ((self.yaw + half_fov) / self.tick_step).ceil() as i32;
+        for i in first..=last {
+            let heading = i as f32 * self.tick_step;
+            let x = center_x + self.heading_offset(heading, bounds.w);
+            let is_major = (heading.rem_euclid(45.0)).abs() < 0.001;
+            let height = bounds.h * if is_major { 0.4 } else { 0.2 };
+            drawing_context.push_line(Vec2::new(x, bounds.y), Vec2::new(x, bounds.y + height), 1.0, color);
+        }
+        drawing_context.push_line(Vec2::new(center_x, bounds.y), Vec2::new(center_x, bounds.y + bounds.h), 2.0, palette::current().accent);
+        drawing_context.commit(CommandKind::Geometry, CommandTexture::None);
+
+        for (i, label) in self.labels.iter().enumerate() {
+            let offset = self.heading_offset(i as f32 * 45.0, bounds.w);
+            if offset.abs() <= bounds.w * 0.5 + Self::LABEL_AREA.x {
+                let position = Vec2::new(
+                    center_x + offset - Self::LABEL_AREA.x * 0.5,
+                    bounds.y + bounds.h - Self::LABEL_AREA.y,
+                );
+                drawing_context.draw_text(position, label);
+            }
+        }
+
+        let half_width = bounds.w * 0.5 - Self::MARKER_SIZE * 0.5;
+        for marker in self.markers.iter() {
+            let offset = self.heading_offset(self.bearing(marker.position), bounds.w);
+            let x = center_x + offset.max(-half_width).min(half_width);
+            let y = bounds.y + bounds.h * 0.4;
+            drawing_context.push_triangle_filled([
+                Vec2::new(x - Self::MARKER_SIZE * 0.5, y),
+                Vec2::new(x + Self::MARKER_SIZE * 0.5, y),
+                Vec2::new(x, y + Self::MARKER_SIZE),
+            ], marker.color);
+        }
+        drawing_context.commit(CommandKind::Geometry, CommandTexture::None);
+    }
+}
+
+pub struct CompassBarBuilder {
+    widget_builder: WidgetBuilder,
+    yaw: f32,
+    field_of_view: f32,
+    tick_step: f32,
+}
+
+impl CompassBarBuilder {
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            yaw: 0.0,
+            field_of_view: 120.0,
+            tick_step: 15.0,
+        }
+    }
+
+    pub fn with_yaw(mut self, yaw: f32) -> Self {
+        self.yaw = yaw;
+        self
+    }
+
+    pub fn with_field_of_view(mut self, field_of_view: f32) -> Self {
+        self.field_of_view = field_of_view;
+        self
+    }
+
+    pub fn with_tick_step(mut self, step: f32) -> Self {
+        self.tick_step = step;
+        self
+    }
+}
+
+impl Builder for CompassBarBuilder {
+    fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
+        let compass_bar = CompassBar {
+            widget: self.widget_builder.build(),
+            yaw: self.yaw,
+            field_of_view: self.field_of_view.max(1.0).min(360.0),
+            tick_step: self.tick_step.max(1.0),
+            observer: Vec2::ZERO,
+            markers: Vec::new(),
+            next_marker_id: 0,
+            labels: CompassBar::make_labels(),
+        };
+
+        ui.add_node(Box::new(compass_bar))
+    }
+}
@@ -0,0 +1,238 @@
+use crate::{
+    core::{
+        color::Color,
+        pool::Handle,
+        math::vec2::Vec2,
+    },
+    widget::{
+        Widget,
+        WidgetBuilder,
+    },
+    draw::{
+        DrawingContext,
+        CommandKind,
+        CommandTexture,
+    },
+    formatted_text::{
+        FormattedText,
+        FormattedTextBuilder,
+    },
+    color_ext::palette,
+    event::{
+        UIEvent,
+        UIEventKind,
+    },
+    UINode,
+    HorizontalAlignment,
+    VerticalAlignment,
+    Control,
+    ControlTemplate,
+    UINodeContainer,
+    Builder,
+};
+use std::collections::HashMap;
+
+/// Overlay for ability icons: darkens part of its content with a clock-like sweep which
+/// shrinks as cooldown progresses, and shows remaining time in the middle. Cooldown is
+/// driven either by `start` with duration (overlay counts down by itself) or manually by
+/// `set_progress`.
+///
+/// # Events
+///
+/// [`CooldownFinished`] - sent when cooldown started by `start` has elapsed.
+pub struct CooldownOverlay {
+    widget: Widget,
+    /// Progress of cooldown in [0; 1], 1 means ready.
+    progress: f32,
+    /// Duration of running cooldown, None if it is driven manually or finished.
+    duration: Option<f32>,
+    sweep_color: Color,
+    show_time: bool,
+    time_text: FormattedText,
+}
+
+impl CooldownOverlay {
+    /// Amount of segments of full circle.
+    const SEGMENTS: usize = 48;
+
+    /// Starts cooldown of given duration in seconds.
+    pub fn start(&mut self, duration: f32) {
+        if duration > 0.0 {
+            self.duration = Some(duration);
+            self.progress = 0.0;
+            self.update_time_text();
+        } else {
+            self.finish();
+        }
+    }
+
+    /// Sets progress in [0; 1] directly, stops cooldown started by `start`.
+    pub fn set_progress(&mut self, progress: f32) -> &mut Self {
+        self.duration = None;
+        self.progress = progress.max(0.0).min(1.0);
+        self.update_time_text();
+        self
+    }
+
+    pub fn progress(&self) -> f32 {
+        self.progress
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.progress >= 1.0
+    }
+
+    /// Returns remaining time in seconds, or None if cooldown is driven manually.
+    pub fn remaining_time(&self) -> Option<f32> {
+        self.duration.map(|duration| duration * (1.0 - self.progress))
+    }
+
+    pub fn set_sweep_color(&mut self, color: Color) -> &mut Self {
+        self.sweep_color = color;
+        self
+    }
+
+    pub fn sweep_color(&self) -> Color {
+        self.sweep_color
+    }
+
+    pub fn set_show_time(&mut self, show_time: bool) -> &mut Self {
+        self.show_time = show_time;
+        self
+    }
+
+    pub fn is_show_time(&self) -> bool {
+        self.show_time
+    }
+
+    fn finish(&mut self) {
+        self.duration = None;
+        self.progress = 1.0;
+        self.update_time_text();
+        self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::CooldownFinished));
+    }
+
+    fn update_time_text(&mut self) {
+        let text = match self.remaining_time() {
+            Some(remaining) if remaining >= 1.0 => format!("{}", remaining.ceil()),
+            Some(remaining) if remaining > 0.0 => format!("{:.1}", remaining),
+            _ => String::new(),
+        };
+        // Text changes only few times per second, cached layout is reused in between.
+        self.time_text.set_text(&text);
+        self.time_text.build();
+    }
+}
+
+impl Control for CooldownOverlay {
+    fn widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    fn widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    fn raw_copy(&self) -> Box<dyn Control> {
+        Box::new(Self {
+            widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
+            progress: self.progress,
+            duration: self.duration,
+            sweep_color: self.sweep_color,
+            show_time: self.show_time,
+            time_text: FormattedTextBuilder::new()
+                .with_font(crate::DEFAULT_FONT.clone())
+                .with_color(palette::current().text)
+                .with_horizontal_alignment(HorizontalAlignment::Center)
+                .with_vertical_alignment(VerticalAlignment::Center)
+                .build(),
+        })
+    }
+
+    fn resolve(&mut self, _: &ControlTemplate, _: &HashMap<Handle<UINode>, Handle<UINode>>) {}
+
+    fn post_draw(&self, drawing_context: &mut DrawingContext) {
+        if self.is_ready() {
+            return;
+        }
+        let bounds = self.widget.get_screen_bounds();
+        let center = Vec2::new(bounds.x + bounds.w * 0.5, bounds.y + bounds.h * 0.5);
+        // Sector must cover corners, node is clipped by its bounds anyway.
+        let radius = Vec2::new(bounds.w, bounds.h).len() * 0.5;
+        let full_turn = 2.0 * std::f32::consts::PI;
+        let start_angle = -0.5 * std::f32::consts::PI + full_turn * self.progress;
+        let segments = ((Self::SEGMENTS as f32 * (1.0 - self.progress)).ceil() as usize).max(1);
+        drawing_context.push_sector(center, radius, start_angle, full_turn * (1.0 - self.progress), segments, self.sweep_color);
+        drawing_context.commit(CommandKind::Geometry, CommandTexture::None);
+
+        if self.show_time {
+            drawing_context.draw_text(Vec2::new(bounds.x, bounds.y), &self.time_text);
+        }
+    }
+
+    fn update(&mut self, dt: f32) {
+        if let Some(duration) = self.duration {
+            self.progress += dt / duration;
+            if self.progress >= 1.0 {
+                self.finish();
+            } else {
+                self.update_time_text();
+            }
+        }
+        // Text is centered in actual bounds of overlay.
+        if !self.time_text.get_raw_text().is_empty() {
+            self.time_text.set_size(self.widget.actual_size());
+            self.time_text.build();
+        }
+    }
+
+    fn needs_update(&self) -> bool {
+        true
+    }
+}
+
+pub struct CooldownOverlayBuilder {
+    widget_builder: WidgetBuilder,
+    sweep_color: Option<Color>,
+    show_time: bool,
+}
+
+impl CooldownOverlayBuilder {
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            sweep_color: None,
+            show_time: true,
+        }
+    }
+
+    pub fn with_sweep_color(mut self, color: Color) -> Self {
+        self.sweep_color = Some(color);
+        self
+    }
+
+    pub fn with_show_time(mut self, show_time: bool) -> Self {
+        self.show_time = show_time;
+        self
+    }
+}
+
+impl Builder for CooldownOverlayBuilder {
+    fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
+        let overlay = CooldownOverlay {
+            widget: self.widget_builder.build(),
+            progress: 1.0,
+            duration: None,
+            sweep_color: self.sweep_color.unwrap_or_else(|| Color::from_rgba(0, 0, 0, 160)),
+            show_time: self.show_time,
+            time_text: FormattedTextBuilder::new()
+                .with_font(crate::DEFAULT_FONT.clone())
+                .with_color(palette::current().text)
+                .with_horizontal_alignment(HorizontalAlignment::Center)
+                .with_vertical_alignment(VerticalAlignment::Center)
+                .build(),
+        };
+
+        ui.add_node(Box::new(overlay))
+    }
+}
@@ -0,0 +1,1368 @@
+use crate::{
+    core::{
+        pool::Handle,
+        math::{
+            vec2::Vec2,
+            Rect,
+        },
+    },
+    widget::{
+        Widget,
+        WidgetBuilder,
+    },
+    header_row::{
+        HeaderRow,
+        HeaderRowBuilder,
+        HeaderColumn,
+    },
+    text::{
+        Text,
+        TextBuilder,
+    },
+    text_box::{
+        TextBox,
+        TextBoxBuilder,
+    },
+    numeric_text_box::{
+        NumericTextBox,
+        NumericTextBoxBuilder,
+    },
+    check_box::{
+        CheckBox,
+        CheckBoxBuilder,
+    },
+    canvas::CanvasBuilder,
+    scroll_bar::{
+        ScrollBar,
+        ScrollBarBuilder,
+        Orientation,
+    },
+    event::{
+        UIEvent,
+        UIEventKind,
+        MouseButton,
+        TimerId,
+    },
+    keymap::WidgetAction,
+    draw::{
+        DrawingContext,
+        CommandKind,
+        CommandTexture,
+    },
+    color_ext::{
+        ColorExt,
+        palette,
+    },
+    maxf,
+    UserInterface,
+    UINode,
+    VerticalAlignment,
+    Visibility,
+    Control,
+    ControlTemplate,
+    UINodeContainer,
+    Builder,
+};
+use std::{
+    collections::HashMap,
+    cell::Cell,
+    fmt,
+    ops::Range,
+    rc::Rc,
+};
+
+/// Value of data grid cell. Type of value defines default editor of the cell.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CellValue {
+    Text(String),
+    Number(f32),
+    Bool(bool),
+}
+
+impl fmt::Display for CellValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CellValue::Text(text) => write!(f, "{}", text),
+            CellValue::Number(number) => write!(f, "{}", number),
+            CellValue::Bool(value) => write!(f, "{}", if *value { "Yes" } else { "No" }),
+        }
+    }
+}
+
+/// Creates editor widget for a cell and reads edited value back from it. Editors can be
+/// assigned to columns of data grid, cells of columns without editor use default editor
+/// for type of their value.
+pub trait CellEditor {
+    fn create(&self, ui: &mut dyn UINodeContainer, value: &CellValue) -> Handle<UINode>;
+
+    /// Returns edited value, None means that editor contains invalid input and the cell
+    /// should keep its value.
+    fn value(&self, ui: &dyn UINodeContainer, editor: Handle<UINode>) -> Option<CellValue>;
+}
+
+/// Edits any value as text.
+pub struct TextCellEditor;
+
+impl CellEditor for TextCellEditor {
+    fn create(&self, ui: &mut dyn UINodeContainer, value: &CellValue) -> Handle<UINode> {
+        TextBoxBuilder::new(WidgetBuilder::new()
+            .with_background(palette::current().field))
+            .with_text(value.to_string())
+            .build(ui)
+    }
+
+    fn value(&self, ui: &dyn UINodeContainer, editor: Handle<UINode>) -> Option<CellValue> {
+        ui.node(editor)
+            .downcast_ref::<TextBox>()
+            .map(|text_box| CellValue::Text(text_box.text()))
+    }
+}
+
+/// Edits numbers with numeric text box.
+pub struct NumberCellEditor {
+    pub precision: usize,
+}
+
+impl Default for NumberCellEditor {
+    fn default() -> Self {
+        Self { precision: 3 }
+    }
+}
+
+impl CellEditor for NumberCellEditor {
+    fn create(&self, ui: &mut dyn UINodeContainer, value: &CellValue) -> Handle<UINode> {
+        let number = match value {
+            CellValue::Number(number) => *number,
+            CellValue::Text(text) => text.trim().parse().unwrap_or(0.0),
+            CellValue::Bool(value) => if *value { 1.0 } else { 0.0 },
+        };
+        NumericTextBoxBuilder::new(WidgetBuilder::new()
+            .with_background(palette::current().field))
+            .with_value(number)
+            .with_precision(self.precision)
+            .build(ui)
+    }
+
+    fn value(&self, ui: &dyn UINodeContainer, editor: Handle<UINode>) -> Option<CellValue> {
+        // Text of the field is parsed directly, numeric text box may not have committed
+        // it yet when editing is finished by Enter.
+        let field = ui.node(editor).downcast_ref::<NumericTextBox>()?.field();
+        ui.node(field)
+            .downcast_ref::<TextBox>()?
+            .text()
+            .trim()
+            .parse()
+            .ok()
+            .map(CellValue::Number)
+    }
+}
+
+/// Edits flags with check box.
+pub struct BoolCellEditor;
+
+impl CellEditor for BoolCellEditor {
+    fn create(&self, ui: &mut dyn UINodeContainer, value: &CellValue) -> Handle<UINode> {
+        CheckBoxBuilder::new(WidgetBuilder::new()
+            .with_width(CheckBox::BOX_SIZE)
+            .with_height(CheckBox::BOX_SIZE)
+            .with_vertical_alignment(VerticalAlignment::Center))
+            .checked(Some(*value == CellValue::Bool(true)))
+            .build(ui)
+    }
+
+    fn value(&self, ui: &dyn UINodeContainer, editor: Handle<UINode>) -> Option<CellValue> {
+        ui.node(editor)
+            .downcast_ref::<CheckBox>()?
+            .checked()
+            .map(CellValue::Bool)
+    }
+}
+
+fn default_editor(value: &CellValue) -> Rc<dyn CellEditor> {
+    match value {
+        CellValue::Text(_) => Rc::new(TextCellEditor),
+        CellValue::Number(_) => Rc::new(NumberCellEditor::default()),
+        CellValue::Bool(_) => Rc::new(BoolCellEditor),
+    }
+}
+
+/// Returns spreadsheet name of column: A, B, ..., Z, AA, AB and so on.
+pub fn column_name(column: usize) -> String {
+    let mut name = Vec::new();
+    let mut n = column + 1;
+    while n > 0 {
+        name.push(b'A' + ((n - 1) % 26) as u8);
+        n = (n - 1) / 26;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap()
+}
+
+/// Returns spreadsheet name of cell, for example `B3` for row 2 and column 1.
+pub fn cell_name(row: usize, column: usize) -> String {
+    format!("{}{}", column_name(column), row + 1)
+}
+
+/// Parses spreadsheet name of cell like `B3` (case insensitive), returns row and column.
+pub fn parse_cell_name(name: &str) -> Option<(usize, usize)> {
+    let name = name.trim();
+    let split = name.find(|c: char| !c.is_ascii_alphabetic())?;
+    let (letters, digits) = name.split_at(split);
+    if letters.is_empty() {
+        return None;
+    }
+    let column = letters
+        .bytes()
+        .try_fold(0usize, |column, c| column.checked_mul(26)?.checked_add((c.to_ascii_uppercase() - b'A') as usize + 1))?;
+    let row = digits.parse::<usize>().ok()?;
+    if row == 0 {
+        return None;
+    }
+    Some((row - 1, column - 1))
+}
+
+/// Rectangular range of cells, rows and columns are in display order. Range of one cell
+/// has equal corners. Formats as `B3` or `A1:C4`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CellRange {
+    pub first: (usize, usize),
+    pub last: (usize, usize),
+}
+
+impl CellRange {
+    pub fn single(cell: (usize, usize)) -> Self {
+        Self { first: cell, last: cell }
+    }
+
+    /// Returns range with top left cell first.
+    pub fn normalized(self) -> Self {
+        Self {
+            first: (self.first.0.min(self.last.0), self.first.1.min(self.last.1)),
+            last: (self.first.0.max(self.last.0), self.first.1.max(self.last.1)),
+        }
+    }
+
+    pub fn contains(&self, row: usize, column: usize) -> bool {
+        let range = self.normalized();
+        row >= range.first.0 && row <= range.last.0 && column >= range.first.1 && column <= range.last.1
+    }
+
+    /// Parses `B3` or `A1:C4`.
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut parts = text.splitn(2, ':');
+        let first = parse_cell_name(parts.next()?)?;
+        let last = match parts.next() {
+            Some(last) => parse_cell_name(last)?,
+            None => first,
+        };
+        Some(Self { first, last })
+    }
+}
+
+impl fmt::Display for CellRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let range = self.normalized();
+        if range.first == range.last {
+            write!(f, "{}", cell_name(range.first.0, range.first.1))
+        } else {
+            write!(f, "{}:{}", cell_name(range.first.0, range.first.1), cell_name(range.last.0, range.last.1))
+        }
+    }
+}
+
+struct CellEdit {
+    row: usize,
+    column: usize,
+    node: Handle<UINode>,
+    editor: Rc<dyn CellEditor>,
+}
+
+/// Source of rows for data grid which does not keep all rows in memory, for example table
+/// backed by database or huge log. Data grid asks only for rows which are visible.
+pub trait RowProvider {
+    fn row_count(&self) -> usize;
+
+    /// Returns values of rows in given range, values of each row are in order of columns of
+    /// data. Rows which are not loaded yet can be left out from the end of result, data grid
+    /// will show them empty and ask for them again on next update.
+    fn fetch_rows(&self, range: Range<usize>) -> Vec<Vec<CellValue>>;
+}
+
+/// Row of cells that is currently shown by data grid.
+#[derive(Clone)]
+struct GridRow {
+    index: usize,
+    /// Cells in display order of columns.
+    cells: Vec<Handle<UINode>>,
+    /// Values fetched from row provider, None if row is not loaded yet.
+    loaded: Option<Vec<CellValue>>,
+    /// Whether cells show values of the row.
+    synced: bool,
+}
+
+/// Table of text cells with column headers. Columns are described by header row, so they
+/// can be resized, reordered and sorted by user; data grid moves its cells when columns are
+/// reordered, but sorting of rows is up to application, which should react on
+/// `ColumnSortChanged` events of header row.
+///
+/// Leading columns and rows can be frozen: they stay visible while the rest of the table
+/// is scrolled. Cells are placed in four panes (frozen corner, frozen rows, frozen columns
+/// and body), each pane clips its cells, so scrolled cells never overlap frozen ones.
+///
+/// Rows are virtualized: cells are created only for visible rows and are reused when rows
+/// are scrolled out of view. Rows are either stored in data grid or requested on demand
+/// from [`RowProvider`].
+///
+/// Cells can be edited in place: double click or F2 on current cell replaces its text with
+/// an editor, Enter or loss of keyboard focus commits edited value, Escape discards it.
+///
+/// In reference selection mode (used by formula bar while formula is typed) clicks do not
+/// change current cell, instead user picks a cell or drags a range of cells which is sent
+/// as `CellReferencePicked`.
+///
+/// # Events
+///
+/// [`CellValueChanged`] - sent when user has committed new value of a cell.
+/// [`CurrentCellChanged`] - sent when user has clicked other cell.
+/// [`CellReferencePicked`] - sent when user has picked cell or range in reference selection mode.
+pub struct DataGrid {
+    widget: Widget,
+    header: Handle<UINode>,
+    corner_pane: Handle<UINode>,
+    rows_pane: Handle<UINode>,
+    columns_pane: Handle<UINode>,
+    body_pane: Handle<UINode>,
+    v_scroll_bar: Handle<UINode>,
+    h_scroll_bar: Handle<UINode>,
+    rows: Vec<GridRow>,
+    /// Cells of rows which went out of view, they are collapsed until reused.
+    spare_rows: Vec<Vec<Handle<UINode>>>,
+    /// Rows stored in data grid, values are in order of columns of data.
+    values: Vec<Vec<CellValue>>,
+    provider: Option<Rc<dyn RowProvider>>,
+    /// Index of column of data for each displayed column.
+    column_order: Vec<usize>,
+    /// Editors of columns of data, None means default editor for cell value.
+    editors: Vec<Option<Rc<dyn CellEditor>>>,
+    row_height: f32,
+    frozen_rows: usize,
+    scroll: Vec2,
+    read_only: bool,
+    current_cell: Option<(usize, usize)>,
+    /// Bounds of current cell in local coordinates, clipped by its pane.
+    current_cell_bounds: Cell<Option<Rect<f32>>>,
+    editing: Option<CellEdit>,
+    /// Timer of first click of possible double click, with clicked cell.
+    click_timer: Option<(TimerId, (usize, usize))>,
+    reference_selection: bool,
+    /// Range which is being picked in reference selection mode.
+    picked_range: Option<CellRange>,
+    /// Bounds of picked range in local coordinates, clipped by view.
+    picked_range_bounds: Cell<Option<Rect<f32>>>,
+}
+
+impl DataGrid {
+    pub const SCROLL_BAR_SIZE: f32 = 20.0;
+    const CELL_PADDING: f32 = 4.0;
+    /// Maximum time between two clicks of double click, in seconds.
+    const DOUBLE_CLICK_TIME: f32 = 0.4;
+
+    pub fn header(&self) -> Handle<UINode> {
+        self.header
+    }
+
+    pub fn row_count(&self) -> usize {
+        match self.provider.as_ref() {
+            Some(provider) => provider.row_count(),
+            None => self.values.len(),
+        }
+    }
+
+    pub fn row_height(&self) -> f32 {
+        self.row_height
+    }
+
+    /// Returns cell node at given row and column in display order, or NONE if row is not
+    /// visible.
+    pub fn cell(&self, row: usize, column: usize) -> Handle<UINode> {
+        self.rows
+            .iter()
+            .find(|grid_row| grid_row.index == row)
+            .and_then(|grid_row| grid_row.cells.get(column))
+            .cloned()
+            .unwrap_or(Handle::NONE)
+    }
+
+    pub fn frozen_rows(&self) -> usize {
+        self.frozen_rows
+    }
+
+    /// Returns index of column of data for each column in display order.
+    pub fn column_order(&self) -> &[usize] {
+        &self.column_order
+    }
+
+    /// Returns value of cell at given row and column in display order. Rows of row provider
+    /// have values only while they are visible.
+    pub fn cell_value(&self, row: usize, column: usize) -> Option<&CellValue> {
+        self.row_values(row)?.get(*self.column_order.get(column)?)
+    }
+
+    fn row_values(&self, row: usize) -> Option<&Vec<CellValue>> {
+        match self.provider {
+            Some(_) => self.rows
+                .iter()
+                .find(|grid_row| grid_row.index == row)
+                .and_then(|grid_row| grid_row.loaded.as_ref()),
+            None => self.values.get(row),
+        }
+    }
+
+    fn row_values_mut(&mut self, row: usize) -> Option<&mut Vec<CellValue>> {
+        match self.provider {
+            Some(_) => self.rows
+                .iter_mut()
+                .find(|grid_row| grid_row.index == row)
+                .and_then(|grid_row| grid_row.loaded.as_mut()),
+            None => self.values.get_mut(row),
+        }
+    }
+
+    /// Returns row and column (in display order) of cell which was clicked last.
+    pub fn current_cell(&self) -> Option<(usize, usize)> {
+        self.current_cell
+    }
+
+    pub fn set_current_cell(&mut self, cell: Option<(usize, usize)>) -> &mut Self {
+        self.current_cell = cell;
+        self
+    }
+
+    /// Returns row and column (in display order) of cell which is being edited.
+    pub fn edited_cell(&self) -> Option<(usize, usize)> {
+        self.editing.as_ref().map(|edit| (edit.row, edit.column))
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    pub fn set_read_only(&mut self, read_only: bool) -> &mut Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Turns reference selection mode on or off, range being picked is discarded when mode
+    /// is turned off.
+    pub fn set_reference_selection(&mut self, reference_selection: bool) -> &mut Self {
+        self.reference_selection = reference_selection;
+        if !reference_selection {
+            self.picked_range = None;
+        }
+        self
+    }
+
+    pub fn is_reference_selection(&self) -> bool {
+        self.reference_selection
+    }
+
+    /// Sets editor of column of data, None restores default editor.
+    pub fn set_column_editor(&mut self, column: usize, editor: Option<Rc<dyn CellEditor>>) {
+        if column >= self.editors.len() {
+            self.editors.resize_with(column + 1, || None);
+        }
+        self.editors[column] = editor;
+    }
+
+    pub fn row_provider(&self) -> Option<Rc<dyn RowProvider>> {
+        self.provider.clone()
+    }
+
+    /// Sets source of rows, None makes data grid show rows stored in it. Visible rows are
+    /// requested again.
+    pub fn set_row_provider(ui: &mut UserInterface, data_grid: Handle<UINode>, provider: Option<Rc<dyn RowProvider>>) {
+        if let Some(data_grid) = ui.node_mut(data_grid).downcast_mut::<DataGrid>() {
+            data_grid.provider = provider;
+        }
+        Self::refresh_rows(ui, data_grid);
+    }
+
+    /// Makes visible rows show actual data, rows of row provider are requested again. Should
+    /// be called when data of row provider has changed, changes of row count are picked up
+    /// automatically.
+    pub fn refresh_rows(ui: &mut UserInterface, data_grid: Handle<UINode>) {
+        let editor = match ui.node_mut(data_grid).downcast_mut::<DataGrid>() {
+            Some(data_grid) => {
+                for grid_row in data_grid.rows.iter_mut() {
+                    grid_row.loaded = None;
+                    grid_row.synced = false;
+                }
+                data_grid.editing
+                    .take()
+                    .map(|edit| (data_grid.cell(edit.row, edit.column), edit))
+            }
+            None => return,
+        };
+        if let Some((cell, edit)) = editor {
+            Self::remove_editor(ui, cell, edit);
+        }
+    }
+
+    /// Adds row to the end of table, missing cells are left empty.
+    pub fn add_row(ui: &mut UserInterface, data_grid: Handle<UINode>, texts: &[&str]) {
+        let values = texts
+            .iter()
+            .map(|text| CellValue::Text(text.to_string()))
+            .collect::<Vec<_>>();
+        Self::add_row_values(ui, data_grid, &values);
+    }
+
+    /// Adds row of typed values to the end of table, missing cells are left empty. Values
+    /// are in order of columns of data. Has no effect on rows of row provider.
+    pub fn add_row_values(ui: &mut UserInterface, data_grid: Handle<UINode>, values: &[CellValue]) {
+        if let Some(data_grid) = ui.node_mut(data_grid).downcast_mut::<DataGrid>() {
+            let values = (0..data_grid.column_order.len())
+                .map(|i| values.get(i).cloned().unwrap_or_else(|| CellValue::Text(String::new())))
+                .collect();
+            data_grid.values.push(values);
+        }
+    }
+
+    /// Removes all rows stored in data grid, editing is cancelled.
+    pub fn clear(ui: &mut UserInterface, data_grid: Handle<UINode>) {
+        let (cells, editor) = match ui.node_mut(data_grid).downcast_mut::<DataGrid>() {
+            Some(data_grid) => {
+                data_grid.values.clear();
+                data_grid.current_cell = None;
+                let cells = data_grid.rows
+                    .drain(..)
+                    .map(|grid_row| grid_row.cells)
+                    .chain(data_grid.spare_rows.drain(..))
+                    .collect::<Vec<_>>();
+                (cells, data_grid.editing.take())
+            }
+            None => return,
+        };
+        if let Some(edit) = editor {
+            ui.remove_node(edit.node);
+        }
+        for cell in cells.into_iter().flatten() {
+            ui.remove_node(cell);
+        }
+    }
+
+    pub fn set_cell_text(ui: &mut UserInterface, data_grid: Handle<UINode>, row: usize, column: usize, text: &str) {
+        Self::set_cell_value(ui, data_grid, row, column, CellValue::Text(text.to_owned()));
+    }
+
+    /// Sets value of cell at given row and column in display order. Rows of row provider can
+    /// be changed only while they are visible, data of provider should be changed too.
+    pub fn set_cell_value(ui: &mut UserInterface, data_grid: Handle<UINode>, row: usize, column: usize, value: CellValue) {
+        let cell = match ui.node_mut(data_grid).downcast_mut::<DataGrid>() {
+            Some(data_grid) => {
+                let data_column = match data_grid.column_order.get(column) {
+                    Some(data_column) => *data_column,
+                    None => return,
+                };
+                match data_grid.row_values_mut(row).and_then(|values| values.get_mut(data_column)) {
+                    Some(cell_value) => *cell_value = value.clone(),
+                    None => return,
+                }
+                data_grid.cell(row, column)
+            }
+            None => return,
+        };
+        show_text(ui, cell, &value.to_string());
+    }
+
+    /// Sets amount of leading columns that stay in place on horizontal scroll.
+    pub fn set_frozen_columns(ui: &mut UserInterface, data_grid: Handle<UINode>, count: usize) {
+        let header = match ui.node(data_grid).downcast_ref::<DataGrid>() {
+            Some(data_grid) => data_grid.header,
+            None => return,
+        };
+        if let Some(header) = ui.node_mut(header).downcast_mut::<HeaderRow>() {
+            header.set_frozen_columns(count);
+        }
+        Self::update_panes(ui, data_grid);
+    }
+
+    /// Sets amount of leading rows that stay in place on vertical scroll.
+    pub fn set_frozen_rows(ui: &mut UserInterface, data_grid: Handle<UINode>, count: usize) {
+        if let Some(data_grid) = ui.node_mut(data_grid).downcast_mut::<DataGrid>() {
+            data_grid.frozen_rows = count;
+        }
+        Self::update_panes(ui, data_grid);
+    }
+
+    fn update_panes(ui: &mut UserInterface, data_grid: Handle<UINode>) {
+        if let Some(data_grid) = ui.node(data_grid).downcast_ref::<DataGrid>() {
+            let moves = data_grid.pane_changes(ui);
+            for (cell, pane) in moves {
+                ui.link_nodes(cell, pane);
+            }
+        }
+    }
+
+    /// Returns cells that are not in pane they belong to, with their new panes.
+    fn pane_changes(&self, ui: &dyn UINodeContainer) -> Vec<(Handle<UINode>, Handle<UINode>)> {
+        let frozen_columns = ui.node(self.header)
+            .downcast_ref::<HeaderRow>()
+            .map_or(0, |header| header.frozen_columns());
+        let mut changes = Vec::new();
+        for grid_row in self.rows.iter() {
+            for (column, cell) in grid_row.cells.iter().enumerate() {
+                let pane = match (grid_row.index < self.frozen_rows, column < frozen_columns) {
+                    (true, true) => self.corner_pane,
+                    (true, false) => self.rows_pane,
+                    (false, true) => self.columns_pane,
+                    (false, false) => self.body_pane,
+                };
+                if ui.node(*cell).widget().parent() != pane {
+                    changes.push((*cell, pane));
+                }
+            }
+        }
+        changes
+    }
+
+    /// Creates or reuses cells of rows that became visible, releases cells of rows that went
+    /// out of view and requests missing rows from row provider.
+    fn update_rows(&mut self, ui: &mut UserInterface) {
+        let count = self.row_count();
+        let frozen = self.frozen_rows.min(count);
+        let body_height = ui.node(self.body_pane).widget().actual_size().y;
+        let first = (frozen + (self.scroll.y / self.row_height) as usize).min(count);
+        let last = (frozen + ((self.scroll.y + body_height) / self.row_height).ceil() as usize).min(count);
+        let edited_row = self.editing.as_ref().map(|edit| edit.row);
+        let is_visible = |index: usize| index < frozen || (index >= first && index < last) || Some(index) == edited_row;
+
+        let mut i = 0;
+        while i < self.rows.len() {
+            if is_visible(self.rows[i].index) {
+                i += 1;
+            } else {
+                let grid_row = self.rows.swap_remove(i);
+                for cell in grid_row.cells.iter() {
+                    ui.node_mut(*cell).widget_mut().set_visibility(Visibility::Collapsed);
+                }
+                self.spare_rows.push(grid_row.cells);
+            }
+        }
+
+        for index in (0..frozen).chain(first..last) {
+            if self.rows.iter().any(|grid_row| grid_row.index == index) {
+                continue;
+            }
+            let cells = match self.spare_rows.pop() {
+                Some(cells) => {
+                    for cell in cells.iter() {
+                        ui.node_mut(*cell).widget_mut().set_visibility(Visibility::Visible);
+                    }
+                    cells
+                }
+                None => (0..self.column_order.len()).map(|_| make_cell(ui, "")).collect(),
+            };
+            self.rows.push(GridRow { index, cells, loaded: None, synced: false });
+        }
+
+        if let Some(provider) = self.provider.clone() {
+            let mut missing = self.rows
+                .iter()
+                .filter(|grid_row| grid_row.loaded.is_none())
+                .map(|grid_row| grid_row.index)
+                .collect::<Vec<_>>();
+            missing.sort_unstable();
+            // Missing rows are requested by contiguous ranges.
+            let mut start = 0;
+            while start < missing.len() {
+                let mut end = start + 1;
+                while end < missing.len() && missing[end] == missing[end - 1] + 1 {
+                    end += 1;
+                }
+                let fetched = provider.fetch_rows(missing[start]..missing[end - 1] + 1);
+                for (index, values) in (missing[start]..).zip(fetched.into_iter()) {
+                    if let Some(grid_row) = self.rows.iter_mut().find(|grid_row| grid_row.index == index) {
+                        grid_row.loaded = Some(values);
+                    }
+                }
+                start = end;
+            }
+        }
+
+        let texts = self.rows
+            .iter()
+            .enumerate()
+            .filter(|(_, grid_row)| !grid_row.synced)
+            .map(|(i, grid_row)| {
+                let texts = match self.row_values(grid_row.index) {
+                    Some(values) => self.column_order
+                        .iter()
+                        .map(|column| values.get(*column).map_or(String::new(), |value| value.to_string()))
+                        .collect(),
+                    None => vec![String::new(); self.column_order.len()],
+                };
+                (i, texts)
+            })
+            .collect::<Vec<(usize, Vec<String>)>>();
+        for (i, texts) in texts {
+            let grid_row = &mut self.rows[i];
+            // Rows of provider which are not loaded yet stay empty until they are fetched.
+            grid_row.synced = self.provider.is_none() || grid_row.loaded.is_some();
+            for (cell, text) in grid_row.cells.iter().zip(texts.iter()) {
+                show_text(ui, *cell, text);
+            }
+        }
+
+        for (cell, pane) in self.pane_changes(ui) {
+            ui.link_nodes(cell, pane);
+        }
+    }
+
+    fn on_columns_reordered(&mut self, self_handle: Handle<UINode>, ui: &mut UserInterface, from: usize, to: usize) {
+        self.finish_edit(self_handle, ui, false);
+        for grid_row in self.rows.iter_mut() {
+            move_item(&mut grid_row.cells, from, to);
+        }
+        for cells in self.spare_rows.iter_mut() {
+            move_item(cells, from, to);
+        }
+        move_item(&mut self.column_order, from, to);
+        if let Some((_, column)) = self.current_cell.as_mut() {
+            if *column == from {
+                *column = to;
+            } else if from < *column && *column <= to {
+                *column -= 1;
+            } else if to <= *column && *column < from {
+                *column += 1;
+            }
+        }
+        for (cell, pane) in self.pane_changes(ui) {
+            ui.link_nodes(cell, pane);
+        }
+    }
+
+    /// Returns row and column of cell under given screen position.
+    fn cell_at(&self, ui: &UserInterface, pos: Vec2) -> Option<(usize, usize)> {
+        let header = ui.node(self.header).downcast_ref::<HeaderRow>()?;
+        let bounds = self.widget.get_screen_bounds();
+        let view_width = ui.node(self.h_scroll_bar).widget().actual_size().x;
+        let view_height = ui.node(self.v_scroll_bar).widget().actual_size().y;
+        let x = pos.x - bounds.x;
+        let y = pos.y - bounds.y - HeaderRow::HEIGHT;
+        if x >= view_width || y < 0.0 || y >= view_height {
+            return None;
+        }
+        let row_count = self.row_count();
+        let frozen_height = self.frozen_rows.min(row_count) as f32 * self.row_height;
+        let row = if y < frozen_height {
+            (y / self.row_height) as usize
+        } else {
+            ((y + self.scroll.y) / self.row_height) as usize
+        };
+        let column = header.column_at(x)?;
+        if row < row_count {
+            Some((row, column))
+        } else {
+            None
+        }
+    }
+
+    fn on_cell_clicked(&mut self, self_handle: Handle<UINode>, ui: &mut UserInterface, cell: (usize, usize)) {
+        match self.click_timer.take() {
+            Some((timer, clicked)) if clicked == cell => {
+                ui.kill_timer(timer);
+                self.begin_edit(ui, cell.0, cell.1);
+            }
+            previous => {
+                if let Some((timer, _)) = previous {
+                    ui.kill_timer(timer);
+                }
+                let timer = ui.set_timer(self_handle, Self::DOUBLE_CLICK_TIME, false);
+                self.click_timer = Some((timer, cell));
+            }
+        }
+        if self.current_cell != Some(cell) {
+            self.current_cell = Some(cell);
+            self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::CurrentCellChanged(Some(cell))));
+        }
+    }
+
+    /// Returns bounds of visible part of cell in local coordinates of data grid.
+    fn visible_cell_bounds(&self, header: &HeaderRow, row: usize, column: usize, view_size: Vec2) -> Option<Rect<f32>> {
+        let header_height = HeaderRow::HEIGHT;
+        let frozen_height = self.frozen_rows.min(self.row_count()) as f32 * self.row_height;
+        let width = header.columns().get(column)?.width;
+        let x = header.display_offset(column);
+        let (y, top) = if row < self.frozen_rows {
+            (header_height + row as f32 * self.row_height, header_height)
+        } else {
+            (header_height + row as f32 * self.row_height - self.scroll.y, header_height + frozen_height)
+        };
+        let left = if column < header.frozen_columns() { 0.0 } else { header.frozen_width() };
+        let x0 = maxf(x, left);
+        let y0 = maxf(y, top);
+        let x1 = (x + width).min(view_size.x);
+        let y1 = (y + self.row_height).min(header_height + view_size.y);
+        if x1 > x0 && y1 > y0 {
+            Some(Rect::new(x0, y0, x1 - x0, y1 - y0))
+        } else {
+            None
+        }
+    }
+
+    fn begin_edit(&mut self, ui: &mut UserInterface, row: usize, column: usize) {
+        if self.read_only || self.editing.is_some() {
+            return;
+        }
+        let cell = self.cell(row, column);
+        let value = match self.cell_value(row, column) {
+            Some(value) if cell.is_some() => value.clone(),
+            _ => return,
+        };
+        let editor = self.editors
+            .get(self.column_order[column])
+            .cloned()
+            .flatten()
+            .unwrap_or_else(|| default_editor(&value));
+        let node = editor.create(ui, &value);
+        let pane = ui.node(cell).widget().parent();
+        ui.link_nodes(node, pane);
+        ui.node_mut(cell).widget_mut().set_visibility(Visibility::Hidden);
+
+        // Composite editors like numeric text box are focused through their field.
+        let focus = ui.find_by_criteria_down(node, &|node| node.widget().is_focusable());
+        ui.set_keyboard_focus(if focus.is_some() { focus } else { node });
+
+        self.current_cell = Some((row, column));
+        self.editing = Some(CellEdit { row, column, node, editor });
+    }
+
+    /// Removes editor and restores presenter of the cell, edited value is stored if `commit`
+    /// is set and differs from current value of the cell.
+    fn finish_edit(&mut self, self_handle: Handle<UINode>, ui: &mut UserInterface, commit: bool) {
+        let edit = match self.editing.take() {
+            Some(edit) => edit,
+            None => return,
+        };
+        let focus = ui.keyboard_focus_node();
+        if focus == edit.node || (focus.is_some() && ui.is_node_child_of(focus, edit.node)) {
+            ui.set_keyboard_focus(self_handle);
+        }
+        let cell = self.cell(edit.row, edit.column);
+        let data_column = self.column_order[edit.column];
+        if commit {
+            let new_value = edit.editor.value(ui, edit.node);
+            let changed = match (new_value, self.row_values_mut(edit.row).and_then(|values| values.get_mut(data_column))) {
+                (Some(new_value), Some(value)) if *value != new_value => {
+                    *value = new_value.clone();
+                    Some(new_value)
+                }
+                _ => None,
+            };
+            if let Some(value) = changed {
+                show_text(ui, cell, &value.to_string());
+                self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::CellValueChanged {
+                    row: edit.row,
+                    column: data_column,
+                    value,
+                }));
+            }
+        }
+        Self::remove_editor(ui, cell, edit);
+    }
+
+    fn remove_editor(ui: &mut UserInterface, cell: Handle<UINode>, edit: CellEdit) {
+        ui.remove_node(edit.node);
+        if cell.is_some() {
+            ui.node_mut(cell).widget_mut().set_visibility(Visibility::Visible);
+        }
+    }
+
+    fn set_scroll_range(ui: &mut UserInterface, scroll_bar: Handle<UINode>, max: f32, viewport_size: f32) {
+        if let Some(scroll_bar) = ui.node_mut(scroll_bar).downcast_mut::<ScrollBar>() {
+            scroll_bar.set_max_value(max);
+            scroll_bar.set_viewport_size(viewport_size);
+        }
+    }
+}
+
+fn move_item<T>(items: &mut Vec<T>, from: usize, to: usize) {
+    if from < items.len() && to < items.len() {
+        let item = items.remove(from);
+        items.insert(to, item);
+    }
+}
+
+fn make_cell(ui: &mut dyn UINodeContainer, text: &str) -> Handle<UINode> {
+    TextBuilder::new(WidgetBuilder::new())
+        .with_text(text)
+        .with_vertical_text_alignment(VerticalAlignment::Center)
+        .build(ui)
+}
+
+fn show_text(ui: &mut UserInterface, cell: Handle<UINode>, text: &str) {
+    if cell.is_some() {
+        if let Some(cell) = ui.node_mut(cell).downcast_mut::<Text>() {
+            if cell.text() != text {
+                cell.set_text(text);
+            }
+        }
+    }
+}
+
+impl Control for DataGrid {
+    fn widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    fn widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    fn raw_copy(&self) -> Box<dyn Control> {
+        Box::new(Self {
+            widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
+            header: self.header,
+            corner_pane: self.corner_pane,
+            rows_pane: self.rows_pane,
+            columns_pane: self.columns_pane,
+            body_pane: self.body_pane,
+            v_scroll_bar: self.v_scroll_bar,
+            h_scroll_bar: self.h_scroll_bar,
+            rows: self.rows.clone(),
+            spare_rows: self.spare_rows.clone(),
+            values: self.values.clone(),
+            provider: self.provider.clone(),
+            column_order: self.column_order.clone(),
+            editors: self.editors.clone(),
+            row_height: self.row_height,
+            frozen_rows: self.frozen_rows,
+            scroll: self.scroll,
+            read_only: self.read_only,
+            current_cell: self.current_cell,
+            current_cell_bounds: Cell::new(None),
+            editing: None,
+            click_timer: None,
+            reference_selection: self.reference_selection,
+            picked_range: None,
+            picked_range_bounds: Cell::new(None),
+        })
+    }
+
+    fn resolve(&mut self, _: &ControlTemplate, node_map: &HashMap<Handle<UINode>, Handle<UINode>>) {
+        self.header = *node_map.get(&self.header).unwrap();
+        self.corner_pane = *node_map.get(&self.corner_pane).unwrap();
+        self.rows_pane = *node_map.get(&self.rows_pane).unwrap();
+        self.columns_pane = *node_map.get(&self.columns_pane).unwrap();
+        self.body_pane = *node_map.get(&self.body_pane).unwrap();
+        self.v_scroll_bar = *node_map.get(&self.v_scroll_bar).unwrap();
+        self.h_scroll_bar = *node_map.get(&self.h_scroll_bar).unwrap();
+        let cells = self.rows
+            .iter_mut()
+            .flat_map(|grid_row| grid_row.cells.iter_mut())
+            .chain(self.spare_rows.iter_mut().flatten());
+        for cell in cells {
+            *cell = *node_map.get(cell).unwrap();
+        }
+    }
+
+    fn measure_override(&self, ui: &UserInterface, available_size: Vec2) -> Vec2 {
+        let header = match ui.node(self.header).downcast_ref::<HeaderRow>() {
+            Some(header) => header,
+            None => return Vec2::ZERO,
+        };
+
+        // Cells are positioned in local coordinates of their panes.
+        let frozen_width = header.frozen_width();
+        let row_count = self.row_count();
+        let frozen_height = self.frozen_rows.min(row_count) as f32 * self.row_height;
+        for grid_row in self.rows.iter() {
+            let row = grid_row.index;
+            let y = if row < self.frozen_rows {
+                row as f32 * self.row_height
+            } else {
+                row as f32 * self.row_height - frozen_height - self.scroll.y
+            };
+            for (column, (cell, header_column)) in grid_row.cells.iter().zip(header.columns().iter()).enumerate() {
+                let x = if column < header.frozen_columns() {
+                    header.column_offset(column)
+                } else {
+                    header.column_offset(column) - frozen_width - self.scroll.x
+                };
+                let widget = ui.node(*cell).widget();
+                widget.desired_local_position.set(Vec2::new(x + Self::CELL_PADDING, y));
+                widget.width.set(maxf(0.0, header_column.width - 2.0 * Self::CELL_PADDING));
+                widget.height.set(self.row_height);
+            }
+        }
+
+        if let Some(edit) = self.editing.as_ref().filter(|edit| self.cell(edit.row, edit.column).is_some()) {
+            let cell = ui.node(self.cell(edit.row, edit.column)).widget();
+            let editor = ui.node(edit.node).widget();
+            editor.desired_local_position.set(cell.desired_local_position.get());
+            editor.width.set(cell.width.get());
+            editor.height.set(cell.height.get());
+        }
+
+        for child_handle in self.widget.children.iter() {
+            ui.node(*child_handle).measure(ui, available_size);
+        }
+
+        Vec2::new(
+            header.total_width() + Self::SCROLL_BAR_SIZE,
+            HeaderRow::HEIGHT + row_count as f32 * self.row_height + Self::SCROLL_BAR_SIZE,
+        )
+    }
+
+    fn arrange_override(&self, ui: &UserInterface, final_size: Vec2) -> Vec2 {
+        let (frozen_width, total_width) = ui.node(self.header)
+            .downcast_ref::<HeaderRow>()
+            .map_or((0.0, 0.0), |header| (header.frozen_width(), header.total_width()));
+        let row_count = self.row_count();
+        let frozen_height = self.frozen_rows.min(row_count) as f32 * self.row_height;
+        let header_height = HeaderRow::HEIGHT;
+        let view_width = maxf(0.0, final_size.x - Self::SCROLL_BAR_SIZE);
+        let view_height = maxf(0.0, final_size.y - header_height - Self::SCROLL_BAR_SIZE);
+        let body_width = maxf(0.0, view_width - frozen_width);
+        let body_height = maxf(0.0, view_height - frozen_height);
+
+        let rects = [
+            (self.header, Rect::new(0.0, 0.0, view_width, header_height)),
+            (self.corner_pane, Rect::new(0.0, header_height, frozen_width, frozen_height)),
+            (self.rows_pane, Rect::new(frozen_width, header_height, body_width, frozen_height)),
+            (self.columns_pane, Rect::new(0.0, header_height + frozen_height, frozen_width, body_height)),
+            (self.body_pane, Rect::new(frozen_width, header_height + frozen_height, body_width, body_height)),
+            (self.v_scroll_bar, Rect::new(view_width, header_height, Self::SCROLL_BAR_SIZE, view_height)),
+            (self.h_scroll_bar, Rect::new(0.0, header_height + view_height, view_width, Self::SCROLL_BAR_SIZE)),
+        ];
+        for (node, rect) in rects.iter() {
+            ui.node(*node).arrange(ui, rect);
+        }
+
+        let view_size = Vec2::new(view_width, view_height);
+        let header = ui.node(self.header).downcast_ref::<HeaderRow>();
+        let current_cell_bounds = self.current_cell.and_then(|(row, column)| {
+            self.visible_cell_bounds(header?, row, column, view_size)
+        });
+        self.current_cell_bounds.set(current_cell_bounds);
+
+        // Range is outlined by union of its visible corner cells.
+        let picked_range_bounds = self.picked_range.and_then(|range| {
+            let range = range.normalized();
+            let first = self.visible_cell_bounds(header?, range.first.0, range.first.1, view_size);
+            let last = self.visible_cell_bounds(header?, range.last.0, range.last.1, view_size);
+            match (first, last) {
+                (Some(a), Some(b)) => {
+                    let x0 = a.x.min(b.x);
+                    let y0 = a.y.min(b.y);
+                    Some(Rect::new(x0, y0, maxf(a.x + a.w, b.x + b.w) - x0, maxf(a.y + a.h, b.y + b.h) - y0))
+                }
+                (a, b) => a.or(b),
+            }
+        });
+        self.picked_range_bounds.set(picked_range_bounds);
+
+        let x_max = maxf(0.0, total_width - view_width);
+        self.widget.events.borrow_mut()
+            .push_back(UIEvent::targeted(self.h_scroll_bar, UIEventKind::MaxValueChanged(x_max)));
+        let y_max = maxf(0.0, row_count as f32 * self.row_height - view_height);
+        self.widget.events.borrow_mut()
+            .push_back(UIEvent::targeted(self.v_scroll_bar, UIEventKind::MaxValueChanged(y_max)));
+
+        final_size
+    }
+
+    fn draw(&self, drawing_context: &mut DrawingContext) {
+        let bounds = self.widget.get_screen_bounds();
+        drawing_context.push_rect_filled(&bounds, None, self.widget.background());
+        drawing_context.commit(CommandKind::Geometry, CommandTexture::None);
+    }
+
+    fn post_draw(&self, drawing_context: &mut DrawingContext) {
+        let bounds = self.widget.get_screen_bounds();
+        if let Some(cell_bounds) = self.current_cell_bounds.get() {
+            let rect = Rect::new(bounds.x + cell_bounds.x, bounds.y + cell_bounds.y, cell_bounds.w, cell_bounds.h);
+            drawing_context.push_rect(&rect, 1.0, palette::current().accent);
+        }
+        if let Some(range_bounds) = self.picked_range_bounds.get() {
+            let rect = Rect::new(bounds.x + range_bounds.x, bounds.y + range_bounds.y, range_bounds.w, range_bounds.h);
+            drawing_context.push_rect_filled(&rect, None, palette::current().accent.with_alpha(40));
+            drawing_context.push_rect(&rect, 2.0, palette::current().accent);
+        }
+        drawing_context.commit(CommandKind::Geometry, CommandTexture::None);
+    }
+
+    fn handle_event(&mut self, self_handle: Handle<UINode>, ui: &mut UserInterface, evt: &mut UIEvent) {
+        if let UIEventKind::MaxValueChanged(max) = evt.kind {
+            if evt.target == self.h_scroll_bar {
+                let viewport = ui.node(self.body_pane).widget().actual_size().x;
+                Self::set_scroll_range(ui, self.h_scroll_bar, max, viewport);
+            } else if evt.target == self.v_scroll_bar {
+                let viewport = ui.node(self.body_pane).widget().actual_size().y;
+                Self::set_scroll_range(ui, self.v_scroll_bar, max, viewport);
+                // Layout is done, so visible range of rows is known.
+                self.update_rows(ui);
+            }
+        }
+
+        match evt.kind {
+            UIEventKind::NumericValueChanged { new_value, .. } => {
+                if evt.source == self.h_scroll_bar {
+                    self.scroll.x = new_value;
+                    if let Some(header) = ui.node_mut(self.header).downcast_mut::<HeaderRow>() {
+                        header.set_scroll(new_value);
+                    }
+                } else if evt.source == self.v_scroll_bar {
+                    self.scroll.y = new_value;
+                    self.update_rows(ui);
+                }
+            }
+            UIEventKind::ColumnReordered { from, to } if evt.source == self.header => {
+                self.on_columns_reordered(self_handle, ui, from, to);
+            }
+            UIEventKind::MouseDown { pos, button: MouseButton::Left } => {
+                let in_editor = self.editing.as_ref().map_or(false, |edit| {
+                    evt.source == edit.node || ui.is_node_child_of(evt.source, edit.node)
+                });
+                if !evt.handled && !in_editor && (evt.source == self_handle || self.widget.has_descendant(evt.source, ui)) {
+                    if let Some(cell) = self.cell_at(ui, pos) {
+                        if self.reference_selection {
+                            if ui.capture_mouse(self_handle) {
+                                self.picked_range = Some(CellRange::single(cell));
+                                evt.handled = true;
+                            }
+                        } else {
+                            self.on_cell_clicked(self_handle, ui, cell);
+                        }
+                    }
+                }
+            }
+            UIEventKind::MouseMove { pos } => {
+                if evt.source == self_handle && self.picked_range.is_some() {
+                    if let Some(cell) = self.cell_at(ui, pos) {
+                        if let Some(range) = self.picked_range.as_mut() {
+                            range.last = cell;
+                        }
+                    }
+                }
+            }
+            UIEventKind::MouseUp { button: MouseButton::Left, .. } => {
+                if evt.source == self_handle {
+                    if let Some(range) = self.picked_range.take() {
+                        ui.release_mouse_capture();
+                        self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::CellReferencePicked(range.normalized())));
+                    }
+                }
+            }
+            UIEventKind::CaptureLost => {
+                if evt.source == self_handle {
+                    self.picked_range = None;
+                }
+            }
+            UIEventKind::Timer(id) if evt.source == self_handle => {
+                if self.click_timer.map_or(false, |(timer, _)| timer == id) {
+                    self.click_timer = None;
+                }
+            }
+            UIEventKind::KeyDown { code } => {
+                if evt.handled || !(evt.source == self_handle || self.widget.has_descendant(evt.source, ui)) {
+                    return;
+                }
+                match (ui.key_action(code), self.editing.is_some()) {
+                    (Some(WidgetAction::Commit), true) => {
+                        self.finish_edit(self_handle, ui, true);
+                        evt.handled = true;
+                    }
+                    (Some(WidgetAction::Cancel), true) => {
+                        self.finish_edit(self_handle, ui, false);
+                        evt.handled = true;
+                    }
+                    (Some(WidgetAction::BeginEdit), false) => {
+                        if let Some((row, column)) = self.current_cell {
+                            self.begin_edit(ui, row, column);
+                            evt.handled = true;
+                        }
+                    }
+                    _ => ()
+                }
+            }
+            UIEventKind::LostFocus => {
+                // Focus may move between parts of the editor, only leaving it commits value.
+                let left_editor = self.editing.as_ref().map_or(false, |edit| {
+                    let focus = ui.keyboard_focus_node();
+                    (evt.source == edit.node || ui.is_node_child_of(evt.source, edit.node)) &&
+                        focus != edit.node && !(focus.is_some() && ui.is_node_child_of(focus, edit.node))
+                });
+                if left_editor {
+                    self.finish_edit(self_handle, ui, true);
+                }
+            }
+            UIEventKind::MouseWheel { amount, .. } => {
+                if !evt.handled && (evt.source == self_handle || self.widget.has_descendant(evt.source, ui)) {
+                    if let Some(v_scroll_bar) = ui.node_mut(self.v_scroll_bar).downcast_mut::<ScrollBar>() {
+                        v_scroll_bar.scroll(-amount * 10.0);
+                        evt.handled = true;
+                    }
+                }
+            }
+            _ => ()
+        }
+    }
+}
+
+pub struct DataGridBuilder {
+    widget_builder: WidgetBuilder,
+    columns: Vec<HeaderColumn>,
+    rows: Vec<Vec<CellValue>>,
+    provider: Option<Rc<dyn RowProvider>>,
+    editors: Vec<Option<Rc<dyn CellEditor>>>,
+    row_height: f32,
+    frozen_columns: usize,
+    frozen_rows: usize,
+    read_only: bool,
+}
+
+impl DataGridBuilder {
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            columns: Vec::new(),
+            rows: Vec::new(),
+            provider: None,
+            editors: Vec::new(),
+            row_height: 22.0,
+            frozen_columns: 0,
+            frozen_rows: 0,
+            read_only: false,
+        }
+    }
+
+    pub fn with_columns(mut self, columns: Vec<HeaderColumn>) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    pub fn with_rows(mut self, rows: Vec<Vec<String>>) -> Self {
+        self.rows = rows
+            .into_iter()
+            .map(|row| row.into_iter().map(CellValue::Text).collect())
+            .collect();
+        self
+    }
+
+    pub fn with_row_values(mut self, rows: Vec<Vec<CellValue>>) -> Self {
+        self.rows = rows;
+        self
+    }
+
+    /// Sets source of rows, rows given by `with_rows` are not shown while data grid has
+    /// row provider.
+    pub fn with_row_provider(mut self, provider: Rc<dyn RowProvider>) -> Self {
+        self.provider = Some(provider);
+        self
+    }
+
+    /// Sets editor of column of data, columns without editor use default editor for value
+    /// of cell.
+    pub fn with_column_editor(mut self, column: usize, editor: Rc<dyn CellEditor>) -> Self {
+        if column >= self.editors.len() {
+            self.editors.resize_with(column + 1, || None);
+        }
+        self.editors[column] = Some(editor);
+        self
+    }
+
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    pub fn with_row_height(mut self, row_height: f32) -> Self {
+        self.row_height = row_height;
+        self
+    }
+
+    pub fn with_frozen_columns(mut self, count: usize) -> Self {
+        self.frozen_columns = count;
+        self
+    }
+
+    pub fn with_frozen_rows(mut self, count: usize) -> Self {
+        self.frozen_rows = count;
+        self
+    }
+}
+
+impl Builder for DataGridBuilder {
+    fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
+        let column_count = self.columns.len();
+
+        let header = HeaderRowBuilder::new(WidgetBuilder::new()
+            .with_background(palette::current().control))
+            .with_columns(self.columns)
+            .build(ui);
+        if let Some(header) = ui.node_mut(header).downcast_mut::<HeaderRow>() {
+            header.set_frozen_columns(self.frozen_columns);
+        }
+
+        let corner_pane = CanvasBuilder::new(WidgetBuilder::new()).build(ui);
+        let rows_pane = CanvasBuilder::new(WidgetBuilder::new()).build(ui);
+        let columns_pane = CanvasBuilder::new(WidgetBuilder::new()).build(ui);
+        let body_pane = CanvasBuilder::new(WidgetBuilder::new()).build(ui);
+
+        let v_scroll_bar = ScrollBarBuilder::new(WidgetBuilder::new())
+            .with_orientation(Orientation::Vertical)
+            .build(ui);
+        let h_scroll_bar = ScrollBarBuilder::new(WidgetBuilder::new())
+            .with_orientation(Orientation::Horizontal)
+            .build(ui);
+
+        let values = self.rows
+            .into_iter()
+            .map(|mut row| {
+                row.resize(column_count, CellValue::Text(String::new()));
+                row
+            })
+            .collect::<Vec<Vec<CellValue>>>();
+
+        let data_grid = DataGrid {
+            widget: self.widget_builder
+                .with_focusable(true)
+                .with_child(body_pane)
+                .with_child(columns_pane)
+                .with_child(rows_pane)
+                .with_child(corner_pane)
+                .with_child(header)
+                .with_child(v_scroll_bar)
+                .with_child(h_scroll_bar)
+                .build(),
+            header,
+            corner_pane,
+            rows_pane,
+            columns_pane,
+            body_pane,
+            v_scroll_bar,
+            h_scroll_bar,
+            rows: Vec::new(),
+            spare_rows: Vec::new(),
+            values,
+            provider: self.provider,
+            column_order: (0..column_count).collect(),
+            editors: self.editors,
+            row_height: self.row_height,
+            frozen_rows: self.frozen_rows,
+            scroll: Vec2::ZERO,
+            read_only: self.read_only,
+            current_cell: None,
+            current_cell_bounds: Cell::new(None),
+            editing: None,
+            click_timer: None,
+            reference_selection: false,
+            picked_range: None,
+            picked_range_bounds: Cell::new(None),
+        };
+
+        ui.add_node(Box::new(data_grid))
+    }
+}
@@ -0,0 +1,260 @@
+use crate::{
+    core::{
+        color::Color,
+        pool::Handle,
+        math::vec2::Vec2,
+    },
+    UINode,
+    draw::DrawingContext,
+    color_ext::{
+        ColorExt,
+        palette,
+    },
+    Thickness,
+    UserInterface,
+    widget::{
+        Widget,
+        WidgetBuilder,
+    },
+    border::Border,
+    event::{
+        UIEvent,
+        UIEventKind,
+    },
+    Control,
+    ControlTemplate,
+    UINodeContainer,
+    Builder,
+};
+use std::{
+    any::Any,
+    collections::HashMap,
+};
+
+/// Border which changes its background depending on mouse interaction with it or with
+/// any of its descendants. It is used by content controls (such as buttons) to give
+/// visual feedback regardless of what content they have.
+pub struct Decorator {
+    border: Border,
+    normal_brush: Color,
+    hover_brush: Color,
+    pressed_brush: Color,
+    selected_brush: Color,
+    is_selected: bool,
+    is_pressed: bool,
+}
+
+impl Decorator {
+    pub fn new(border: Border) -> Self {
+        Self {
+            border,
+            normal_brush: palette::current().control,
+            hover_brush: palette::current().control_hover,
+            pressed_brush: palette::current().frame,
+            selected_brush: palette::current().frame,
+            is_selected: false,
+            is_pressed: false,
+        }
+    }
+
+    /// Selected decorator uses selected brush while it is not hovered or pressed, this is
+    /// used by toggled buttons for example.
+    pub fn set_selected(&mut self, selected: bool) -> &mut Self {
+        self.is_selected = selected;
+        let brush = self.idle_brush();
+        self.border.widget_mut().set_background(brush);
+        self
+    }
+
+    pub fn is_selected(&self) -> bool {
+        self.is_selected
+    }
+
+    pub fn set_normal_brush(&mut self, brush: Color) -> &mut Self {
+        self.normal_brush = brush;
+        self
+    }
+
+    pub fn set_hover_brush(&mut self, brush: Color) -> &mut Self {
+        self.hover_brush = brush;
+        self
+    }
+
+    pub fn set_pressed_brush(&mut self, brush: Color) -> &mut Self {
+        self.pressed_brush = brush;
+        self
+    }
+
+    pub fn set_selected_brush(&mut self, brush: Color) -> &mut Self {
+        self.selected_brush = brush;
+        self
+    }
+
+    fn idle_brush(&self) -> Color {
+        if self.is_selected {
+            self.selected_brush
+        } else {
+            self.normal_brush
+        }
+    }
+}
+
+impl Control for Decorator {
+    fn widget(&self) -> &Widget {
+        self.border.widget()
+    }
+
+    fn widget_mut(&mut self) -> &mut Widget {
+        self.border.widget_mut()
+    }
+
+    fn raw_copy(&self) -> Box<dyn Control> {
+        Box::new(Self {
+            border: *self.border.raw_copy().downcast::<Border>().unwrap_or_else(|_| panic!()),
+            normal_brush: self.normal_brush,
+            hover_brush: self.hover_brush,
+            pressed_brush: self.pressed_brush,
+            selected_brush: self.selected_brush,
+            is_selected: self.is_selected,
+            is_pressed: false,
+        })
+    }
+
+    fn resolve(&mut self, template: &ControlTemplate, node_map: &HashMap<Handle<UINode>, Handle<UINode>>) {
+        self.border.resolve(template, node_map)
+    }
+
+    fn measure_override(&self, ui: &UserInterface, available_size: Vec2) -> Vec2 {
+        self.border.measure_override(ui, available_size)
+    }
+
+    fn arrange_override(&self, ui: &UserInterface, final_size: Vec2) -> Vec2 {
+        self.border.arrange_override(ui, final_size)
+    }
+
+    fn draw(&self, drawing_context: &mut DrawingContext) {
+        self.border.draw(drawing_context)
+    }
+
+    fn set_property(&mut self, name: &str, value: &dyn Any) {
+        self.border.set_property(name, value)
+    }
+
+    fn get_property(&self, name: &str) -> Option<&dyn Any> {
+        self.border.get_property(name)
+    }
+
+    fn handle_event(&mut self, self_handle: Handle<UINode>, ui: &mut UserInterface, evt: &mut UIEvent) {
+        if evt.source == self_handle || self.widget().has_descendant(evt.source, ui) {
+            let brush = match evt.kind {
+                UIEventKind::MouseDown { .. } => {
+                    self.is_pressed = true;
+                    Some(self.pressed_brush)
+                }
+                UIEventKind::MouseUp { .. } => {
+                    self.is_pressed = false;
+                    if self.widget().is_mouse_over {
+                        Some(self.hover_brush)
+                    } else {
+                        Some(self.idle_brush())
+                    }
+                }
+                UIEventKind::MouseEnter => {
+                    if self.is_pressed {
+                        Some(self.pressed_brush)
+                    } else {
+                        Some(self.hover_brush)
+                    }
+                }
+                UIEventKind::MouseLeave => Some(self.idle_brush()),
+                _ => None
+            };
+
+            if let Some(brush) = brush {
+                self.widget_mut().set_background(brush);
+            }
+        }
+    }
+}
+
+pub struct DecoratorBuilder {
+    widget_builder: WidgetBuilder,
+    stroke_thickness: Option<Thickness>,
+    normal_brush: Option<Color>,
+    hover_brush: Option<Color>,
+    pressed_brush: Option<Color>,
+    selected_brush: Option<Color>,
+    is_selected: bool,
+}
+
+impl DecoratorBuilder {
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            stroke_thickness: None,
+            normal_brush: None,
+            hover_brush: None,
+            pressed_brush: None,
+            selected_brush: None,
+            is_selected: false,
+        }
+    }
+
+    pub fn with_stroke_thickness(mut self, stroke_thickness: Thickness) -> Self {
+        self.stroke_thickness = Some(stroke_thickness);
+        self
+    }
+
+    pub fn with_normal_brush(mut self, brush: Color) -> Self {
+        self.normal_brush = Some(brush);
+        self
+    }
+
+    pub fn with_hover_brush(mut self, brush: Color) -> Self {
+        self.hover_brush = Some(brush);
+        self
+    }
+
+    pub fn with_pressed_brush(mut self, brush: Color) -> Self {
+        self.pressed_brush = Some(brush);
+        self
+    }
+
+    pub fn with_selected_brush(mut self, brush: Color) -> Self {
+        self.selected_brush = Some(brush);
+        self
+    }
+
+    pub fn with_selected(mut self, selected: bool) -> Self {
+        self.is_selected = selected;
+        self
+    }
+}
+
+impl Builder for DecoratorBuilder {
+    fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
+        let mut border = Border::new(self.widget_builder.build());
+        border.set_stroke_thickness(self.stroke_thickness.unwrap_or_else(|| Thickness::uniform(1.0)));
+
+        let mut decorator = Decorator::new(border);
+        if let Some(brush) = self.normal_brush {
+            // Other states are derived from custom normal brush unless they are set too.
+            decorator.normal_brush = brush;
+            decorator.hover_brush = brush.lighten(0.25);
+            decorator.pressed_brush = brush.darken(0.2);
+            decorator.selected_brush = brush.darken(0.2);
+        }
+        if let Some(brush) = self.hover_brush {
+            decorator.hover_brush = brush;
+        }
+        if let Some(brush) = self.pressed_brush {
+            decorator.pressed_brush = brush;
+        }
+        if let Some(brush) = self.selected_brush {
+            decorator.selected_brush = brush;
+        }
+        decorator.set_selected(self.is_selected);
+
+        ui.add_node(Box::new(decorator))
+    }
+}
@@ -0,0 +1,345 @@
+use crate::{
+    core::{
+        pool::Handle,
+        math::{
+            vec2::Vec2,
+            Rect,
+        },
+    },
+    widget::{
+        Widget,
+        WidgetBuilder,
+    },
+    draw::{
+        DrawingContext,
+        CommandKind,
+        CommandTexture,
+    },
+    event::{
+        UIEvent,
+        UIEventKind,
+        MouseButton,
+    },
+    gizmo::{
+        TransformGizmo,
+        TransformGizmoBuilder,
+        GizmoHandleKind,
+    },
+    canvas::Canvas,
+    color_ext::{
+        ColorExt,
+        palette,
+    },
+    UserInterface,
+    UINode,
+    Visibility,
+    Control,
+    ControlTemplate,
+    UINodeContainer,
+    Builder,
+};
+use std::{
+    collections::HashMap,
+    fmt,
+};
+
+/// Snapshot of layout of one node of edited tree. Position is local position of node in
+/// its parent, it is meaningful for children of canvases which are the only containers that
+/// respect position set by user.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LayoutNode {
+    pub name: String,
+    pub position: Vec2,
+    pub size: Vec2,
+    pub children: Vec<LayoutNode>,
+}
+
+impl LayoutNode {
+    fn write(&self, f: &mut fmt::Formatter, depth: usize) -> fmt::Result {
+        writeln!(f, "{:indent$}'{}' position: ({}, {}) size: ({}, {})", "", self.name,
+                 self.position.x, self.position.y, self.size.x, self.size.y, indent = depth * 2)?;
+        for child in self.children.iter() {
+            child.write(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes tree as indented text, one node per line.
+impl fmt::Display for LayoutNode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+/// Returns deepest visible node of subtree under given screen point, `except` and its
+/// subtree are skipped. Children are checked from last to first, so node which is drawn on
+/// top wins.
+fn pick<F>(ui: &UserInterface, node: Handle<UINode>, point: Vec2, except: Handle<UINode>, filter: &F) -> Handle<UINode>
+    where F: Fn(&UINode) -> bool {
+    if node == except {
+        return Handle::NONE;
+    }
+    let widget = ui.node(node).widget();
+    if widget.get_visibility() != Visibility::Visible || !widget.get_screen_bounds().contains(point.x, point.y) {
+        return Handle::NONE;
+    }
+    for child in widget.children().iter().rev() {
+        let picked = pick(ui, *child, point, except, filter);
+        if picked.is_some() {
+            return picked;
+        }
+    }
+    if filter(ui.node(node)) {
+        node
+    } else {
+        Handle::NONE
+    }
+}
+
+/// Design mode overlay which turns part of interface into a minimal layout editor. Surface
+/// should cover edited root (for example be placed on a layer or in the same grid cell after
+/// the root), while surface is visible it intercepts mouse input of edited tree:
+///
+/// - click selects deepest node under cursor, click on empty space clears selection;
+/// - selected node gets transform gizmo, its move handle moves node and resize handles
+///   change its size; canvas snapping is used when parent of node is a canvas with snapping;
+/// - when node is dropped over other canvas of edited tree, it is re-parented to that canvas
+///   keeping its screen position.
+///
+/// Hide surface to leave design mode. Resulting tree can be taken with `export`.
+///
+/// # Events
+///
+/// [`DesignSelectionChanged`] - sent when other node was selected.
+/// [`DesignChanged`] - sent when node was moved, resized or re-parented, once per drag.
+pub struct DesignSurface {
+    widget: Widget,
+    root: Handle<UINode>,
+    selection: Handle<UINode>,
+    gizmo: Handle<UINode>,
+    /// Unsnapped local position of selected node during move, snapping is applied to it on
+    /// every delta so node can leave snapped position.
+    drag_position: Option<Vec2>,
+}
+
+impl DesignSurface {
+    pub fn root(&self) -> Handle<UINode> {
+        self.root
+    }
+
+    pub fn selection(&self) -> Handle<UINode> {
+        self.selection
+    }
+
+    /// Selects given node of edited tree, or clears selection if handle is none.
+    pub fn select(ui: &mut UserInterface, surface: Handle<UINode>, node: Handle<UINode>) {
+        let gizmo = match ui.node_mut(surface).downcast_mut::<DesignSurface>() {
+            Some(surface_ref) => surface_ref.set_selection(node),
+            None => return,
+        };
+        Self::attach_gizmo(ui, gizmo, node);
+    }
+
+    /// Makes tree snapshot of edited root.
+    pub fn export(&self, ui: &UserInterface) -> LayoutNode {
+        Self::snapshot(ui, self.root)
+    }
+
+    fn snapshot(ui: &UserInterface, node: Handle<UINode>) -> LayoutNode {
+        let widget = ui.node(node).widget();
+        LayoutNode {
+            name: widget.name().to_owned(),
+            position: widget.desired_local_position(),
+            size: widget.actual_size(),
+            children: widget.children().iter().map(|child| Self::snapshot(ui, *child)).collect(),
+        }
+    }
+
+    /// Changes selection and returns gizmo which should be attached to new selection.
+    fn set_selection(&mut self, node: Handle<UINode>) -> Handle<UINode> {
+        if self.selection != node {
+            self.selection = node;
+            self.drag_position = None;
+            self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::DesignSelectionChanged(node)));
+        }
+        self.gizmo
+    }
+
+    fn attach_gizmo(ui: &mut UserInterface, gizmo: Handle<UINode>, node: Handle<UINode>) {
+        TransformGizmo::set_target(ui, gizmo, node);
+        ui.node_mut(gizmo).widget_mut().set_visibility(if node.is_some() {
+            Visibility::Visible
+        } else {
+            Visibility::Collapsed
+        });
+    }
+
+    fn apply_delta(&mut self, ui: &mut UserInterface, translation: Vec2, size: Vec2) {
+        let parent = ui.node(self.selection).widget().parent();
+        let position = self.drag_position.unwrap_or_else(|| ui.node(self.selection).widget().desired_local_position()) + translation;
+        self.drag_position = Some(position);
+        let snapped = if size == Vec2::ZERO {
+            match ui.node(parent).downcast_ref::<Canvas>() {
+                Some(canvas) => canvas.snap(ui, self.selection, position),
+                None => position,
+            }
+        } else {
+            position
+        };
+        let widget = ui.node_mut(self.selection).widget_mut();
+        widget.set_desired_local_position(snapped);
+        // Explicit size is used if set, so several deltas between layouts accumulate.
+        let actual_size = widget.actual_size();
+        let current = |explicit: f32, actual: f32| if explicit.is_nan() { actual } else { explicit };
+        if size.x != 0.0 {
+            let width = current(widget.width.get(), actual_size.x) + size.x;
+            widget.set_width(width.max(1.0));
+        }
+        if size.y != 0.0 {
+            let height = current(widget.height.get(), actual_size.y) + size.y;
+            widget.set_height(height.max(1.0));
+        }
+    }
+
+    /// Moves selected node to canvas under its center if it differs from current parent.
+    fn reparent(&self, ui: &mut UserInterface) {
+        let bounds = ui.node(self.selection).widget().get_screen_bounds();
+        let center = Vec2::new(bounds.x + bounds.w * 0.5, bounds.y + bounds.h * 0.5);
+        let target = pick(ui, self.root, center, self.selection, &|node| node.downcast_ref::<Canvas>().is_some());
+        if target.is_none() || target == ui.node(self.selection).widget().parent() {
+            return;
+        }
+        let origin = ui.node(target).widget().screen_position;
+        ui.link_nodes(self.selection, target);
+        ui.node_mut(self.selection)
+            .widget_mut()
+            .set_desired_local_position(Vec2::new(bounds.x, bounds.y) - origin);
+    }
+}
+
+impl Control for DesignSurface {
+    fn widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    fn widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    fn raw_copy(&self) -> Box<dyn Control> {
+        Box::new(Self {
+            widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
+            root: self.root,
+            selection: self.selection,
+            gizmo: self.gizmo,
+            drag_position: None,
+        })
+    }
+
+    fn resolve(&mut self, _: &ControlTemplate, node_map: &HashMap<Handle<UINode>, Handle<UINode>>) {
+        if self.root.is_some() {
+            self.root = *node_map.get(&self.root).unwrap();
+        }
+        if self.selection.is_some() {
+            self.selection = *node_map.get(&self.selection).unwrap();
+        }
+        self.gizmo = *node_map.get(&self.gizmo).unwrap();
+    }
+
+    fn measure_override(&self, ui: &UserInterface, available_size: Vec2) -> Vec2 {
+        for child in self.widget.children.iter() {
+            ui.node(*child).measure(ui, Vec2::new(std::f32::INFINITY, std::f32::INFINITY));
+        }
+        self.widget.measure_override(ui, available_size)
+    }
+
+    fn arrange_override(&self, ui: &UserInterface, final_size: Vec2) -> Vec2 {
+        // Gizmo positions itself like a child of canvas.
+        for child_handle in self.widget.children.iter() {
+            let child = ui.node(*child_handle).widget();
+            let position = child.desired_local_position();
+            let size = child.desired_size.get();
+            ui.node(*child_handle).arrange(ui, &Rect::new(position.x, position.y, size.x, size.y));
+        }
+        final_size
+    }
+
+    fn draw(&self, drawing_context: &mut DrawingContext) {
+        // Transparent fill is needed for hit testing, surface catches clicks of edited tree.
+        let bounds = self.widget.get_screen_bounds();
+        drawing_context.push_rect_filled(&bounds, None, palette::current().accent.with_alpha(0));
+        drawing_context.commit(CommandKind::Geometry, CommandTexture::None);
+    }
+
+    fn handle_event(&mut self, self_handle: Handle<UINode>, ui: &mut UserInterface, evt: &mut UIEvent) {
+        match evt.kind {
+            UIEventKind::MouseDown { pos, button } => {
+                if evt.source == self_handle && button == MouseButton::Left && self.root.is_some() {
+                    let picked = pick(ui, self.root, pos, self_handle, &|_| true);
+                    let node = if picked == self.root { Handle::NONE } else { picked };
+                    let gizmo = self.set_selection(node);
+                    Self::attach_gizmo(ui, gizmo, node);
+                    evt.handled = true;
+                }
+            }
+            UIEventKind::TransformDelta(delta) => {
+                if evt.source == self.gizmo && self.selection.is_some() {
+                    self.apply_delta(ui, delta.translation, delta.size);
+                }
+            }
+            UIEventKind::TransformCompleted(kind) => {
+                if evt.source == self.gizmo && self.selection.is_some() {
+                    self.drag_position = None;
+                    if kind == GizmoHandleKind::Move {
+                        self.reparent(ui);
+                    }
+                    self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::DesignChanged(self.selection)));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+pub struct DesignSurfaceBuilder {
+    widget_builder: WidgetBuilder,
+    root: Handle<UINode>,
+}
+
+impl DesignSurfaceBuilder {
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            root: Handle::NONE,
+        }
+    }
+
+    /// Sets root of edited tree.
+    pub fn with_root(mut self, root: Handle<UINode>) -> Self {
+        self.root = root;
+        self
+    }
+}
+
+impl Builder for DesignSurfaceBuilder {
+    fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
+        // Widgets are not rotated, so rotation handle would do nothing.
+        let gizmo = TransformGizmoBuilder::new(WidgetBuilder::new()
+            .with_visibility(Visibility::Collapsed))
+            .with_rotatable(false)
+            .build(ui);
+
+        let surface = DesignSurface {
+            widget: self.widget_builder
+                .with_child(gizmo)
+                .build(),
+            root: self.root,
+            selection: Handle::NONE,
+            gizmo,
+            drag_position: None,
+        };
+
+        ui.add_node(Box::new(surface))
+    }
+}
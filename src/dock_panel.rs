@@ -0,0 +1,264 @@
+use crate::{
+    core::{
+        pool::Handle,
+        math::{
+            vec2::Vec2,
+            Rect,
+        },
+    },
+    widget::{
+        Widget,
+        WidgetBuilder,
+    },
+    maxf,
+    UserInterface,
+    UINode,
+    Control,
+    ControlTemplate,
+    UINodeContainer,
+    Builder,
+};
+use std::collections::HashMap;
+
+/// Edge of dock panel to which a child is attached.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Dock {
+    Left,
+    Top,
+    Right,
+    Bottom,
+}
+
+impl Default for Dock {
+    fn default() -> Self {
+        Dock::Left
+    }
+}
+
+/// Arranges children one by one against its edges using `DockPanel::DOCK` attached
+/// property (`WidgetBuilder::with_dock` or `set_property`), each child takes a strip of
+/// space that is left after previous children. Last child fills remaining space unless
+/// it is disabled by `DockPanelBuilder::with_last_child_fill`. Children without dock are
+/// docked to the left edge.
+///
+/// Typical layout of an editor window is menu docked to top, status bar docked to bottom,
+/// tool panels docked to sides and viewport as last child.
+pub struct DockPanel {
+    widget: Widget,
+    last_child_fill: bool,
+}
+
+impl DockPanel {
+    /// Name of attached property of type `Dock`.
+    pub const DOCK: &'static str = "Dock";
+
+    pub fn new(widget: Widget) -> Self {
+        Self {
+            widget,
+            last_child_fill: true,
+        }
+    }
+
+    pub fn set_last_child_fill(&mut self, last_child_fill: bool) -> &mut Self {
+        self.last_child_fill = last_child_fill;
+        self
+    }
+
+    pub fn is_last_child_fill(&self) -> bool {
+        self.last_child_fill
+    }
+}
+
+impl WidgetBuilder {
+    /// Sets edge of `DockPanel` to which widget is attached.
+    pub fn with_dock(self, dock: Dock) -> Self {
+        self.with_attached_property(DockPanel::DOCK, dock)
+    }
+}
+
+fn dock_of(widget: &Widget) -> Dock {
+    widget.attached_property::<Dock>(DockPanel::DOCK)
+        .cloned()
+        .unwrap_or_default()
+}
+
+impl Control for DockPanel {
+    fn widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    fn widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    fn raw_copy(&self) -> Box<dyn Control> {
+        Box::new(Self {
+            widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
+            last_child_fill: self.last_child_fill,
+        })
+    }
+
+    fn resolve(&mut self, _: &ControlTemplate, _: &HashMap<Handle<UINode>, Handle<UINode>>) {}
+
+    fn measure_override(&self, ui: &UserInterface, available_size: Vec2) -> Vec2 {
+        let mut desired_size = Vec2::ZERO;
+        // Space taken by already measured children on each axis.
+        let mut taken = Vec2::ZERO;
+
+        for child_handle in self.widget.children.iter() {
+            let size_for_child = Vec2::new(
+                maxf(0.0, available_size.x - taken.x),
+                maxf(0.0, available_size.y - taken.y),
+            );
+            let child = ui.node(*child_handle);
+            child.measure(ui, size_for_child);
+            let child_size = child.widget().desired_size.get();
+
+            match dock_of(child.widget()) {
+                Dock::Left | Dock::Right => {
+                    desired_size.y = maxf(desired_size.y, taken.y + child_size.y);
+                    taken.x += child_size.x;
+                }
+                Dock::Top | Dock::Bottom => {
+                    desired_size.x = maxf(desired_size.x, taken.x + child_size.x);
+                    taken.y += child_size.y;
+                }
+            }
+        }
+
+        Vec2::new(
+            maxf(desired_size.x, taken.x),
+            maxf(desired_size.y, taken.y),
+        )
+    }
+
+    fn arrange_override(&self, ui: &UserInterface, final_size: Vec2) -> Vec2 {
+        let count = self.widget.children.len();
+        let fill_index = if self.last_child_fill { count.saturating_sub(1) } else { count };
+        let (mut left, mut top, mut right, mut bottom) = (0.0, 0.0, 0.0, 0.0);
+
+        for (i, child_handle) in self.widget.children.iter().enumerate() {
+            let child = ui.node(*child_handle);
+            let child_size = child.widget().desired_size.get();
+            let mut rect = Rect::new(
+                left,
+                top,
+                maxf(0.0, final_size.x - (left + right)),
+                maxf(0.0, final_size.y - (top + bottom)),
+            );
+
+            if i < fill_index {
+                match dock_of(child.widget()) {
+                    Dock::Left => {
+                        left += child_size.x;
+                        rect.w = child_size.x;
+                    }
+                    Dock::Right => {
+                        right += child_size.x;
+                        rect.x = maxf(0.0, final_size.x - right);
+                        rect.w = child_size.x;
+                    }
+                    Dock::Top => {
+                        top += child_size.y;
+                        rect.h = child_size.y;
+                    }
+                    Dock::Bottom => {
+                        bottom += child_size.y;
+                        rect.y = maxf(0.0, final_size.y - bottom);
+                        rect.h = child_size.y;
+                    }
+                }
+            }
+
+            child.arrange(ui, &rect);
+        }
+
+        final_size
+    }
+}
+
+pub struct DockPanelBuilder {
+    widget_builder: WidgetBuilder,
+    last_child_fill: bool,
+}
+
+impl DockPanelBuilder {
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            last_child_fill: true,
+        }
+    }
+
+    pub fn with_last_child_fill(mut self, last_child_fill: bool) -> Self {
+        self.last_child_fill = last_child_fill;
+        self
+    }
+}
+
+impl Builder for DockPanelBuilder {
+    fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
+        ui.add_node(Box::new(DockPanel {
+            widget: self.widget_builder.build(),
+            last_child_fill: self.last_child_fill,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::math::vec2::Vec2,
+        dock_panel::{
+            Dock,
+            DockPanel,
+            DockPanelBuilder,
+        },
+        border::BorderBuilder,
+        widget::WidgetBuilder,
+        UserInterface,
+        UINodeContainer,
+        Builder,
+    };
+
+    #[test]
+    fn arrange_test() {
+        let mut ui = UserInterface::headless();
+        let top = BorderBuilder::new(WidgetBuilder::new()
+            .with_height(20.0)
+            .with_dock(Dock::Top))
+            .build(&mut ui);
+        let bottom = BorderBuilder::new(WidgetBuilder::new()
+            .with_height(10.0))
+            .build(&mut ui);
+        // Styles and templates set dock by name.
+        ui.node_mut(bottom).set_property(DockPanel::DOCK, &Dock::Bottom);
+        let left = BorderBuilder::new(WidgetBuilder::new()
+            .with_width(50.0))
+            .build(&mut ui);
+        let fill = BorderBuilder::new(WidgetBuilder::new()
+            .with_dock(Dock::Right))
+            .build(&mut ui);
+        DockPanelBuilder::new(WidgetBuilder::new()
+            .with_width(300.0)
+            .with_height(200.0)
+            .with_child(top)
+            .with_child(bottom)
+            .with_child(left)
+            .with_child(fill))
+            .build(&mut ui);
+
+        ui.update(Vec2::new(800.0, 600.0), 0.0);
+
+        let bounds = |node| {
+            let bounds = ui.node(node).widget().get_screen_bounds();
+            (bounds.x, bounds.y, bounds.w, bounds.h)
+        };
+        assert_eq!(bounds(top), (0.0, 0.0, 300.0, 20.0));
+        assert_eq!(bounds(bottom), (0.0, 190.0, 300.0, 10.0));
+        // Widget without dock goes to the left.
+        assert_eq!(bounds(left), (0.0, 20.0, 50.0, 170.0));
+        // Last child ignores its dock and fills the rest.
+        assert_eq!(bounds(fill), (50.0, 20.0, 250.0, 170.0));
+    }
+}
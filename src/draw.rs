@@ -1,5 +1,7 @@
 use crate::{
     Thickness,
+    LayoutTransform,
+    RenderTransform,
     formatted_text::FormattedText,
     ttf::Font,
     core::{
@@ -18,10 +20,11 @@ use crate::core::math::TriangleDefinition;
 
 
 #[repr(C)]
+#[derive(Copy, Clone)]
 pub struct Vertex {
-    pos: Vec2,
-    tex_coord: Vec2,
-    color: Color,
+    pub(in crate) pos: Vec2,
+    pub(in crate) tex_coord: Vec2,
+    pub(in crate) color: Color,
 }
 
 impl Vertex {
@@ -34,6 +37,111 @@ impl Vertex {
     }
 }
 
+/// Brush that cycles frames of sprite sheet, each frame is a region of the same texture.
+/// Animation is advanced by user interface each update when brush is set as background
+/// animation of a widget.
+#[derive(Clone)]
+pub struct AnimatedSpriteBrush {
+    texture: Arc<Texture>,
+    frames: Vec<Rect<f32>>,
+    fps: f32,
+    time: f32,
+    looping: bool,
+    playing: bool,
+}
+
+impl AnimatedSpriteBrush {
+    pub fn new(texture: Arc<Texture>, frames: Vec<Rect<f32>>, fps: f32) -> Self {
+        Self {
+            texture,
+            frames,
+            fps,
+            time: 0.0,
+            looping: true,
+            playing: true,
+        }
+    }
+
+    /// Creates animation from sprite sheet with frames of equal size placed in rows from
+    /// left to right, `frame_count` allows to use partially filled sheet.
+    pub fn from_grid(texture: Arc<Texture>, columns: usize, rows: usize, frame_count: usize, fps: f32) -> Self {
+        let columns = columns.max(1);
+        let rows = rows.max(1);
+        let w = 1.0 / columns as f32;
+        let h = 1.0 / rows as f32;
+        let frames = (0..frame_count.min(columns * rows))
+            .map(|i| Rect::new((i % columns) as f32 * w, (i / columns) as f32 * h, w, h))
+            .collect();
+        Self::new(texture, frames, fps)
+    }
+
+    pub fn with_looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    pub fn set_fps(&mut self, fps: f32) {
+        self.fps = fps;
+    }
+
+    pub fn fps(&self) -> f32 {
+        self.fps
+    }
+
+    pub fn play(&mut self) {
+        self.playing = true;
+    }
+
+    pub fn stop(&mut self) {
+        self.playing = false;
+    }
+
+    /// Stops animation and rewinds it to first frame.
+    pub fn reset(&mut self) {
+        self.playing = false;
+        self.time = 0.0;
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    pub fn current_frame(&self) -> usize {
+        if self.frames.is_empty() {
+            return 0;
+        }
+        let frame = (self.time * self.fps) as usize;
+        if self.looping {
+            frame % self.frames.len()
+        } else {
+            frame.min(self.frames.len() - 1)
+        }
+    }
+
+    /// Advances animation, returns true if current frame has changed.
+    pub fn update(&mut self, dt: f32) -> bool {
+        if !self.playing || self.frames.is_empty() || self.fps <= 0.0 {
+            return false;
+        }
+        let old_frame = self.current_frame();
+        self.time += dt;
+        if self.looping {
+            // Keep time in range of one cycle to not lose precision over time.
+            self.time %= self.frames.len() as f32 / self.fps;
+        }
+        old_frame != self.current_frame()
+    }
+
+    /// Returns brush that shows current frame.
+    pub fn current_brush(&self) -> TextureBrush {
+        let brush = TextureBrush::new(self.texture.clone());
+        match self.frames.get(self.current_frame()) {
+            Some(frame) => brush.with_uv_rect(*frame),
+            None => brush,
+        }
+    }
+}
+
 #[derive(PartialEq, Copy, Clone, Debug)]
 pub enum CommandKind {
     Geometry,
@@ -42,6 +150,42 @@ pub enum CommandKind {
 
 pub type Texture = dyn Any + Sync + Send;
 
+/// Reference to external texture (render target, video frame, atlas) with region of it
+/// that should be shown. UV rect is given in normalized texture coordinates.
+#[derive(Clone)]
+pub struct TextureBrush {
+    pub texture: Arc<Texture>,
+    pub uv_rect: Rect<f32>,
+}
+
+impl TextureBrush {
+    /// Creates brush that shows whole texture.
+    pub fn new(texture: Arc<Texture>) -> Self {
+        Self {
+            texture,
+            uv_rect: Rect::new(0.0, 0.0, 1.0, 1.0),
+        }
+    }
+
+    /// Sets region of texture, useful for sprites in atlas.
+    pub fn with_uv_rect(mut self, uv_rect: Rect<f32>) -> Self {
+        self.uv_rect = uv_rect;
+        self
+    }
+
+    /// Returns texture coordinates for corners of rectangle in the same order as
+    /// `push_rect_filled` expects them.
+    pub fn tex_coords(&self) -> [Vec2; 4] {
+        let r = &self.uv_rect;
+        [
+            Vec2::new(r.x, r.y),
+            Vec2::new(r.x + r.w, r.y),
+            Vec2::new(r.x + r.w, r.y + r.h),
+            Vec2::new(r.x, r.y + r.h),
+        ]
+    }
+}
+
 #[derive(Clone)]
 pub enum CommandTexture {
     None,
@@ -85,12 +229,72 @@ impl Command {
     }
 }
 
+/// Geometry that was generated once and can be pushed into drawing context many times
+/// without generating it again, for example glyph quads of static text. Vertices are
+/// stored relative to origin of geometry.
+pub struct RetainedGeometry {
+    vertices: Vec<Vertex>,
+    triangles: Vec<[u32; 3]>,
+    texture: CommandTexture,
+}
+
+impl RetainedGeometry {
+    /// Bakes glyphs of formatted text, text must be built.
+    pub fn from_text(formatted_text: &FormattedText) -> Option<Self> {
+        let font = formatted_text.get_font()?;
+        let mut vertices = Vec::with_capacity(formatted_text.get_glyphs().len() * 4);
+        let mut triangles = Vec::with_capacity(formatted_text.get_glyphs().len() * 2);
+        for glyph in formatted_text.get_glyphs() {
+            let b = glyph.get_bounds();
+            let t = glyph.get_tex_coords();
+            let color = glyph.get_color();
+            let index = vertices.len() as u32;
+            vertices.push(Vertex::new(Vec2::new(b.x, b.y), t[0], color));
+            vertices.push(Vertex::new(Vec2::new(b.x + b.w, b.y), t[1], color));
+            vertices.push(Vertex::new(Vec2::new(b.x + b.w, b.y + b.h), t[2], color));
+            vertices.push(Vertex::new(Vec2::new(b.x, b.y + b.h), t[3], color));
+            triangles.push([index, index + 1, index + 2]);
+            triangles.push([index, index + 2, index + 3]);
+        }
+        Some(Self {
+            vertices,
+            triangles,
+            texture: CommandTexture::Font(font),
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.triangles.is_empty()
+    }
+}
+
+/// Look of disabled widgets, see `DrawingContext::ghost_commands`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GhostStyle {
+    /// Multiplier of alpha of every vertex.
+    pub opacity: f32,
+    /// Amount of desaturation in [0; 1], 1 makes colors completely gray.
+    pub desaturation: f32,
+}
+
+impl Default for GhostStyle {
+    fn default() -> Self {
+        Self {
+            opacity: 0.5,
+            desaturation: 1.0,
+        }
+    }
+}
+
 pub struct DrawingContext {
     vertex_buffer: Vec<Vertex>,
     triangle_buffer: Vec<TriangleDefinition>,
     command_buffer: Vec<Command>,
     clip_cmd_stack: Vec<usize>,
     opacity_stack: Vec<f32>,
+    /// Layout transforms of widgets that are being drawn with their pivots in screen space.
+    /// Pivot, transform around pivot and translation applied after transform.
+    transform_stack: Vec<(Vec2, LayoutTransform, Vec2)>,
     triangles_to_commit: usize,
     current_nesting: u8,
 }
@@ -119,6 +323,7 @@ impl DrawingContext {
             command_buffer: Vec::new(),
             clip_cmd_stack: Vec::new(),
             opacity_stack: Vec::new(),
+            transform_stack: Vec::new(),
             triangles_to_commit: 0,
             current_nesting: 0,
         }
@@ -131,6 +336,7 @@ impl DrawingContext {
         self.command_buffer.clear();
         self.clip_cmd_stack.clear();
         self.opacity_stack.clear();
+        self.transform_stack.clear();
         self.triangles_to_commit = 0;
         self.current_nesting = 0;
     }
@@ -147,9 +353,37 @@ impl DrawingContext {
 
     #[inline]
     fn push_vertex(&mut self, pos: Vec2, tex_coord: Vec2, color: Color) {
+        let pos = self.transform_point(pos);
         self.vertex_buffer.push(Vertex::new(pos, tex_coord, color));
     }
 
+    /// Applies transforms from innermost to outermost one.
+    fn transform_point(&self, mut pos: Vec2) -> Vec2 {
+        for (pivot, transform, translation) in self.transform_stack.iter().rev() {
+            pos = *pivot + transform.transform_vector(pos - *pivot) + *translation;
+        }
+        pos
+    }
+
+    /// Every vertex pushed after this call will be transformed around given pivot
+    /// until matching `pop_transform`.
+    #[inline]
+    pub fn push_transform(&mut self, pivot: Vec2, transform: LayoutTransform) {
+        self.transform_stack.push((pivot, transform, Vec2::ZERO));
+    }
+
+    /// Same as `push_transform`, but for render transform relative to given origin.
+    /// Must be popped by `pop_transform`.
+    #[inline]
+    pub fn push_render_transform(&mut self, origin: Vec2, transform: RenderTransform) {
+        self.transform_stack.push((origin, LayoutTransform::scale(transform.scale), transform.translation));
+    }
+
+    #[inline]
+    pub fn pop_transform(&mut self) {
+        self.transform_stack.pop();
+    }
+
     #[inline]
     pub fn set_nesting(&mut self, nesting: u8) {
         self.current_nesting = nesting;
@@ -291,6 +525,52 @@ impl DrawingContext {
         self.push_triangle(index, index + 2, index + 3);
     }
 
+    /// Pushes filled circle sector, angles are in radians and grow clockwise starting
+    /// from positive X axis.
+    pub fn push_sector(&mut self, center: Vec2, radius: f32, start_angle: f32, sweep_angle: f32, segments: usize, color: Color) {
+        let segments = segments.max(1);
+        let step = sweep_angle / segments as f32;
+        self.push_vertex(center, Vec2::new(0.5, 0.5), color);
+        let origin = self.get_index_origin();
+        for i in 0..=segments {
+            let angle = start_angle + step * i as f32;
+            let (sin, cos) = angle.sin_cos();
+            self.push_vertex(center + Vec2::new(cos * radius, sin * radius), Vec2::new(0.5 + cos * 0.5, 0.5 + sin * 0.5), color);
+        }
+        for i in 0..segments as u32 {
+            self.push_triangle(origin, origin + i + 1, origin + i + 2);
+        }
+    }
+
+    /// Pushes arc of given thickness, angles are the same as in `push_sector`.
+    pub fn push_arc(&mut self, center: Vec2, radius: f32, start_angle: f32, sweep_angle: f32, thickness: f32, segments: usize, color: Color) {
+        let segments = segments.max(1);
+        let step = sweep_angle / segments as f32;
+        let inner = (radius - thickness).max(0.0);
+        let origin = self.get_index_origin();
+        for i in 0..=segments {
+            let angle = start_angle + step * i as f32;
+            let (sin, cos) = angle.sin_cos();
+            let t = i as f32 / segments as f32;
+            self.push_vertex(center + Vec2::new(cos * inner, sin * inner), Vec2::new(t, 0.0), color);
+            self.push_vertex(center + Vec2::new(cos * radius, sin * radius), Vec2::new(t, 1.0), color);
+        }
+        for i in 0..segments as u32 {
+            let a = origin + i * 2;
+            self.push_triangle(a, a + 1, a + 2);
+            self.push_triangle(a + 2, a + 1, a + 3);
+        }
+    }
+
+    pub fn push_triangle_filled(&mut self, vertices: [Vec2; 3], color: Color) {
+        self.push_vertex(vertices[0], Vec2::new(0.0, 0.0), color);
+        self.push_vertex(vertices[1], Vec2::new(1.0, 0.0), color);
+        self.push_vertex(vertices[2], Vec2::new(1.0, 1.0), color);
+
+        let index = self.get_index_origin();
+        self.push_triangle(index, index + 1, index + 2);
+    }
+
     pub fn commit(&mut self, kind: CommandKind, texture: CommandTexture) {
         if self.triangles_to_commit > 0 {
             let command = Command {
@@ -329,6 +609,72 @@ impl DrawingContext {
         self.commit(CommandKind::Geometry, CommandTexture::Font(font))
     }
 
+    /// Pushes previously baked geometry at given position and commits it as one command.
+    pub fn push_retained(&mut self, geometry: &RetainedGeometry, position: Vec2) {
+        let base = self.vertex_buffer.len() as u32;
+        for vertex in geometry.vertices.iter() {
+            self.push_vertex(vertex.pos + position, vertex.tex_coord, vertex.color);
+        }
+        for [a, b, c] in geometry.triangles.iter() {
+            self.push_triangle(base + a, base + b, base + c);
+        }
+        self.commit(CommandKind::Geometry, geometry.texture.clone());
+    }
+
+    /// Pushes filled rectangle and commits it, if brush is given rectangle is textured
+    /// with its region of texture and tinted by color.
+    pub fn push_brush_rect(&mut self, rect: &Rect<f32>, brush: Option<&TextureBrush>, color: Color) {
+        match brush {
+            Some(brush) => {
+                self.push_rect_filled(rect, Some(&brush.tex_coords()), color);
+                self.commit(CommandKind::Geometry, CommandTexture::Texture(brush.texture.clone()));
+            }
+            None => {
+                self.push_rect_filled(rect, None, color);
+                self.commit(CommandKind::Geometry, CommandTexture::None);
+            }
+        }
+    }
+
+    /// Dims geometry of all commands starting from given index, used to draw disabled
+    /// state. Widget should remember number of commands before it draws itself and call
+    /// this method after, so every control looks disabled in the same way.
+    pub fn ghost_commands(&mut self, first_command: usize, style: GhostStyle) {
+        let mut ghosted = vec![false; self.vertex_buffer.len()];
+        for command in self.command_buffer.iter().skip(first_command) {
+            // Clipping geometry is not visible, it only fills stencil.
+            if command.kind == CommandKind::Clip {
+                continue;
+            }
+            let end = command.start_triangle + command.triangle_count;
+            for triangle in self.triangle_buffer[command.start_triangle..end].iter() {
+                for index in triangle.indices.iter() {
+                    if let Some(flag) = ghosted.get_mut(*index as usize) {
+                        *flag = true;
+                    }
+                }
+            }
+        }
+        for (vertex, _) in self.vertex_buffer.iter_mut().zip(ghosted).filter(|(_, ghosted)| *ghosted) {
+            let c = vertex.color;
+            let gray = 0.299 * c.r as f32 + 0.587 * c.g as f32 + 0.114 * c.b as f32;
+            let desaturate = |v: u8| (v as f32 + (gray - v as f32) * style.desaturation).round() as u8;
+            vertex.color = Color::from_rgba(
+                desaturate(c.r),
+                desaturate(c.g),
+                desaturate(c.b),
+                (c.a as f32 * style.opacity).round() as u8,
+            );
+        }
+    }
+
+    /// Pushes and commits translucent rectangle over given bounds, it is an alternative to
+    /// `ghost_commands` for widgets with textured content where desaturation is not possible.
+    pub fn push_ghost_overlay(&mut self, bounds: &Rect<f32>, color: Color) {
+        self.push_rect_filled(bounds, None, color);
+        self.commit(CommandKind::Geometry, CommandTexture::None);
+    }
+
     pub fn commit_clip_rect(&mut self, clip_rect: &Rect<f32>) {
         self.push_rect_filled(clip_rect, None, Color::BLACK);
         let index = self.command_buffer.len();
@@ -337,7 +683,7 @@ impl DrawingContext {
     }
 
     pub fn ready_to_draw(&self) -> bool {
-        self.clip_cmd_stack.is_empty() && self.triangles_to_commit == 0 && self.opacity_stack.is_empty()
+        self.clip_cmd_stack.is_empty() && self.triangles_to_commit == 0 && self.opacity_stack.is_empty() && self.transform_stack.is_empty()
     }
 
     pub fn revert_clip_geom(&mut self) {
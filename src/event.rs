@@ -1,12 +1,42 @@
 use crate::{
     core::{
-        math::vec2::Vec2,
+        math::{
+            vec2::Vec2,
+            Rect,
+        },
         pool::Handle,
     },
+    calendar::Date,
+    data_grid::{
+        CellValue,
+        CellRange,
+    },
+    gizmo::{
+        GizmoHandleKind,
+        TransformDelta,
+    },
+    header_row::SortDirection,
+    keymap::Hotkey,
+    settings_view::SettingValue,
+    text_box::{
+        Position,
+        SelectionRange,
+    },
     UINode,
 };
-use std::any::Any;
+use std::{
+    any::Any,
+    rc::Rc,
+};
 
+/// Unique identifier of a timer created by `UserInterface::set_timer`.
+#[derive(Debug, Hash, Ord, PartialOrd, PartialEq, Eq, Clone, Copy)]
+pub struct TimerId(pub(in crate) u64);
+
+/// Kinds of events. Payloads avoid heap allocations where possible: changed text is not
+/// copied (it is read from its widget) and user data is shared, so events are cheap to
+/// clone.
+#[derive(Clone)]
 pub enum UIEventKind {
     /// Generated when some mouse button was pressed.
     MouseDown {
@@ -40,15 +70,43 @@ pub enum UIEventKind {
         code: KeyCode
     },
 
+    /// Generated when some gamepad button was pressed while widget had keyboard focus.
+    GamepadButtonDown {
+        button: u32
+    },
+
+    /// Generated when some gamepad button was released while widget had keyboard focus.
+    GamepadButtonUp {
+        button: u32
+    },
+
     /// Generated when mouse wheel was rolled while cursor was in bounds of widget.
     MouseWheel {
         pos: Vec2,
         amount: f32,
     },
 
+    /// Generated when user makes pinch gesture over widget, `delta` is relative change
+    /// of scale, positive delta means zoom in.
+    Pinch {
+        pos: Vec2,
+        delta: f32,
+    },
+
     /// Generated once when mouse leaves bounds of widget.
     MouseLeave,
 
+    /// Generated for node on which mouse button was pressed when mouse moved further than
+    /// drag threshold of user interface with pressed button, `pos` is position of press.
+    DragStarted {
+        pos: Vec2,
+        button: MouseButton,
+    },
+
+    /// Generated for node that owned mouse capture when capture was forcibly taken by
+    /// other node or when node was removed.
+    CaptureLost,
+
     /// Generated once when mouse enters bounds of widget.
     MouseEnter,
 
@@ -72,8 +130,56 @@ pub enum UIEventKind {
     /// Generated by any widget that has min numeric value (scroll bar for example).
     MinValueChanged(f32),
 
-    /// Generated by any ItemsControl that has selection behaviour.
-    SelectionChanged(Option<usize>),
+    /// Generated by any ItemsControl that has selection behaviour. Contains indices of all
+    /// selected items in ascending order, empty if nothing is selected.
+    SelectionChanged(Vec<usize>),
+
+    /// Generated by list box when user moved item from one position to another by dragging.
+    ItemsReordered {
+        from: usize,
+        to: usize,
+    },
+
+    /// Generated by zoom panel when its zoom has changed.
+    ZoomChanged(f32),
+
+    /// Generated by scroll viewer when its offsets has changed.
+    ScrollChanged {
+        horizontal: f32,
+        vertical: f32,
+    },
+
+    /// Generated by widget with enabled bounds notifications (see
+    /// `WidgetBuilder::with_bounds_notifications`) when its screen bounds has changed after
+    /// layout. Bounds are given in screen coordinates.
+    ActualBoundsChanged(Rect<f32>),
+
+    /// Generated by any widget when its global visibility has changed, for example when
+    /// one of its parents was hidden.
+    VisibilityChanged(bool),
+
+    /// Generated by masked text box when all required characters of mask were entered
+    /// (true) or when text became incomplete (false).
+    MaskCompletenessChanged(bool),
+
+    /// Generated by calendar when user clicks a day, and by date picker when date was
+    /// typed or picked.
+    DateSelected(Date),
+
+    /// Generated by chat box when user submits non-empty message.
+    ChatMessageSubmitted(String),
+
+    /// Generated by cooldown overlay when cooldown started with duration has elapsed.
+    CooldownFinished,
+
+    /// Generated by image when texture requested by `Image::load_async` has been loaded
+    /// or loading has failed.
+    ImageLoaded {
+        success: bool,
+    },
+
+    /// Generated by hotkey field when new hotkey was recorded or hotkey was cleared.
+    HotkeyChanged(Option<Hotkey>),
 
     /// Generated by opened window.
     Opened,
@@ -99,12 +205,213 @@ pub enum UIEventKind {
     /// Generated by checkbox that has changed its checked state.
     Checked(Option<bool>),
 
-    /// Any kind of user-defined event.
-    User(Box<dyn Any>),
+    /// Generated by header row while user drags separator of column.
+    ColumnResized {
+        column: usize,
+        width: f32,
+    },
+
+    /// Generated by header row when user dropped dragged column at new position.
+    ColumnReordered {
+        from: usize,
+        to: usize,
+    },
+
+    /// Generated by header row when user clicked header of sortable column.
+    ColumnSortChanged {
+        column: usize,
+        direction: SortDirection,
+    },
+
+    /// Generated by data grid (or its formula bar) when user has committed edited value of
+    /// a cell. Column is index of column of data, it does not change when columns are
+    /// reordered.
+    CellValueChanged {
+        row: usize,
+        column: usize,
+        value: CellValue,
+    },
+
+    /// Generated by data grid when user has clicked other cell, contains row and column
+    /// in display order.
+    CurrentCellChanged(Option<(usize, usize)>),
+
+    /// Generated by data grid in reference selection mode when user has picked a cell or
+    /// a range of cells.
+    CellReferencePicked(CellRange),
+
+    /// Generated by formula bar when user has committed text starting with `=`. Formulas
+    /// are not evaluated by the crate, application computes value and sets it to the cell.
+    /// Column is index of column of data.
+    FormulaCommitted {
+        row: usize,
+        column: usize,
+        formula: String,
+    },
+
+    /// Generated by tree item with lazy children when it is expanded first time, children
+    /// should be added by application.
+    Expanding,
+
+    /// Generated by text box when its text has changed either by user or programmatically.
+    /// Text is not copied to event on every keystroke, use `TextBox::text` of source.
+    TextChanged,
+
+    /// Generated by text box when caret was moved by `TextBox::set_caret_position` or
+    /// `TextBox::select_range`.
+    CaretMoved(Position),
+
+    /// Generated by text box when selection was changed by `TextBox::select_range` or
+    /// `TextBox::clear_selection`, None means there is no selection.
+    TextSelectionChanged(Option<SelectionRange>),
+
+    /// Generated for node that owns timer when timer interval has elapsed.
+    Timer(TimerId),
+
+    /// Generated by rubber band while its selection rectangle is dragged, contains
+    /// handles of nodes that intersect the rectangle.
+    SelectionRectChanged(Vec<Handle<UINode>>),
+
+    /// Generated by rubber band when mouse button was released, `rect` is in screen
+    /// coordinates.
+    SelectionRectFinished {
+        rect: Rect<f32>,
+        items: Vec<Handle<UINode>>,
+    },
+
+    /// Generated by gizmo handle (and by transform gizmo for its handles) on every mouse
+    /// move while handle is dragged.
+    TransformDelta(TransformDelta),
+
+    /// Generated by gizmo handle (and by transform gizmo for its handles) when dragging of
+    /// handle has finished.
+    TransformCompleted(GizmoHandleKind),
+
+    /// Generated by guide layer when guide was added (for example dragged out of ruler)
+    /// or removed.
+    GuidesChanged,
+
+    /// Generated by design surface when other node of edited tree was selected, contains
+    /// none handle when selection was cleared.
+    DesignSelectionChanged(Handle<UINode>),
+
+    /// Generated by design surface when node was moved, resized or re-parented by user.
+    DesignChanged(Handle<UINode>),
+
+    /// Generated by waveform when user moved playhead, contains time in seconds.
+    PlayheadMoved(f32),
+
+    /// Generated by waveform when user selected range of clip (start and end in seconds)
+    /// or cleared selection.
+    RangeSelected(Option<(f32, f32)>),
+
+    /// Generated by wizard when other page was shown.
+    WizardPageChanged {
+        from: usize,
+        to: usize,
+    },
+
+    /// Generated by wizard when user clicked Finish and last page passed validation.
+    WizardFinished,
+
+    /// Generated by wizard when user clicked Cancel.
+    WizardCancelled,
+
+    /// Generated by form when user clicked Submit and all fields passed validation.
+    FormSubmitted,
+
+    /// Generated by settings view when user has changed a setting, value is already written
+    /// to settings store.
+    SettingChanged {
+        key: String,
+        value: SettingValue,
+    },
+
+    /// Generated by loading screen when its fade-out has ended and it was collapsed.
+    LoadingFinished,
+
+    /// Any kind of user-defined event. Payload is shared, so application can keep and
+    /// resend the same payload without allocating it for every event.
+    User(Rc<dyn Any>),
+}
+
+impl UIEventKind {
+    /// Returns name of variant, used by event tracing.
+    pub fn name(&self) -> &'static str {
+        match self {
+            UIEventKind::MouseDown { .. } => "MouseDown",
+            UIEventKind::MouseUp { .. } => "MouseUp",
+            UIEventKind::MouseMove { .. } => "MouseMove",
+            UIEventKind::Text { .. } => "Text",
+            UIEventKind::KeyDown { .. } => "KeyDown",
+            UIEventKind::KeyUp { .. } => "KeyUp",
+            UIEventKind::GamepadButtonDown { .. } => "GamepadButtonDown",
+            UIEventKind::GamepadButtonUp { .. } => "GamepadButtonUp",
+            UIEventKind::MouseWheel { .. } => "MouseWheel",
+            UIEventKind::Pinch { .. } => "Pinch",
+            UIEventKind::MouseLeave => "MouseLeave",
+            UIEventKind::DragStarted { .. } => "DragStarted",
+            UIEventKind::CaptureLost => "CaptureLost",
+            UIEventKind::MouseEnter => "MouseEnter",
+            UIEventKind::Click => "Click",
+            UIEventKind::NumericValueChanged { .. } => "NumericValueChanged",
+            UIEventKind::MaxValueChanged(..) => "MaxValueChanged",
+            UIEventKind::MinValueChanged(..) => "MinValueChanged",
+            UIEventKind::SelectionChanged(..) => "SelectionChanged",
+            UIEventKind::ItemsReordered { .. } => "ItemsReordered",
+            UIEventKind::ZoomChanged(..) => "ZoomChanged",
+            UIEventKind::ScrollChanged { .. } => "ScrollChanged",
+            UIEventKind::ActualBoundsChanged(..) => "ActualBoundsChanged",
+            UIEventKind::VisibilityChanged(..) => "VisibilityChanged",
+            UIEventKind::MaskCompletenessChanged(..) => "MaskCompletenessChanged",
+            UIEventKind::DateSelected(..) => "DateSelected",
+            UIEventKind::ChatMessageSubmitted(..) => "ChatMessageSubmitted",
+            UIEventKind::CooldownFinished => "CooldownFinished",
+            UIEventKind::ImageLoaded { .. } => "ImageLoaded",
+            UIEventKind::HotkeyChanged(..) => "HotkeyChanged",
+            UIEventKind::Opened => "Opened",
+            UIEventKind::Closed => "Closed",
+            UIEventKind::GotFocus => "GotFocus",
+            UIEventKind::LostFocus => "LostFocus",
+            UIEventKind::Minimized(..) => "Minimized",
+            UIEventKind::CanMinimizeChanged(..) => "CanMinimizeChanged",
+            UIEventKind::CanCloseChanged(..) => "CanCloseChanged",
+            UIEventKind::Checked(..) => "Checked",
+            UIEventKind::ColumnResized { .. } => "ColumnResized",
+            UIEventKind::ColumnReordered { .. } => "ColumnReordered",
+            UIEventKind::ColumnSortChanged { .. } => "ColumnSortChanged",
+            UIEventKind::CellValueChanged { .. } => "CellValueChanged",
+            UIEventKind::CurrentCellChanged(..) => "CurrentCellChanged",
+            UIEventKind::CellReferencePicked(..) => "CellReferencePicked",
+            UIEventKind::FormulaCommitted { .. } => "FormulaCommitted",
+            UIEventKind::Expanding => "Expanding",
+            UIEventKind::TextChanged => "TextChanged",
+            UIEventKind::CaretMoved(..) => "CaretMoved",
+            UIEventKind::TextSelectionChanged(..) => "TextSelectionChanged",
+            UIEventKind::Timer(..) => "Timer",
+            UIEventKind::SelectionRectChanged(..) => "SelectionRectChanged",
+            UIEventKind::SelectionRectFinished { .. } => "SelectionRectFinished",
+            UIEventKind::TransformDelta(..) => "TransformDelta",
+            UIEventKind::TransformCompleted(..) => "TransformCompleted",
+            UIEventKind::GuidesChanged => "GuidesChanged",
+            UIEventKind::DesignSelectionChanged(..) => "DesignSelectionChanged",
+            UIEventKind::DesignChanged(..) => "DesignChanged",
+            UIEventKind::PlayheadMoved(..) => "PlayheadMoved",
+            UIEventKind::RangeSelected(..) => "RangeSelected",
+            UIEventKind::WizardPageChanged { .. } => "WizardPageChanged",
+            UIEventKind::WizardFinished => "WizardFinished",
+            UIEventKind::WizardCancelled => "WizardCancelled",
+            UIEventKind::FormSubmitted => "FormSubmitted",
+            UIEventKind::SettingChanged { .. } => "SettingChanged",
+            UIEventKind::LoadingFinished => "LoadingFinished",
+            UIEventKind::User(..) => "User",
+        }
+    }
 }
 
 /// Event is basic communication element that is used to deliver information to UI nodes
 /// or some other places.
+#[derive(Clone)]
 pub struct UIEvent {
     /// Flag which allows to mark event as handled. This can be useful if multiple listeners
     /// can handle event but event should be handled only once.
@@ -183,6 +490,13 @@ pub enum OsEvent {
     },
     Character(char),
     MouseWheel(f32, f32),
+    /// Pinch (magnify) gesture from touchpad or touch screen, positive delta means zoom in.
+    PinchGesture(f32),
+    /// Button of gamepad, numbering of buttons is defined by host.
+    GamepadInput {
+        button: u32,
+        state: ButtonState,
+    },
 }
 
 #[derive(Debug, Hash, Ord, PartialOrd, PartialEq, Eq, Clone, Copy)]
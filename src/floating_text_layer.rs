@@ -0,0 +1,270 @@
+use crate::{
+    core::{
+        color::Color,
+        pool::Handle,
+        math::vec2::Vec2,
+    },
+    widget::{
+        Widget,
+        WidgetBuilder,
+    },
+    draw::DrawingContext,
+    formatted_text::{
+        FormattedText,
+        FormattedTextBuilder,
+    },
+    color_ext::ColorExt,
+    ttf::Font,
+    UINode,
+    HorizontalAlignment,
+    VerticalAlignment,
+    Control,
+    ControlTemplate,
+    UINodeContainer,
+    Builder,
+};
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc,
+        Mutex,
+    },
+};
+
+struct FloatingText {
+    formatted_text: FormattedText,
+    /// Start position in local coordinates of layer.
+    position: Vec2,
+    color: Color,
+    age: f32,
+    is_alive: bool,
+}
+
+/// Layer for damage numbers, pickup notifications and other short-lived text. Each spawned
+/// text rises from given position and fades out, then its slot is reused by next spawned
+/// text, so layer does not allocate once enough slots were created.
+///
+/// Texts are drawn by layer itself, they are not nodes and do not take part in layout or
+/// hit testing.
+pub struct FloatingTextLayer {
+    widget: Widget,
+    texts: Vec<FloatingText>,
+    font: Arc<Mutex<Font>>,
+    /// Lifetime of text in seconds.
+    lifetime: f32,
+    /// Distance in pixels which text rises during its lifetime.
+    rise_distance: f32,
+    /// Part of lifetime (from the end) during which text fades out, in [0; 1].
+    fade_fraction: f32,
+}
+
+impl FloatingTextLayer {
+    /// Size of area in which each text is centered.
+    const TEXT_AREA: Vec2 = Vec2 { x: 256.0, y: 64.0 };
+
+    /// Spawns text centered at given position in local coordinates of layer.
+    pub fn spawn(&mut self, text: &str, position: Vec2, color: Color) {
+        let index = match self.texts.iter().position(|text| !text.is_alive) {
+            Some(index) => index,
+            None => {
+                self.texts.push(FloatingText {
+                    formatted_text: FormattedTextBuilder::new()
+                        .with_font(self.font.clone())
+                        .with_size(Self::TEXT_AREA)
+                        .with_horizontal_alignment(HorizontalAlignment::Center)
+                        .with_vertical_alignment(VerticalAlignment::Center)
+                        .build(),
+                    position,
+                    color,
+                    age: 0.0,
+                    is_alive: false,
+                });
+                self.texts.len() - 1
+            }
+        };
+        let floating_text = &mut self.texts[index];
+        floating_text.position = position;
+        floating_text.color = color;
+        floating_text.age = 0.0;
+        floating_text.is_alive = true;
+        let formatted_text = &mut floating_text.formatted_text;
+        formatted_text.set_text(text);
+        formatted_text.set_color(color);
+        formatted_text.build();
+    }
+
+    /// Removes all texts, slots are kept for reuse.
+    pub fn clear(&mut self) {
+        for text in self.texts.iter_mut() {
+            text.is_alive = false;
+        }
+    }
+
+    pub fn alive_count(&self) -> usize {
+        self.texts.iter().filter(|text| text.is_alive).count()
+    }
+
+    /// Returns total amount of slots, alive and free.
+    pub fn capacity(&self) -> usize {
+        self.texts.len()
+    }
+
+    pub fn set_lifetime(&mut self, lifetime: f32) -> &mut Self {
+        self.lifetime = lifetime.max(std::f32::EPSILON);
+        self
+    }
+
+    pub fn lifetime(&self) -> f32 {
+        self.lifetime
+    }
+
+    pub fn set_rise_distance(&mut self, distance: f32) -> &mut Self {
+        self.rise_distance = distance;
+        self
+    }
+
+    pub fn rise_distance(&self) -> f32 {
+        self.rise_distance
+    }
+
+    pub fn set_fade_fraction(&mut self, fraction: f32) -> &mut Self {
+        self.fade_fraction = fraction.max(0.0).min(1.0);
+        self
+    }
+
+    pub fn fade_fraction(&self) -> f32 {
+        self.fade_fraction
+    }
+
+    /// Font of texts spawned after this call.
+    pub fn set_font(&mut self, font: Arc<Mutex<Font>>) -> &mut Self {
+        self.font = font;
+        for text in self.texts.iter_mut().filter(|text| !text.is_alive) {
+            text.formatted_text.set_font(self.font.clone());
+        }
+        self
+    }
+
+    pub fn font(&self) -> Arc<Mutex<Font>> {
+        self.font.clone()
+    }
+}
+
+impl Control for FloatingTextLayer {
+    fn widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    fn widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    fn raw_copy(&self) -> Box<dyn Control> {
+        Box::new(Self {
+            widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
+            texts: Vec::new(),
+            font: self.font.clone(),
+            lifetime: self.lifetime,
+            rise_distance: self.rise_distance,
+            fade_fraction: self.fade_fraction,
+        })
+    }
+
+    fn resolve(&mut self, _: &ControlTemplate, _: &HashMap<Handle<UINode>, Handle<UINode>>) {}
+
+    fn draw(&self, drawing_context: &mut DrawingContext) {
+        let bounds = self.widget.get_screen_bounds();
+        for text in self.texts.iter().filter(|text| text.is_alive) {
+            let t = text.age / self.lifetime;
+            // Ease out, text slows down while it rises.
+            let rise = if self.widget.reduced_motion() {
+                0.0
+            } else {
+                self.rise_distance * (1.0 - (1.0 - t) * (1.0 - t))
+            };
+            let position = Vec2::new(
+                bounds.x + text.position.x - Self::TEXT_AREA.x * 0.5,
+                bounds.y + text.position.y - Self::TEXT_AREA.y * 0.5 - rise,
+            );
+            drawing_context.draw_text(position, &text.formatted_text);
+        }
+    }
+
+    fn update(&mut self, dt: f32) {
+        let fade_start = 1.0 - self.fade_fraction;
+        for text in self.texts.iter_mut().filter(|text| text.is_alive) {
+            text.age += dt;
+            if text.age >= self.lifetime {
+                text.is_alive = false;
+                continue;
+            }
+            let t = text.age / self.lifetime;
+            // With reduced motion text stays still and disappears at once.
+            if t > fade_start && !self.widget.reduced_motion() {
+                let alpha = 1.0 - (t - fade_start) / self.fade_fraction;
+                // Only color changes, so build just recolors glyphs.
+                text.formatted_text.set_color(text.color.with_alpha((text.color.a as f32 * alpha) as u8));
+                text.formatted_text.build();
+            }
+        }
+    }
+
+    fn needs_update(&self) -> bool {
+        true
+    }
+}
+
+pub struct FloatingTextLayerBuilder {
+    widget_builder: WidgetBuilder,
+    font: Option<Arc<Mutex<Font>>>,
+    lifetime: Option<f32>,
+    rise_distance: Option<f32>,
+    fade_fraction: Option<f32>,
+}
+
+impl FloatingTextLayerBuilder {
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            font: None,
+            lifetime: None,
+            rise_distance: None,
+            fade_fraction: None,
+        }
+    }
+
+    pub fn with_font(mut self, font: Arc<Mutex<Font>>) -> Self {
+        self.font = Some(font);
+        self
+    }
+
+    pub fn with_lifetime(mut self, lifetime: f32) -> Self {
+        self.lifetime = Some(lifetime);
+        self
+    }
+
+    pub fn with_rise_distance(mut self, distance: f32) -> Self {
+        self.rise_distance = Some(distance);
+        self
+    }
+
+    pub fn with_fade_fraction(mut self, fraction: f32) -> Self {
+        self.fade_fraction = Some(fraction);
+        self
+    }
+}
+
+impl Builder for FloatingTextLayerBuilder {
+    fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
+        let layer = FloatingTextLayer {
+            widget: self.widget_builder.build(),
+            texts: Vec::new(),
+            font: self.font.unwrap_or_else(|| crate::DEFAULT_FONT.clone()),
+            lifetime: self.lifetime.unwrap_or(1.0).max(std::f32::EPSILON),
+            rise_distance: self.rise_distance.unwrap_or(40.0),
+            fade_fraction: self.fade_fraction.unwrap_or(0.5).max(0.0).min(1.0),
+        };
+
+        ui.add_node(Box::new(layer))
+    }
+}
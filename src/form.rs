@@ -0,0 +1,383 @@
+use crate::{
+    core::{
+        color::Color,
+        pool::Handle,
+        math::Rect,
+    },
+    widget::{
+        Widget,
+        WidgetBuilder,
+    },
+    grid::{
+        GridBuilder,
+        Column,
+        Row,
+    },
+    text::{
+        Text,
+        TextBuilder,
+    },
+    button::ButtonBuilder,
+    draw::{
+        DrawingContext,
+        CommandKind,
+        CommandTexture,
+    },
+    event::{
+        UIEvent,
+        UIEventKind,
+    },
+    UserInterface,
+    UINode,
+    HorizontalAlignment,
+    VerticalAlignment,
+    Thickness,
+    Visibility,
+    Control,
+    ControlTemplate,
+    UINodeContainer,
+    Builder,
+};
+use std::{
+    collections::HashMap,
+    rc::Rc,
+};
+
+/// Checks value of a field, receives handle of editor of the field. Error message is shown
+/// under the editor and in validation summary of the form.
+pub type FieldValidator = Rc<dyn Fn(&UserInterface, Handle<UINode>) -> Result<(), String>>;
+
+pub struct FormField {
+    pub label: String,
+    pub editor: Handle<UINode>,
+    pub validator: Option<FieldValidator>,
+}
+
+impl FormField {
+    pub fn new(label: &str, editor: Handle<UINode>) -> Self {
+        Self {
+            label: label.to_owned(),
+            editor,
+            validator: None,
+        }
+    }
+
+    pub fn with_validator<F>(mut self, validator: F) -> Self
+        where F: Fn(&UserInterface, Handle<UINode>) -> Result<(), String> + 'static {
+        self.validator = Some(Rc::new(validator));
+        self
+    }
+}
+
+#[derive(Clone)]
+struct Field {
+    label: String,
+    editor: Handle<UINode>,
+    error: Handle<UINode>,
+    validator: Option<FieldValidator>,
+    /// Message of last failed validation, None if field is valid or was not validated yet.
+    message: Option<String>,
+    /// Screen bounds of editor from last layout, used to draw error frame.
+    bounds: Option<Rect<f32>>,
+}
+
+impl Field {
+    fn check(&self, ui: &UserInterface) -> Result<(), String> {
+        match self.validator.as_ref() {
+            Some(validator) => validator(ui, self.editor),
+            None => Ok(()),
+        }
+    }
+
+    /// Runs validator and shows or hides error message under the editor.
+    fn validate(&mut self, ui: &mut UserInterface) -> bool {
+        self.message = self.check(&*ui).err();
+        let (text, visibility) = match self.message.as_ref() {
+            Some(message) => (message.as_str(), Visibility::Visible),
+            None => ("", Visibility::Collapsed),
+        };
+        if let Some(error) = ui.node_mut(self.error).downcast_mut::<Text>() {
+            error.set_text(text);
+            error.widget_mut().set_visibility(visibility);
+        }
+        self.message.is_none()
+    }
+}
+
+/// Lists messages of all invalid fields in summary text, hides summary if there are none.
+fn update_summary(ui: &mut UserInterface, fields: &[Field], summary: Handle<UINode>) {
+    let lines = fields
+        .iter()
+        .filter_map(|field| field.message.as_ref().map(|message| format!("{}: {}", field.label, message)))
+        .collect::<Vec<String>>();
+    if let Some(summary) = ui.node_mut(summary).downcast_mut::<Text>() {
+        summary.set_text(lines.join("\n"));
+        summary.widget_mut()
+            .set_visibility(if lines.is_empty() { Visibility::Collapsed } else { Visibility::Visible });
+    }
+}
+
+/// Panel for settings screens and dialogs which puts labels and editors of fields into two
+/// aligned columns: labels column is as wide as the longest label, editors take the rest.
+///
+/// Field is validated every time its editor (or any descendant of it) reports changed value
+/// and on submit. Invalid editor gets red frame and message of validator under it, all
+/// messages are also listed in validation summary above Submit button. Submit validates all
+/// fields and sends `FormSubmitted` only if all of them are valid.
+///
+/// # Events
+///
+/// [`FormSubmitted`] - sent when Submit was clicked and all fields are valid.
+pub struct Form {
+    widget: Widget,
+    fields: Vec<Field>,
+    summary: Handle<UINode>,
+    submit: Handle<UINode>,
+}
+
+impl Form {
+    const ERROR_COLOR: Color = Color { r: 220, g: 80, b: 80, a: 255 };
+
+    pub fn field_count(&self) -> usize {
+        self.fields.len()
+    }
+
+    pub fn editor(&self, index: usize) -> Handle<UINode> {
+        self.fields.get(index).map_or(Handle::NONE, |field| field.editor)
+    }
+
+    pub fn submit_button(&self) -> Handle<UINode> {
+        self.submit
+    }
+
+    /// Returns message of last failed validation of field.
+    pub fn field_error(&self, index: usize) -> Option<&str> {
+        self.fields.get(index).and_then(|field| field.message.as_deref())
+    }
+
+    /// Runs validators of all fields without showing errors.
+    pub fn is_valid(&self, ui: &UserInterface) -> bool {
+        self.fields.iter().all(|field| field.check(ui).is_ok())
+    }
+
+    /// Validates all fields and shows errors of invalid ones, returns true if every field
+    /// is valid.
+    pub fn validate(ui: &mut UserInterface, form: Handle<UINode>) -> bool {
+        let (mut fields, summary) = match ui.node(form).downcast_ref::<Form>() {
+            Some(form_ref) => (form_ref.fields.clone(), form_ref.summary),
+            None => return false,
+        };
+        let valid = validate_fields(ui, &mut fields, summary);
+        if let Some(form_ref) = ui.node_mut(form).downcast_mut::<Form>() {
+            form_ref.fields = fields;
+        }
+        valid
+    }
+
+    fn field_of(&self, ui: &UserInterface, node: Handle<UINode>) -> Option<usize> {
+        self.fields.iter().position(|field| {
+            field.editor == node || ui.node(field.editor).widget().has_descendant(node, ui)
+        })
+    }
+}
+
+fn validate_fields(ui: &mut UserInterface, fields: &mut [Field], summary: Handle<UINode>) -> bool {
+    let mut valid = true;
+    for field in fields.iter_mut() {
+        valid &= field.validate(ui);
+    }
+    update_summary(ui, fields, summary);
+    valid
+}
+
+impl Control for Form {
+    fn widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    fn widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    fn raw_copy(&self) -> Box<dyn Control> {
+        Box::new(Self {
+            widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
+            fields: self.fields.clone(),
+            summary: self.summary,
+            submit: self.submit,
+        })
+    }
+
+    fn resolve(&mut self, _: &ControlTemplate, node_map: &HashMap<Handle<UINode>, Handle<UINode>>) {
+        for field in self.fields.iter_mut() {
+            field.editor = *node_map.get(&field.editor).unwrap();
+            field.error = *node_map.get(&field.error).unwrap();
+        }
+        self.summary = *node_map.get(&self.summary).unwrap();
+        self.submit = *node_map.get(&self.submit).unwrap();
+    }
+
+    fn post_draw(&self, drawing_context: &mut DrawingContext) {
+        // Editors are drawn already, frame goes on top of them.
+        let mut any = false;
+        for field in self.fields.iter().filter(|field| field.message.is_some()) {
+            if let Some(bounds) = field.bounds.as_ref() {
+                drawing_context.push_rect(bounds, 1.0, Self::ERROR_COLOR);
+                any = true;
+            }
+        }
+        if any {
+            drawing_context.commit(CommandKind::Geometry, CommandTexture::None);
+        }
+    }
+
+    fn handle_event(&mut self, _self_handle: Handle<UINode>, ui: &mut UserInterface, evt: &mut UIEvent) {
+        if let UIEventKind::ActualBoundsChanged(bounds) = evt.kind {
+            if let Some(field) = self.fields.iter_mut().find(|field| field.editor == evt.source) {
+                field.bounds = Some(bounds);
+            }
+            return;
+        }
+
+        if let UIEventKind::Click = evt.kind {
+            if evt.source == self.submit {
+                if validate_fields(ui, &mut self.fields, self.summary) {
+                    self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::FormSubmitted));
+                }
+                return;
+            }
+        }
+
+        let changed = match evt.kind {
+            UIEventKind::TextChanged |
+            UIEventKind::NumericValueChanged { .. } |
+            UIEventKind::Checked(..) |
+            UIEventKind::SelectionChanged(..) |
+            UIEventKind::DateSelected(..) |
+            UIEventKind::HotkeyChanged(..) |
+            UIEventKind::MaskCompletenessChanged(..) => true,
+            _ => false,
+        };
+        if changed {
+            if let Some(index) = self.field_of(ui, evt.source) {
+                self.fields[index].validate(ui);
+                update_summary(ui, &self.fields, self.summary);
+            }
+        }
+    }
+}
+
+pub struct FormBuilder {
+    widget_builder: WidgetBuilder,
+    fields: Vec<FormField>,
+    submit_text: String,
+}
+
+impl FormBuilder {
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            fields: Vec::new(),
+            submit_text: "Submit".to_owned(),
+        }
+    }
+
+    pub fn with_field(mut self, field: FormField) -> Self {
+        self.fields.push(field);
+        self
+    }
+
+    pub fn with_fields(mut self, fields: Vec<FormField>) -> Self {
+        self.fields.extend(fields);
+        self
+    }
+
+    pub fn with_submit_text(mut self, text: &str) -> Self {
+        self.submit_text = text.to_owned();
+        self
+    }
+}
+
+impl Builder for FormBuilder {
+    fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
+        let mut children = Vec::new();
+        let mut fields = Vec::new();
+        let mut grid_builder = GridBuilder::new(WidgetBuilder::new())
+            .add_column(Column::auto())
+            .add_column(Column::stretch());
+
+        // Each field takes two rows: label with editor and error message under editor.
+        for (i, field) in self.fields.into_iter().enumerate() {
+            let row = i * 2;
+            let label = TextBuilder::new(WidgetBuilder::new()
+                .with_margin(Thickness::uniform(2.0))
+                .on_row(row)
+                .on_column(0))
+                .with_text(&field.label)
+                .with_vertical_text_alignment(VerticalAlignment::Center)
+                .build(ui);
+            // Bounds of editors are used to draw error frames.
+            ui.node_mut(field.editor)
+                .widget_mut()
+                .set_row(row)
+                .set_column(1)
+                .set_bounds_notifications(true);
+            let error = TextBuilder::new(WidgetBuilder::new()
+                .with_foreground(Form::ERROR_COLOR)
+                .with_visibility(Visibility::Collapsed)
+                .with_margin(Thickness::uniform(2.0))
+                .on_row(row + 1)
+                .on_column(1))
+                .build(ui);
+            children.extend_from_slice(&[label, field.editor, error]);
+            grid_builder = grid_builder
+                .add_row(Row::auto())
+                .add_row(Row::auto());
+            fields.push(Field {
+                label: field.label,
+                editor: field.editor,
+                error,
+                validator: field.validator,
+                message: None,
+                bounds: None,
+            });
+        }
+
+        let summary_row = fields.len() * 2;
+        let summary = TextBuilder::new(WidgetBuilder::new()
+            .with_foreground(Form::ERROR_COLOR)
+            .with_visibility(Visibility::Collapsed)
+            .with_margin(Thickness::uniform(4.0))
+            .on_row(summary_row)
+            .on_column(1))
+            .build(ui);
+        let submit = ButtonBuilder::new(WidgetBuilder::new()
+            .with_width(80.0)
+            .with_margin(Thickness::uniform(2.0))
+            .with_horizontal_alignment(HorizontalAlignment::Right)
+            .on_row(summary_row + 1)
+            .on_column(1))
+            .with_text(&self.submit_text)
+            .build(ui);
+        children.extend_from_slice(&[summary, submit]);
+
+        let grid = grid_builder
+            .add_row(Row::auto())
+            .add_row(Row::auto())
+            .build(ui);
+        for child in children {
+            ui.link_nodes(child, grid);
+        }
+
+        let form = Form {
+            widget: self.widget_builder
+                .with_child(grid)
+                .build(),
+            fields,
+            summary,
+            submit,
+        };
+
+        ui.add_node(Box::new(form))
+    }
+}
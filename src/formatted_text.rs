@@ -7,6 +7,8 @@ use crate::{
         },
     },
     ttf::Font,
+    resource::ResourceRequest,
+    worker::WorkerPool,
     HorizontalAlignment,
     VerticalAlignment,
 };
@@ -16,6 +18,11 @@ use std::{
         Arc,
         Mutex,
     },
+    collections::hash_map::DefaultHasher,
+    hash::{
+        Hash,
+        Hasher,
+    },
 };
 
 #[derive(Debug)]
@@ -90,6 +97,21 @@ pub struct FormattedText {
     horizontal_alignment: HorizontalAlignment,
     color: Color,
     size: Vec2,
+    /// Multiplier for height of each line, 1.0 means that lines will use font ascender as height.
+    line_height_scale: f32,
+    /// Additional horizontal space between each pair of characters.
+    letter_spacing: f32,
+    /// Additional vertical space after each line that ends with new line character.
+    paragraph_spacing: f32,
+    /// Key of parameters which were used to build current lines and glyphs, build is
+    /// skipped if key is the same.
+    build_key: Option<u64>,
+    /// Color of current glyphs, change of color does not require new layout.
+    built_color: Color,
+}
+
+fn is_new_line(code: u32) -> bool {
+    code == u32::from(b'\n') || code == u32::from(b'\r')
 }
 
 impl FormattedText {
@@ -125,16 +147,116 @@ impl FormattedText {
         self.size = size;
     }
 
+    pub fn set_line_height_scale(&mut self, line_height_scale: f32) {
+        self.line_height_scale = line_height_scale;
+    }
+
+    pub fn line_height_scale(&self) -> f32 {
+        self.line_height_scale
+    }
+
+    pub fn set_letter_spacing(&mut self, letter_spacing: f32) {
+        self.letter_spacing = letter_spacing;
+    }
+
+    pub fn letter_spacing(&self) -> f32 {
+        self.letter_spacing
+    }
+
+    pub fn set_paragraph_spacing(&mut self, paragraph_spacing: f32) {
+        self.paragraph_spacing = paragraph_spacing;
+    }
+
+    pub fn paragraph_spacing(&self) -> f32 {
+        self.paragraph_spacing
+    }
+
     pub fn get_raw_text(&self) -> &[u32] {
         &self.text
     }
 
+    /// Converts internal UTF32 representation back to string.
+    pub fn text(&self) -> String {
+        self.text
+            .iter()
+            .filter_map(|c| std::char::from_u32(*c))
+            .collect()
+    }
+
+    /// Converts index of character to offset in bytes of UTF-8 representation of text.
+    /// Index past the end of text maps to length of text in bytes.
+    pub fn char_to_byte(&self, char_index: usize) -> usize {
+        self.chars()
+            .take(char_index)
+            .map(|c| c.len_utf8())
+            .sum()
+    }
+
+    /// Converts offset in bytes of UTF-8 representation to index of character that contains
+    /// this byte. Offset past the end of text maps to number of characters.
+    pub fn byte_to_char(&self, byte_offset: usize) -> usize {
+        let mut bytes = 0;
+        for (i, c) in self.chars().enumerate() {
+            bytes += c.len_utf8();
+            if bytes > byte_offset {
+                return i;
+            }
+        }
+        self.text.len()
+    }
+
+    /// Converts index of character to offset in UTF-16 code units, which is used by IME
+    /// of most platforms.
+    pub fn char_to_utf16(&self, char_index: usize) -> usize {
+        self.chars()
+            .take(char_index)
+            .map(|c| c.len_utf16())
+            .sum()
+    }
+
+    /// Converts offset in UTF-16 code units to index of character that contains it.
+    pub fn utf16_to_char(&self, utf16_offset: usize) -> usize {
+        let mut units = 0;
+        for (i, c) in self.chars().enumerate() {
+            units += c.len_utf16();
+            if units > utf16_offset {
+                return i;
+            }
+        }
+        self.text.len()
+    }
+
+    /// Converts index of character to line and offset in the line. Text must be built,
+    /// index past the end of text maps to the end of last line.
+    pub fn char_to_position(&self, char_index: usize) -> (usize, usize) {
+        for (line_index, line) in self.lines.iter().enumerate() {
+            if char_index >= line.begin && char_index <= line.end {
+                return (line_index, char_index - line.begin);
+            }
+        }
+        self.lines.last().map_or((0, 0), |line| (self.lines.len() - 1, line.len()))
+    }
+
+    /// Converts line and offset in the line to index of character, offset is clamped to
+    /// length of the line. Returns None if there is no such line.
+    pub fn position_to_char(&self, line: usize, offset: usize) -> Option<usize> {
+        self.lines
+            .get(line)
+            .map(|line| line.begin + std::cmp::min(offset, line.len()))
+    }
+
+    fn chars(&self) -> impl Iterator<Item=char> + '_ {
+        self.text
+            .iter()
+            .map(|c| std::char::from_u32(*c).unwrap_or(std::char::REPLACEMENT_CHARACTER))
+    }
+
     pub fn get_range_width(&self, range: Range<usize>) -> f32 {
         let mut width = 0.0;
         if let Some(ref font) = self.font {
             let font = font.lock().unwrap();
             for index in range {
-                width += font.get_glyph_advance(self.text[index]);
+                width += font.get_glyph_advance(self.text[index]) + self.letter_spacing;
             }
         }
         width
@@ -157,16 +279,105 @@ impl FormattedText {
         }
     }
 
+    pub fn insert_str(&mut self, str: &str, index: usize) {
+        let index = std::cmp::min(index, self.text.len());
+        self.text.splice(index..index, str.chars().map(|c| c as u32));
+    }
+
     pub fn remove_at(&mut self, index: usize) {
         self.text.remove(index);
     }
 
+    pub fn remove_range(&mut self, range: Range<usize>) {
+        self.text.drain(range);
+    }
+
+    /// Returns key of parameters used by last build, it changes every time when text is
+    /// laid out again, so it can be used to invalidate caches of glyph geometry.
+    pub fn build_key(&self) -> Option<u64> {
+        self.build_key
+    }
+
+    /// Forces next `build` to lay out text again, should be called when font has changed
+    /// its glyphs (for example after atlas was rebuilt).
+    pub fn invalidate(&mut self) {
+        self.build_key = None;
+    }
+
+    /// Returns copy of text with all its parameters, but without lines and glyphs. Copy is
+    /// cheap compared to layout and can be built on another thread, see `build_async`.
+    pub fn unbuilt_copy(&self) -> FormattedText {
+        FormattedText {
+            font: self.font.clone(),
+            text: self.text.clone(),
+            lines: Vec::new(),
+            glyphs: Vec::new(),
+            vertical_alignment: self.vertical_alignment,
+            horizontal_alignment: self.horizontal_alignment,
+            color: self.color,
+            size: self.size,
+            line_height_scale: self.line_height_scale,
+            letter_spacing: self.letter_spacing,
+            paragraph_spacing: self.paragraph_spacing,
+            build_key: None,
+            built_color: self.color,
+        }
+    }
+
+    /// Builds text on thread of text worker pool, so layout of large text does not stall
+    /// UI thread. Font of text is locked while text is built. If pool has no threads text
+    /// is built synchronously and returned request is already completed.
+    pub fn build_async(mut self) -> ResourceRequest<FormattedText> {
+        let request = ResourceRequest::new();
+        let result = request.clone();
+        WorkerPool::text().execute(move || {
+            self.build();
+            request.complete(self);
+        });
+        result
+    }
+
+    /// Hash of everything that affects layout of text, build is skipped if key of
+    /// current glyphs is the same, see `build_key`.
+    pub fn layout_key(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.text.hash(&mut hasher);
+        if let Some(font) = self.font.as_ref() {
+            (&**font as *const Mutex<Font> as usize).hash(&mut hasher);
+        }
+        self.size.x.to_bits().hash(&mut hasher);
+        self.size.y.to_bits().hash(&mut hasher);
+        (self.vertical_alignment as u8).hash(&mut hasher);
+        (self.horizontal_alignment as u8).hash(&mut hasher);
+        self.line_height_scale.to_bits().hash(&mut hasher);
+        self.letter_spacing.to_bits().hash(&mut hasher);
+        self.paragraph_spacing.to_bits().hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Lays out text and generates glyphs. Result is cached, so calling this method many
+    /// times for text that did not change is cheap.
     pub fn build(&mut self) {
+        let key = self.layout_key();
+        if self.build_key == Some(key) {
+            if self.built_color != self.color {
+                for glyph in self.glyphs.iter_mut() {
+                    glyph.color = self.color;
+                }
+                self.built_color = self.color;
+            }
+            return;
+        }
+
         let font = if let Some(font) = &self.font {
             font.lock().unwrap()
         } else {
             return;
         };
+        self.build_key = Some(key);
+        self.built_color = self.color;
+
+        let line_height = font.get_ascender() * self.line_height_scale;
 
         // Split on lines.
         let mut total_height = 0.0;
@@ -177,25 +388,34 @@ impl FormattedText {
                 match font.get_glyph(*code) {
                     Some(glyph) => glyph.get_advance(),
                     None => font.get_height()
-                };
-            let is_new_line = *code == u32::from(b'\n') || *code == u32::from(b'\r');
+                } + self.letter_spacing;
+            if is_new_line(*code) {
+                self.lines.push(current_line);
+                current_line.begin = i + 1;
+                current_line.end = i + 1;
+                current_line.width = 0.0;
+                total_height += line_height + self.paragraph_spacing;
+                continue;
+            }
             let new_width = current_line.width + advance;
-            if new_width > self.size.x || is_new_line {
+            if new_width > self.size.x {
                 self.lines.push(current_line);
-                current_line.begin = if is_new_line { i + 1 } else { i };
-                current_line.end = current_line.begin + 1;
+                current_line.begin = i;
+                current_line.end = i + 1;
                 current_line.width = advance;
-                total_height += font.get_ascender();
+                total_height += line_height;
             } else {
                 current_line.width = new_width;
                 current_line.end += 1;
             }
         }
-        // Commit rest of text.
-        if current_line.begin != current_line.end {
+        // Commit rest of text. Empty text and text ending with new line still get a
+        // last (empty) line, so caret has a place to be.
+        let ends_with_new_line = self.text.last().map_or(true, |code| is_new_line(*code));
+        if current_line.begin != current_line.end || ends_with_new_line {
             current_line.end = self.text.len();
             self.lines.push(current_line);
-            total_height += font.get_ascender();
+            total_height += line_height;
         }
 
         // Align lines according to desired alignment.
@@ -242,7 +462,7 @@ impl FormattedText {
                             };
                             self.glyphs.push(text_glyph);
                         }
-                        cursor.x += glyph.get_advance();
+                        cursor.x += glyph.get_advance() + self.letter_spacing;
                     }
                     None => {
                         // Insert invalid symbol
@@ -257,13 +477,17 @@ impl FormattedText {
                             tex_coords: [Vec2::ZERO; 4],
                             color: self.color,
                         });
-                        cursor.x += rect.w;
+                        cursor.x += rect.w + self.letter_spacing;
                     }
                 }
             }
-            line.height = font.get_ascender();
+            line.height = line_height;
             line.y_offset = cursor.y;
-            cursor.y += font.get_ascender();
+            cursor.y += line_height;
+            // Line that was terminated by new line character ends a paragraph.
+            if self.text.get(line.end).map_or(false, |code| is_new_line(*code)) {
+                cursor.y += self.paragraph_spacing;
+            }
         }
     }
 }
@@ -275,6 +499,9 @@ pub struct FormattedTextBuilder {
     text: String,
     vertical_alignment: VerticalAlignment,
     horizontal_alignment: HorizontalAlignment,
+    line_height_scale: f32,
+    letter_spacing: f32,
+    paragraph_spacing: f32,
 }
 
 impl Default for FormattedTextBuilder {
@@ -293,6 +520,9 @@ impl FormattedTextBuilder {
             vertical_alignment: VerticalAlignment::Top,
             color: Color::WHITE,
             size: Vec2::new(128.0, 128.0),
+            line_height_scale: 1.0,
+            letter_spacing: 0.0,
+            paragraph_spacing: 0.0,
         }
     }
 
@@ -301,6 +531,13 @@ impl FormattedTextBuilder {
         self
     }
 
+    /// Text without font has no glyphs and is not drawn, this is used by headless user
+    /// interface.
+    pub fn with_opt_font(mut self, font: Option<Arc<Mutex<Font>>>) -> Self {
+        self.font = font;
+        self
+    }
+
     pub fn with_vertical_alignment(mut self, vertical_alignment: VerticalAlignment) -> Self {
         self.vertical_alignment = vertical_alignment;
         self
@@ -326,6 +563,21 @@ impl FormattedTextBuilder {
         self
     }
 
+    pub fn with_line_height_scale(mut self, line_height_scale: f32) -> Self {
+        self.line_height_scale = line_height_scale;
+        self
+    }
+
+    pub fn with_letter_spacing(mut self, letter_spacing: f32) -> Self {
+        self.letter_spacing = letter_spacing;
+        self
+    }
+
+    pub fn with_paragraph_spacing(mut self, paragraph_spacing: f32) -> Self {
+        self.paragraph_spacing = paragraph_spacing;
+        self
+    }
+
     pub fn build(self) -> FormattedText {
         FormattedText {
             font: self.font,
@@ -336,6 +588,11 @@ impl FormattedTextBuilder {
             horizontal_alignment: self.horizontal_alignment,
             color: self.color,
             size: Vec2::new(120.0, 120.0),
+            line_height_scale: self.line_height_scale,
+            letter_spacing: self.letter_spacing,
+            paragraph_spacing: self.paragraph_spacing,
+            build_key: None,
+            built_color: self.color,
         }
     }
 }
\ No newline at end of file
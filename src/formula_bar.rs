@@ -0,0 +1,298 @@
+use crate::{
+    core::pool::Handle,
+    widget::{
+        Widget,
+        WidgetBuilder,
+    },
+    grid::{
+        GridBuilder,
+        Column,
+        Row,
+    },
+    text::{
+        Text,
+        TextBuilder,
+    },
+    text_box::{
+        TextBox,
+        TextBoxBuilder,
+    },
+    data_grid::{
+        DataGrid,
+        CellValue,
+        cell_name,
+    },
+    event::{
+        UIEvent,
+        UIEventKind,
+    },
+    keymap::WidgetAction,
+    color_ext::palette,
+    UserInterface,
+    UINode,
+    Thickness,
+    VerticalAlignment,
+    Control,
+    ControlTemplate,
+    UINodeContainer,
+    Builder,
+};
+use std::collections::HashMap;
+
+/// Companion of data grid which shows name and value of current cell of the grid and lets
+/// user edit the value as text, like the formula bar of spreadsheets. Enter commits text,
+/// Escape restores value of the cell.
+///
+/// Text starting with `=` is a formula: while it is typed, data grid is switched to
+/// reference selection mode, so click on a cell or drag over a range inserts its name
+/// (`B3`, `A1:C4`) at caret. Formulas are not evaluated, on commit formula bar sends
+/// `FormulaCommitted` and application is expected to compute the value and put it into the
+/// cell with `DataGrid::set_cell_value`. Other text is converted to type of current value of
+/// the cell and stored directly.
+///
+/// # Events
+///
+/// [`CellValueChanged`] - sent when plain value was committed to a cell.
+/// [`FormulaCommitted`] - sent when formula was committed.
+pub struct FormulaBar {
+    widget: Widget,
+    data_grid: Handle<UINode>,
+    name_box: Handle<UINode>,
+    field: Handle<UINode>,
+    /// Current cell of data grid, row and column in display order.
+    cell: Option<(usize, usize)>,
+}
+
+impl FormulaBar {
+    pub fn data_grid(&self) -> Handle<UINode> {
+        self.data_grid
+    }
+
+    pub fn field(&self) -> Handle<UINode> {
+        self.field
+    }
+
+    pub fn cell(&self) -> Option<(usize, usize)> {
+        self.cell
+    }
+
+    fn field_text(&self, ui: &UserInterface) -> String {
+        ui.node(self.field)
+            .downcast_ref::<TextBox>()
+            .map_or(String::new(), |field| field.text())
+    }
+
+    fn set_reference_selection(&self, ui: &mut UserInterface, reference_selection: bool) {
+        if let Some(data_grid) = ui.node_mut(self.data_grid).downcast_mut::<DataGrid>() {
+            if data_grid.is_reference_selection() != reference_selection {
+                data_grid.set_reference_selection(reference_selection);
+            }
+        }
+    }
+
+    /// Shows name and value of current cell.
+    fn sync(&self, ui: &mut UserInterface) {
+        let value = self.cell.and_then(|(row, column)| {
+            ui.node(self.data_grid)
+                .downcast_ref::<DataGrid>()?
+                .cell_value(row, column)
+                .map(|value| value.to_string())
+        });
+        let name = self.cell.map_or(String::new(), |(row, column)| cell_name(row, column));
+        if let Some(name_box) = ui.node_mut(self.name_box).downcast_mut::<Text>() {
+            name_box.set_text(name);
+        }
+        if let Some(field) = ui.node_mut(self.field).downcast_mut::<TextBox>() {
+            field.set_text(value.unwrap_or_default());
+        }
+        self.set_reference_selection(ui, false);
+    }
+
+    fn commit(&mut self, ui: &mut UserInterface) {
+        let (row, column) = match self.cell {
+            Some(cell) => cell,
+            None => return,
+        };
+        let text = self.field_text(ui);
+        let (data_column, current) = match ui.node(self.data_grid).downcast_ref::<DataGrid>() {
+            Some(data_grid) if !data_grid.is_read_only() => {
+                match (data_grid.column_order().get(column), data_grid.cell_value(row, column)) {
+                    (Some(data_column), Some(current)) => (*data_column, current.clone()),
+                    _ => return,
+                }
+            }
+            _ => return,
+        };
+
+        if text.starts_with('=') {
+            self.set_reference_selection(ui, false);
+            self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::FormulaCommitted {
+                row,
+                column: data_column,
+                formula: text,
+            }));
+            return;
+        }
+
+        let value = match current {
+            CellValue::Text(_) => Some(CellValue::Text(text)),
+            CellValue::Number(_) => text.trim().parse().ok().map(CellValue::Number),
+            CellValue::Bool(_) => match text.trim().to_lowercase().as_str() {
+                "yes" | "true" | "1" => Some(CellValue::Bool(true)),
+                "no" | "false" | "0" => Some(CellValue::Bool(false)),
+                _ => None,
+            },
+        };
+        match value {
+            Some(value) if value != current => {
+                DataGrid::set_cell_value(ui, self.data_grid, row, column, value.clone());
+                self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::CellValueChanged {
+                    row,
+                    column: data_column,
+                    value,
+                }));
+                self.sync(ui);
+            }
+            // Invalid input is discarded.
+            _ => self.sync(ui),
+        }
+    }
+}
+
+impl Control for FormulaBar {
+    fn widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    fn widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    fn raw_copy(&self) -> Box<dyn Control> {
+        Box::new(Self {
+            widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
+            data_grid: self.data_grid,
+            name_box: self.name_box,
+            field: self.field,
+            cell: self.cell,
+        })
+    }
+
+    fn resolve(&mut self, _: &ControlTemplate, node_map: &HashMap<Handle<UINode>, Handle<UINode>>) {
+        if self.data_grid.is_some() {
+            self.data_grid = *node_map.get(&self.data_grid).unwrap();
+        }
+        self.name_box = *node_map.get(&self.name_box).unwrap();
+        self.field = *node_map.get(&self.field).unwrap();
+    }
+
+    fn handle_event(&mut self, _self_handle: Handle<UINode>, ui: &mut UserInterface, evt: &mut UIEvent) {
+        if evt.source == self.data_grid && self.data_grid.is_some() {
+            match &evt.kind {
+                UIEventKind::CurrentCellChanged(cell) => {
+                    self.cell = *cell;
+                    self.sync(ui);
+                }
+                UIEventKind::CellValueChanged { row, .. } => {
+                    // Column of event is column of data, current cell is compared by row
+                    // only, resync is cheap.
+                    if self.cell.map_or(false, |(current_row, _)| current_row == *row) {
+                        self.sync(ui);
+                    }
+                }
+                UIEventKind::CellReferencePicked(range) => {
+                    let reference = range.to_string();
+                    if let Some(field) = ui.node_mut(self.field).downcast_mut::<TextBox>() {
+                        field.insert_str(&reference);
+                    }
+                    // Click on the grid took focus, typing of formula continues.
+                    ui.set_keyboard_focus(self.field);
+                }
+                _ => ()
+            }
+        } else if evt.source == self.field {
+            match &evt.kind {
+                UIEventKind::TextChanged => {
+                    let is_formula = ui.node(self.field)
+                        .downcast_ref::<TextBox>()
+                        .map_or(false, |field| field.text().starts_with('='));
+                    self.set_reference_selection(ui, is_formula && self.cell.is_some());
+                }
+                UIEventKind::KeyDown { code } => {
+                    match ui.key_action(*code) {
+                        Some(WidgetAction::Commit) => {
+                            self.commit(ui);
+                            evt.handled = true;
+                        }
+                        Some(WidgetAction::Cancel) => {
+                            self.sync(ui);
+                            evt.handled = true;
+                        }
+                        _ => ()
+                    }
+                }
+                _ => ()
+            }
+        }
+    }
+}
+
+pub struct FormulaBarBuilder {
+    widget_builder: WidgetBuilder,
+    data_grid: Handle<UINode>,
+    name_width: f32,
+}
+
+impl FormulaBarBuilder {
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            data_grid: Handle::NONE,
+            name_width: 60.0,
+        }
+    }
+
+    pub fn with_data_grid(mut self, data_grid: Handle<UINode>) -> Self {
+        self.data_grid = data_grid;
+        self
+    }
+
+    /// Sets width of box with name of current cell.
+    pub fn with_name_width(mut self, width: f32) -> Self {
+        self.name_width = width;
+        self
+    }
+}
+
+impl Builder for FormulaBarBuilder {
+    fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
+        let name_box = TextBuilder::new(WidgetBuilder::new()
+            .with_margin(Thickness::uniform(2.0))
+            .on_column(0))
+            .with_vertical_text_alignment(VerticalAlignment::Center)
+            .build(ui);
+        let field = TextBoxBuilder::new(WidgetBuilder::new()
+            .with_background(palette::current().field)
+            .with_margin(Thickness::uniform(2.0))
+            .on_column(1))
+            .build(ui);
+
+        let formula_bar = FormulaBar {
+            widget: self.widget_builder
+                .with_child(GridBuilder::new(WidgetBuilder::new()
+                    .with_child(name_box)
+                    .with_child(field))
+                    .add_column(Column::strict(self.name_width))
+                    .add_column(Column::stretch())
+                    .add_row(Row::stretch())
+                    .build(ui))
+                .build(),
+            data_grid: self.data_grid,
+            name_box,
+            field,
+            cell: None,
+        };
+
+        ui.add_node(Box::new(formula_bar))
+    }
+}
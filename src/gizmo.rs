@@ -0,0 +1,539 @@
+use crate::{
+    core::{
+        color::Color,
+        pool::Handle,
+        math::{
+            vec2::Vec2,
+            Rect,
+        },
+    },
+    draw::{
+        CommandKind,
+        CommandTexture,
+        DrawingContext,
+    },
+    event::{
+        UIEvent,
+        UIEventKind,
+        MouseButton,
+    },
+    widget::{
+        Widget,
+        WidgetBuilder,
+    },
+    color_ext::{
+        ColorExt,
+        palette,
+    },
+    UserInterface,
+    UINode,
+    Control,
+    ControlTemplate,
+    UINodeContainer,
+    Builder,
+};
+use std::collections::HashMap;
+
+/// Side or corner of manipulated rectangle which is moved by resize handle.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ResizeDirection {
+    TopLeft,
+    Top,
+    TopRight,
+    Right,
+    BottomRight,
+    Bottom,
+    BottomLeft,
+    Left,
+}
+
+impl ResizeDirection {
+    pub const ALL: [ResizeDirection; 8] = [
+        ResizeDirection::TopLeft,
+        ResizeDirection::Top,
+        ResizeDirection::TopRight,
+        ResizeDirection::Right,
+        ResizeDirection::BottomRight,
+        ResizeDirection::Bottom,
+        ResizeDirection::BottomLeft,
+        ResizeDirection::Left,
+    ];
+
+    /// Returns position of handle in normalized coordinates of rectangle, where (0, 0) is
+    /// top left corner and (1, 1) is bottom right corner.
+    pub fn anchor(self) -> Vec2 {
+        match self {
+            ResizeDirection::TopLeft => Vec2::new(0.0, 0.0),
+            ResizeDirection::Top => Vec2::new(0.5, 0.0),
+            ResizeDirection::TopRight => Vec2::new(1.0, 0.0),
+            ResizeDirection::Right => Vec2::new(1.0, 0.5),
+            ResizeDirection::BottomRight => Vec2::new(1.0, 1.0),
+            ResizeDirection::Bottom => Vec2::new(0.5, 1.0),
+            ResizeDirection::BottomLeft => Vec2::new(0.0, 1.0),
+            ResizeDirection::Left => Vec2::new(0.0, 0.5),
+        }
+    }
+}
+
+/// Kind of manipulation which is done by a handle.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum GizmoHandleKind {
+    /// Moves whole rectangle, handle usually covers the rectangle.
+    Move,
+    /// Moves given side or corner of rectangle, opposite side stays in place.
+    Resize(ResizeDirection),
+    /// Rotates rectangle around pivot of handle.
+    Rotate,
+}
+
+/// Change of position, size and rotation of manipulated rectangle made by one mouse move.
+/// Translation is change of position of top left corner, so resizing by left or top side
+/// moves rectangle and changes its size at the same time. Rotation is in radians, clockwise.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct TransformDelta {
+    pub kind: GizmoHandleKind,
+    pub translation: Vec2,
+    pub size: Vec2,
+    pub rotation: f32,
+}
+
+/// Draggable handle of 2D manipulation, building block of sprite and level editors. While
+/// handle is dragged with left mouse button, it sends `TransformDelta` event on every mouse
+/// move, and `TransformCompleted` when button is released, so editor can group changes into
+/// one undo step. Handle does not change anything by itself, application applies deltas to
+/// its objects.
+///
+/// Rotation handle rotates around pivot in screen coordinates, if pivot is not set center
+/// of parent of handle is used.
+pub struct GizmoHandle {
+    widget: Widget,
+    kind: GizmoHandleKind,
+    pivot: Option<Vec2>,
+    /// Last mouse position while handle is dragged.
+    drag_position: Option<Vec2>,
+    fill_brush: Color,
+    stroke_brush: Color,
+}
+
+impl GizmoHandle {
+    pub fn kind(&self) -> GizmoHandleKind {
+        self.kind
+    }
+
+    pub fn set_pivot(&mut self, pivot: Option<Vec2>) -> &mut Self {
+        self.pivot = pivot;
+        self
+    }
+
+    pub fn pivot(&self) -> Option<Vec2> {
+        self.pivot
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.drag_position.is_some()
+    }
+
+    fn make_delta(&self, ui: &UserInterface, from: Vec2, to: Vec2) -> TransformDelta {
+        let offset = to - from;
+        let mut delta = TransformDelta {
+            kind: self.kind,
+            translation: Vec2::ZERO,
+            size: Vec2::ZERO,
+            rotation: 0.0,
+        };
+        match self.kind {
+            GizmoHandleKind::Move => delta.translation = offset,
+            GizmoHandleKind::Resize(direction) => {
+                let anchor = direction.anchor();
+                // Left and top sides move origin and shrink size, right and bottom sides
+                // only grow size, middle handles do not affect perpendicular axis.
+                if anchor.x == 0.0 {
+                    delta.translation.x = offset.x;
+                    delta.size.x = -offset.x;
+                } else if anchor.x == 1.0 {
+                    delta.size.x = offset.x;
+                }
+                if anchor.y == 0.0 {
+                    delta.translation.y = offset.y;
+                    delta.size.y = -offset.y;
+                } else if anchor.y == 1.0 {
+                    delta.size.y = offset.y;
+                }
+            }
+            GizmoHandleKind::Rotate => {
+                let pivot = self.pivot.unwrap_or_else(|| {
+                    let bounds = ui.node(self.widget.parent).widget().get_screen_bounds();
+                    Vec2::new(bounds.x + bounds.w * 0.5, bounds.y + bounds.h * 0.5)
+                });
+                let a = from - pivot;
+                let b = to - pivot;
+                let mut angle = b.y.atan2(b.x) - a.y.atan2(a.x);
+                if angle > std::f32::consts::PI {
+                    angle -= 2.0 * std::f32::consts::PI;
+                } else if angle < -std::f32::consts::PI {
+                    angle += 2.0 * std::f32::consts::PI;
+                }
+                delta.rotation = angle;
+            }
+        }
+        delta
+    }
+}
+
+impl Control for GizmoHandle {
+    fn widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    fn widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    fn raw_copy(&self) -> Box<dyn Control> {
+        Box::new(Self {
+            widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
+            kind: self.kind,
+            pivot: self.pivot,
+            drag_position: None,
+            fill_brush: self.fill_brush,
+            stroke_brush: self.stroke_brush,
+        })
+    }
+
+    fn resolve(&mut self, _: &ControlTemplate, _: &HashMap<Handle<UINode>, Handle<UINode>>) {}
+
+    fn draw(&self, drawing_context: &mut DrawingContext) {
+        let bounds = self.widget.get_screen_bounds();
+        match self.kind {
+            GizmoHandleKind::Move => {
+                // Transparent fill is needed for hit testing.
+                drawing_context.push_rect_filled(&bounds, None, self.fill_brush.with_alpha(0));
+                drawing_context.push_rect(&bounds, 1.0, self.stroke_brush);
+            }
+            GizmoHandleKind::Resize(_) => {
+                drawing_context.push_rect_filled(&bounds, None, self.fill_brush);
+                drawing_context.push_rect(&bounds, 1.0, self.stroke_brush);
+            }
+            GizmoHandleKind::Rotate => {
+                let center = Vec2::new(bounds.x + bounds.w * 0.5, bounds.y + bounds.h * 0.5);
+                let radius = bounds.w.min(bounds.h) * 0.5;
+                drawing_context.push_sector(center, radius, 0.0, 2.0 * std::f32::consts::PI, 16, self.stroke_brush);
+                drawing_context.push_sector(center, (radius - 1.0).max(0.0), 0.0, 2.0 * std::f32::consts::PI, 16, self.fill_brush);
+            }
+        }
+        drawing_context.commit(CommandKind::Geometry, CommandTexture::None);
+    }
+
+    fn handle_event(&mut self, self_handle: Handle<UINode>, ui: &mut UserInterface, evt: &mut UIEvent) {
+        if evt.source != self_handle {
+            return;
+        }
+
+        match evt.kind {
+            UIEventKind::MouseDown { pos, button } => {
+                if button == MouseButton::Left && ui.capture_mouse(self_handle) {
+                    self.drag_position = Some(pos);
+                    evt.handled = true;
+                }
+            }
+            UIEventKind::MouseMove { pos } => {
+                if let Some(from) = self.drag_position {
+                    let delta = self.make_delta(ui, from, pos);
+                    self.drag_position = Some(pos);
+                    self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::TransformDelta(delta)));
+                }
+            }
+            UIEventKind::MouseUp { button, .. } => {
+                if button == MouseButton::Left && self.drag_position.take().is_some() {
+                    ui.release_mouse_capture();
+                    self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::TransformCompleted(self.kind)));
+                }
+            }
+            UIEventKind::CaptureLost => {
+                if self.drag_position.take().is_some() {
+                    self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::TransformCompleted(self.kind)));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+pub struct GizmoHandleBuilder {
+    widget_builder: WidgetBuilder,
+    kind: GizmoHandleKind,
+    pivot: Option<Vec2>,
+    fill_brush: Option<Color>,
+    stroke_brush: Option<Color>,
+}
+
+impl GizmoHandleBuilder {
+    pub fn new(widget_builder: WidgetBuilder, kind: GizmoHandleKind) -> Self {
+        Self {
+            widget_builder,
+            kind,
+            pivot: None,
+            fill_brush: None,
+            stroke_brush: None,
+        }
+    }
+
+    pub fn with_pivot(mut self, pivot: Vec2) -> Self {
+        self.pivot = Some(pivot);
+        self
+    }
+
+    pub fn with_fill_brush(mut self, brush: Color) -> Self {
+        self.fill_brush = Some(brush);
+        self
+    }
+
+    pub fn with_stroke_brush(mut self, brush: Color) -> Self {
+        self.stroke_brush = Some(brush);
+        self
+    }
+}
+
+impl Builder for GizmoHandleBuilder {
+    fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
+        let handle = GizmoHandle {
+            widget: self.widget_builder.build(),
+            kind: self.kind,
+            pivot: self.pivot,
+            drag_position: None,
+            fill_brush: self.fill_brush.unwrap_or_else(|| palette::current().text),
+            stroke_brush: self.stroke_brush.unwrap_or_else(|| palette::current().accent),
+        };
+
+        ui.add_node(Box::new(handle))
+    }
+}
+
+/// Complete set of handles around target node: move handle over target, eight resize
+/// handles on its corners and sides and rotation handle above top side. Gizmo should be
+/// placed on the same canvas as target (after it, so it is drawn on top), it follows screen
+/// bounds of target after every layout. Deltas of all handles are sent by gizmo itself as
+/// `TransformDelta` events, so application does not need to know handles.
+pub struct TransformGizmo {
+    widget: Widget,
+    target: Handle<UINode>,
+    handles: Vec<Handle<UINode>>,
+    handle_size: f32,
+    /// Distance from top side of target to center of rotation handle.
+    rotation_offset: f32,
+}
+
+impl TransformGizmo {
+    pub fn target(&self) -> Handle<UINode> {
+        self.target
+    }
+
+    /// Attaches gizmo to other node.
+    pub fn set_target(ui: &mut UserInterface, gizmo: Handle<UINode>, target: Handle<UINode>) {
+        let origin = parent_origin(ui, ui.node(gizmo).widget().parent);
+        let bounds = if target.is_some() {
+            Some(ui.node(target).widget().get_screen_bounds())
+        } else {
+            None
+        };
+        if target.is_some() {
+            // Gizmo follows target by its bounds notifications.
+            ui.node_mut(target).widget_mut().set_bounds_notifications(true);
+        }
+        if let Some(gizmo_ref) = ui.node_mut(gizmo).downcast_mut::<TransformGizmo>() {
+            gizmo_ref.target = target;
+            if let Some(bounds) = bounds {
+                gizmo_ref.place(origin, bounds);
+            }
+        }
+    }
+
+    /// Space around target which is occupied by handles.
+    fn padding(&self) -> f32 {
+        self.handle_size + self.rotation_offset
+    }
+
+    /// Moves gizmo so it surrounds given screen bounds of target, origin is screen
+    /// position of parent of gizmo.
+    fn place(&mut self, origin: Vec2, bounds: Rect<f32>) {
+        let padding = self.padding();
+        self.widget
+            .set_desired_local_position(Vec2::new(bounds.x - origin.x - padding, bounds.y - origin.y - padding))
+            .set_width(bounds.w + 2.0 * padding)
+            .set_height(bounds.h + 2.0 * padding);
+    }
+}
+
+fn parent_origin(ui: &UserInterface, parent: Handle<UINode>) -> Vec2 {
+    if parent.is_some() {
+        ui.node(parent).widget().screen_position
+    } else {
+        Vec2::ZERO
+    }
+}
+
+impl Control for TransformGizmo {
+    fn widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    fn widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    fn raw_copy(&self) -> Box<dyn Control> {
+        Box::new(Self {
+            widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
+            target: self.target,
+            handles: self.handles.clone(),
+            handle_size: self.handle_size,
+            rotation_offset: self.rotation_offset,
+        })
+    }
+
+    fn resolve(&mut self, _: &ControlTemplate, node_map: &HashMap<Handle<UINode>, Handle<UINode>>) {
+        if self.target.is_some() {
+            self.target = *node_map.get(&self.target).unwrap();
+        }
+        for handle in self.handles.iter_mut() {
+            *handle = *node_map.get(handle).unwrap();
+        }
+    }
+
+    fn arrange_override(&self, ui: &UserInterface, final_size: Vec2) -> Vec2 {
+        let padding = self.padding();
+        let target = Rect::new(padding, padding, (final_size.x - 2.0 * padding).max(0.0), (final_size.y - 2.0 * padding).max(0.0));
+        let half = self.handle_size * 0.5;
+        for handle in self.handles.iter() {
+            let node = ui.node(*handle);
+            let rect = match node.downcast_ref::<GizmoHandle>().map(|h| h.kind) {
+                Some(GizmoHandleKind::Move) => target,
+                Some(GizmoHandleKind::Resize(direction)) => {
+                    let anchor = direction.anchor();
+                    Rect::new(target.x + target.w * anchor.x - half, target.y + target.h * anchor.y - half, self.handle_size, self.handle_size)
+                }
+                Some(GizmoHandleKind::Rotate) => {
+                    Rect::new(target.x + target.w * 0.5 - half, target.y - self.rotation_offset - half, self.handle_size, self.handle_size)
+                }
+                None => Rect::new(0.0, 0.0, final_size.x, final_size.y),
+            };
+            node.arrange(ui, &rect);
+        }
+        final_size
+    }
+
+    fn draw(&self, drawing_context: &mut DrawingContext) {
+        // Stem of rotation handle.
+        let bounds = self.widget.get_screen_bounds();
+        let padding = self.padding();
+        let x = bounds.x + bounds.w * 0.5;
+        drawing_context.push_line(Vec2::new(x, bounds.y + padding - self.rotation_offset), Vec2::new(x, bounds.y + padding), 1.0, palette::current().accent);
+        drawing_context.commit(CommandKind::Geometry, CommandTexture::None);
+    }
+
+    fn handle_event(&mut self, _self_handle: Handle<UINode>, ui: &mut UserInterface, evt: &mut UIEvent) {
+        match &evt.kind {
+            UIEventKind::ActualBoundsChanged(bounds) => {
+                if self.target.is_some() && evt.source == self.target {
+                    self.place(parent_origin(ui, self.widget.parent), *bounds);
+                }
+            }
+            UIEventKind::TransformDelta(delta) => {
+                if self.handles.contains(&evt.source) {
+                    self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::TransformDelta(*delta)));
+                }
+            }
+            UIEventKind::TransformCompleted(kind) => {
+                if self.handles.contains(&evt.source) {
+                    self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::TransformCompleted(*kind)));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn on_attached_to_ui(&mut self, _self_handle: Handle<UINode>, ui: &mut UserInterface) {
+        if self.target.is_some() {
+            let bounds = ui.node(self.target).widget().get_screen_bounds();
+            self.place(parent_origin(ui, self.widget.parent), bounds);
+        }
+    }
+}
+
+pub struct TransformGizmoBuilder {
+    widget_builder: WidgetBuilder,
+    target: Handle<UINode>,
+    handle_size: f32,
+    rotation_offset: f32,
+    resizable: bool,
+    rotatable: bool,
+}
+
+impl TransformGizmoBuilder {
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            target: Handle::NONE,
+            handle_size: 8.0,
+            rotation_offset: 20.0,
+            resizable: true,
+            rotatable: true,
+        }
+    }
+
+    pub fn with_target(mut self, target: Handle<UINode>) -> Self {
+        self.target = target;
+        self
+    }
+
+    pub fn with_handle_size(mut self, size: f32) -> Self {
+        self.handle_size = size;
+        self
+    }
+
+    pub fn with_rotation_offset(mut self, offset: f32) -> Self {
+        self.rotation_offset = offset;
+        self
+    }
+
+    /// Gizmo without resize handles can only be moved (and rotated).
+    pub fn with_resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    pub fn with_rotatable(mut self, rotatable: bool) -> Self {
+        self.rotatable = rotatable;
+        self
+    }
+}
+
+impl Builder for TransformGizmoBuilder {
+    fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
+        let mut handles = vec![GizmoHandleBuilder::new(WidgetBuilder::new(), GizmoHandleKind::Move).build(ui)];
+        if self.resizable {
+            for direction in ResizeDirection::ALL.iter() {
+                handles.push(GizmoHandleBuilder::new(WidgetBuilder::new(), GizmoHandleKind::Resize(*direction)).build(ui));
+            }
+        }
+        if self.rotatable {
+            handles.push(GizmoHandleBuilder::new(WidgetBuilder::new(), GizmoHandleKind::Rotate).build(ui));
+        }
+
+        if self.target.is_some() {
+            ui.node_mut(self.target).widget_mut().set_bounds_notifications(true);
+        }
+
+        let gizmo = TransformGizmo {
+            widget: self.widget_builder
+                .with_children(&handles)
+                .build(),
+            target: self.target,
+            handles,
+            handle_size: self.handle_size,
+            rotation_offset: if self.rotatable { self.rotation_offset } else { 0.0 },
+        };
+
+        ui.add_node(Box::new(gizmo))
+    }
+}
@@ -1,6 +1,7 @@
 use std::{
     cell::RefCell,
-    collections::HashMap
+    collections::HashMap,
+    ops::Range,
 };
 use crate::{
     core::{
@@ -12,6 +13,7 @@ use crate::{
     },
         Visibility,
         UserInterface,
+        maxf,
         widget::{
             WidgetBuilder,
             Widget
@@ -31,12 +33,13 @@ pub enum SizeMode {
     Stretch,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub struct Column {
     size_mode: SizeMode,
     desired_width: f32,
     actual_width: f32,
     x: f32,
+    shared_size_group: Option<String>,
 }
 
 impl Column {
@@ -46,6 +49,7 @@ impl Column {
             desired_width,
             actual_width: 0.0,
             x: 0.0,
+            shared_size_group: None,
         }
     }
 
@@ -55,6 +59,7 @@ impl Column {
             desired_width,
             actual_width: 0.0,
             x: 0.0,
+            shared_size_group: None,
         }
     }
 
@@ -64,6 +69,7 @@ impl Column {
             desired_width: 0.0,
             actual_width: 0.0,
             x: 0.0,
+            shared_size_group: None,
         }
     }
 
@@ -73,16 +79,29 @@ impl Column {
             desired_width: 0.0,
             actual_width: 0.0,
             x: 0.0,
+            shared_size_group: None,
         }
     }
+
+    /// Puts column into a shared size group. All non-stretch columns with the same group
+    /// name will have the same width in every grid of the user interface.
+    pub fn with_shared_size_group(mut self, group: &str) -> Self {
+        self.shared_size_group = Some(group.to_owned());
+        self
+    }
+
+    pub fn shared_size_group(&self) -> Option<&str> {
+        self.shared_size_group.as_ref().map(|s| s.as_str())
+    }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub struct Row {
     size_mode: SizeMode,
     desired_height: f32,
     actual_height: f32,
     y: f32,
+    shared_size_group: Option<String>,
 }
 
 impl Row {
@@ -92,6 +111,7 @@ impl Row {
             desired_height,
             actual_height: 0.0,
             y: 0.0,
+            shared_size_group: None,
         }
     }
 
@@ -101,6 +121,7 @@ impl Row {
             desired_height,
             actual_height: 0.0,
             y: 0.0,
+            shared_size_group: None,
         }
     }
 
@@ -110,6 +131,7 @@ impl Row {
             desired_height: 0.0,
             actual_height: 0.0,
             y: 0.0,
+            shared_size_group: None,
         }
     }
 
@@ -119,11 +141,53 @@ impl Row {
             desired_height: 0.0,
             actual_height: 0.0,
             y: 0.0,
+            shared_size_group: None,
         }
     }
+
+    /// Puts row into a shared size group. All non-stretch rows with the same group
+    /// name will have the same height in every grid of the user interface.
+    pub fn with_shared_size_group(mut self, group: &str) -> Self {
+        self.shared_size_group = Some(group.to_owned());
+        self
+    }
+
+    pub fn shared_size_group(&self) -> Option<&str> {
+        self.shared_size_group.as_ref().map(|s| s.as_str())
+    }
+}
+
+/// Sizes of shared size groups of grid columns and rows. Every grid reports sizes of its
+/// grouped columns and rows during measure pass, the largest reported size becomes size
+/// of the group on next layout pass. This way separate grids will align after one frame
+/// and groups are able to shrink when content becomes smaller.
+#[derive(Default)]
+pub(in crate) struct SharedSizeGroups {
+    previous: HashMap<String, f32>,
+    current: HashMap<String, f32>,
 }
 
-/// Automatically arranges children by rows and columns
+impl SharedSizeGroups {
+    pub(in crate) fn begin_layout(&mut self) {
+        self.previous = std::mem::replace(&mut self.current, HashMap::new());
+    }
+
+    /// Registers size of a column or row and returns size that it should have.
+    fn fit(&mut self, group: &str, size: f32) -> f32 {
+        let current = self.current.entry(group.to_owned()).or_insert(0.0);
+        *current = maxf(*current, size);
+        maxf(size, self.previous.get(group).cloned().unwrap_or(0.0))
+    }
+}
+
+/// Returns indices of columns or rows occupied by child, clamped to their count.
+fn span_range(start: usize, span: usize, count: usize) -> Range<usize> {
+    start.min(count)..(start + span).min(count)
+}
+
+/// Automatically arranges children by rows and columns. Child is placed into cell given by
+/// `Widget::row` and `Widget::column` attached properties and may occupy several cells with
+/// `Widget::row_span` and `Widget::column_span`.
 pub struct Grid {
     widget: Widget,
     rows: RefCell<Vec<Row>>,
@@ -177,9 +241,10 @@ impl Control for Grid {
         for child_handle in self.widget.children.iter() {
             let size_for_child = {
                 let child = ui.nodes.borrow(*child_handle).widget();
+                let rect = self.cell_rect(child);
                 Vec2 {
-                    x: self.columns.borrow()[child.column()].actual_width,
-                    y: self.rows.borrow()[child.row()].actual_height,
+                    x: rect.w,
+                    y: rect.h,
                 }
             };
             ui.node(*child_handle).measure(ui, size_for_child);
@@ -206,21 +271,9 @@ impl Control for Grid {
         }
 
         for child_handle in self.widget.children.iter() {
-            let mut final_rect = None;
-
             let child = ui.nodes.borrow(*child_handle).widget();
-            if let Some(column) = self.columns.borrow().get(child.column()) {
-                if let Some(row) = self.rows.borrow().get(child.row()) {
-                    final_rect = Some(Rect::new(
-                        column.x,
-                        row.y,
-                        column.actual_width,
-                        row.actual_height,
-                    ));
-                }
-            }
-
-            if let Some(rect) = final_rect {
+            if child.column() < self.columns.borrow().len() && child.row() < self.rows.borrow().len() {
+                let rect = self.cell_rect(child);
                 ui.nodes.borrow(*child_handle).arrange(ui, &rect);
             }
         }
@@ -310,22 +363,68 @@ impl Grid {
         self.rows = RefCell::new(rows);
     }
 
+    /// Returns rect of cells occupied by child in local coordinates of grid, columns and
+    /// rows must be arranged already.
+    fn cell_rect(&self, child: &Widget) -> Rect<f32> {
+        let columns = self.columns.borrow();
+        let rows = self.rows.borrow();
+        let column_range = span_range(child.column(), child.column_span(), columns.len());
+        let row_range = span_range(child.row(), child.row_span(), rows.len());
+        Rect::new(
+            columns.get(column_range.start).map_or(0.0, |column| column.x),
+            rows.get(row_range.start).map_or(0.0, |row| row.y),
+            columns[column_range].iter().map(|column| column.actual_width).sum(),
+            rows[row_range].iter().map(|row| row.actual_height).sum(),
+        )
+    }
+
     fn calculate_preset_width(&self, ui: &UserInterface) -> f32 {
         let mut preset_width = 0.0;
+        let mut shared_size_groups = ui.shared_size_groups.borrow_mut();
+        let mut columns = self.columns.borrow_mut();
 
-        // Calculate size of strict-sized and auto-sized columns.
-        for (i, col) in self.columns.borrow_mut().iter_mut().enumerate() {
+        // Calculate size of strict-sized and auto-sized columns, only children that occupy
+        // single column are taken into account here.
+        for (i, col) in columns.iter_mut().enumerate() {
             if col.size_mode == SizeMode::Strict {
                 col.actual_width = col.desired_width;
-                preset_width += col.actual_width;
             } else if col.size_mode == SizeMode::Auto {
                 col.actual_width = col.desired_width;
                 for child_handle in self.widget.children.iter() {
                     let child = ui.nodes.borrow(*child_handle).widget();
-                    if child.column() == i && child.visibility == Visibility::Visible && child.desired_size.get().x > col.actual_width {
+                    if child.column() == i && child.column_span() == 1 && child.visibility == Visibility::Visible && child.desired_size.get().x > col.actual_width {
                         col.actual_width = child.desired_size.get().x;
                     }
                 }
+            }
+        }
+
+        // Children that span several columns and do not fit into them widen auto-sized
+        // columns of the span evenly. Span with stretch-sized column gets the rest of
+        // the grid, so it is left as is.
+        for child_handle in self.widget.children.iter() {
+            let child = ui.nodes.borrow(*child_handle).widget();
+            if child.column_span() > 1 && child.visibility == Visibility::Visible {
+                let range = span_range(child.column(), child.column_span(), columns.len());
+                let spanned = &mut columns[range];
+                if spanned.iter().all(|col| col.size_mode != SizeMode::Stretch) {
+                    let occupied: f32 = spanned.iter().map(|col| col.actual_width).sum();
+                    let auto_count = spanned.iter().filter(|col| col.size_mode == SizeMode::Auto).count();
+                    let excess = child.desired_size.get().x - occupied;
+                    if excess > 0.0 && auto_count > 0 {
+                        for col in spanned.iter_mut().filter(|col| col.size_mode == SizeMode::Auto) {
+                            col.actual_width += excess / auto_count as f32;
+                        }
+                    }
+                }
+            }
+        }
+
+        for col in columns.iter_mut() {
+            if col.size_mode != SizeMode::Stretch {
+                if let Some(group) = col.shared_size_group.as_ref() {
+                    col.actual_width = shared_size_groups.fit(group, col.actual_width);
+                }
                 preset_width += col.actual_width;
             }
         }
@@ -335,20 +434,49 @@ impl Grid {
 
     fn calculate_preset_height(&self, ui: &UserInterface) -> f32 {
         let mut preset_height = 0.0;
+        let mut shared_size_groups = ui.shared_size_groups.borrow_mut();
+        let mut rows = self.rows.borrow_mut();
 
-        // Calculate size of strict-sized and auto-sized rows.
-        for (i, row) in self.rows.borrow_mut().iter_mut().enumerate() {
+        // Calculate size of strict-sized and auto-sized rows, only children that occupy
+        // single row are taken into account here.
+        for (i, row) in rows.iter_mut().enumerate() {
             if row.size_mode == SizeMode::Strict {
                 row.actual_height = row.desired_height;
-                preset_height += row.actual_height;
             } else if row.size_mode == SizeMode::Auto {
                 row.actual_height = row.desired_height;
                 for child_handle in self.widget.children.iter() {
                     let child = ui.nodes.borrow(*child_handle).widget();
-                    if child.row() == i && child.visibility == Visibility::Visible && child.desired_size.get().y > row.actual_height {
+                    if child.row() == i && child.row_span() == 1 && child.visibility == Visibility::Visible && child.desired_size.get().y > row.actual_height {
                         row.actual_height = child.desired_size.get().y;
                     }
                 }
+            }
+        }
+
+        // Same as for columns: spanning children widen auto-sized rows of the span.
+        for child_handle in self.widget.children.iter() {
+            let child = ui.nodes.borrow(*child_handle).widget();
+            if child.row_span() > 1 && child.visibility == Visibility::Visible {
+                let range = span_range(child.row(), child.row_span(), rows.len());
+                let spanned = &mut rows[range];
+                if spanned.iter().all(|row| row.size_mode != SizeMode::Stretch) {
+                    let occupied: f32 = spanned.iter().map(|row| row.actual_height).sum();
+                    let auto_count = spanned.iter().filter(|row| row.size_mode == SizeMode::Auto).count();
+                    let excess = child.desired_size.get().y - occupied;
+                    if excess > 0.0 && auto_count > 0 {
+                        for row in spanned.iter_mut().filter(|row| row.size_mode == SizeMode::Auto) {
+                            row.actual_height += excess / auto_count as f32;
+                        }
+                    }
+                }
+            }
+        }
+
+        for row in rows.iter_mut() {
+            if row.size_mode != SizeMode::Stretch {
+                if let Some(group) = row.shared_size_group.as_ref() {
+                    row.actual_height = shared_size_groups.fit(group, row.actual_height);
+                }
                 preset_height += row.actual_height;
             }
         }
@@ -435,3 +563,93 @@ impl Grid {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            pool::Handle,
+            math::{
+                vec2::Vec2,
+                Rect,
+            },
+        },
+        grid::{
+            GridBuilder,
+            Row,
+            Column,
+            SharedSizeGroups,
+        },
+        border::BorderBuilder,
+        widget::WidgetBuilder,
+        UserInterface,
+        UINode,
+        UINodeContainer,
+        Builder,
+    };
+
+    fn assert_bounds(ui: &UserInterface, node: Handle<UINode>, expected: Rect<f32>) {
+        let bounds = ui.node(node).widget().get_screen_bounds();
+        assert_eq!((bounds.x, bounds.y, bounds.w, bounds.h), (expected.x, expected.y, expected.w, expected.h));
+    }
+
+    #[test]
+    fn spans_test() {
+        let mut ui = UserInterface::headless();
+        let wide = BorderBuilder::new(WidgetBuilder::new()
+            .on_row(0)
+            .on_column(0)
+            .with_column_span(2))
+            .build(&mut ui);
+        let tall = BorderBuilder::new(WidgetBuilder::new()
+            .on_row(0)
+            .on_column(2)
+            .with_row_span(2))
+            .build(&mut ui);
+        // Span that goes out of grid is cut by last column.
+        let cut = BorderBuilder::new(WidgetBuilder::new()
+            .on_row(1)
+            .on_column(1)
+            .with_column_span(5))
+            .build(&mut ui);
+        GridBuilder::new(WidgetBuilder::new()
+            .with_width(300.0)
+            .with_height(100.0)
+            .with_child(wide)
+            .with_child(tall)
+            .with_child(cut))
+            .add_columns(vec![Column::strict(100.0), Column::strict(100.0), Column::strict(100.0)])
+            .add_rows(vec![Row::strict(50.0), Row::strict(50.0)])
+            .build(&mut ui);
+
+        ui.update(Vec2::new(800.0, 600.0), 0.0);
+
+        assert_bounds(&ui, wide, Rect::new(0.0, 0.0, 200.0, 50.0));
+        assert_bounds(&ui, tall, Rect::new(200.0, 0.0, 100.0, 100.0));
+        assert_bounds(&ui, cut, Rect::new(100.0, 50.0, 200.0, 50.0));
+    }
+
+    #[test]
+    fn shared_size_groups_test() {
+        let mut groups = SharedSizeGroups::default();
+        groups.begin_layout();
+        // Nothing is known on first pass, every column keeps its own size.
+        assert_eq!(groups.fit("Label", 40.0), 40.0);
+        assert_eq!(groups.fit("Label", 60.0), 60.0);
+        assert_eq!(groups.fit("Value", 10.0), 10.0);
+
+        // Largest size of previous pass is size of group.
+        groups.begin_layout();
+        assert_eq!(groups.fit("Label", 30.0), 60.0);
+        assert_eq!(groups.fit("Label", 70.0), 70.0);
+        assert_eq!(groups.fit("Value", 5.0), 10.0);
+
+        // Group shrinks when content becomes smaller.
+        groups.begin_layout();
+        assert_eq!(groups.fit("Label", 30.0), 70.0);
+        groups.begin_layout();
+        assert_eq!(groups.fit("Label", 30.0), 30.0);
+        // Group that was not reported on previous pass is forgotten.
+        assert_eq!(groups.fit("Value", 5.0), 5.0);
+    }
+}
@@ -0,0 +1,23 @@
+use crate::{
+    core::pool::Handle,
+    UINode,
+};
+
+/// Kind of haptic feedback of gamepad navigation.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum HapticFeedback {
+    /// Keyboard focus moved to another widget.
+    FocusMoved,
+    /// Item was selected, button clicked or check box toggled.
+    Selected,
+    /// Directional navigation found no widget in requested direction.
+    BoundaryHit,
+}
+
+/// Receives feedback of gamepad navigation, so console UIs can provide rumble ticks.
+/// Handler is called only while gamepad is the last used input device, see
+/// `UserInterface::set_haptic_handler`.
+pub trait HapticHandler {
+    /// Node is the newly focused or selected node, or focused node for boundary hits.
+    fn rumble(&mut self, feedback: HapticFeedback, node: Handle<UINode>);
+}
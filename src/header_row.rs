@@ -0,0 +1,496 @@
+use crate::{
+    core::{
+        color::Color,
+        pool::Handle,
+        math::{
+            vec2::Vec2,
+            Rect,
+        },
+    },
+    widget::{
+        Widget,
+        WidgetBuilder,
+    },
+    draw::{
+        DrawingContext,
+        CommandKind,
+        CommandTexture,
+    },
+    formatted_text::{
+        FormattedText,
+        FormattedTextBuilder,
+    },
+    event::{
+        UIEvent,
+        UIEventKind,
+        MouseButton,
+    },
+    color_ext::palette,
+    UserInterface,
+    UINode,
+    VerticalAlignment,
+    Control,
+    ControlTemplate,
+    UINodeContainer,
+    Builder,
+};
+use std::collections::HashMap;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+/// Description of column of header row.
+#[derive(Clone, Debug)]
+pub struct HeaderColumn {
+    pub title: String,
+    pub width: f32,
+    pub min_width: f32,
+    pub sortable: bool,
+    pub sort_direction: Option<SortDirection>,
+}
+
+impl HeaderColumn {
+    pub fn new(title: &str, width: f32) -> Self {
+        Self {
+            title: title.to_owned(),
+            width,
+            min_width: 16.0,
+            sortable: true,
+            sort_direction: None,
+        }
+    }
+
+    pub fn with_min_width(mut self, min_width: f32) -> Self {
+        self.min_width = min_width;
+        self
+    }
+
+    pub fn with_sortable(mut self, sortable: bool) -> Self {
+        self.sortable = sortable;
+        self
+    }
+}
+
+enum HeaderDrag {
+    Resize {
+        column: usize,
+        start_x: f32,
+        start_width: f32,
+    },
+    Reorder {
+        column: usize,
+        start_x: f32,
+        /// Drag becomes active when mouse moved far enough, until then release is a click.
+        active: bool,
+        /// Index of gap between columns where column will be dropped.
+        slot: usize,
+    },
+}
+
+/// Row of column headers for multi-column views. Columns are resized by dragging separators
+/// between them and reordered by dragging headers, click on header of sortable column
+/// changes sort direction: ascending, then descending. Only one column is sorted at a time,
+/// clicking other column resets sort direction of previous one.
+///
+/// Header row does not sort or move anything itself, owner of row reacts on events and
+/// uses `column_offset` and widths of columns to lay out its cells.
+///
+/// Leading columns can be frozen: they stay in place while other columns are shifted by
+/// horizontal scroll offset set by owner.
+///
+/// # Events
+///
+/// [`ColumnResized`] - sent while separator of column is dragged.
+/// [`ColumnReordered`] - sent when dragged column was dropped at new position.
+/// [`ColumnSortChanged`] - sent when user clicked header of sortable column.
+pub struct HeaderRow {
+    widget: Widget,
+    columns: Vec<HeaderColumn>,
+    titles: Vec<FormattedText>,
+    resizable: bool,
+    reorderable: bool,
+    drag: Option<HeaderDrag>,
+    frozen_columns: usize,
+    /// Horizontal scroll offset of columns that are not frozen.
+    scroll: f32,
+}
+
+impl HeaderRow {
+    pub const HEIGHT: f32 = 24.0;
+    /// Distance from separator in which mouse grabs separator instead of header.
+    const SEPARATOR_GRAB: f32 = 4.0;
+    /// Distance which mouse should pass before header drag starts.
+    const DRAG_THRESHOLD: f32 = 4.0;
+    const PADDING: f32 = 4.0;
+    const ARROW_SIZE: f32 = 8.0;
+
+    pub fn columns(&self) -> &[HeaderColumn] {
+        &self.columns
+    }
+
+    /// Returns horizontal offset of column from left side of header row.
+    pub fn column_offset(&self, column: usize) -> f32 {
+        self.columns.iter().take(column).map(|column| column.width).sum()
+    }
+
+    /// Returns horizontal position of column from left side of header row, with scroll
+    /// offset applied to columns which are not frozen.
+    pub fn display_offset(&self, column: usize) -> f32 {
+        let offset = self.column_offset(column);
+        if column < self.frozen_columns {
+            offset
+        } else {
+            offset - self.scroll
+        }
+    }
+
+    /// Returns total width of frozen columns.
+    pub fn frozen_width(&self) -> f32 {
+        self.column_offset(self.frozen_columns.min(self.columns.len()))
+    }
+
+    pub fn set_frozen_columns(&mut self, count: usize) -> &mut Self {
+        self.frozen_columns = count;
+        self
+    }
+
+    pub fn frozen_columns(&self) -> usize {
+        self.frozen_columns
+    }
+
+    pub fn set_scroll(&mut self, scroll: f32) -> &mut Self {
+        self.scroll = scroll;
+        self
+    }
+
+    pub fn scroll(&self) -> f32 {
+        self.scroll
+    }
+
+    /// Returns total width of all columns.
+    pub fn total_width(&self) -> f32 {
+        self.columns.iter().map(|column| column.width).sum()
+    }
+
+    pub fn set_column_width(&mut self, column: usize, width: f32) {
+        if let Some(header) = self.columns.get_mut(column) {
+            header.width = width.max(header.min_width);
+            self.titles[column] = make_title(header);
+        }
+    }
+
+    /// Sets sort direction of given column, other columns become unsorted.
+    pub fn set_sort(&mut self, column: usize, direction: Option<SortDirection>) {
+        for (i, header) in self.columns.iter_mut().enumerate() {
+            header.sort_direction = if i == column { direction } else { None };
+        }
+    }
+
+    /// Returns sorted column and its direction.
+    pub fn sort(&self) -> Option<(usize, SortDirection)> {
+        self.columns
+            .iter()
+            .enumerate()
+            .find_map(|(i, column)| column.sort_direction.map(|direction| (i, direction)))
+    }
+
+    pub fn move_column(&mut self, from: usize, to: usize) {
+        if from == to || from >= self.columns.len() || to >= self.columns.len() {
+            return;
+        }
+        let column = self.columns.remove(from);
+        self.columns.insert(to, column);
+        let title = self.titles.remove(from);
+        self.titles.insert(to, title);
+    }
+
+    pub fn is_resizable(&self) -> bool {
+        self.resizable
+    }
+
+    pub fn set_resizable(&mut self, resizable: bool) -> &mut Self {
+        self.resizable = resizable;
+        self
+    }
+
+    pub fn is_reorderable(&self) -> bool {
+        self.reorderable
+    }
+
+    pub fn set_reorderable(&mut self, reorderable: bool) -> &mut Self {
+        self.reorderable = reorderable;
+        self
+    }
+
+    /// Scrolled columns are hidden under frozen ones.
+    fn is_hidden_by_frozen(&self, x: f32) -> bool {
+        self.frozen_columns > 0 && x < self.frozen_width()
+    }
+
+    /// Returns column under given horizontal position, relative to left side of header row.
+    pub(in crate) fn column_at(&self, x: f32) -> Option<usize> {
+        (0..self.columns.len()).find(|i| {
+            let offset = self.display_offset(*i);
+            x >= offset && x < offset + self.columns[*i].width &&
+                (*i < self.frozen_columns || !self.is_hidden_by_frozen(x))
+        })
+    }
+
+    fn separator_at(&self, x: f32) -> Option<usize> {
+        (0..self.columns.len()).find(|i| {
+            let right = self.display_offset(*i) + self.columns[*i].width;
+            (x - right).abs() <= Self::SEPARATOR_GRAB &&
+                (*i < self.frozen_columns || !self.is_hidden_by_frozen(right))
+        })
+    }
+
+    /// Returns index of gap between columns which is nearest to given position.
+    fn slot_at(&self, x: f32) -> usize {
+        (0..self.columns.len())
+            .filter(|i| x > self.display_offset(*i) + self.columns[*i].width * 0.5)
+            .count()
+    }
+
+    fn draw_columns(&self, drawing_context: &mut DrawingContext, bounds: &Rect<f32>, columns: std::ops::Range<usize>) {
+        for i in columns.clone() {
+            let column = &self.columns[i];
+            let right = bounds.x + self.display_offset(i) + column.width;
+            drawing_context.push_line(Vec2::new(right, bounds.y), Vec2::new(right, bounds.y + bounds.h), 1.0, palette::current().frame);
+            if let Some(direction) = column.sort_direction {
+                let center = Vec2::new(right - Self::PADDING - Self::ARROW_SIZE * 0.5, bounds.y + bounds.h * 0.5);
+                let half = Self::ARROW_SIZE * 0.5;
+                let (tip, base) = match direction {
+                    SortDirection::Ascending => (center.y - half * 0.5, center.y + half * 0.5),
+                    SortDirection::Descending => (center.y + half * 0.5, center.y - half * 0.5),
+                };
+                drawing_context.push_triangle_filled([
+                    Vec2::new(center.x - half, base),
+                    Vec2::new(center.x + half, base),
+                    Vec2::new(center.x, tip),
+                ], palette::current().text);
+            }
+        }
+        drawing_context.commit(CommandKind::Geometry, CommandTexture::None);
+
+        let y = bounds.y + (bounds.h - Self::HEIGHT) * 0.5;
+        for i in columns {
+            let x = bounds.x + self.display_offset(i) + Self::PADDING;
+            drawing_context.draw_text(Vec2::new(x, y), &self.titles[i]);
+        }
+    }
+
+    fn on_click(&mut self, column: usize) {
+        if !self.columns[column].sortable {
+            return;
+        }
+        let direction = match self.columns[column].sort_direction {
+            Some(SortDirection::Ascending) => SortDirection::Descending,
+            _ => SortDirection::Ascending,
+        };
+        self.set_sort(column, Some(direction));
+        self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::ColumnSortChanged {
+            column,
+            direction,
+        }));
+    }
+}
+
+fn make_title(column: &HeaderColumn) -> FormattedText {
+    let width = (column.width - 2.0 * HeaderRow::PADDING - HeaderRow::ARROW_SIZE).max(0.0);
+    let mut text = FormattedTextBuilder::new()
+        .with_font(crate::DEFAULT_FONT.clone())
+        .with_text(column.title.clone())
+        .with_size(Vec2::new(width, HeaderRow::HEIGHT))
+        .with_color(palette::current().text)
+        .with_vertical_alignment(VerticalAlignment::Center)
+        .build();
+    text.build();
+    text
+}
+
+impl Control for HeaderRow {
+    fn widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    fn widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    fn raw_copy(&self) -> Box<dyn Control> {
+        Box::new(Self {
+            widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
+            columns: self.columns.clone(),
+            titles: self.columns.iter().map(make_title).collect(),
+            resizable: self.resizable,
+            reorderable: self.reorderable,
+            drag: None,
+            frozen_columns: self.frozen_columns,
+            scroll: self.scroll,
+        })
+    }
+
+    fn resolve(&mut self, _: &ControlTemplate, _: &HashMap<Handle<UINode>, Handle<UINode>>) {}
+
+    fn measure_override(&self, _ui: &UserInterface, _available_size: Vec2) -> Vec2 {
+        Vec2::new(self.total_width(), Self::HEIGHT)
+    }
+
+    fn draw(&self, drawing_context: &mut DrawingContext) {
+        let bounds = self.widget.get_screen_bounds();
+        drawing_context.push_rect_filled(&bounds, None, self.widget.background());
+
+        // Scrolled columns first, then frozen ones over them on their own background.
+        let frozen = self.frozen_columns.min(self.columns.len());
+        self.draw_columns(drawing_context, &bounds, frozen..self.columns.len());
+        if frozen > 0 {
+            let frozen_bounds = Rect::new(bounds.x, bounds.y, self.frozen_width(), bounds.h);
+            drawing_context.push_rect_filled(&frozen_bounds, None, self.widget.background());
+            self.draw_columns(drawing_context, &bounds, 0..frozen);
+        }
+
+        if let Some(HeaderDrag::Reorder { column, active: true, slot, .. }) = self.drag {
+            let dragged = Rect::new(
+                bounds.x + self.display_offset(column),
+                bounds.y,
+                self.columns[column].width,
+                bounds.h,
+            );
+            drawing_context.push_rect_filled(&dragged, None, Color::from_rgba(255, 255, 255, 40));
+            let slot_x = bounds.x + if slot < self.columns.len() {
+                self.display_offset(slot)
+            } else {
+                self.display_offset(slot - 1) + self.columns[slot - 1].width
+            };
+            drawing_context.push_line(Vec2::new(slot_x, bounds.y), Vec2::new(slot_x, bounds.y + bounds.h), 2.0, palette::current().accent);
+            drawing_context.commit(CommandKind::Geometry, CommandTexture::None);
+        }
+    }
+
+    fn handle_event(&mut self, self_handle: Handle<UINode>, ui: &mut UserInterface, evt: &mut UIEvent) {
+        if evt.source != self_handle {
+            return;
+        }
+        let left = self.widget.get_screen_bounds().x;
+        match evt.kind {
+            UIEventKind::MouseDown { pos, button: MouseButton::Left } => {
+                let x = pos.x - left;
+                let separator = if self.resizable { self.separator_at(x) } else { None };
+                if let Some(column) = separator {
+                    self.drag = Some(HeaderDrag::Resize {
+                        column,
+                        start_x: pos.x,
+                        start_width: self.columns[column].width,
+                    });
+                } else if let Some(column) = self.column_at(x) {
+                    self.drag = Some(HeaderDrag::Reorder {
+                        column,
+                        start_x: pos.x,
+                        active: false,
+                        slot: column,
+                    });
+                }
+                if self.drag.is_some() {
+                    ui.capture_mouse(self_handle);
+                    evt.handled = true;
+                }
+            }
+            UIEventKind::MouseMove { pos } => {
+                let slot_under_mouse = self.slot_at(pos.x - left);
+                let mut resized = None;
+                match self.drag.as_mut() {
+                    Some(HeaderDrag::Resize { column, start_x, start_width }) => {
+                        resized = Some((*column, *start_width + pos.x - *start_x));
+                    }
+                    Some(HeaderDrag::Reorder { start_x, active, slot, .. }) => {
+                        if !*active && self.reorderable && (pos.x - *start_x).abs() >= Self::DRAG_THRESHOLD {
+                            *active = true;
+                        }
+                        if *active {
+                            *slot = slot_under_mouse;
+                        }
+                    }
+                    None => (),
+                }
+                if let Some((column, width)) = resized {
+                    if (width.max(self.columns[column].min_width) - self.columns[column].width).abs() > std::f32::EPSILON {
+                        self.set_column_width(column, width);
+                        let width = self.columns[column].width;
+                        self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::ColumnResized { column, width }));
+                    }
+                }
+            }
+            UIEventKind::MouseUp { button: MouseButton::Left, .. } => {
+                if let Some(drag) = self.drag.take() {
+                    ui.release_mouse_capture();
+                    if let HeaderDrag::Reorder { column, active, slot, .. } = drag {
+                        if active {
+                            let to = if slot > column { slot - 1 } else { slot };
+                            if to != column {
+                                self.move_column(column, to);
+                                self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::ColumnReordered { from: column, to }));
+                            }
+                        } else {
+                            self.on_click(column);
+                        }
+                    }
+                    evt.handled = true;
+                }
+            }
+            _ => ()
+        }
+    }
+}
+
+pub struct HeaderRowBuilder {
+    widget_builder: WidgetBuilder,
+    columns: Vec<HeaderColumn>,
+    resizable: bool,
+    reorderable: bool,
+}
+
+impl HeaderRowBuilder {
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            columns: Vec::new(),
+            resizable: true,
+            reorderable: true,
+        }
+    }
+
+    pub fn with_columns(mut self, columns: Vec<HeaderColumn>) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    pub fn with_resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    pub fn with_reorderable(mut self, reorderable: bool) -> Self {
+        self.reorderable = reorderable;
+        self
+    }
+}
+
+impl Builder for HeaderRowBuilder {
+    fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
+        let header_row = HeaderRow {
+            widget: self.widget_builder.build(),
+            titles: self.columns.iter().map(make_title).collect(),
+            columns: self.columns,
+            resizable: self.resizable,
+            reorderable: self.reorderable,
+            drag: None,
+            frozen_columns: 0,
+            scroll: 0.0,
+        };
+
+        ui.add_node(Box::new(header_row))
+    }
+}
@@ -0,0 +1,376 @@
+use crate::{
+    core::{
+        color::Color,
+        pool::Handle,
+        math::{
+            vec2::Vec2,
+            Rect,
+        },
+    },
+    widget::{
+        Widget,
+        WidgetBuilder,
+    },
+    draw::{
+        DrawingContext,
+        CommandKind,
+        CommandTexture,
+    },
+    event::{
+        UIEvent,
+        UIEventKind,
+    },
+    formatted_text::{
+        FormattedText,
+        FormattedTextBuilder,
+    },
+    color_ext::{
+        ColorExt,
+        palette,
+    },
+    ttf::Font,
+    UserInterface,
+    UINode,
+    HorizontalAlignment,
+    VerticalAlignment,
+    Control,
+    ControlTemplate,
+    UINodeContainer,
+    Builder,
+};
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc,
+        Mutex,
+    },
+};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum HistogramMode {
+    /// Each bin is a vertical bar, height of bar is proportional to value.
+    Bars,
+    /// Each bin is a full-height cell, color of cell goes from low to high color with value.
+    Heatmap,
+}
+
+/// Counts samples that fall into `bin_count` equal bins of given range, samples outside of
+/// range are ignored (except `max` which goes to the last bin).
+pub fn bin_samples(samples: &[f32], bin_count: usize, min: f32, max: f32) -> Vec<f32> {
+    let mut bins = vec![0.0; bin_count];
+    if bin_count == 0 || max <= min {
+        return bins;
+    }
+    let width = (max - min) / bin_count as f32;
+    for sample in samples {
+        if *sample >= min && *sample <= max {
+            let index = (((sample - min) / width) as usize).min(bin_count - 1);
+            bins[index] += 1.0;
+        }
+    }
+    bins
+}
+
+/// Shows binned data as bars or as heatmap strip. Hovered bin is highlighted and its range
+/// and value are shown in top left corner. Values are normalized by largest value unless
+/// maximum is set explicitly, logarithmic scale makes small values visible next to spikes
+/// (typical for frame time profiles and audio spectra).
+///
+/// Range of values is used only for readout: bin `i` covers
+/// `[min + i * (max - min) / n; min + (i + 1) * (max - min) / n)`.
+pub struct Histogram {
+    widget: Widget,
+    bins: Vec<f32>,
+    range: (f32, f32),
+    mode: HistogramMode,
+    log_scale: bool,
+    max_value: Option<f32>,
+    bar_color: Color,
+    low_color: Color,
+    high_color: Color,
+    hovered: Option<usize>,
+    font: Option<Arc<Mutex<Font>>>,
+    readout: FormattedText,
+}
+
+impl Histogram {
+    /// Gap between bars in pixels.
+    const BAR_GAP: f32 = 1.0;
+
+    pub fn set_bins(&mut self, bins: Vec<f32>) -> &mut Self {
+        self.bins = bins;
+        if self.hovered.map_or(false, |hovered| hovered >= self.bins.len()) {
+            self.hovered = None;
+        }
+        self.update_readout();
+        self
+    }
+
+    pub fn bins(&self) -> &[f32] {
+        &self.bins
+    }
+
+    /// Sets range of values which is covered by bins, used for readout.
+    pub fn set_range(&mut self, min: f32, max: f32) -> &mut Self {
+        self.range = (min, max);
+        self.update_readout();
+        self
+    }
+
+    pub fn range(&self) -> (f32, f32) {
+        self.range
+    }
+
+    pub fn set_mode(&mut self, mode: HistogramMode) -> &mut Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn mode(&self) -> HistogramMode {
+        self.mode
+    }
+
+    pub fn set_log_scale(&mut self, log_scale: bool) -> &mut Self {
+        self.log_scale = log_scale;
+        self
+    }
+
+    pub fn is_log_scale(&self) -> bool {
+        self.log_scale
+    }
+
+    /// Sets value which corresponds to full height (or high color), None means largest
+    /// value of bins.
+    pub fn set_max_value(&mut self, max_value: Option<f32>) -> &mut Self {
+        self.max_value = max_value;
+        self
+    }
+
+    pub fn max_value(&self) -> Option<f32> {
+        self.max_value
+    }
+
+    pub fn hovered_bin(&self) -> Option<usize> {
+        self.hovered
+    }
+
+    /// Returns value in [0; 1] which is used for height or color of bin.
+    fn normalize(&self, value: f32) -> f32 {
+        let max = self.max_value.unwrap_or_else(|| self.bins.iter().cloned().fold(0.0, f32::max));
+        if max <= 0.0 {
+            return 0.0;
+        }
+        let value = value.max(0.0).min(max);
+        if self.log_scale {
+            (1.0 + value).ln() / (1.0 + max).ln()
+        } else {
+            value / max
+        }
+    }
+
+    fn bin_rect(&self, bounds: &Rect<f32>, index: usize) -> Rect<f32> {
+        let width = bounds.w / self.bins.len() as f32;
+        Rect::new(bounds.x + index as f32 * width, bounds.y, width, bounds.h)
+    }
+
+    fn bin_at(&self, point: Vec2) -> Option<usize> {
+        let bounds = self.widget.get_screen_bounds();
+        if self.bins.is_empty() || !bounds.contains(point.x, point.y) || bounds.w <= 0.0 {
+            return None;
+        }
+        let index = ((point.x - bounds.x) / bounds.w * self.bins.len() as f32) as usize;
+        Some(index.min(self.bins.len() - 1))
+    }
+
+    fn update_readout(&mut self) {
+        let text = match self.hovered {
+            Some(index) => {
+                let (min, max) = self.range;
+                let width = (max - min) / self.bins.len() as f32;
+                let begin = min + index as f32 * width;
+                format!("{:.2}..{:.2}: {}", begin, begin + width, self.bins[index])
+            }
+            None => String::new(),
+        };
+        self.readout.set_text(&text);
+        self.readout.set_size(self.widget.actual_size());
+        self.readout.build();
+    }
+}
+
+impl Control for Histogram {
+    fn widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    fn widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    fn raw_copy(&self) -> Box<dyn Control> {
+        Box::new(Self {
+            widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
+            bins: self.bins.clone(),
+            range: self.range,
+            mode: self.mode,
+            log_scale: self.log_scale,
+            max_value: self.max_value,
+            bar_color: self.bar_color,
+            low_color: self.low_color,
+            high_color: self.high_color,
+            hovered: None,
+            font: self.font.clone(),
+            readout: make_readout(self.font.clone()),
+        })
+    }
+
+    fn resolve(&mut self, _: &ControlTemplate, _: &HashMap<Handle<UINode>, Handle<UINode>>) {}
+
+    fn draw(&self, drawing_context: &mut DrawingContext) {
+        let bounds = self.widget.get_screen_bounds();
+        // Background also makes whole area hit-testable, so empty bins report hover too.
+        drawing_context.push_rect_filled(&bounds, None, self.widget.background());
+
+        for (index, value) in self.bins.iter().enumerate() {
+            let cell = self.bin_rect(&bounds, index);
+            let normalized = self.normalize(*value);
+            match self.mode {
+                HistogramMode::Bars => {
+                    let height = cell.h * normalized;
+                    let width = (cell.w - Self::BAR_GAP).max(1.0);
+                    let bar = Rect::new(cell.x, cell.y + cell.h - height, width, height);
+                    let color = if self.hovered == Some(index) { self.bar_color.lighten(0.3) } else { self.bar_color };
+                    drawing_context.push_rect_filled(&bar, None, color);
+                }
+                HistogramMode::Heatmap => {
+                    drawing_context.push_rect_filled(&cell, None, self.low_color.lerp(self.high_color, normalized));
+                }
+            }
+        }
+
+        if let Some(hovered) = self.hovered {
+            drawing_context.push_rect(&self.bin_rect(&bounds, hovered), 1.0, palette::current().accent);
+        }
+        drawing_context.commit(CommandKind::Geometry, CommandTexture::None);
+
+        if self.hovered.is_some() {
+            drawing_context.draw_text(Vec2::new(bounds.x + 2.0, bounds.y + 2.0), &self.readout);
+        }
+    }
+
+    fn handle_event(&mut self, self_handle: Handle<UINode>, _ui: &mut UserInterface, evt: &mut UIEvent) {
+        if evt.source != self_handle {
+            return;
+        }
+
+        let hovered = match evt.kind {
+            UIEventKind::MouseMove { pos } => self.bin_at(pos),
+            UIEventKind::MouseLeave => None,
+            _ => return,
+        };
+        if hovered != self.hovered {
+            self.hovered = hovered;
+            self.update_readout();
+        }
+    }
+}
+
+fn make_readout(font: Option<Arc<Mutex<Font>>>) -> FormattedText {
+    FormattedTextBuilder::new()
+        .with_opt_font(font)
+        .with_color(palette::current().text)
+        .with_horizontal_alignment(HorizontalAlignment::Left)
+        .with_vertical_alignment(VerticalAlignment::Top)
+        .build()
+}
+
+pub struct HistogramBuilder {
+    widget_builder: WidgetBuilder,
+    bins: Vec<f32>,
+    range: Option<(f32, f32)>,
+    mode: HistogramMode,
+    log_scale: bool,
+    max_value: Option<f32>,
+    bar_color: Option<Color>,
+    low_color: Option<Color>,
+    high_color: Option<Color>,
+}
+
+impl HistogramBuilder {
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            bins: Vec::new(),
+            range: None,
+            mode: HistogramMode::Bars,
+            log_scale: false,
+            max_value: None,
+            bar_color: None,
+            low_color: None,
+            high_color: None,
+        }
+    }
+
+    pub fn with_bins(mut self, bins: Vec<f32>) -> Self {
+        self.bins = bins;
+        self
+    }
+
+    /// Sets range of values covered by bins, default is [0; bin count].
+    pub fn with_range(mut self, min: f32, max: f32) -> Self {
+        self.range = Some((min, max));
+        self
+    }
+
+    pub fn with_mode(mut self, mode: HistogramMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn with_log_scale(mut self, log_scale: bool) -> Self {
+        self.log_scale = log_scale;
+        self
+    }
+
+    pub fn with_max_value(mut self, max_value: f32) -> Self {
+        self.max_value = Some(max_value);
+        self
+    }
+
+    pub fn with_bar_color(mut self, color: Color) -> Self {
+        self.bar_color = Some(color);
+        self
+    }
+
+    /// Sets colors of heatmap for zero and maximal values.
+    pub fn with_heat_colors(mut self, low: Color, high: Color) -> Self {
+        self.low_color = Some(low);
+        self.high_color = Some(high);
+        self
+    }
+}
+
+impl Builder for HistogramBuilder {
+    fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
+        let font = ui.default_font();
+        let p = palette::current();
+        let histogram = Histogram {
+            widget: self.widget_builder
+                .with_background(p.field)
+                .build(),
+            range: self.range.unwrap_or((0.0, self.bins.len() as f32)),
+            bins: self.bins,
+            mode: self.mode,
+            log_scale: self.log_scale,
+            max_value: self.max_value,
+            bar_color: self.bar_color.unwrap_or(p.accent),
+            low_color: self.low_color.unwrap_or(p.field),
+            high_color: self.high_color.unwrap_or(p.accent),
+            hovered: None,
+            readout: make_readout(font.clone()),
+            font,
+        };
+
+        ui.add_node(Box::new(histogram))
+    }
+}
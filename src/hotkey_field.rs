@@ -0,0 +1,249 @@
+use crate::{
+    core::pool::Handle,
+    widget::{
+        Widget,
+        WidgetBuilder,
+    },
+    text::{
+        Text,
+        TextBuilder,
+    },
+    border::BorderBuilder,
+    color_ext::palette,
+    keymap::{
+        Hotkey,
+        KeyChord,
+        WidgetAction,
+    },
+    event::{
+        UIEvent,
+        UIEventKind,
+        KeyCode,
+        MouseButton,
+    },
+    UserInterface,
+    UINode,
+    Thickness,
+    HitTestVisibility,
+    VerticalAlignment,
+    Control,
+    ControlTemplate,
+    UINodeContainer,
+    Builder,
+};
+use std::collections::HashMap;
+
+/// Field for control remapping screens. When field gets keyboard focus (or is clicked)
+/// it starts recording: next key chord with modifiers or gamepad button becomes its
+/// hotkey. While recording, field captures keys, so Tab and chords bound to widget
+/// actions (like Ctrl+F4) can be recorded too. Chord bound to `WidgetAction::Cancel`
+/// (Escape by default) stops recording and keeps previous hotkey.
+///
+/// # Events
+///
+/// [`HotkeyChanged`] - sent when new hotkey was recorded or set by code.
+pub struct HotkeyField {
+    widget: Widget,
+    border: Handle<UINode>,
+    text: Handle<UINode>,
+    hotkey: Option<Hotkey>,
+    is_recording: bool,
+}
+
+impl HotkeyField {
+    pub const PROMPT: &'static str = "Press a key...";
+
+    pub fn hotkey(&self) -> Option<Hotkey> {
+        self.hotkey
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.is_recording
+    }
+
+    /// Sets hotkey, None clears field.
+    pub fn set_hotkey(&mut self, ui: &mut UserInterface, hotkey: Option<Hotkey>) {
+        if self.hotkey != hotkey {
+            self.hotkey = hotkey;
+            self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::HotkeyChanged(hotkey)));
+        }
+        self.sync_text(ui, None);
+    }
+
+    /// Same as `set_hotkey`, for field that is stored in user interface.
+    pub fn set_hotkey_of(ui: &mut UserInterface, field: Handle<UINode>, hotkey: Option<Hotkey>) {
+        let text = match ui.node_mut(field).downcast_mut::<HotkeyField>() {
+            Some(field_ref) => {
+                if field_ref.hotkey != hotkey {
+                    field_ref.hotkey = hotkey;
+                    field_ref.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::HotkeyChanged(hotkey)));
+                }
+                if field_ref.is_recording {
+                    return;
+                }
+                field_ref.text
+            }
+            None => return,
+        };
+        if let Some(text_node) = ui.node_mut(text).downcast_mut::<Text>() {
+            text_node.set_text(hotkey.map_or(String::new(), |hotkey| hotkey.to_string()));
+        }
+    }
+
+    fn start_recording(&mut self, self_handle: Handle<UINode>, ui: &mut UserInterface) {
+        self.is_recording = true;
+        ui.set_key_capture(self_handle);
+        ui.node_mut(self.border).widget_mut().set_foreground(palette::current().accent);
+        self.sync_text(ui, None);
+    }
+
+    fn stop_recording(&mut self, self_handle: Handle<UINode>, ui: &mut UserInterface) {
+        self.is_recording = false;
+        if ui.key_capture() == self_handle {
+            ui.set_key_capture(Handle::NONE);
+        }
+        ui.node_mut(self.border).widget_mut().set_foreground(palette::current().frame);
+        self.sync_text(ui, None);
+    }
+
+    /// Shows hotkey, or prompt while recording. `partial` is chord of modifiers that are
+    /// held at the moment.
+    fn sync_text(&self, ui: &mut UserInterface, partial: Option<&str>) {
+        let text = if self.is_recording {
+            partial.map_or(Self::PROMPT.to_owned(), |partial| format!("{}...", partial))
+        } else {
+            self.hotkey.map_or(String::new(), |hotkey| hotkey.to_string())
+        };
+        if let Some(text_node) = ui.node_mut(self.text).downcast_mut::<Text>() {
+            text_node.set_text(text);
+        }
+    }
+}
+
+fn is_modifier(code: KeyCode) -> bool {
+    match code {
+        KeyCode::LShift | KeyCode::RShift |
+        KeyCode::LControl | KeyCode::RControl |
+        KeyCode::LAlt | KeyCode::RAlt => true,
+        _ => false
+    }
+}
+
+impl Control for HotkeyField {
+    fn widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    fn widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    fn raw_copy(&self) -> Box<dyn Control> {
+        Box::new(Self {
+            widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
+            border: self.border,
+            text: self.text,
+            hotkey: self.hotkey,
+            is_recording: false,
+        })
+    }
+
+    fn resolve(&mut self, _: &ControlTemplate, node_map: &HashMap<Handle<UINode>, Handle<UINode>>) {
+        self.border = *node_map.get(&self.border).unwrap();
+        self.text = *node_map.get(&self.text).unwrap();
+    }
+
+    fn handle_event(&mut self, self_handle: Handle<UINode>, ui: &mut UserInterface, evt: &mut UIEvent) {
+        if evt.source != self_handle {
+            return;
+        }
+
+        match evt.kind {
+            UIEventKind::GotFocus => {
+                self.start_recording(self_handle, ui);
+            }
+            UIEventKind::MouseDown { button: MouseButton::Left, .. } => {
+                if !self.is_recording {
+                    self.start_recording(self_handle, ui);
+                }
+            }
+            UIEventKind::LostFocus => {
+                self.stop_recording(self_handle, ui);
+            }
+            UIEventKind::KeyDown { code } if self.is_recording => {
+                let chord = KeyChord::with_modifiers(code, ui.keyboard_modifiers());
+                if is_modifier(code) {
+                    let partial = chord.to_string();
+                    // Drop name of modifier key itself, it is already in prefix.
+                    let partial = partial.trim_end_matches(|c| c != '+').trim_end_matches('+');
+                    self.sync_text(ui, Some(partial));
+                } else if ui.key_map().action(chord) == Some(WidgetAction::Cancel) {
+                    self.stop_recording(self_handle, ui);
+                } else {
+                    self.hotkey = Some(Hotkey::Key(chord));
+                    self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::HotkeyChanged(self.hotkey)));
+                    self.stop_recording(self_handle, ui);
+                }
+                evt.handled = true;
+            }
+            UIEventKind::GamepadButtonDown { button } if self.is_recording => {
+                self.hotkey = Some(Hotkey::GamepadButton(button));
+                self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::HotkeyChanged(self.hotkey)));
+                self.stop_recording(self_handle, ui);
+                evt.handled = true;
+            }
+            _ => ()
+        }
+    }
+}
+
+pub struct HotkeyFieldBuilder {
+    widget_builder: WidgetBuilder,
+    hotkey: Option<Hotkey>,
+}
+
+impl HotkeyFieldBuilder {
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            hotkey: None,
+        }
+    }
+
+    pub fn with_hotkey(mut self, hotkey: Hotkey) -> Self {
+        self.hotkey = Some(hotkey);
+        self
+    }
+}
+
+impl Builder for HotkeyFieldBuilder {
+    fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
+        let text = TextBuilder::new(WidgetBuilder::new()
+            .with_margin(Thickness::uniform(2.0)))
+            .with_text(&self.hotkey.map_or(String::new(), |hotkey| hotkey.to_string()))
+            .with_vertical_text_alignment(VerticalAlignment::Center)
+            .build(ui);
+
+        // Field itself must be picked by mouse to get focus on click.
+        let border = BorderBuilder::new(WidgetBuilder::new()
+            .with_hit_test_visibility(HitTestVisibility::SubtreeInvisible)
+            .with_background(palette::current().field)
+            .with_foreground(palette::current().frame)
+            .with_child(text))
+            .with_stroke_thickness(Thickness::uniform(1.0))
+            .build(ui);
+
+        let hotkey_field = HotkeyField {
+            widget: self.widget_builder
+                .with_focusable(true)
+                .with_child(border)
+                .build(),
+            border,
+            text,
+            hotkey: self.hotkey,
+            is_recording: false,
+        };
+
+        ui.add_node(Box::new(hotkey_field))
+    }
+}
@@ -4,7 +4,17 @@ use std::{
 };
 use crate::{
     core::{
-        pool::Handle
+        pool::Handle,
+        math::vec2::Vec2,
+    },
+    event::{
+        UIEvent,
+        UIEventKind,
+    },
+    resource::{
+        Placeholder,
+        ResourceState,
+        TextureRequest,
     },
     UINode,
     draw::{
@@ -17,25 +27,241 @@ use crate::{
     Control,
     ControlTemplate,
     UINodeContainer,
+    UserInterface,
     Builder,
 };
-use crate::draw::Texture;
+use crate::draw::{
+    Texture,
+    TextureBrush,
+};
+
+/// Frame of flipbook animation.
+#[derive(Clone)]
+pub struct FlipbookFrame {
+    pub brush: TextureBrush,
+    /// Time in seconds for which frame is shown.
+    pub duration: f32,
+}
+
+/// Sequence of frames with individual durations, for example frames of decoded GIF.
+/// Unlike `AnimatedSpriteBrush` frames may come from different textures and be shown
+/// for different time.
+#[derive(Clone)]
+pub struct Flipbook {
+    frames: Vec<FlipbookFrame>,
+    current: usize,
+    /// Time spent on current frame.
+    elapsed: f32,
+    looping: bool,
+    playing: bool,
+}
+
+impl Flipbook {
+    /// Creates looping flipbook which starts playing immediately.
+    pub fn new(frames: Vec<FlipbookFrame>) -> Self {
+        Self {
+            frames,
+            current: 0,
+            elapsed: 0.0,
+            looping: true,
+            playing: true,
+        }
+    }
+
+    pub fn with_looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    pub fn frames(&self) -> &[FlipbookFrame] {
+        &self.frames
+    }
+
+    pub fn play(&mut self) {
+        self.playing = true;
+    }
+
+    pub fn pause(&mut self) {
+        self.playing = false;
+    }
+
+    /// Stops playback and rewinds to first frame.
+    pub fn stop(&mut self) {
+        self.playing = false;
+        self.current = 0;
+        self.elapsed = 0.0;
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    pub fn set_looping(&mut self, looping: bool) {
+        self.looping = looping;
+    }
+
+    pub fn is_looping(&self) -> bool {
+        self.looping
+    }
+
+    pub fn current_frame(&self) -> usize {
+        self.current
+    }
+
+    pub fn set_current_frame(&mut self, frame: usize) {
+        if frame < self.frames.len() {
+            self.current = frame;
+            self.elapsed = 0.0;
+        }
+    }
+
+    /// Advances playback, returns true if current frame has changed. Non-looping
+    /// flipbook stops at last frame.
+    pub fn update(&mut self, dt: f32) -> bool {
+        if !self.playing || self.frames.is_empty() {
+            return false;
+        }
+        let old_frame = self.current;
+        self.elapsed += dt;
+        // Frames with zero duration are skipped, guard prevents endless loop when
+        // all frames have zero duration.
+        let mut guard = self.frames.len();
+        while self.elapsed >= self.frames[self.current].duration && guard > 0 {
+            self.elapsed -= self.frames[self.current].duration;
+            if self.current + 1 < self.frames.len() {
+                self.current += 1;
+            } else if self.looping {
+                self.current = 0;
+            } else {
+                self.elapsed = 0.0;
+                self.playing = false;
+                break;
+            }
+            if self.frames[self.current].duration <= 0.0 {
+                guard -= 1;
+            }
+        }
+        old_frame != self.current
+    }
+
+    pub fn current_brush(&self) -> Option<&TextureBrush> {
+        self.frames.get(self.current).map(|frame| &frame.brush)
+    }
+}
 
 pub struct Image {
     widget: Widget,
-    texture: Option<Arc<Texture>>,
+    brush: Option<TextureBrush>,
+    /// Texture which is being loaded by host, image shows placeholder until it is ready.
+    request: Option<TextureRequest>,
+    placeholder: Placeholder,
+    /// Loading has failed, placeholder is shown instead of texture.
+    failed: bool,
+    spinner_angle: f32,
+    flipbook: Option<Flipbook>,
 }
 
 impl Image {
     pub fn new(widget: Widget) -> Self {
         Self {
             widget,
-            texture: None,
+            brush: None,
+            request: None,
+            placeholder: Default::default(),
+            failed: false,
+            spinner_angle: 0.0,
+            flipbook: None,
+        }
+    }
+
+    /// Sets frames to play instead of static texture, None returns image to its texture.
+    pub fn set_flipbook(ui: &mut UserInterface, image: Handle<UINode>, flipbook: Option<Flipbook>) {
+        if let Some(image_ref) = ui.node_mut(image).downcast_mut::<Image>() {
+            let needs_update = flipbook.is_some() || image_ref.request.is_some();
+            image_ref.flipbook = flipbook;
+            ui.set_needs_update(image, needs_update);
         }
     }
 
+    pub fn flipbook(&self) -> Option<&Flipbook> {
+        self.flipbook.as_ref()
+    }
+
+    /// Returns flipbook to control playback, for example to pause or rewind it.
+    pub fn flipbook_mut(&mut self) -> Option<&mut Flipbook> {
+        self.flipbook.as_mut()
+    }
+
+    /// Shows placeholder until texture of request is loaded, then swaps texture in and
+    /// generates `ImageLoaded` event. Host should load texture on another thread and
+    /// complete request when it is done, so UI thread is never blocked by loading.
+    pub fn load_async(ui: &mut UserInterface, image: Handle<UINode>, request: TextureRequest, placeholder: Placeholder) {
+        if let Some(image_ref) = ui.node_mut(image).downcast_mut::<Image>() {
+            image_ref.brush = None;
+            image_ref.request = Some(request);
+            image_ref.placeholder = placeholder;
+            image_ref.failed = false;
+            ui.set_needs_update(image, true);
+        }
+    }
+
+    /// Returns true while texture requested by `load_async` is not loaded yet.
+    pub fn is_loading(&self) -> bool {
+        self.request.is_some()
+    }
+
+    pub fn is_failed(&self) -> bool {
+        self.failed
+    }
+
+    fn poll_request(&mut self) {
+        let state = match self.request.as_ref() {
+            Some(request) => request.state(),
+            None => return,
+        };
+        let loaded = match state {
+            ResourceState::Pending => return,
+            ResourceState::Loaded(texture) => {
+                self.brush = Some(TextureBrush::new(texture));
+                true
+            }
+            ResourceState::Failed(_) => {
+                self.failed = true;
+                false
+            }
+        };
+        self.request = None;
+        self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::ImageLoaded { success: loaded }));
+    }
+
+    fn draw_placeholder(&self, drawing_context: &mut DrawingContext) {
+        let bounds = self.widget.get_screen_bounds();
+        match self.placeholder {
+            Placeholder::Color(color) => {
+                drawing_context.push_rect_filled(&bounds, None, color);
+            }
+            Placeholder::Spinner { color, .. } => {
+                let center = Vec2::new(bounds.x + bounds.w * 0.5, bounds.y + bounds.h * 0.5);
+                let radius = bounds.w.min(bounds.h) * 0.25;
+                let thickness = (radius * 0.25).max(1.0);
+                let sweep = 1.5 * std::f32::consts::PI;
+                drawing_context.push_arc(center, radius, self.spinner_angle, sweep, thickness, 24, color);
+            }
+        }
+        drawing_context.commit(CommandKind::Geometry, CommandTexture::None);
+    }
+
     pub fn set_texture(&mut self, texture: Arc<Texture>) {
-        self.texture = Some(texture);
+        self.brush = Some(TextureBrush::new(texture));
+    }
+
+    /// Sets region of texture to show, for example sprite from `SpriteRegistry`.
+    pub fn set_brush(&mut self, brush: Option<TextureBrush>) {
+        self.brush = brush;
+    }
+
+    pub fn brush(&self) -> Option<&TextureBrush> {
+        self.brush.as_ref()
     }
 }
 
@@ -51,7 +277,12 @@ impl Control for Image {
     fn raw_copy(&self) -> Box<dyn Control> {
         Box::new(Self {
             widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
-            texture: self.texture.clone(),
+            brush: self.brush.clone(),
+            request: self.request.clone(),
+            placeholder: self.placeholder,
+            failed: self.failed,
+            spinner_angle: self.spinner_angle,
+            flipbook: self.flipbook.clone(),
         })
     }
 
@@ -59,33 +290,88 @@ impl Control for Image {
 
     fn draw(&self, drawing_context: &mut DrawingContext) {
         let bounds = self.widget.get_screen_bounds();
-        drawing_context.push_rect_filled(&bounds, None, self.widget.background());
-        let texture = self.texture.as_ref().map_or(CommandTexture::None,
-                                                   |t| { CommandTexture::Texture(t.clone()) });
-        drawing_context.commit(CommandKind::Geometry, texture);
+        if self.request.is_some() || self.failed {
+            self.draw_placeholder(drawing_context);
+        } else if let Some(brush) = self.flipbook.as_ref().and_then(|f| f.current_brush()).or_else(|| self.brush.as_ref()) {
+            drawing_context.push_rect_filled(&bounds, Some(&brush.tex_coords()), self.widget.background());
+            drawing_context.commit(CommandKind::Geometry, CommandTexture::Texture(brush.texture.clone()));
+        } else {
+            // Image without own texture can still show region of texture set as background.
+            drawing_context.push_brush_rect(&bounds, self.widget.background_texture(), self.widget.background());
+        }
+    }
+
+    fn update(&mut self, dt: f32) {
+        let reduced_motion = self.widget.reduced_motion();
+        if let Some(flipbook) = self.flipbook.as_mut() {
+            if !reduced_motion {
+                flipbook.update(dt);
+            }
+        }
+        if self.request.is_none() {
+            return;
+        }
+        if let (Placeholder::Spinner { speed, .. }, false) = (self.placeholder, reduced_motion) {
+            let full_turn = 2.0 * std::f32::consts::PI;
+            self.spinner_angle = (self.spinner_angle + full_turn * speed * dt) % full_turn;
+        }
+        self.poll_request();
+    }
+
+    fn needs_update(&self) -> bool {
+        self.request.is_some() || self.flipbook.is_some()
     }
 }
 
 pub struct ImageBuilder {
     widget_builder: WidgetBuilder,
-    texture: Option<Arc<Texture>>,
+    brush: Option<TextureBrush>,
+    request: Option<TextureRequest>,
+    placeholder: Placeholder,
+    flipbook: Option<Flipbook>,
 }
 
 impl ImageBuilder {
     pub fn new(widget_builder: WidgetBuilder) -> Self {
         Self {
             widget_builder,
-            texture: None,
+            brush: None,
+            request: None,
+            placeholder: Default::default(),
+            flipbook: None,
         }
     }
 
     pub fn with_texture(mut self, texture: Arc<Texture>) -> Self {
-        self.texture = Some(texture);
+        self.brush = Some(TextureBrush::new(texture));
         self
     }
 
     pub fn with_opt_texture(mut self, texture: Option<Arc<Texture>>) -> Self {
-        self.texture = texture;
+        self.brush = texture.map(TextureBrush::new);
+        self
+    }
+
+    /// Sets region of texture to show, for example sprite from `SpriteRegistry`.
+    pub fn with_brush(mut self, brush: TextureBrush) -> Self {
+        self.brush = Some(brush);
+        self
+    }
+
+    /// Sets texture which is loaded asynchronously, see `Image::load_async`.
+    pub fn with_request(mut self, request: TextureRequest) -> Self {
+        self.request = Some(request);
+        self
+    }
+
+    pub fn with_placeholder(mut self, placeholder: Placeholder) -> Self {
+        self.placeholder = placeholder;
+        self
+    }
+
+    /// Sets frames to play, see `Flipbook`.
+    pub fn with_flipbook(mut self, flipbook: Flipbook) -> Self {
+        self.flipbook = Some(flipbook);
         self
     }
 }
@@ -94,7 +380,12 @@ impl Builder for ImageBuilder {
     fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
         let image = Image {
             widget: self.widget_builder.build(),
-            texture: self.texture,
+            brush: self.brush,
+            request: self.request,
+            placeholder: self.placeholder,
+            failed: false,
+            spinner_angle: 0.0,
+            flipbook: self.flipbook,
         };
 
         ui.add_node(Box::new(image))
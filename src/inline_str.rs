@@ -0,0 +1,162 @@
+use std::{
+    fmt,
+    hash::{
+        Hash,
+        Hasher,
+    },
+    iter::FromIterator,
+    ops::Deref,
+    rc::Rc,
+};
+
+/// Number of bytes that are stored without heap allocation.
+const INLINE_CAPACITY: usize = 22;
+
+#[derive(Clone)]
+enum Repr {
+    Inline {
+        len: u8,
+        bytes: [u8; INLINE_CAPACITY],
+    },
+    Heap(Rc<str>),
+}
+
+/// Immutable string for event payloads. Short strings (up to 22 bytes of UTF-8) are stored
+/// inline, longer ones are shared, so neither typing in small fields nor cloning of events
+/// allocates.
+#[derive(Clone)]
+pub struct InlineStr(Repr);
+
+impl InlineStr {
+    pub fn new() -> Self {
+        Self(Repr::Inline {
+            len: 0,
+            bytes: [0; INLINE_CAPACITY],
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        match &self.0 {
+            Repr::Inline { len, bytes } => {
+                std::str::from_utf8(&bytes[..*len as usize]).expect("inline string is always valid UTF-8")
+            }
+            Repr::Heap(string) => string,
+        }
+    }
+
+    /// Returns true if string is stored without heap allocation.
+    pub fn is_inline(&self) -> bool {
+        match self.0 {
+            Repr::Inline { .. } => true,
+            Repr::Heap(_) => false,
+        }
+    }
+}
+
+impl Default for InlineStr {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for InlineStr {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for InlineStr {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl From<&str> for InlineStr {
+    fn from(string: &str) -> Self {
+        if string.len() <= INLINE_CAPACITY {
+            let mut bytes = [0; INLINE_CAPACITY];
+            bytes[..string.len()].copy_from_slice(string.as_bytes());
+            Self(Repr::Inline {
+                len: string.len() as u8,
+                bytes,
+            })
+        } else {
+            Self(Repr::Heap(Rc::from(string)))
+        }
+    }
+}
+
+impl From<String> for InlineStr {
+    fn from(string: String) -> Self {
+        if string.len() <= INLINE_CAPACITY {
+            Self::from(string.as_str())
+        } else {
+            Self(Repr::Heap(Rc::from(string)))
+        }
+    }
+}
+
+impl FromIterator<char> for InlineStr {
+    /// Collects characters into inline storage and moves them to heap only if they do
+    /// not fit.
+    fn from_iter<I: IntoIterator<Item=char>>(iter: I) -> Self {
+        let mut bytes = [0; INLINE_CAPACITY];
+        let mut len = 0;
+        let mut iter = iter.into_iter();
+        while let Some(c) = iter.next() {
+            let char_len = c.len_utf8();
+            if len + char_len > INLINE_CAPACITY {
+                let mut string = String::from(std::str::from_utf8(&bytes[..len]).expect("valid UTF-8"));
+                string.push(c);
+                string.extend(iter);
+                return Self(Repr::Heap(Rc::from(string)));
+            }
+            c.encode_utf8(&mut bytes[len..]);
+            len += char_len;
+        }
+        Self(Repr::Inline {
+            len: len as u8,
+            bytes,
+        })
+    }
+}
+
+impl PartialEq for InlineStr {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for InlineStr {}
+
+impl PartialEq<str> for InlineStr {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for InlineStr {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl Hash for InlineStr {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+impl fmt::Debug for InlineStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for InlineStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
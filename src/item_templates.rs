@@ -0,0 +1,103 @@
+use crate::{
+    core::pool::Handle,
+    UserInterface,
+    UINode,
+};
+use std::{
+    any::{
+        Any,
+        TypeId,
+    },
+    collections::HashMap,
+};
+
+/// Function that creates widget for an item.
+pub type ItemTemplate<T> = Box<dyn Fn(&mut UserInterface, &T) -> Handle<UINode>>;
+
+/// Key by which item template is selected.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TemplateKey {
+    /// Concrete type of item, used by templates of `dyn Any` items.
+    Type(TypeId),
+    /// Name returned by selector.
+    Name(String),
+}
+
+/// Set of item templates for items controls with heterogeneous items, for example outliner
+/// which shows lights, meshes and cameras with different layouts of rows. Selector maps
+/// each item to key of its template, items without template are created by fallback
+/// template. Templates of `dyn Any` items can be selected by concrete type of item, see
+/// [`by_type`](ItemTemplates::by_type).
+pub struct ItemTemplates<T: ?Sized> {
+    selector: Box<dyn Fn(&T) -> TemplateKey>,
+    templates: HashMap<TemplateKey, ItemTemplate<T>>,
+    fallback: Option<ItemTemplate<T>>,
+}
+
+impl<T: ?Sized> ItemTemplates<T> {
+    /// Creates empty set of templates which are selected by name returned by selector.
+    pub fn with_selector<S>(selector: S) -> Self
+        where S: Fn(&T) -> String + 'static {
+        Self {
+            selector: Box::new(move |item| TemplateKey::Name(selector(item))),
+            templates: HashMap::new(),
+            fallback: None,
+        }
+    }
+
+    /// Registers template for items for which selector returns given name.
+    pub fn with_template<F>(mut self, name: &str, template: F) -> Self
+        where F: Fn(&mut UserInterface, &T) -> Handle<UINode> + 'static {
+        self.templates.insert(TemplateKey::Name(name.to_owned()), Box::new(template));
+        self
+    }
+
+    /// Sets template for items which have no registered template.
+    pub fn with_fallback<F>(mut self, template: F) -> Self
+        where F: Fn(&mut UserInterface, &T) -> Handle<UINode> + 'static {
+        self.fallback = Some(Box::new(template));
+        self
+    }
+
+    /// Returns key of template for given item.
+    pub fn select(&self, item: &T) -> TemplateKey {
+        (self.selector)(item)
+    }
+
+    pub fn has_template(&self, key: &TemplateKey) -> bool {
+        self.templates.contains_key(key)
+    }
+
+    /// Creates widget for item with its template, returns NONE if there is no template for
+    /// the item and no fallback template.
+    pub fn create(&self, ui: &mut UserInterface, item: &T) -> Handle<UINode> {
+        match self.templates.get(&self.select(item)).or_else(|| self.fallback.as_ref()) {
+            Some(template) => template(ui, item),
+            None => Handle::NONE,
+        }
+    }
+}
+
+impl ItemTemplates<dyn Any> {
+    /// Creates empty set of templates which are selected by concrete type of item.
+    pub fn by_type() -> Self {
+        Self {
+            selector: Box::new(|item| TemplateKey::Type(Any::type_id(item))),
+            templates: HashMap::new(),
+            fallback: None,
+        }
+    }
+
+    /// Registers template for items of type `U`.
+    pub fn with_type_template<U, F>(mut self, template: F) -> Self
+        where U: Any,
+              F: Fn(&mut UserInterface, &U) -> Handle<UINode> + 'static {
+        self.templates.insert(TemplateKey::Type(TypeId::of::<U>()), Box::new(move |ui, item: &dyn Any| {
+            match item.downcast_ref::<U>() {
+                Some(item) => template(ui, item),
+                None => Handle::NONE,
+            }
+        }));
+        self
+    }
+}
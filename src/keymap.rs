@@ -0,0 +1,206 @@
+use crate::event::KeyCode;
+use std::{
+    collections::HashMap,
+    fmt,
+};
+
+/// State of modifier keys.
+#[derive(Copy, Clone, Default, Debug, Hash, PartialEq, Eq)]
+pub struct KeyModifiers {
+    pub shift: bool,
+    pub control: bool,
+    pub alt: bool,
+}
+
+impl KeyModifiers {
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    pub fn control() -> Self {
+        Self { control: true, ..Self::default() }
+    }
+
+    pub fn shift() -> Self {
+        Self { shift: true, ..Self::default() }
+    }
+
+    pub fn alt() -> Self {
+        Self { alt: true, ..Self::default() }
+    }
+}
+
+/// Key with modifiers which must be held while key is pressed.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    pub fn new(code: KeyCode) -> Self {
+        Self { code, modifiers: KeyModifiers::none() }
+    }
+
+    pub fn with_modifiers(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        Self { code, modifiers }
+    }
+}
+
+impl fmt::Display for KeyChord {
+    /// Formats chord as `Ctrl+Shift+A`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.control {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.alt {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.shift {
+            write!(f, "Shift+")?;
+        }
+        let name = format!("{:?}", self.code);
+        // Digit keys are named Key0..Key9.
+        match name.strip_prefix("Key") {
+            Some(digit) if !digit.is_empty() => write!(f, "{}", digit),
+            _ => write!(f, "{}", name),
+        }
+    }
+}
+
+/// Input that can be bound to a game action: key chord or gamepad button.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub enum Hotkey {
+    Key(KeyChord),
+    GamepadButton(u32),
+}
+
+impl fmt::Display for Hotkey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Hotkey::Key(chord) => write!(f, "{}", chord),
+            Hotkey::GamepadButton(button) => write!(f, "Gamepad {}", button),
+        }
+    }
+}
+
+/// Logical actions of built-in widgets that can be triggered from keyboard.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub enum WidgetAction {
+    /// Moves caret of text box one line up, or selection of list one item up.
+    CaretUp,
+    /// Moves caret of text box one line down, or selection of list one item down.
+    CaretDown,
+    /// Moves caret of text box one character left.
+    CaretLeft,
+    /// Moves caret of text box one character right.
+    CaretRight,
+    /// Moves caret of text box to the beginning of previous word.
+    CaretWordLeft,
+    /// Moves caret of text box to the beginning of next word.
+    CaretWordRight,
+    /// Removes character right to the caret of text box.
+    DeleteForward,
+    /// Removes character left to the caret of text box.
+    DeleteBackward,
+    /// Toggles overwrite mode of text box.
+    ToggleOverwrite,
+    /// Commits value of numeric text box.
+    Commit,
+    /// Discards edits of numeric text box.
+    Cancel,
+    /// Scrolls scroll viewer one page up, or moves selection of list one page up.
+    PageUp,
+    /// Scrolls scroll viewer one page down, or moves selection of list one page down.
+    PageDown,
+    /// Scrolls scroll viewer to the beginning of content.
+    ScrollToStart,
+    /// Scrolls scroll viewer to the end of content.
+    ScrollToEnd,
+    /// Closes window that contains focused node.
+    WindowClose,
+    /// Selects first item of list.
+    SelectFirst,
+    /// Selects last item of list.
+    SelectLast,
+    /// Selects all items of list which allows multiple selection.
+    SelectAll,
+    /// Starts editing of current cell of data grid.
+    BeginEdit,
+}
+
+/// Maps key chords to logical widget actions. Built-in widgets ask user interface for
+/// action of pressed key instead of checking key codes directly, so applications can
+/// rebind or disable default keys.
+pub struct KeyMap {
+    bindings: HashMap<KeyChord, WidgetAction>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        let mut key_map = Self::empty();
+        key_map
+            .bind(KeyChord::new(KeyCode::Up), WidgetAction::CaretUp)
+            .bind(KeyChord::new(KeyCode::Down), WidgetAction::CaretDown)
+            .bind(KeyChord::new(KeyCode::Left), WidgetAction::CaretLeft)
+            .bind(KeyChord::new(KeyCode::Right), WidgetAction::CaretRight)
+            .bind(KeyChord::with_modifiers(KeyCode::Left, KeyModifiers::control()), WidgetAction::CaretWordLeft)
+            .bind(KeyChord::with_modifiers(KeyCode::Right, KeyModifiers::control()), WidgetAction::CaretWordRight)
+            .bind(KeyChord::new(KeyCode::Delete), WidgetAction::DeleteForward)
+            .bind(KeyChord::new(KeyCode::Backspace), WidgetAction::DeleteBackward)
+            .bind(KeyChord::new(KeyCode::Insert), WidgetAction::ToggleOverwrite)
+            .bind(KeyChord::new(KeyCode::Return), WidgetAction::Commit)
+            .bind(KeyChord::new(KeyCode::NumpadEnter), WidgetAction::Commit)
+            .bind(KeyChord::new(KeyCode::Escape), WidgetAction::Cancel)
+            .bind(KeyChord::new(KeyCode::PageUp), WidgetAction::PageUp)
+            .bind(KeyChord::new(KeyCode::PageDown), WidgetAction::PageDown)
+            .bind(KeyChord::with_modifiers(KeyCode::Home, KeyModifiers::control()), WidgetAction::ScrollToStart)
+            .bind(KeyChord::with_modifiers(KeyCode::End, KeyModifiers::control()), WidgetAction::ScrollToEnd)
+            .bind(KeyChord::with_modifiers(KeyCode::F4, KeyModifiers::control()), WidgetAction::WindowClose)
+            .bind(KeyChord::new(KeyCode::Home), WidgetAction::SelectFirst)
+            .bind(KeyChord::new(KeyCode::End), WidgetAction::SelectLast)
+            .bind(KeyChord::with_modifiers(KeyCode::A, KeyModifiers::control()), WidgetAction::SelectAll)
+            .bind(KeyChord::new(KeyCode::F2), WidgetAction::BeginEdit);
+        key_map
+    }
+}
+
+impl KeyMap {
+    /// Creates key map without any bindings, all keyboard actions of widgets are disabled.
+    pub fn empty() -> Self {
+        Self {
+            bindings: Default::default(),
+        }
+    }
+
+    /// Binds chord to action, previous binding of the chord is replaced. Action can have
+    /// any number of chords.
+    pub fn bind(&mut self, chord: KeyChord, action: WidgetAction) -> &mut Self {
+        self.bindings.insert(chord, action);
+        self
+    }
+
+    pub fn unbind(&mut self, chord: KeyChord) -> &mut Self {
+        self.bindings.remove(&chord);
+        self
+    }
+
+    /// Removes all chords of given action, so action cannot be triggered from keyboard.
+    pub fn unbind_action(&mut self, action: WidgetAction) -> &mut Self {
+        self.bindings.retain(|_, bound| *bound != action);
+        self
+    }
+
+    pub fn action(&self, chord: KeyChord) -> Option<WidgetAction> {
+        self.bindings.get(&chord).cloned()
+    }
+
+    /// Returns all chords bound to given action.
+    pub fn chords(&self, action: WidgetAction) -> Vec<KeyChord> {
+        self.bindings
+            .iter()
+            .filter(|(_, bound)| **bound == action)
+            .map(|(chord, _)| *chord)
+            .collect()
+    }
+}
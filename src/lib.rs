@@ -6,11 +6,34 @@ extern crate downcast_rs;
 pub use rg3d_core as core;
 
 pub mod draw;
+pub mod raster;
+pub mod color_ext;
 pub mod text;
 pub mod border;
+pub mod decorator;
 pub mod image;
+pub mod resource;
+pub mod worker;
+pub mod symbol_icon;
+pub mod sprite_atlas;
 pub mod canvas;
+pub mod world_marker_layer;
+pub mod floating_text_layer;
+pub mod cooldown_overlay;
+pub mod loading_screen;
+pub mod histogram;
+pub mod waveform;
+pub mod reticle;
+pub mod compass_bar;
+pub mod anchor_panel;
+pub mod dock_panel;
+pub mod rubber_band;
+pub mod gizmo;
+pub mod design_mode;
+pub mod zoom_panel;
+pub mod ruler;
 pub mod event;
+pub mod inline_str;
 pub mod button;
 pub mod scroll_bar;
 pub mod scroll_content_presenter;
@@ -20,11 +43,32 @@ pub mod window;
 pub mod formatted_text;
 pub mod widget;
 pub mod list_box;
+pub mod collection_view;
+pub mod item_templates;
+pub mod tree;
+pub mod header_row;
+pub mod data_grid;
+pub mod formula_bar;
 pub mod stack_panel;
 pub mod text_box;
+pub mod numeric_text_box;
+pub mod masked_text_box;
+pub mod chat_box;
+pub mod calendar;
+pub mod hotkey_field;
 pub mod check_box;
 pub mod style;
 pub mod tab_control;
+pub mod wizard;
+pub mod form;
+pub mod settings_view;
+pub mod status_bar;
+pub mod toolbar;
+pub mod taskbar;
+pub mod keymap;
+pub mod sound;
+pub mod haptics;
+pub mod swatch_sheet;
 pub mod ttf;
 
 use std::{
@@ -32,12 +76,16 @@ use std::{
         VecDeque,
         HashMap,
     },
-    any::Any,
+    any::{
+        Any,
+        TypeId,
+    },
     sync::{
         Arc,
         Mutex,
     },
     rc::Rc,
+    cell::RefCell,
 };
 use crate::{
     core::{
@@ -55,17 +103,45 @@ use crate::{
         DrawingContext,
         CommandKind,
         CommandTexture,
+        AnimatedSpriteBrush,
     },
     canvas::Canvas,
+    window::{
+        Window,
+        WindowLayout,
+        WorkspaceLayout,
+    },
     event::{
         UIEvent,
         UIEventKind,
+        TimerId,
     },
     style::Style,
-    widget::Widget,
+    widget::{
+        Widget,
+        WidgetBuilder,
+        AttachedPropertyCloner,
+        clone_attached_property,
+    },
     ttf::Font,
+    grid::SharedSizeGroups,
+    dock_panel::{
+        Dock,
+        DockPanel,
+    },
+    anchor_panel::{
+        Anchor,
+        AnchorPanel,
+    },
+    toolbar::Toolbar,
 };
-use crate::event::{OsEvent, ButtonState};
+use crate::event::{OsEvent, ButtonState, KeyCode, MouseButton};
+use crate::keymap::{KeyMap, KeyChord, KeyModifiers, WidgetAction};
+use crate::symbol_icon::IconRegistry;
+use crate::sprite_atlas::SpriteRegistry;
+use crate::sound::{UiSound, UiSoundHandler};
+use crate::haptics::{HapticFeedback, HapticHandler};
+use crate::raster::CpuImage;
 
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub enum HorizontalAlignment {
@@ -117,6 +193,192 @@ impl Thickness {
     }
 }
 
+/// Transform that is applied to a widget before layout, so parent panel will reserve
+/// space for transformed bounds of the widget. For example text rotated by 90 degrees
+/// will occupy tall and narrow slot in a stack panel. Transform is applied around
+/// center of widget bounds and affects drawing and hit testing of widget and all its
+/// descendants.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct LayoutTransform {
+    /// Rotation angle in radians, clockwise.
+    pub rotation: f32,
+    pub scale: Vec2,
+}
+
+impl Default for LayoutTransform {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+/// Multiplies given value by absolute value of a coefficient, treats tiny coefficients
+/// as zero so infinite sizes will not turn into NaN or infinity because of rounding
+/// errors of trigonometric functions.
+fn mul_abs(k: f32, v: f32) -> f32 {
+    if k.abs() < 0.00001 {
+        0.0
+    } else {
+        k.abs() * v
+    }
+}
+
+impl LayoutTransform {
+    pub fn identity() -> Self {
+        Self { rotation: 0.0, scale: Vec2::new(1.0, 1.0) }
+    }
+
+    pub fn rotation(angle: f32) -> Self {
+        Self { rotation: angle, scale: Vec2::new(1.0, 1.0) }
+    }
+
+    pub fn scale(scale: Vec2) -> Self {
+        Self { rotation: 0.0, scale }
+    }
+
+    pub fn is_identity(&self) -> bool {
+        *self == Self::identity()
+    }
+
+    /// Scales and then rotates given vector.
+    pub fn transform_vector(&self, v: Vec2) -> Vec2 {
+        let (sin, cos) = self.rotation.sin_cos();
+        let x = v.x * self.scale.x;
+        let y = v.y * self.scale.y;
+        Vec2::new(x * cos - y * sin, x * sin + y * cos)
+    }
+
+    /// Returns size of axis-aligned bounds of a rectangle with given size after transformation.
+    pub fn bounds_size(&self, size: Vec2) -> Vec2 {
+        let (sin, cos) = self.rotation.sin_cos();
+        let w = size.x * self.scale.x.abs();
+        let h = size.y * self.scale.y.abs();
+        Vec2::new(mul_abs(cos, w) + mul_abs(sin, h), mul_abs(sin, w) + mul_abs(cos, h))
+    }
+
+    /// Returns size of a rectangle which will fit into given size after transformation.
+    /// It is exact for rotations by multiples of 90 degrees, for other angles size will
+    /// be bigger than needed.
+    pub fn inverse_bounds_size(&self, size: Vec2) -> Vec2 {
+        let (sin, cos) = self.rotation.sin_cos();
+        let w = mul_abs(cos, size.x) + mul_abs(sin, size.y);
+        let h = mul_abs(sin, size.x) + mul_abs(cos, size.y);
+        Vec2::new(w / self.scale.x.abs(), h / self.scale.y.abs())
+    }
+}
+
+/// Transform which is applied to a widget and its descendants after layout, so it does
+/// not affect layout of the widget and its siblings. Transform is applied relative to top
+/// left corner of widget bounds: content is scaled first and then moved by translation.
+/// Affects drawing and hit testing, but not screen positions of descendants.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct RenderTransform {
+    pub scale: Vec2,
+    pub translation: Vec2,
+}
+
+impl Default for RenderTransform {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl RenderTransform {
+    pub fn identity() -> Self {
+        Self { scale: Vec2::new(1.0, 1.0), translation: Vec2::ZERO }
+    }
+
+    pub fn new(scale: Vec2, translation: Vec2) -> Self {
+        Self { scale, translation }
+    }
+
+    pub fn is_identity(&self) -> bool {
+        *self == Self::identity()
+    }
+
+    /// Transforms point in coordinates relative to origin of transform.
+    pub fn transform_point(&self, p: Vec2) -> Vec2 {
+        Vec2::new(p.x * self.scale.x + self.translation.x, p.y * self.scale.y + self.translation.y)
+    }
+
+    /// Converts transformed point back, returns point as is if scale is degenerate.
+    pub fn inverse_transform_point(&self, p: Vec2) -> Vec2 {
+        if self.scale.x == 0.0 || self.scale.y == 0.0 {
+            return p;
+        }
+        Vec2::new((p.x - self.translation.x) / self.scale.x, (p.y - self.translation.y) / self.scale.y)
+    }
+}
+
+/// Defines how widget and its descendants take part in hit testing.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum HitTestVisibility {
+    /// Widget and its descendants can be picked.
+    Visible,
+    /// Widget itself is transparent for mouse, but its descendants can be picked. Useful
+    /// for overlay decorations that must pass clicks to content beneath them.
+    SelfInvisible,
+    /// Neither widget nor its descendants can be picked.
+    SubtreeInvisible,
+}
+
+impl Default for HitTestVisibility {
+    fn default() -> Self {
+        HitTestVisibility::Visible
+    }
+}
+
+/// Shape which is used to check whether a point is inside of a widget. All shapes are
+/// defined in normalized coordinates of widget bounds, where (0, 0) is top left corner and
+/// (1, 1) is bottom right corner, so shapes follow size of widget. Shapes other than
+/// `Geometry` ignore render and layout transforms of widget.
+#[derive(Clone)]
+pub enum HitTestShape {
+    /// Point must be inside of geometry drawn by widget, this is default shape.
+    Geometry,
+    /// Ellipse inscribed into widget bounds, useful for round buttons.
+    Ellipse,
+    /// Arbitrary polygon in normalized coordinates.
+    Polygon(Vec<Vec2>),
+    /// Callback that receives point in normalized coordinates, for example to check alpha
+    /// channel of image at given point.
+    Custom(Rc<dyn Fn(Vec2) -> bool>),
+}
+
+impl Default for HitTestShape {
+    fn default() -> Self {
+        HitTestShape::Geometry
+    }
+}
+
+impl HitTestShape {
+    /// Checks if point in normalized coordinates is inside of shape. Always returns true
+    /// for `Geometry` shape, because it is checked by drawing context.
+    pub fn contains_point(&self, p: Vec2) -> bool {
+        match self {
+            HitTestShape::Geometry => true,
+            HitTestShape::Ellipse => {
+                let dx = p.x - 0.5;
+                let dy = p.y - 0.5;
+                dx * dx + dy * dy <= 0.25
+            }
+            HitTestShape::Polygon(points) => {
+                // Even-odd rule.
+                let mut inside = false;
+                let mut j = points.len().wrapping_sub(1);
+                for (i, a) in points.iter().enumerate() {
+                    let b = points[j];
+                    if (a.y > p.y) != (b.y > p.y) && p.x < (b.x - a.x) * (p.y - a.y) / (b.y - a.y) + a.x {
+                        inside = !inside;
+                    }
+                    j = i;
+                }
+                inside
+            }
+            HitTestShape::Custom(func) => func(p),
+        }
+    }
+}
+
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub enum Visibility {
     Visible,
@@ -196,6 +458,12 @@ pub trait Control: downcast_rs::Downcast {
             size.y = minf(size.y, widget.desired_size.get().y - margin_y);
         }
 
+        // Size in parent's space must be converted to local space of transformed widget.
+        let transform = widget.layout_transform;
+        if !transform.is_identity() {
+            size = transform.inverse_bounds_size(size);
+        }
+
         if widget.width.get() > 0.0 {
             size.x = widget.width.get();
         }
@@ -203,7 +471,13 @@ pub trait Control: downcast_rs::Downcast {
             size.y = widget.height.get();
         }
 
-        size = self.arrange_override(ui, size);
+        let local_size = self.arrange_override(ui, size);
+
+        size = if transform.is_identity() {
+            local_size
+        } else {
+            transform.bounds_size(local_size)
+        };
 
         if size.x > final_rect.w {
             size.x = final_rect.w;
@@ -232,8 +506,17 @@ pub trait Control: downcast_rs::Downcast {
             _ => ()
         }
 
-        widget.actual_size.set(size);
-        widget.actual_local_position.set(Vec2 { x: origin_x, y: origin_y });
+        if transform.is_identity() {
+            widget.actual_size.set(ui.snap_layout(size));
+            widget.actual_local_position.set(ui.snap_layout(Vec2 { x: origin_x, y: origin_y }));
+        } else {
+            // Untransformed bounds are centered in the slot, transform is applied around center.
+            widget.actual_size.set(ui.snap_layout(local_size));
+            widget.actual_local_position.set(ui.snap_layout(Vec2 {
+                x: origin_x + (size.x - local_size.x) * 0.5,
+                y: origin_y + (size.y - local_size.y) * 0.5,
+            }));
+        }
         widget.arrange_valid.set(true);
     }
 
@@ -244,12 +527,20 @@ pub trait Control: downcast_rs::Downcast {
             y: widget.margin.top + widget.margin.bottom,
         };
 
+        let mut available_local_size = Vec2 {
+            x: maxf(0.0, available_size.x - margin.x),
+            y: maxf(0.0, available_size.y - margin.y),
+        };
+        if !widget.layout_transform.is_identity() {
+            available_local_size = widget.layout_transform.inverse_bounds_size(available_local_size);
+        }
+
         let size_for_child = Vec2 {
             x: {
                 let w = if widget.width.get() > 0.0 {
                     widget.width.get()
                 } else {
-                    maxf(0.0, available_size.x - margin.x)
+                    available_local_size.x
                 };
 
                 if w > widget.max_size.x {
@@ -264,7 +555,7 @@ pub trait Control: downcast_rs::Downcast {
                 let h = if widget.height.get() > 0.0 {
                     widget.height.get()
                 } else {
-                    maxf(0.0, available_size.y - margin.y)
+                    available_local_size.y
                 };
 
                 if h > widget.max_size.y {
@@ -300,6 +591,10 @@ pub trait Control: downcast_rs::Downcast {
                 desired_size.y = widget.height.get();
             }
 
+            if !widget.layout_transform.is_identity() {
+                desired_size = widget.layout_transform.bounds_size(desired_size);
+            }
+
             desired_size += margin;
 
             // Make sure that node won't go outside of available bounds.
@@ -310,7 +605,7 @@ pub trait Control: downcast_rs::Downcast {
                 desired_size.y = available_size.y;
             }
 
-            widget.desired_size.set(desired_size);
+            widget.desired_size.set(ui.snap_layout(desired_size));
         } else {
             widget.desired_size.set(Vec2::new(0.0, 0.0));
         }
@@ -320,8 +615,20 @@ pub trait Control: downcast_rs::Downcast {
 
     fn draw(&self, _drawing_context: &mut DrawingContext) {}
 
+    /// Called after children of node were drawn, allows to draw overlays over content.
+    fn post_draw(&self, _drawing_context: &mut DrawingContext) {}
+
+    /// Called every frame only for nodes which returned `true` from `needs_update` when
+    /// they were added to user interface, or which were registered by
+    /// `UserInterface::set_needs_update`.
     fn update(&mut self, _dt: f32) {}
 
+    /// Should return `true` if node has some animation or other time-dependent logic in
+    /// `update`. Most widgets are static and do not need to be visited every frame.
+    fn needs_update(&self) -> bool {
+        false
+    }
+
     fn set_property(&mut self, _name: &str, _value: &dyn Any) {}
 
     fn get_property(&self, _name: &str) -> Option<&'_ dyn Any> {
@@ -337,6 +644,17 @@ pub trait Control: downcast_rs::Downcast {
     /// to check if event came from/for this node or to capture input on node.
     fn handle_event(&mut self, _self_handle: Handle<UINode>, _ui: &mut UserInterface, _evt: &mut UIEvent) {}
 
+    /// Called once when node was added to user interface, at the beginning of next
+    /// `UserInterface::update`. At this moment whole tree of node is built and handles
+    /// of template nodes are resolved. Same restrictions as for `handle_event` apply to
+    /// `self_handle`.
+    fn on_attached_to_ui(&mut self, _self_handle: Handle<UINode>, _ui: &mut UserInterface) {}
+
+    /// Called when node was removed from user interface by `UserInterface::remove_node`.
+    /// Node is already out of pool at this moment and will be destroyed right after
+    /// this call.
+    fn on_removed(&mut self, _self_handle: Handle<UINode>, _ui: &mut UserInterface) {}
+
     fn apply_style(&mut self, style: Rc<Style>) {
         // Apply base style first.
         if let Some(base_style) = style.base_style() {
@@ -350,6 +668,14 @@ pub trait Control: downcast_rs::Downcast {
         for setter in style.setters() {
             self.set_property(setter.name(), setter.value());
         }
+
+        // State setters go last so they override usual ones.
+        let states = self.widget().states.clone();
+        for state in states.iter() {
+            for setter in style.state_setters(state) {
+                self.set_property(setter.name(), setter.value());
+            }
+        }
     }
 }
 
@@ -357,6 +683,49 @@ impl_downcast!(Control);
 
 pub type UINode = Box<dyn Control>;
 
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum AnnouncementPriority {
+    /// Announcement should be spoken when screen reader is idle.
+    Polite,
+    /// Announcement should interrupt current speech, pending polite announcements are
+    /// dropped.
+    Assertive,
+}
+
+/// Text which should be read by screen reader, announcements are collected by user
+/// interface and then retrieved by accessibility bridge of host application.
+#[derive(Clone, Debug)]
+pub struct Announcement {
+    pub text: String,
+    pub priority: AnnouncementPriority,
+}
+
+/// Mouse button press that can turn into drag.
+struct MousePress {
+    node: Handle<UINode>,
+    pos: Vec2,
+    button: MouseButton,
+    drag_started: bool,
+}
+
+/// Key which is held down and repeated by user interface.
+struct KeyRepeat {
+    code: KeyCode,
+    /// Character that was entered by the key, it is repeated together with key.
+    symbol: Option<char>,
+    elapsed: f32,
+    repeating: bool,
+}
+
+struct Timer {
+    id: TimerId,
+    node: Handle<UINode>,
+    /// Interval in seconds.
+    interval: f32,
+    elapsed: f32,
+    repeating: bool,
+}
+
 pub struct UserInterface {
     nodes: Pool<UINode>,
     drawing_context: DrawingContext,
@@ -370,6 +739,56 @@ pub struct UserInterface {
     mouse_position: Vec2,
     events: VecDeque<UIEvent>,
     stack: Vec<Handle<UINode>>,
+    pub(in crate) shared_size_groups: RefCell<SharedSizeGroups>,
+    /// Nodes that were added since last update and waiting for `on_attached_to_ui` call.
+    attached_nodes: Vec<Handle<UINode>>,
+    timers: Vec<Timer>,
+    next_timer_id: u64,
+    /// Nodes which `update` method will be called every frame.
+    updatable_nodes: Vec<Handle<UINode>>,
+    /// Nodes which background animation is advanced every frame.
+    animated_nodes: Vec<Handle<UINode>>,
+    /// Insets from screen edges for TV overscan or phone notches.
+    safe_area_inset: Thickness,
+    /// Layer canvases in order of creation, draw order is defined by order of children
+    /// of root canvas.
+    layers: Vec<Handle<UINode>>,
+    /// Last focused node of each focus scope.
+    focus_scope_memory: HashMap<Handle<UINode>, Handle<UINode>>,
+    active_window: Handle<UINode>,
+    keyboard_modifiers: KeyModifiers,
+    key_map: KeyMap,
+    /// Focused node which receives all keys as is, see `set_key_capture`.
+    key_capture_node: Handle<UINode>,
+    mouse_press: Option<MousePress>,
+    /// Distance in pixels which mouse should pass with pressed button to start drag.
+    drag_threshold: f32,
+    /// Initial delay and interval of synthesized key repeats, None if host delivers
+    /// repeats by itself.
+    key_repeat_settings: Option<(f32, f32)>,
+    key_repeat: Option<KeyRepeat>,
+    announcements: VecDeque<Announcement>,
+    /// Named icons of icon fonts shared by all widgets.
+    icons: IconRegistry,
+    /// Named regions of texture atlases.
+    sprites: SpriteRegistry,
+    /// User-defined scale of whole interface, see `set_ui_scale`.
+    ui_scale: f32,
+    /// Number of fractional bits of fixed-point layout, see `set_layout_precision`.
+    layout_precision: Option<u32>,
+    reduced_motion: bool,
+    sound_handler: Option<Box<dyn UiSoundHandler>>,
+    haptic_handler: Option<Box<dyn HapticHandler>>,
+    /// Last input came from gamepad, haptic feedback is given only in this case.
+    gamepad_active: bool,
+    /// Released nodes by their types, see `spawn_from_pool`.
+    widget_pool: HashMap<TypeId, Vec<Handle<UINode>>>,
+    /// Font of text widgets that were built without explicit font.
+    default_font: Option<Arc<Mutex<Font>>>,
+    /// Headless interface does not draw and hit tests by bounds, see `headless`.
+    headless: bool,
+    /// Dispatched events and layout changes are written to log, see `set_event_tracing`.
+    event_tracing: bool,
 }
 
 #[inline]
@@ -398,8 +817,29 @@ lazy_static! {
     };
 }
 
+/// Attached properties of built-in panels, so styles and templates can set them by name.
+fn builtin_attached_properties() -> Vec<(&'static str, AttachedPropertyCloner)> {
+    vec![
+        (DockPanel::DOCK, clone_attached_property::<Dock> as AttachedPropertyCloner),
+        (AnchorPanel::ANCHOR, clone_attached_property::<Anchor>),
+        (Toolbar::TOGGLE_GROUP, clone_attached_property::<String>),
+    ]
+}
+
 impl UserInterface {
     pub fn new() -> UserInterface {
+        Self::with_mode(Some(DEFAULT_FONT.clone()), false)
+    }
+
+    /// Creates user interface that does not need fonts and renderer, it is intended for
+    /// tests of layout and event logic. Text widgets built without explicit font have no
+    /// glyphs, `draw` produces no commands and hit testing uses screen bounds of widgets
+    /// instead of drawn geometry, so input events can be simulated right after `update`.
+    pub fn headless() -> UserInterface {
+        Self::with_mode(None, true)
+    }
+
+    fn with_mode(default_font: Option<Arc<Mutex<Font>>>, headless: bool) -> UserInterface {
         let mut ui = UserInterface {
             events: VecDeque::new(),
             visual_debug: false,
@@ -412,65 +852,1093 @@ impl UserInterface {
             prev_picked_node: Handle::NONE,
             keyboard_focus_node: Handle::NONE,
             stack: Default::default(),
+            shared_size_groups: Default::default(),
+            attached_nodes: Default::default(),
+            timers: Default::default(),
+            next_timer_id: 0,
+            updatable_nodes: Default::default(),
+            animated_nodes: Default::default(),
+            safe_area_inset: Thickness::zero(),
+            layers: Default::default(),
+            focus_scope_memory: Default::default(),
+            active_window: Handle::NONE,
+            keyboard_modifiers: Default::default(),
+            key_map: Default::default(),
+            key_capture_node: Handle::NONE,
+            mouse_press: None,
+            drag_threshold: 4.0,
+            key_repeat_settings: None,
+            key_repeat: None,
+            announcements: Default::default(),
+            icons: Default::default(),
+            sprites: Default::default(),
+            ui_scale: 1.0,
+            layout_precision: None,
+            reduced_motion: false,
+            sound_handler: None,
+            haptic_handler: None,
+            gamepad_active: false,
+            widget_pool: Default::default(),
+            default_font,
+            headless,
+            event_tracing: false,
         };
         ui.root_canvas = ui.add_node(Box::new(Canvas::new(Widget::default())));
         ui
     }
 
-    #[inline]
-    pub fn capture_mouse(&mut self, node: Handle<UINode>) -> bool {
-        if self.captured_node.is_none() {
-            self.captured_node = node;
-            true
+    #[inline]
+    pub fn is_headless(&self) -> bool {
+        self.headless
+    }
+
+    /// Sets font of text widgets which will be built without explicit font, None means
+    /// that such widgets will have no glyphs. Already built widgets keep their font.
+    pub fn set_default_font(&mut self, font: Option<Arc<Mutex<Font>>>) {
+        self.default_font = font;
+    }
+
+    #[inline]
+    pub fn capture_mouse(&mut self, node: Handle<UINode>) -> bool {
+        if self.captured_node.is_none() {
+            self.captured_node = node;
+            true
+        } else {
+            false
+        }
+    }
+
+    #[inline]
+    pub fn release_mouse_capture(&mut self) {
+        self.captured_node = Handle::NONE;
+    }
+
+    /// Queues text for screen reader, see `AnnouncementPriority` for details.
+    pub fn announce(&mut self, text: &str, priority: AnnouncementPriority) {
+        if priority == AnnouncementPriority::Assertive {
+            self.announcements.retain(|announcement| announcement.priority == AnnouncementPriority::Assertive);
+        }
+        self.announcements.push_back(Announcement {
+            text: text.to_owned(),
+            priority,
+        });
+    }
+
+    /// Returns next announcement, should be called by accessibility bridge every frame
+    /// until it returns None.
+    pub fn poll_announcement(&mut self) -> Option<Announcement> {
+        self.announcements.pop_front()
+    }
+
+    #[inline]
+    pub fn keyboard_modifiers(&self) -> KeyModifiers {
+        self.keyboard_modifiers
+    }
+
+    #[inline]
+    pub fn key_map(&self) -> &KeyMap {
+        &self.key_map
+    }
+
+    /// Returns key map which can be used to rebind or disable keys of built-in widgets.
+    #[inline]
+    pub fn key_map_mut(&mut self) -> &mut KeyMap {
+        &mut self.key_map
+    }
+
+    #[inline]
+    pub fn icons(&self) -> &IconRegistry {
+        &self.icons
+    }
+
+    /// Returns registry of icon fonts and named icons, see `SymbolIcon`.
+    #[inline]
+    pub fn icons_mut(&mut self) -> &mut IconRegistry {
+        &mut self.icons
+    }
+
+    #[inline]
+    pub fn sprites(&self) -> &SpriteRegistry {
+        &self.sprites
+    }
+
+    /// Returns registry of named sprites of texture atlases.
+    #[inline]
+    pub fn sprites_mut(&mut self) -> &mut SpriteRegistry {
+        &mut self.sprites
+    }
+
+    /// Returns action bound to given key with currently pressed modifiers. Returns None
+    /// while some node captures keys.
+    pub fn key_action(&self, code: KeyCode) -> Option<WidgetAction> {
+        if self.key_capture_node.is_some() {
+            return None;
+        }
+        self.key_map.action(KeyChord::with_modifiers(code, self.keyboard_modifiers))
+    }
+
+    /// Makes focused node receive all keys as is: Tab does not move focus and key
+    /// actions of widgets are disabled, so any chord can be recorded. Capture is
+    /// released when node loses focus, pass NONE to release it explicitly.
+    pub fn set_key_capture(&mut self, node: Handle<UINode>) {
+        self.key_capture_node = if node == self.keyboard_focus_node {
+            node
+        } else {
+            Handle::NONE
+        };
+    }
+
+    #[inline]
+    pub fn key_capture(&self) -> Handle<UINode> {
+        self.key_capture_node
+    }
+
+    #[inline]
+    pub fn mouse_position(&self) -> Vec2 {
+        self.mouse_position
+    }
+
+    /// Sets distance in pixels which mouse should pass with pressed button before
+    /// `DragStarted` event is sent, default is 4 pixels.
+    #[inline]
+    pub fn set_drag_threshold(&mut self, threshold: f32) {
+        self.drag_threshold = threshold;
+    }
+
+    #[inline]
+    pub fn drag_threshold(&self) -> f32 {
+        self.drag_threshold
+    }
+
+    /// Returns true if mouse button is pressed and mouse was moved further than drag
+    /// threshold since press.
+    #[inline]
+    pub fn is_dragging(&self) -> bool {
+        self.mouse_press.as_ref().map_or(false, |press| press.drag_started)
+    }
+
+    /// Takes mouse capture even if it is owned by other node, previous owner will receive
+    /// `CaptureLost` event so it can cancel its drag operation.
+    pub fn force_capture_mouse(&mut self, node: Handle<UINode>) {
+        if self.captured_node != node {
+            self.send_capture_lost();
+            self.captured_node = node;
+        }
+    }
+
+    #[inline]
+    pub fn captured_node(&self) -> Handle<UINode> {
+        self.captured_node
+    }
+
+    fn send_capture_lost(&mut self) {
+        if self.captured_node.is_some() {
+            self.events.push_back(UIEvent {
+                handled: false,
+                kind: UIEventKind::CaptureLost,
+                target: Handle::NONE,
+                source: self.captured_node,
+            });
+        }
+    }
+
+    #[inline]
+    pub fn get_drawing_context(&self) -> &DrawingContext {
+        &self.drawing_context
+    }
+
+    #[inline]
+    pub fn get_drawing_context_mut(&mut self) -> &mut DrawingContext {
+        &mut self.drawing_context
+    }
+
+    fn update_nodes(&mut self) {
+        self.stack.clear();
+        self.stack.push(self.root_canvas);
+        while let Some(node_handle) = self.stack.pop() {
+            let widget = self.nodes.borrow(node_handle).widget();
+            for child_handle in widget.children.iter() {
+                self.stack.push(*child_handle);
+            }
+            let (screen_position, parent_visibility) =
+                if widget.parent.is_some() {
+                    let parent_widget = self.nodes.borrow(widget.parent).widget();
+                    (widget.actual_local_position.get() + parent_widget.screen_position, parent_widget.global_visibility)
+                } else {
+                    (widget.actual_local_position.get(), true)
+                };
+            let widget = self.nodes.borrow_mut(node_handle).widget_mut();
+            let old_bounds = widget.get_screen_bounds();
+            let old_visibility = widget.global_visibility;
+            widget.screen_position = screen_position;
+            widget.global_visibility = widget.visibility == Visibility::Visible && !widget.culled.get() && parent_visibility;
+            let bounds = widget.get_screen_bounds();
+            let bounds_changed = bounds.x != old_bounds.x || bounds.y != old_bounds.y || bounds.w != old_bounds.w || bounds.h != old_bounds.h;
+            if bounds_changed && widget.bounds_notifications {
+                widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::ActualBoundsChanged(bounds)));
+            }
+            if widget.global_visibility != old_visibility {
+                widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::VisibilityChanged(widget.global_visibility)));
+            }
+            if self.event_tracing && bounds_changed {
+                log::debug!(target: "rg3d_ui::layout", "{:indent$}{} bounds: {} {} {} {}",
+                            "", self.describe_node(node_handle), bounds.x, bounds.y, bounds.w, bounds.h,
+                            indent = 2 * self.node_depth(node_handle));
+            }
+        }
+    }
+
+    pub fn update(&mut self, screen_size: Vec2, dt: f32) {
+        self.notify_attached_nodes();
+        self.shared_size_groups.borrow_mut().begin_layout();
+        // Layout is done in logical units, drawing scales it back to screen size.
+        let screen_size = self.snap_layout(screen_size.scale(1.0 / self.ui_scale));
+        self.node(self.root_canvas)
+            .measure(self, screen_size);
+        self.node(self.root_canvas)
+            .arrange(self, &Rect::new(0.0, 0.0, screen_size.x, screen_size.y));
+        self.update_nodes();
+        for handle in self.updatable_nodes.iter() {
+            self.nodes.borrow_mut(*handle).update(dt)
+        }
+        self.update_timers(dt);
+        self.update_key_repeat(dt);
+        self.update_animations(dt);
+    }
+
+    fn update_animations(&mut self, dt: f32) {
+        if self.reduced_motion {
+            return;
+        }
+        let nodes = &mut self.nodes;
+        // Animation could be removed directly through widget, such nodes are forgotten here.
+        self.animated_nodes.retain(|handle| {
+            let widget = nodes.borrow_mut(*handle).widget_mut();
+            match widget.background_animation.as_mut() {
+                Some(animation) => {
+                    if animation.update(dt) {
+                        let brush = animation.current_brush();
+                        widget.set_background_texture(Some(brush));
+                    }
+                    true
+                }
+                None => false,
+            }
+        });
+    }
+
+    /// Sets sprite animation of background of node, only nodes with animation set by this
+    /// method (or before node was added to user interface) are animated.
+    pub fn set_background_animation(&mut self, node: Handle<UINode>, animation: Option<AnimatedSpriteBrush>) {
+        let animated = animation.is_some();
+        self.nodes.borrow_mut(node).widget_mut().set_background_animation(animation);
+        let registered = self.animated_nodes.contains(&node);
+        if animated && !registered {
+            self.animated_nodes.push(node);
+        } else if !animated && registered {
+            self.animated_nodes.retain(|h| *h != node);
+        }
+    }
+
+    /// Enables synthesis of repeated `KeyDown` (and `Text`) events for held keys with given
+    /// initial delay and interval in seconds. Should be used only if host does not deliver
+    /// key repeats of operating system, otherwise keys will be repeated twice. None disables
+    /// synthesis, this is default.
+    pub fn set_key_repeat(&mut self, settings: Option<(f32, f32)>) {
+        self.key_repeat_settings = settings;
+        self.key_repeat = None;
+    }
+
+    pub fn key_repeat(&self) -> Option<(f32, f32)> {
+        self.key_repeat_settings
+    }
+
+    fn update_key_repeat(&mut self, dt: f32) {
+        let (delay, interval) = match self.key_repeat_settings {
+            Some(settings) => settings,
+            None => return,
+        };
+        if self.keyboard_focus_node.is_none() {
+            self.key_repeat = None;
+            return;
+        }
+        let (code, symbol) = match self.key_repeat.as_mut() {
+            Some(key_repeat) => {
+                key_repeat.elapsed += dt;
+                let period = if key_repeat.repeating { interval } else { delay };
+                if key_repeat.elapsed < period {
+                    return;
+                }
+                // Overshoot is kept so repeat rate does not drift with frame time.
+                key_repeat.elapsed = (key_repeat.elapsed - period).min(interval);
+                key_repeat.repeating = true;
+                (key_repeat.code, key_repeat.symbol)
+            }
+            None => return,
+        };
+        if code == KeyCode::Tab && self.key_capture_node.is_none() {
+            // Held Tab keeps moving focus, the same as in `process_input_event`.
+            let backwards = self.keyboard_modifiers.shift;
+            self.move_focus(backwards);
+            return;
+        }
+        self.events.push_back(UIEvent {
+            handled: false,
+            kind: UIEventKind::KeyDown { code },
+            target: Handle::NONE,
+            source: self.keyboard_focus_node,
+        });
+        if let Some(symbol) = symbol {
+            self.events.push_back(UIEvent {
+                handled: false,
+                kind: UIEventKind::Text { symbol },
+                target: Handle::NONE,
+                source: self.keyboard_focus_node,
+            });
+        }
+    }
+
+    /// Creates timer that will send `Timer` event to given node when `interval` (in seconds)
+    /// has elapsed. Repeating timer will fire every interval until it is killed, others are
+    /// removed after first event. Timer without positive interval fires once on next update
+    /// even if it is repeating.
+    pub fn set_timer(&mut self, node: Handle<UINode>, interval: f32, repeating: bool) -> TimerId {
+        let id = TimerId(self.next_timer_id);
+        self.next_timer_id += 1;
+        self.timers.push(Timer {
+            id,
+            node,
+            interval,
+            elapsed: 0.0,
+            // Otherwise it would fire every frame forever.
+            repeating: repeating && interval > 0.0,
+        });
+        id
+    }
+
+    /// Sets or removes state of node (for example `Widget::SELECTED_STATE`) and re-applies
+    /// style of node so its state setters take effect.
+    pub fn set_node_state(&mut self, node: Handle<UINode>, state: &str, active: bool) {
+        let node = self.nodes.borrow_mut(node);
+        if node.widget_mut().set_state(state, active) {
+            if let Some(style) = node.widget().style() {
+                node.apply_style(style);
+            }
+        }
+    }
+
+    pub fn keyboard_focus_node(&self) -> Handle<UINode> {
+        self.keyboard_focus_node
+    }
+
+    /// Moves keyboard focus to given node (or removes it if handle is NONE), sends
+    /// `LostFocus` and `GotFocus` events and remembers node as last focused one in its
+    /// focus scope.
+    pub fn set_keyboard_focus(&mut self, node: Handle<UINode>) {
+        if self.keyboard_focus_node == node {
+            return;
+        }
+        self.key_capture_node = Handle::NONE;
+        if self.keyboard_focus_node.is_some() {
+            self.events.push_back(UIEvent {
+                handled: false,
+                kind: UIEventKind::LostFocus,
+                target: Handle::NONE,
+                source: self.keyboard_focus_node,
+            });
+        }
+        if node.is_some() {
+            self.events.push_back(UIEvent {
+                handled: false,
+                kind: UIEventKind::GotFocus,
+                target: Handle::NONE,
+                source: node,
+            });
+            let scope = self.focus_scope_of(node);
+            self.focus_scope_memory.insert(scope, node);
+        }
+        self.keyboard_focus_node = node;
+    }
+
+    /// Returns nearest focus scope up on tree from given node. Layers and root canvas
+    /// are focus scopes too.
+    pub fn focus_scope_of(&self, node: Handle<UINode>) -> Handle<UINode> {
+        let mut handle = self.nodes.borrow(node).widget().parent;
+        while handle.is_some() {
+            let widget = self.nodes.borrow(handle).widget();
+            if widget.is_focus_scope || self.layers.contains(&handle) {
+                return handle;
+            }
+            handle = widget.parent;
+        }
+        self.root_canvas
+    }
+
+    /// Collects visible focusable nodes of scope in tree order, nested scopes are skipped.
+    fn focusable_nodes(&self, scope: Handle<UINode>) -> Vec<Handle<UINode>> {
+        let mut result = Vec::new();
+        let mut stack = self.nodes.borrow(scope).widget().children.clone();
+        stack.reverse();
+        while let Some(handle) = stack.pop() {
+            let widget = self.nodes.borrow(handle).widget();
+            if !widget.global_visibility {
+                continue;
+            }
+            if widget.is_focusable {
+                result.push(handle);
+            }
+            if !widget.is_focus_scope && !self.layers.contains(&handle) {
+                stack.extend(widget.children.iter().rev());
+            }
+        }
+        result
+    }
+
+    /// Scope of currently focused node, or root canvas if there is no focused node.
+    fn active_focus_scope(&self) -> Handle<UINode> {
+        if self.keyboard_focus_node.is_some() {
+            self.focus_scope_of(self.keyboard_focus_node)
+        } else {
+            self.root_canvas
+        }
+    }
+
+    /// Moves focus to next (or previous) focusable node in active focus scope, focus
+    /// wraps around at the end of scope.
+    pub fn move_focus(&mut self, backwards: bool) {
+        let candidates = self.focusable_nodes(self.active_focus_scope());
+        if candidates.is_empty() {
+            return;
+        }
+        let next = match candidates.iter().position(|h| *h == self.keyboard_focus_node) {
+            Some(i) => if backwards {
+                (i + candidates.len() - 1) % candidates.len()
+            } else {
+                (i + 1) % candidates.len()
+            },
+            None => if backwards { candidates.len() - 1 } else { 0 },
+        };
+        self.set_keyboard_focus(candidates[next]);
+        self.give_haptic_feedback(HapticFeedback::FocusMoved, candidates[next]);
+    }
+
+    /// Moves focus to nearest focusable node of active focus scope in given direction,
+    /// this is used for arrow or gamepad navigation.
+    pub fn move_focus_in_direction(&mut self, direction: Vec2) {
+        let direction = match direction.normalized() {
+            Some(direction) => direction,
+            None => return,
+        };
+        let center = |widget: &Widget| {
+            let bounds = widget.get_screen_bounds();
+            Vec2::new(bounds.x + bounds.w * 0.5, bounds.y + bounds.h * 0.5)
+        };
+        if self.keyboard_focus_node.is_none() {
+            self.move_focus(false);
+            return;
+        }
+        let origin = center(self.nodes.borrow(self.keyboard_focus_node).widget());
+        let mut best = Handle::NONE;
+        let mut best_cost = std::f32::MAX;
+        for candidate in self.focusable_nodes(self.active_focus_scope()) {
+            if candidate == self.keyboard_focus_node {
+                continue;
+            }
+            let delta = center(self.nodes.borrow(candidate).widget()) - origin;
+            let along = delta.dot(direction);
+            if along <= 0.0 {
+                continue;
+            }
+            // Nodes that are off the axis of movement are penalized.
+            let across = (delta - direction.scale(along)).len();
+            let cost = along + across * 2.0;
+            if cost < best_cost {
+                best_cost = cost;
+                best = candidate;
+            }
+        }
+        if best.is_some() {
+            self.set_keyboard_focus(best);
+            self.give_haptic_feedback(HapticFeedback::FocusMoved, best);
+        } else {
+            self.give_haptic_feedback(HapticFeedback::BoundaryHit, self.keyboard_focus_node);
+        }
+    }
+
+    /// Makes given focus scope active by focusing node that was focused in it last time,
+    /// or its first focusable node.
+    pub fn activate_focus_scope(&mut self, scope: Handle<UINode>) {
+        let candidates = self.focusable_nodes(scope);
+        let remembered = self.focus_scope_memory.get(&scope).cloned();
+        match remembered.filter(|node| candidates.contains(node)) {
+            Some(node) => self.set_keyboard_focus(node),
+            None => if let Some(first) = candidates.first() {
+                self.set_keyboard_focus(*first);
+            }
+        }
+    }
+
+    /// Returns handles of all windows, including closed ones, in order of their position in
+    /// pool.
+    pub fn windows(&self) -> Vec<Handle<UINode>> {
+        self.nodes
+            .pair_iter()
+            .filter(|(_, node)| node.downcast_ref::<Window>().is_some())
+            .map(|(handle, _)| handle)
+            .collect()
+    }
+
+    /// Returns handles of open windows, minimized windows are open too.
+    pub fn open_windows(&self) -> Vec<Handle<UINode>> {
+        self.windows()
+            .into_iter()
+            .filter(|handle| self.node(*handle).downcast_ref::<Window>().map_or(false, |w| w.is_open()))
+            .collect()
+    }
+
+    /// Searches window by node name or by text of its title.
+    pub fn find_window(&self, name: &str) -> Handle<UINode> {
+        self.nodes
+            .pair_iter()
+            .find(|(_, node)| node.downcast_ref::<Window>().map_or(false, |window| {
+                window.widget().name() == name || window.title() == name
+            }))
+            .map_or(Handle::NONE, |(handle, _)| handle)
+    }
+
+    /// Returns window which contains keyboard focus, or last activated window if focus is
+    /// outside of any window.
+    pub fn active_window(&self) -> Handle<UINode> {
+        let mut node = self.keyboard_focus_node;
+        while node.is_some() {
+            if self.node(node).downcast_ref::<Window>().is_some() {
+                return node;
+            }
+            node = self.node(node).widget().parent;
+        }
+        if self.nodes.is_valid_handle(self.active_window) {
+            self.active_window
+        } else {
+            Handle::NONE
+        }
+    }
+
+    /// Opens window if it was closed, restores it if it was minimized, brings it on top of
+    /// its siblings and moves keyboard focus into it.
+    pub fn activate_window(&mut self, window: Handle<UINode>) {
+        if let Some(window_ref) = self.nodes.borrow_mut(window).downcast_mut::<Window>() {
+            if !window_ref.is_open() {
+                window_ref.open();
+            }
+            if window_ref.is_minimized() {
+                window_ref.minimize(false);
+            }
+        } else {
+            return;
+        }
+        self.bring_to_front(window);
+        self.active_window = window;
+        self.activate_focus_scope(window);
+    }
+
+    /// Places open windows in cascade starting from top-left corner of given area, each
+    /// next window is shifted by `step` and all windows get the same size.
+    pub fn cascade_windows(&mut self, area: Rect<f32>, step: f32) {
+        let windows = self.arrangeable_windows();
+        let count = windows.len() as f32;
+        let width = (area.w - step * (count - 1.0)).max(area.w * 0.5);
+        let height = (area.h - step * (count - 1.0)).max(area.h * 0.5);
+        for (i, handle) in windows.into_iter().enumerate() {
+            let offset = step * i as f32;
+            self.nodes.borrow_mut(handle)
+                .widget_mut()
+                .set_desired_local_position(Vec2::new(area.x + offset, area.y + offset))
+                .set_width(width)
+                .set_height(height);
+            self.bring_to_front(handle);
+        }
+    }
+
+    /// Children are drawn in order, so last child of parent is on top of its siblings.
+    fn bring_to_front(&mut self, node: Handle<UINode>) {
+        let parent = self.node(node).widget().parent;
+        if parent.is_some() {
+            self.node_mut(parent).widget_mut().move_child(node, std::usize::MAX);
+        }
+    }
+
+    /// Places open windows in a grid which fills given area, grid is as close to square as
+    /// possible.
+    pub fn tile_windows(&mut self, area: Rect<f32>) {
+        let windows = self.arrangeable_windows();
+        if windows.is_empty() {
+            return;
+        }
+        let columns = (windows.len() as f32).sqrt().ceil() as usize;
+        let rows = (windows.len() + columns - 1) / columns;
+        let width = area.w / columns as f32;
+        let height = area.h / rows as f32;
+        for (i, handle) in windows.into_iter().enumerate() {
+            let column = i % columns;
+            let row = i / columns;
+            self.nodes.borrow_mut(handle)
+                .widget_mut()
+                .set_desired_local_position(Vec2::new(area.x + column as f32 * width, area.y + row as f32 * height))
+                .set_width(width)
+                .set_height(height);
+        }
+    }
+
+    /// Captures position, size and state of every window that has a name or a title text,
+    /// windows without any of them cannot be matched on restore and are skipped.
+    pub fn capture_layout(&self) -> WorkspaceLayout {
+        let mut windows = self.windows();
+        // Order of windows in layout is the order they are drawn in.
+        windows.sort_by_key(|handle| {
+            let parent = self.node(*handle).widget().parent;
+            if parent.is_some() {
+                self.node(parent).widget().child_index(*handle).unwrap_or(0)
+            } else {
+                0
+            }
+        });
+        WorkspaceLayout {
+            windows: windows
+                .into_iter()
+                .filter_map(|handle| {
+                    let window = self.node(handle).downcast_ref::<Window>()?;
+                    let name = if !window.widget().name().is_empty() {
+                        window.widget().name().to_owned()
+                    } else if !window.title().is_empty() {
+                        window.title().to_owned()
+                    } else {
+                        return None;
+                    };
+                    Some(WindowLayout {
+                        name,
+                        position: window.widget().desired_local_position(),
+                        size: window.widget().actual_size(),
+                        open: window.is_open(),
+                        minimized: window.is_minimized(),
+                    })
+                })
+                .collect()
+        }
+    }
+
+    /// Restores layout captured by `capture_layout`. Windows that are in layout but not in
+    /// user interface are ignored, windows that are not in layout keep their state.
+    pub fn apply_layout(&mut self, layout: &WorkspaceLayout) {
+        for window_layout in layout.windows.iter() {
+            let handle = self.find_window(&window_layout.name);
+            if handle.is_none() {
+                continue;
+            }
+            if let Some(window) = self.nodes.borrow_mut(handle).downcast_mut::<Window>() {
+                if window.is_open() != window_layout.open {
+                    if window_layout.open { window.open() } else { window.close() }
+                }
+                if window.is_minimized() != window_layout.minimized {
+                    window.minimize(window_layout.minimized);
+                }
+                window.widget_mut()
+                    .set_desired_local_position(window_layout.position)
+                    .set_width(window_layout.size.x)
+                    .set_height(window_layout.size.y);
+            }
+            self.bring_to_front(handle);
+        }
+    }
+
+    /// Open and not minimized windows.
+    fn arrangeable_windows(&self) -> Vec<Handle<UINode>> {
+        self.open_windows()
+            .into_iter()
+            .filter(|handle| self.node(*handle).downcast_ref::<Window>().map_or(false, |w| !w.is_minimized()))
+            .collect()
+    }
+
+    /// Creates new layer which is a full-screen canvas on top of all other nodes of user
+    /// interface. Layers can be shown, hidden and excluded from input as a whole, nodes
+    /// are added to layer by linking them with returned canvas.
+    pub fn add_layer(&mut self, name: &str) -> Handle<UINode> {
+        let mut canvas = Canvas::new(WidgetBuilder::new()
+            .with_name(name)
+            .build());
+        canvas.is_layer = true;
+        let layer = self.add_node(Box::new(canvas));
+        self.layers.push(layer);
+        layer
+    }
+
+    /// Returns first layer with given name.
+    pub fn find_layer(&self, name: &str) -> Handle<UINode> {
+        self.layers
+            .iter()
+            .find(|layer| self.nodes.borrow(**layer).widget().name() == name)
+            .cloned()
+            .unwrap_or(Handle::NONE)
+    }
+
+    pub fn layers(&self) -> &[Handle<UINode>] {
+        &self.layers
+    }
+
+    pub fn set_layer_visible(&mut self, layer: Handle<UINode>, visible: bool) {
+        self.nodes.borrow_mut(layer).widget_mut().set_visibility(bool_to_visibility(visible));
+    }
+
+    /// Disabled layer is transparent for mouse, nodes below it will receive input instead.
+    pub fn set_layer_input_enabled(&mut self, layer: Handle<UINode>, enabled: bool) {
+        self.nodes.borrow_mut(layer).widget_mut().set_hit_test_visibility(if enabled {
+            HitTestVisibility::Visible
+        } else {
+            HitTestVisibility::SubtreeInvisible
+        });
+    }
+
+    pub fn is_layer_input_enabled(&self, layer: Handle<UINode>) -> bool {
+        self.nodes.borrow(layer).widget().hit_test_visibility() != HitTestVisibility::SubtreeInvisible
+    }
+
+    /// Moves layer to given position in draw order, layers with bigger index are drawn
+    /// on top and receive input first.
+    pub fn set_layer_order(&mut self, layer: Handle<UINode>, index: usize) {
+        let root = self.root_canvas;
+        self.nodes.borrow_mut(root).widget_mut().move_child(layer, index);
+    }
+
+    /// Sets insets from screen edges, root-level widgets that use safe area will be laid
+    /// out inside rectangle that is left after insets.
+    pub fn set_safe_area_inset(&mut self, inset: Thickness) {
+        self.safe_area_inset = inset;
+    }
+
+    pub fn safe_area_inset(&self) -> Thickness {
+        self.safe_area_inset
+    }
+
+    /// Returns safe area rectangle for given screen size.
+    pub fn safe_area(&self, screen_size: Vec2) -> Rect<f32> {
+        let inset = self.safe_area_inset;
+        Rect::new(
+            inset.left,
+            inset.top,
+            maxf(0.0, screen_size.x - inset.left - inset.right),
+            maxf(0.0, screen_size.y - inset.top - inset.bottom),
+        )
+    }
+
+    /// Sets scale of whole interface: sizes, margins, fonts and positions of mouse events.
+    /// Unlike DPI scale, it is intended to be exposed to user as accessibility option, for
+    /// example for playing on TV from a couch. Layout is done in logical units, so screen
+    /// size passed to `update` is divided by scale and whole interface is relaid out on
+    /// next update. Glyphs are scaled as geometry, fonts are not rasterized again.
+    pub fn set_ui_scale(&mut self, scale: f32) {
+        if scale > 0.0 {
+            self.ui_scale = scale;
+        }
+    }
+
+    pub fn ui_scale(&self) -> f32 {
+        self.ui_scale
+    }
+
+    /// Enables fixed-point layout: every desired size, actual size and position computed by
+    /// measure and arrange is snapped to multiple of `1 / 2^bits`. Such values and their
+    /// sums are represented exactly by floats, so layout is bit-deterministic across
+    /// platforms, which is needed for lockstep multiplayer and snapshot tests. None (default)
+    /// disables snapping. Results of layout transforms with rotation depend on platform
+    /// trigonometry and are only snapped.
+    pub fn set_layout_precision(&mut self, bits: Option<u32>) {
+        self.layout_precision = bits.map(|bits| bits.min(16));
+    }
+
+    pub fn layout_precision(&self) -> Option<u32> {
+        self.layout_precision
+    }
+
+    /// Snaps value computed by layout to fixed-point precision of layout, returns value as
+    /// is if fixed-point layout is disabled. Custom panels may use it for intermediate values.
+    #[inline]
+    pub fn snap_layout(&self, v: Vec2) -> Vec2 {
+        match self.layout_precision {
+            Some(bits) => {
+                let scale = (1u32 << bits) as f32;
+                Vec2::new((v.x * scale).round() / scale, (v.y * scale).round() / scale)
+            }
+            None => v,
+        }
+    }
+
+    /// Enables reduced motion mode, accessibility setting which replaces animations of
+    /// built-in widgets with instant state changes: animated sprites and flipbooks show
+    /// first frame, transitions jump to their final values, caret does not blink.
+    pub fn set_reduced_motion(&mut self, reduced_motion: bool) {
+        self.reduced_motion = reduced_motion;
+        for node in self.nodes.iter_mut() {
+            node.widget_mut().reduced_motion = reduced_motion;
+        }
+    }
+
+    pub fn reduced_motion(&self) -> bool {
+        self.reduced_motion
+    }
+
+    /// Sets handler of interaction sounds of stock widgets, see `UiSound` for list of sounds.
+    pub fn set_sound_handler(&mut self, handler: Option<Box<dyn UiSoundHandler>>) {
+        self.sound_handler = handler;
+    }
+
+    /// Plays sound through sound handler, for sounds which are not tied to events of stock
+    /// widgets, for example `UiSound::Error` when application rejects input.
+    pub fn play_sound(&mut self, sound: UiSound, source: Handle<UINode>) {
+        if let Some(handler) = self.sound_handler.as_mut() {
+            handler.play(sound, source);
+        }
+    }
+
+    /// Sets handler of haptic feedback of gamepad navigation, see `HapticFeedback`.
+    pub fn set_haptic_handler(&mut self, handler: Option<Box<dyn HapticHandler>>) {
+        self.haptic_handler = handler;
+    }
+
+    /// Returns true if last input event came from gamepad rather than mouse or keyboard.
+    pub fn is_gamepad_active(&self) -> bool {
+        self.gamepad_active
+    }
+
+    fn give_haptic_feedback(&mut self, feedback: HapticFeedback, node: Handle<UINode>) {
+        if self.gamepad_active {
+            if let Some(handler) = self.haptic_handler.as_mut() {
+                handler.rumble(feedback, node);
+            }
+        }
+    }
+
+    /// Enables tracing: every event dispatched by `poll_ui_event` is written to `log` with
+    /// its kind, source, target and handled flag, and every change of screen bounds after
+    /// layout is written too. Lines are indented by depth of node in tree. Events go to
+    /// `rg3d_ui::event` target and layout changes go to `rg3d_ui::layout` target, both
+    /// with debug level, so they can be filtered by logger of application.
+    pub fn set_event_tracing(&mut self, enabled: bool) {
+        self.event_tracing = enabled;
+    }
+
+    pub fn is_event_tracing(&self) -> bool {
+        self.event_tracing
+    }
+
+    /// Number of ancestors of node, root canvas has depth 0.
+    fn node_depth(&self, node: Handle<UINode>) -> usize {
+        let mut depth = 0;
+        let mut handle = node;
+        while self.nodes.is_valid_handle(handle) {
+            handle = self.nodes.borrow(handle).widget().parent;
+            if handle.is_some() {
+                depth += 1;
+            }
+        }
+        depth
+    }
+
+    fn describe_node(&self, node: Handle<UINode>) -> String {
+        if node.is_none() {
+            "-".to_owned()
+        } else if !self.nodes.is_valid_handle(node) {
+            format!("#{} (removed)", node.get_index())
         } else {
-            false
+            let name = self.nodes.borrow(node).widget().name();
+            if name.is_empty() {
+                format!("#{}", node.get_index())
+            } else {
+                format!("#{} '{}'", node.get_index(), name)
+            }
         }
     }
 
-    #[inline]
-    pub fn release_mouse_capture(&mut self) {
-        self.captured_node = Handle::NONE;
+    fn trace_event(&self, event: &UIEvent) {
+        log::debug!(target: "rg3d_ui::event", "{:indent$}{} source: {} target: {} handled: {}",
+                    "", event.kind.name(), self.describe_node(event.source), self.describe_node(event.target), event.handled,
+                    indent = 2 * self.node_depth(event.source));
     }
 
-    #[inline]
-    pub fn get_drawing_context(&self) -> &DrawingContext {
-        &self.drawing_context
+    /// Adds or removes node from the list of nodes that are updated every frame. This
+    /// overrides value returned by `Control::needs_update` when node was added.
+    pub fn set_needs_update(&mut self, node: Handle<UINode>, needs_update: bool) {
+        let registered = self.updatable_nodes.contains(&node);
+        if needs_update && !registered {
+            self.updatable_nodes.push(node);
+        } else if !needs_update && registered {
+            self.updatable_nodes.retain(|h| *h != node);
+        }
     }
 
-    #[inline]
-    pub fn get_drawing_context_mut(&mut self) -> &mut DrawingContext {
-        &mut self.drawing_context
+    /// Removes timer, returns `false` if there is no such timer (it could be already fired).
+    pub fn kill_timer(&mut self, id: TimerId) -> bool {
+        let count = self.timers.len();
+        self.timers.retain(|timer| timer.id != id);
+        count != self.timers.len()
     }
 
-    fn update_nodes(&mut self) {
-        self.stack.clear();
-        self.stack.push(self.root_canvas);
-        while let Some(node_handle) = self.stack.pop() {
-            let widget = self.nodes.borrow(node_handle).widget();
-            for child_handle in widget.children.iter() {
-                self.stack.push(*child_handle);
+    fn update_timers(&mut self, dt: f32) {
+        let events = &mut self.events;
+        for timer in self.timers.iter_mut() {
+            timer.elapsed += dt;
+            if timer.elapsed >= timer.interval {
+                let mut event = UIEvent::targeted(timer.node, UIEventKind::Timer(timer.id));
+                event.source = timer.node;
+                events.push_back(event);
+                if timer.repeating {
+                    // Overshoot is kept so timer does not drift with frame time, but not more
+                    // than one interval, long frame must not cause burst of events.
+                    timer.elapsed = (timer.elapsed - timer.interval).min(timer.interval);
+                }
             }
-            let (screen_position, parent_visibility) =
-                if widget.parent.is_some() {
-                    let parent_widget = self.nodes.borrow(widget.parent).widget();
-                    (widget.actual_local_position.get() + parent_widget.screen_position, parent_widget.global_visibility)
-                } else {
-                    (widget.actual_local_position.get(), true)
-                };
-            let widget = self.nodes.borrow_mut(node_handle).widget_mut();
-            widget.screen_position = screen_position;
-            widget.global_visibility = widget.visibility == Visibility::Visible && parent_visibility;
         }
+        self.timers.retain(|timer| timer.repeating || timer.elapsed < timer.interval);
     }
 
-    pub fn update(&mut self, screen_size: Vec2, dt: f32) {
-        self.node(self.root_canvas)
-            .measure(self, screen_size);
-        self.node(self.root_canvas)
-            .arrange(self, &Rect::new(0.0, 0.0, screen_size.x, screen_size.y));
-        self.update_nodes();
-        for node in self.nodes.iter_mut() {
-            node.update(dt)
+    fn notify_attached_nodes(&mut self) {
+        while !self.attached_nodes.is_empty() {
+            // Hooks are allowed to create new nodes, they will be notified on next iteration.
+            for handle in std::mem::replace(&mut self.attached_nodes, Vec::new()) {
+                let index = handle.get_index();
+                if let Some(mut node) = self.nodes.take_at(index) {
+                    node.on_attached_to_ui(handle, self);
+                    let old = self.nodes.replace_at(index, node);
+                    assert!(old.is_none());
+                }
+            }
+        }
+    }
+
+    /// Removes node and all its descendants from user interface. `on_removed` will be
+    /// called for every removed node, parent first.
+    pub fn remove_node(&mut self, node: Handle<UINode>) {
+        if node == self.root_canvas {
+            return;
+        }
+
+        self.unlink_node(node);
+
+        let mut stack = vec![node];
+        let mut removed = Vec::new();
+        while let Some(handle) = stack.pop() {
+            stack.extend_from_slice(&self.nodes.borrow(handle).widget().children);
+            removed.push(handle);
+        }
+
+        for handle in removed {
+            self.forget_node(handle);
+
+            let mut removed_node = self.nodes.free(handle);
+            removed_node.on_removed(handle, self);
+        }
+    }
+
+    /// Resets all references of user interface to given node: capture, focus, timers, etc.
+    fn forget_node(&mut self, handle: Handle<UINode>) {
+        if self.captured_node == handle {
+            // Node is gone, but its ancestors and other listeners may track the capture.
+            self.send_capture_lost();
+            self.captured_node = Handle::NONE;
+        }
+        if self.keyboard_focus_node == handle {
+            self.keyboard_focus_node = Handle::NONE;
+            self.key_capture_node = Handle::NONE;
+        }
+        if self.picked_node == handle {
+            self.picked_node = Handle::NONE;
+        }
+        if self.mouse_press.as_ref().map_or(false, |press| press.node == handle) {
+            self.mouse_press = None;
+        }
+        if self.prev_picked_node == handle {
+            self.prev_picked_node = Handle::NONE;
+        }
+        self.attached_nodes.retain(|h| *h != handle);
+        self.timers.retain(|timer| timer.node != handle);
+        self.updatable_nodes.retain(|h| *h != handle);
+        self.animated_nodes.retain(|h| *h != handle);
+        self.layers.retain(|h| *h != handle);
+        self.focus_scope_memory.remove(&handle);
+        self.focus_scope_memory.retain(|_, focused| *focused != handle);
+    }
+
+    /// Returns node of type `T` that was released to pool, or builds new one by `build`
+    /// if pool is empty. Reused node is attached to root canvas again, `init` is called
+    /// for both reused and new nodes and should reset state of node. Pooling avoids
+    /// allocations and handle churn for frequently created and destroyed widgets (list
+    /// rows, notifications and so on).
+    pub fn spawn_from_pool<T, B, I>(&mut self, build: B, init: I) -> Handle<UINode>
+        where T: Control,
+              B: FnOnce(&mut UserInterface) -> Handle<UINode>,
+              I: FnOnce(&mut T) {
+        let mut handle = Handle::NONE;
+        if let Some(pool) = self.widget_pool.get_mut(&TypeId::of::<T>()) {
+            while let Some(pooled) = pool.pop() {
+                if self.nodes.is_valid_handle(pooled) {
+                    handle = pooled;
+                    break;
+                }
+            }
+        }
+        if handle.is_some() {
+            self.link_nodes(handle, self.root_canvas);
+            let mut stack = vec![handle];
+            while let Some(node) = stack.pop() {
+                let node_ref = self.nodes.borrow(node);
+                stack.extend_from_slice(&node_ref.widget().children);
+                if node_ref.needs_update() {
+                    self.updatable_nodes.push(node);
+                }
+                if node_ref.widget().background_animation.is_some() {
+                    self.animated_nodes.push(node);
+                }
+            }
+        } else {
+            handle = build(self);
+        }
+        if let Some(node) = self.nodes.borrow_mut(handle).downcast_mut::<T>() {
+            init(node);
+        }
+        handle
+    }
+
+    /// Detaches node from tree and keeps it for reuse by `spawn_from_pool`, node and its
+    /// descendants lose focus, capture and timers, but their handles stay valid. Node is
+    /// not updated while it is in pool.
+    pub fn release_to_pool(&mut self, node: Handle<UINode>) {
+        if node == self.root_canvas || !self.nodes.is_valid_handle(node) {
+            return;
+        }
+        self.unlink_node(node);
+        let mut stack = vec![node];
+        while let Some(handle) = stack.pop() {
+            stack.extend_from_slice(&self.nodes.borrow(handle).widget().children);
+            self.forget_node(handle);
+        }
+        let type_id = Any::type_id((**self.nodes.borrow(node)).as_any());
+        let pool = self.widget_pool.entry(type_id).or_insert_with(Vec::new);
+        if !pool.contains(&node) {
+            pool.push(node);
+        }
+    }
+
+    /// Returns number of nodes of type `T` waiting for reuse.
+    pub fn pooled_count<T: Control>(&self) -> usize {
+        self.widget_pool.get(&TypeId::of::<T>()).map_or(0, |pool| pool.len())
+    }
+
+    /// Removes all pooled nodes.
+    pub fn clear_widget_pool(&mut self) {
+        for (_, pool) in std::mem::replace(&mut self.widget_pool, HashMap::new()) {
+            for handle in pool {
+                if self.nodes.is_valid_handle(handle) {
+                    self.remove_node(handle);
+                }
+            }
         }
     }
 
@@ -481,6 +1949,17 @@ impl UserInterface {
             return;
         }
 
+        let transform = node.widget().layout_transform;
+        if !transform.is_identity() {
+            let center = Vec2::new(bounds.x + bounds.w * 0.5, bounds.y + bounds.h * 0.5);
+            self.drawing_context.push_transform(center, transform);
+        }
+
+        let render_transform = node.widget().render_transform;
+        if !render_transform.is_identity() {
+            self.drawing_context.push_render_transform(Vec2::new(bounds.x, bounds.y), render_transform);
+        }
+
         let start_index = self.drawing_context.get_commands().len();
         self.drawing_context.set_nesting(nesting);
         self.drawing_context.commit_clip_rect(&bounds.inflate(0.9, 0.9));
@@ -503,7 +1982,22 @@ impl UserInterface {
             self.draw_node(*child_node, nesting + 1);
         }
 
+        let start_index = self.drawing_context.get_commands().len();
+        self.drawing_context.set_nesting(nesting);
+        let node = self.nodes.borrow(node_handle);
+        node.post_draw(&mut self.drawing_context);
+        let end_index = self.drawing_context.get_commands().len();
+        node.widget().command_indices.borrow_mut().extend(start_index..end_index);
+
         self.drawing_context.revert_clip_geom();
+
+        if !render_transform.is_identity() {
+            self.drawing_context.pop_transform();
+        }
+
+        if !transform.is_identity() {
+            self.drawing_context.pop_transform();
+        }
     }
 
     pub fn draw(&mut self) -> &DrawingContext {
@@ -516,6 +2010,15 @@ impl UserInterface {
                 .clear();
         }
 
+        if self.headless {
+            return &self.drawing_context;
+        }
+
+        let scaled = self.ui_scale != 1.0;
+        if scaled {
+            self.drawing_context.push_render_transform(Vec2::ZERO, RenderTransform::new(Vec2::new(self.ui_scale, self.ui_scale), Vec2::ZERO));
+        }
+
         let root_canvas = self.root_canvas;
         self.draw_node(root_canvas, 1);
 
@@ -535,9 +2038,20 @@ impl UserInterface {
             }
         }
 
+        if scaled {
+            self.drawing_context.pop_transform();
+        }
+
         &self.drawing_context
     }
 
+    /// Draws interface and renders it by software rasterizer into image of given size,
+    /// see `raster::rasterize`. Should be called after `update` with the same screen size.
+    pub fn rasterize(&mut self, width: usize, height: usize, background: Color) -> CpuImage {
+        self.draw();
+        raster::rasterize(&self.drawing_context, width, height, background)
+    }
+
     fn is_node_clipped(&self, node_handle: Handle<UINode>, pt: Vec2) -> bool {
         let mut clipped = true;
 
@@ -546,9 +2060,24 @@ impl UserInterface {
             return clipped;
         }
 
+        if self.headless {
+            // There are no clipping commands, every node clips by its bounds.
+            let mut handle = node_handle;
+            while handle.is_some() {
+                let widget = self.nodes.borrow(handle).widget();
+                if !widget.get_screen_bounds().contains(pt.x, pt.y) {
+                    return true;
+                }
+                handle = widget.parent;
+            }
+            return false;
+        }
+
+        // Drawn geometry is in screen units, while point is in logical units.
+        let screen_pt = pt.scale(self.ui_scale);
         for command_index in widget.command_indices.borrow().iter() {
             if let Some(command) = self.drawing_context.get_commands().get(*command_index) {
-                if *command.get_kind() == CommandKind::Clip && self.drawing_context.is_command_contains_point(command, pt) {
+                if *command.get_kind() == CommandKind::Clip && self.drawing_context.is_command_contains_point(command, screen_pt) {
                     clipped = false;
                     break;
                 }
@@ -571,9 +2100,29 @@ impl UserInterface {
         }
 
         if !self.is_node_clipped(node_handle, pt) {
+            if self.headless {
+                if let HitTestShape::Geometry = widget.hit_test_shape {
+                    // Bounds were checked by clipping.
+                    return true;
+                }
+            }
+
+            if let HitTestShape::Geometry = widget.hit_test_shape {
+                // Checked by geometry below.
+            } else {
+                let bounds = widget.get_screen_bounds();
+                if bounds.w <= 0.0 || bounds.h <= 0.0 {
+                    return false;
+                }
+                let normalized = Vec2::new((pt.x - bounds.x) / bounds.w, (pt.y - bounds.y) / bounds.h);
+                return normalized.x >= 0.0 && normalized.x <= 1.0 && normalized.y >= 0.0 && normalized.y <= 1.0 &&
+                    widget.hit_test_shape.contains_point(normalized);
+            }
+
+            let screen_pt = pt.scale(self.ui_scale);
             for command_index in widget.command_indices.borrow().iter() {
                 if let Some(command) = self.drawing_context.get_commands().get(*command_index) {
-                    if *command.get_kind() == CommandKind::Geometry && self.drawing_context.is_command_contains_point(command, pt) {
+                    if *command.get_kind() == CommandKind::Geometry && self.drawing_context.is_command_contains_point(command, screen_pt) {
                         return true;
                     }
                 }
@@ -586,12 +2135,12 @@ impl UserInterface {
     fn pick_node(&self, node_handle: Handle<UINode>, pt: Vec2, level: &mut i32) -> Handle<UINode> {
         let widget = self.nodes.borrow(node_handle).widget();
 
-        if !widget.is_hit_test_visible {
+        if widget.hit_test_visibility == HitTestVisibility::SubtreeInvisible {
             return Handle::NONE;
         }
 
         let (mut picked, mut topmost_picked_level) =
-            if self.is_node_contains_point(node_handle, pt) {
+            if widget.hit_test_visibility == HitTestVisibility::Visible && self.is_node_contains_point(node_handle, pt) {
                 (node_handle, *level)
             } else {
                 (Handle::NONE, 0)
@@ -738,13 +2287,7 @@ impl UserInterface {
     /// of most important methods which must be called each frame of your game loop, otherwise
     /// UI will not respond to any kind of events and simply speaking will just not work.
     pub fn poll_ui_event(&mut self) -> Option<UIEvent> {
-        // Gather events from nodes.
-        for (handle, node) in self.nodes.pair_iter_mut() {
-            while let Some(mut response_event) = node.widget_mut().events.borrow_mut().pop_front() {
-                response_event.source = handle;
-                self.events.push_back(response_event)
-            }
-        }
+        self.gather_events();
 
         let mut event = self.events.pop_front();
 
@@ -757,26 +2300,113 @@ impl UserInterface {
                     assert!(old.is_none());
                 }
             }
+
+            if self.event_tracing {
+                self.trace_event(event);
+            }
+
+            if self.sound_handler.is_some() {
+                if let Some(sound) = UiSound::from_event(self, event) {
+                    self.play_sound(sound, event.source);
+                }
+            }
+
+            match event.kind {
+                UIEventKind::Click | UIEventKind::Checked(_) | UIEventKind::SelectionChanged(_) => {
+                    self.give_haptic_feedback(HapticFeedback::Selected, event.source);
+                }
+                _ => (),
+            }
         }
 
         event
     }
 
+    /// Moves events produced by nodes into common queue.
+    fn gather_events(&mut self) {
+        for (handle, node) in self.nodes.pair_iter_mut() {
+            while let Some(mut response_event) = node.widget_mut().events.borrow_mut().pop_front() {
+                response_event.source = handle;
+                self.events.push_back(response_event)
+            }
+        }
+    }
+
+    /// Returns next event that will be returned by `poll_ui_event` without consuming it.
+    pub fn peek_ui_event(&mut self) -> Option<&UIEvent> {
+        self.gather_events();
+        self.events.front()
+    }
+
+    /// Returns iterator over all pending events, events are not consumed.
+    pub fn pending_events(&mut self) -> impl Iterator<Item=&UIEvent> {
+        self.gather_events();
+        self.events.iter()
+    }
+
+    /// Returns iterator over pending events produced by given node.
+    pub fn pending_events_from(&mut self, source: Handle<UINode>) -> impl Iterator<Item=&UIEvent> {
+        self.pending_events().filter(move |evt| evt.source == source)
+    }
+
+    /// Returns iterator over pending events of the same kind as given one, values of
+    /// event fields are ignored, for example `UIEventKind::MaxValueChanged(0.0)` will
+    /// match all `MaxValueChanged` events.
+    pub fn pending_events_of_kind(&mut self, kind: &UIEventKind) -> impl Iterator<Item=&UIEvent> {
+        let discriminant = std::mem::discriminant(kind);
+        self.pending_events().filter(move |evt| std::mem::discriminant(&evt.kind) == discriminant)
+    }
+
+    /// Dispatches all pending events to nodes and returns them in order of processing.
+    /// Events produced by nodes while handling other events are processed in the same
+    /// call, each such generation of events is one pass. Number of passes is limited by
+    /// `max_passes` to prevent infinite loops when nodes respond to events of each other,
+    /// events that were not processed stay in queue.
+    pub fn process_events(&mut self, max_passes: usize) -> Vec<UIEvent> {
+        let mut processed = Vec::new();
+        for _ in 0..max_passes {
+            self.gather_events();
+            let count = self.events.len();
+            if count == 0 {
+                break;
+            }
+            for _ in 0..count {
+                if let Some(event) = self.poll_ui_event() {
+                    processed.push(event);
+                }
+            }
+        }
+        processed
+    }
+
     /// Translates raw window event into some specific UI event. This is one of the
     /// most important methods of UI. You must call it each time you received a message
     /// from a window.
     pub fn process_input_event(&mut self, event: &OsEvent) -> bool {
         let mut event_processed = false;
 
+        self.gamepad_active = match event {
+            OsEvent::GamepadInput { .. } => true,
+            OsEvent::MouseInput { .. } | OsEvent::KeyboardInput { .. } | OsEvent::Character(_) | OsEvent::MouseWheel(..) => false,
+            _ => self.gamepad_active,
+        };
+
         match event {
             OsEvent::MouseInput { button, state, .. } => {
                 match state {
                     ButtonState::Pressed => {
                         self.picked_node = self.hit_test(self.mouse_position);
 
-                        self.keyboard_focus_node = self.picked_node;
+                        self.set_keyboard_focus(self.picked_node);
 
                         if !self.picked_node.is_none() {
+                            self.mouse_press = Some(MousePress {
+                                node: self.picked_node,
+                                pos: self.mouse_position,
+                                button: *button,
+                                drag_started: false,
+                            });
+
                             self.events.push_back(UIEvent {
                                 handled: false,
                                 kind: UIEventKind::MouseDown {
@@ -790,6 +2420,10 @@ impl UserInterface {
                         }
                     }
                     ButtonState::Released => {
+                        if self.mouse_press.as_ref().map_or(false, |press| press.button == *button) {
+                            self.mouse_press = None;
+                        }
+
                         if !self.picked_node.is_none() {
                             self.events.push_back(UIEvent {
                                 handled: false,
@@ -806,9 +2440,25 @@ impl UserInterface {
                 }
             }
             OsEvent::CursorMoved { position } => {
-                self.mouse_position = *position;
+                self.mouse_position = position.scale(1.0 / self.ui_scale);
                 self.picked_node = self.hit_test(self.mouse_position);
 
+                // Distinguish click from drag in one place, so all widgets behave the same.
+                if let Some(press) = self.mouse_press.as_mut() {
+                    if !press.drag_started && (self.mouse_position - press.pos).len() > self.drag_threshold {
+                        press.drag_started = true;
+                        self.events.push_back(UIEvent {
+                            handled: false,
+                            kind: UIEventKind::DragStarted {
+                                pos: press.pos,
+                                button: press.button,
+                            },
+                            target: Handle::NONE,
+                            source: press.node,
+                        });
+                    }
+                }
+
                 // Fire mouse leave for previously picked node
                 if self.picked_node != self.prev_picked_node {
                     let mut fire_mouse_leave = false;
@@ -875,8 +2525,53 @@ impl UserInterface {
                     event_processed = true;
                 }
             }
+            OsEvent::PinchGesture(delta) => {
+                if !self.picked_node.is_none() {
+                    self.events.push_back(UIEvent {
+                        handled: false,
+                        kind: UIEventKind::Pinch {
+                            pos: self.mouse_position,
+                            delta: *delta,
+                        },
+                        target: Handle::NONE,
+                        source: self.picked_node,
+                    });
+
+                    event_processed = true;
+                }
+            }
             OsEvent::KeyboardInput { button, state } => {
-                if self.keyboard_focus_node.is_some() {
+                let pressed = *state == ButtonState::Pressed;
+                if *button == KeyCode::LShift || *button == KeyCode::RShift {
+                    self.keyboard_modifiers.shift = pressed;
+                } else if *button == KeyCode::LControl || *button == KeyCode::RControl {
+                    self.keyboard_modifiers.control = pressed;
+                } else if *button == KeyCode::LAlt || *button == KeyCode::RAlt {
+                    self.keyboard_modifiers.alt = pressed;
+                } else if self.key_repeat_settings.is_some() {
+                    match state {
+                        ButtonState::Pressed => {
+                            self.key_repeat = Some(KeyRepeat {
+                                code: *button,
+                                symbol: None,
+                                elapsed: 0.0,
+                                repeating: false,
+                            });
+                        }
+                        ButtonState::Released => {
+                            if self.key_repeat.as_ref().map_or(false, |key_repeat| key_repeat.code == *button) {
+                                self.key_repeat = None;
+                            }
+                        }
+                    }
+                }
+
+                if *button == KeyCode::Tab && *state == ButtonState::Pressed && self.key_capture_node.is_none() {
+                    // Tab is consumed by focus navigation.
+                    let backwards = self.keyboard_modifiers.shift;
+                    self.move_focus(backwards);
+                    event_processed = true;
+                } else if self.keyboard_focus_node.is_some() {
                     let event = UIEvent {
                         handled: false,
                         kind: match state {
@@ -901,6 +2596,13 @@ impl UserInterface {
                 }
             }
             OsEvent::Character(unicode) => {
+                if let Some(key_repeat) = self.key_repeat.as_mut() {
+                    // Character is received right after key press.
+                    if !key_repeat.repeating {
+                        key_repeat.symbol = Some(*unicode);
+                    }
+                }
+
                 if self.keyboard_focus_node.is_some() {
                     let event = UIEvent {
                         handled: false,
@@ -913,6 +2615,21 @@ impl UserInterface {
 
                     self.events.push_back(event);
 
+                    event_processed = true;
+                }
+            }
+            OsEvent::GamepadInput { button, state } => {
+                if self.keyboard_focus_node.is_some() {
+                    self.events.push_back(UIEvent {
+                        handled: false,
+                        kind: match state {
+                            ButtonState::Pressed => UIEventKind::GamepadButtonDown { button: *button },
+                            ButtonState::Released => UIEventKind::GamepadButtonUp { button: *button },
+                        },
+                        target: Handle::NONE,
+                        source: self.keyboard_focus_node,
+                    });
+
                     event_processed = true;
                 }
             }
@@ -945,9 +2662,14 @@ impl UINodeContainer for UserInterface {
         self.root_canvas
     }
 
+    fn default_font(&self) -> Option<Arc<Mutex<Font>>> {
+        self.default_font.clone()
+    }
+
     fn add_node(&mut self, mut node: UINode) -> Handle<UINode> {
         let children = node.widget().children.clone();
         node.widget_mut().children.clear();
+        node.widget_mut().reduced_motion = self.reduced_motion;
         let node_handle = self.nodes_mut().spawn(node);
         if self.root_canvas.is_some() {
             self.link_nodes(node_handle, self.root_canvas);
@@ -955,6 +2677,13 @@ impl UINodeContainer for UserInterface {
         for child in children {
             self.link_nodes(child, node_handle)
         }
+        self.attached_nodes.push(node_handle);
+        if self.nodes.borrow(node_handle).needs_update() {
+            self.updatable_nodes.push(node_handle);
+        }
+        if self.nodes.borrow(node_handle).widget().background_animation.is_some() {
+            self.animated_nodes.push(node_handle);
+        }
         node_handle
     }
 }
@@ -1045,6 +2774,11 @@ pub trait UINodeContainer {
 
     fn add_node(&mut self, node: UINode) -> Handle<UINode>;
 
+    /// Font of text widgets that are built without explicit font.
+    fn default_font(&self) -> Option<Arc<Mutex<Font>>> {
+        Some(DEFAULT_FONT.clone())
+    }
+
     #[inline]
     fn node(&self, node_handle: Handle<UINode>) -> &UINode {
         self.nodes()
@@ -1093,4 +2827,142 @@ pub trait UINodeContainer {
 
 pub trait Builder {
     fn build(self, container: &mut dyn UINodeContainer) -> Handle<UINode> where Self: Sized;
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            pool::Handle,
+            math::vec2::Vec2,
+        },
+        event::{
+            UIEventKind,
+            TimerId,
+            OsEvent,
+            ButtonState,
+            KeyCode,
+        },
+        border::BorderBuilder,
+        widget::WidgetBuilder,
+        UserInterface,
+        UINode,
+        UINodeContainer,
+        Builder,
+    };
+
+    fn update(ui: &mut UserInterface, dt: f32) {
+        ui.update(Vec2::new(800.0, 600.0), dt);
+    }
+
+    /// Returns ids of timers that fired for given node since last call.
+    fn fired_timers(ui: &mut UserInterface, node: Handle<UINode>) -> Vec<TimerId> {
+        let mut fired = Vec::new();
+        while let Some(event) = ui.poll_ui_event() {
+            if let UIEventKind::Timer(id) = event.kind {
+                if event.source == node {
+                    fired.push(id);
+                }
+            }
+        }
+        fired
+    }
+
+    #[test]
+    fn repeating_timer_test() {
+        let mut ui = UserInterface::headless();
+        let node = ui.root();
+        let id = ui.set_timer(node, 0.375, true);
+        let mut fired = Vec::new();
+        for _ in 0..5 {
+            update(&mut ui, 0.25);
+            fired.extend(fired_timers(&mut ui, node));
+        }
+        // 1.25 seconds contain three full intervals, overshoot of frames must not be lost.
+        assert_eq!(fired, vec![id, id, id]);
+        assert!(ui.kill_timer(id));
+    }
+
+    #[test]
+    fn one_shot_timer_test() {
+        let mut ui = UserInterface::headless();
+        let node = ui.root();
+        let once = ui.set_timer(node, 0.5, false);
+        let zero = ui.set_timer(node, 0.0, true);
+        let mut fired = Vec::new();
+        for _ in 0..4 {
+            update(&mut ui, 0.25);
+            fired.extend(fired_timers(&mut ui, node));
+        }
+        // Repeating timer without interval fires once instead of every frame.
+        assert_eq!(fired, vec![zero, once]);
+        assert!(!ui.kill_timer(once));
+        assert!(!ui.kill_timer(zero));
+    }
+
+    fn make_focusable(ui: &mut UserInterface) -> Handle<UINode> {
+        BorderBuilder::new(WidgetBuilder::new()
+            .with_width(10.0)
+            .with_height(10.0)
+            .with_focusable(true))
+            .build(ui)
+    }
+
+    fn press(ui: &mut UserInterface, button: KeyCode) {
+        ui.process_input_event(&OsEvent::KeyboardInput { button, state: ButtonState::Pressed });
+    }
+
+    /// Returns number of `KeyDown` events with given code since last call.
+    fn key_downs(ui: &mut UserInterface, code: KeyCode) -> usize {
+        let mut count = 0;
+        while let Some(event) = ui.poll_ui_event() {
+            if let UIEventKind::KeyDown { code: event_code } = event.kind {
+                if event_code == code {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    #[test]
+    fn key_repeat_test() {
+        let mut ui = UserInterface::headless();
+        ui.set_key_repeat(Some((0.25, 0.1875)));
+        let node = make_focusable(&mut ui);
+        update(&mut ui, 0.0);
+        ui.set_keyboard_focus(node);
+
+        press(&mut ui, KeyCode::A);
+        assert_eq!(key_downs(&mut ui, KeyCode::A), 1);
+        let mut repeats = 0;
+        for _ in 0..8 {
+            update(&mut ui, 0.125);
+            repeats += key_downs(&mut ui, KeyCode::A);
+        }
+        // First repeat after delay, then every interval up to 1 second.
+        assert_eq!(repeats, 5);
+
+        ui.process_input_event(&OsEvent::KeyboardInput { button: KeyCode::A, state: ButtonState::Released });
+        update(&mut ui, 1.0);
+        assert_eq!(key_downs(&mut ui, KeyCode::A), 0);
+    }
+
+    #[test]
+    fn tab_repeat_test() {
+        let mut ui = UserInterface::headless();
+        ui.set_key_repeat(Some((0.25, 0.1875)));
+        let first = make_focusable(&mut ui);
+        let second = make_focusable(&mut ui);
+        update(&mut ui, 0.0);
+        ui.set_keyboard_focus(first);
+
+        press(&mut ui, KeyCode::Tab);
+        assert_eq!(ui.keyboard_focus_node(), second);
+        update(&mut ui, 0.125);
+        update(&mut ui, 0.125);
+        // Repeated Tab moves focus instead of being sent to focused widget.
+        assert_eq!(ui.keyboard_focus_node(), first);
+        assert_eq!(key_downs(&mut ui, KeyCode::Tab), 0);
+    }
+}
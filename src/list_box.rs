@@ -1,7 +1,11 @@
 use crate::{
-        scroll_viewer::ScrollViewerBuilder,
+        scroll_viewer::{
+            ScrollViewer,
+            ScrollViewerBuilder,
+        },
         Thickness,
         border::BorderBuilder,
+        text::Text,
         widget::{
             Widget,
             WidgetBuilder
@@ -12,7 +16,25 @@ use crate::{
         event::{
             UIEventKind,
             UIEvent,
+            MouseButton,
+            TimerId,
+        },
+        grid::{
+            GridBuilder,
+            Column,
+            Row,
+        },
+        keymap::{
+            WidgetAction,
+            KeyModifiers,
         },
+        style::{
+            Style,
+            StyleBuilder,
+        },
+        Visibility,
+        VerticalAlignment,
+        HitTestVisibility,
         Control,
         border::Border,
         ControlTemplate,
@@ -21,55 +43,707 @@ use crate::{
     core::{
         pool::Handle,
         color::Color,
+        math::vec2::Vec2,
     },
 };
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    rc::Rc,
+};
+
+/// State of item that is pressed or dragged for reordering.
+struct ItemDrag {
+    index: usize,
+    start: Vec2,
+    /// Drag is active when item is actually moved, otherwise it waits for mouse movement
+    /// (drag handle) or for long-press timer.
+    active: bool,
+    long_press_timer: Option<TimerId>,
+}
 
 pub struct ListBox {
     widget: Widget,
+    /// Most recently selected item, keyboard navigation starts from it.
     selected_index: Option<usize>,
+    /// Indices of all selected items in ascending order.
+    selection: Vec<usize>,
+    /// Item from which Shift+Click selects range.
+    anchor: Option<usize>,
+    multi_selection: bool,
     items: Vec<Handle<UINode>>,
+    panel: Handle<UINode>,
+    scroll_viewer: Handle<UINode>,
+    reorderable: bool,
+    /// Drag handle of each item, empty if items have no handles.
+    drag_handles: Vec<Handle<UINode>>,
+    /// Whether items added after creation get drag handles.
+    has_drag_handles: bool,
+    item_style: Rc<Style>,
+    drop_indicator: Handle<UINode>,
+    drag: Option<ItemDrag>,
+    /// Characters typed for incremental search, lowercase.
+    search_buffer: String,
+    search_timer: Option<TimerId>,
 }
 
 impl ListBox {
+    /// Time in seconds for which item should be pressed to start drag without handle.
+    pub const LONG_PRESS_TIME: f32 = 0.5;
+
+    /// Time in seconds after last typed character when incremental search starts over.
+    pub const TYPE_AHEAD_TIMEOUT: f32 = 1.0;
+
+    /// Style of item containers which is used when list box builder has no item style.
+    pub fn default_item_style() -> Rc<Style> {
+        Rc::new(StyleBuilder::new()
+            .with_setter(Widget::FOREGROUND, Box::new(Color::opaque(80, 80, 80)))
+            .with_setter(Border::STROKE_THICKNESS, Box::new(Thickness::uniform(1.0)))
+            .with_state_setter(Widget::SELECTED_STATE, Widget::FOREGROUND, Box::new(Color::opaque(0, 0, 0)))
+            .with_state_setter(Widget::SELECTED_STATE, Border::STROKE_THICKNESS, Box::new(Thickness::uniform(2.0)))
+            .build())
+    }
+
     pub fn new(widget: Widget, items: Vec<Handle<UINode>>) -> Self {
         Self {
             widget,
             selected_index: None,
-            items
+            selection: Vec::new(),
+            anchor: None,
+            multi_selection: false,
+            items,
+            panel: Handle::NONE,
+            scroll_viewer: Handle::NONE,
+            reorderable: false,
+            drag_handles: Vec::new(),
+            has_drag_handles: false,
+            item_style: Self::default_item_style(),
+            drop_indicator: Handle::NONE,
+            drag: None,
+            search_buffer: String::new(),
+            search_timer: None,
         }
     }
 
-    pub fn set_selected(&mut self, new_index: Option<usize>) {
-        let old_value = self.selected_index;
+    /// Adds item to the end of list, content is wrapped into item container.
+    pub fn add_item(ui: &mut UserInterface, list_box: Handle<UINode>, content: Handle<UINode>) {
+        Self::insert_item(ui, list_box, std::usize::MAX, content);
+    }
 
-        self.selected_index = new_index;
+    /// Inserts item at given position, content is wrapped into item container. Position
+    /// is clamped to amount of items. Selection stays on the same items.
+    pub fn insert_item(ui: &mut UserInterface, list_box: Handle<UINode>, index: usize, content: Handle<UINode>) {
+        let (panel, item_style, has_drag_handles, index) = match ui.node(list_box).downcast_ref::<ListBox>() {
+            Some(list_box) if list_box.panel.is_some() => {
+                (list_box.panel, list_box.item_style.clone(), list_box.has_drag_handles, index.min(list_box.items.len()))
+            }
+            _ => return,
+        };
+        let (item, drag_handle) = make_item_container(ui, content, index, has_drag_handles, &item_style);
+        ui.link_nodes(item, panel);
+        ui.node_mut(panel).widget_mut().move_child(item, index);
 
-        if old_value.is_none() && new_index.is_some() ||
-            old_value.is_some() && new_index.is_none() ||
-            old_value.unwrap() != new_index.unwrap() {
-            self.widget
-                .events
-                .borrow_mut()
-                .push_back(UIEvent::new(UIEventKind::SelectionChanged(self.selected_index)))
+        let items = match ui.node_mut(list_box).downcast_mut::<ListBox>() {
+            Some(list_box) => {
+                list_box.items.insert(index, item);
+                if has_drag_handles {
+                    list_box.drag_handles.insert(index, drag_handle);
+                }
+                list_box.remap_selection(|selected| Some(if selected >= index { selected + 1 } else { selected }));
+                list_box.items.clone()
+            }
+            None => return,
+        };
+        update_item_indices(ui, &items);
+    }
+
+    /// Removes item at given position together with its content. `SelectionChanged` is
+    /// sent if removed item was selected.
+    pub fn remove_item(ui: &mut UserInterface, list_box: Handle<UINode>, index: usize) {
+        let (item, items) = match ui.node_mut(list_box).downcast_mut::<ListBox>() {
+            Some(list_box) if index < list_box.items.len() => {
+                let item = list_box.items.remove(index);
+                if !list_box.drag_handles.is_empty() {
+                    list_box.drag_handles.remove(index);
+                }
+                let was_selected = list_box.is_selected(index);
+                list_box.remap_selection(|selected| if selected == index {
+                    None
+                } else if selected > index {
+                    Some(selected - 1)
+                } else {
+                    Some(selected)
+                });
+                if was_selected {
+                    list_box.widget
+                        .events
+                        .borrow_mut()
+                        .push_back(UIEvent::new(UIEventKind::SelectionChanged(list_box.selection.clone())));
+                }
+                (item, list_box.items.clone())
+            }
+            _ => return,
+        };
+        ui.remove_node(item);
+        update_item_indices(ui, &items);
+    }
+
+    /// Moves item to new position, selection follows moved items. Unlike reordering by
+    /// user, this does not send `ItemsReordered` event.
+    pub fn move_item(ui: &mut UserInterface, list_box: Handle<UINode>, from: usize, to: usize) {
+        let (panel, items) = match ui.node_mut(list_box).downcast_mut::<ListBox>() {
+            Some(list_box) if list_box.shift_item(from, to) => (list_box.panel, list_box.items.clone()),
+            _ => return,
+        };
+        ui.node_mut(panel).widget_mut().move_child(items[to], to);
+        update_item_indices(ui, &items);
+    }
+
+    /// Changes indices of selected items, items mapped to None are deselected.
+    fn remap_selection<F: Fn(usize) -> Option<usize>>(&mut self, remap: F) {
+        self.selected_index = self.selected_index.and_then(&remap);
+        self.anchor = self.anchor.and_then(&remap);
+        self.selection = self.selection.iter().filter_map(|selected| remap(*selected)).collect();
+        self.selection.sort_unstable();
+    }
+
+    pub fn is_multi_selection(&self) -> bool {
+        self.multi_selection
+    }
+
+    /// Enables or disables selection of multiple items. Disabling keeps only most
+    /// recently selected item.
+    pub fn set_multi_selection(&mut self, multi_selection: bool) -> &mut Self {
+        self.multi_selection = multi_selection;
+        if !multi_selection {
+            self.set_selected(self.selected_index);
+        }
+        self
+    }
+
+    pub fn is_reorderable(&self) -> bool {
+        self.reorderable
+    }
+
+    /// Enables or disables reordering of items by user. List box must have drop
+    /// indicator to be reorderable, which is the case for list boxes created by builder.
+    pub fn set_reorderable(&mut self, reorderable: bool) -> &mut Self {
+        self.reorderable = reorderable && self.drop_indicator.is_some();
+        self
+    }
+
+    fn item_index_of(&self, node: Handle<UINode>, ui: &UserInterface) -> Option<usize> {
+        self.items.iter().position(|item| *item == node || ui.node(*item).widget().has_descendant(node, ui))
+    }
+
+    fn is_drag_handle(&self, index: usize, node: Handle<UINode>, ui: &UserInterface) -> bool {
+        match self.drag_handles.get(index) {
+            Some(handle) if handle.is_some() => *handle == node || ui.node(*handle).widget().has_descendant(node, ui),
+            _ => false,
         }
     }
 
+    /// Returns index of slot between items at which dragged item will be inserted, and
+    /// vertical position of the slot in screen coordinates.
+    fn insertion_slot(&self, ui: &UserInterface, pos: Vec2) -> (usize, f32) {
+        let mut slot_y = 0.0;
+        for (i, item) in self.items.iter().enumerate() {
+            let bounds = ui.node(*item).widget().get_screen_bounds();
+            if pos.y < bounds.y + bounds.h * 0.5 {
+                return (i, bounds.y);
+            }
+            slot_y = bounds.y + bounds.h;
+        }
+        (self.items.len(), slot_y)
+    }
+
+    fn update_drop_indicator(&self, ui: &mut UserInterface, pos: Vec2) {
+        let (_, slot_y) = self.insertion_slot(ui, pos);
+        let top = slot_y - self.widget.get_screen_bounds().y - 1.0;
+        ui.node_mut(self.drop_indicator)
+            .widget_mut()
+            .set_margin(Thickness { left: 3.0, top, right: 3.0, bottom: 0.0 })
+            .set_visibility(Visibility::Visible);
+    }
+
+    fn cancel_drag(&mut self, ui: &mut UserInterface) {
+        if let Some(drag) = self.drag.take() {
+            if let Some(timer) = drag.long_press_timer {
+                ui.kill_timer(timer);
+            }
+        }
+        ui.node_mut(self.drop_indicator)
+            .widget_mut()
+            .set_visibility(Visibility::Collapsed);
+    }
+
+    /// Moves item to new position in list of items, indices of items between old and new
+    /// positions are shifted and selection follows selected items. Returns false if item
+    /// was not moved.
+    fn shift_item(&mut self, from: usize, to: usize) -> bool {
+        if from == to || from >= self.items.len() || to >= self.items.len() {
+            return false;
+        }
+
+        let item = self.items.remove(from);
+        self.items.insert(to, item);
+        if !self.drag_handles.is_empty() {
+            let handle = self.drag_handles.remove(from);
+            self.drag_handles.insert(to, handle);
+        }
+
+        self.remap_selection(|selected| Some(if selected == from {
+            to
+        } else if from < selected && selected <= to {
+            selected - 1
+        } else if to <= selected && selected < from {
+            selected + 1
+        } else {
+            selected
+        }));
+        true
+    }
+
+    /// Moves item dropped by user to new position. Emits `ItemsReordered` event.
+    fn reorder_item(&mut self, ui: &mut UserInterface, from: usize, to: usize) {
+        if !self.shift_item(from, to) {
+            return;
+        }
+        ui.node_mut(self.panel).widget_mut().move_child(self.items[to], to);
+        update_item_indices(ui, &self.items);
+
+        self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::ItemsReordered { from, to }));
+    }
+
+    /// Replaces selection with single item, or clears selection if index is None or out
+    /// of range.
+    pub fn set_selected(&mut self, new_index: Option<usize>) {
+        let new_index = new_index.filter(|i| *i < self.items.len());
+        self.selected_index = new_index;
+        self.anchor = new_index;
+        self.change_selection(new_index.into_iter().collect());
+    }
+
+    /// Returns most recently selected item.
     pub fn get_selected(&self) -> Option<usize> {
         self.selected_index
     }
 
+    /// Returns indices of all selected items in ascending order.
+    pub fn selection(&self) -> &[usize] {
+        &self.selection
+    }
+
+    pub fn is_selected(&self, index: usize) -> bool {
+        self.selection.binary_search(&index).is_ok()
+    }
+
+    /// Replaces selection with given items, only first item is used if list box does not
+    /// allow multiple selection. Indices out of range are ignored.
+    pub fn set_selection(&mut self, indices: &[usize]) {
+        let count = self.items.len();
+        let mut selection = indices.iter().cloned().filter(|i| *i < count).collect::<Vec<_>>();
+        if !self.multi_selection {
+            selection.truncate(1);
+        }
+        self.selected_index = selection.last().cloned();
+        self.anchor = self.selected_index;
+        self.change_selection(selection);
+    }
+
+    /// Selects all items if list box allows multiple selection.
+    pub fn select_all(&mut self) {
+        if self.multi_selection {
+            self.change_selection((0..self.items.len()).collect());
+        }
+    }
+
+    /// Adds item to selection or removes it from selection, like Ctrl+Click does. When
+    /// item is removed, last of remaining selected items becomes selected one.
+    pub fn toggle_selected(&mut self, index: usize) {
+        if !self.multi_selection {
+            self.set_selected(Some(index));
+            return;
+        }
+        if index >= self.items.len() {
+            return;
+        }
+        let mut selection = self.selection.clone();
+        match selection.binary_search(&index) {
+            Ok(position) => {
+                selection.remove(position);
+                self.selected_index = selection.last().cloned();
+            }
+            Err(position) => {
+                selection.insert(position, index);
+                self.selected_index = Some(index);
+            }
+        }
+        self.anchor = self.selected_index;
+        self.change_selection(selection);
+    }
+
+    /// Selects items between anchor (last clicked item) and given item, like Shift+Click
+    /// does. Anchor is kept, so next range starts from the same item.
+    pub fn select_range(&mut self, index: usize) {
+        let count = self.items.len();
+        if index >= count {
+            return;
+        }
+        match self.anchor {
+            Some(anchor) if self.multi_selection => {
+                self.selected_index = Some(index);
+                self.change_selection((anchor.min(index)..=anchor.max(index))
+                    .filter(|i| *i < count)
+                    .collect());
+            }
+            _ => self.set_selected(Some(index)),
+        }
+    }
+
+    /// Applies click on item with given modifiers.
+    fn click_item(&mut self, index: usize, modifiers: KeyModifiers) {
+        if modifiers.shift {
+            self.select_range(index);
+        } else if modifiers.control {
+            self.toggle_selected(index);
+        } else {
+            self.set_selected(Some(index));
+        }
+    }
+
+    fn change_selection(&mut self, mut selection: Vec<usize>) {
+        selection.sort_unstable();
+        selection.dedup();
+        if selection != self.selection {
+            self.selection = selection;
+            self.widget
+                .events
+                .borrow_mut()
+                .push_back(UIEvent::new(UIEventKind::SelectionChanged(self.selection.clone())))
+        }
+    }
+
     pub fn get_items(&self) -> &[Handle<UINode>] {
         &self.items
     }
+
+    /// Selects item with given index and scrolls list so item is visible.
+    fn select_and_show(&mut self, ui: &mut UserInterface, index: usize) {
+        if let Some(item) = self.items.get(index).cloned() {
+            self.set_selected(Some(index));
+            if self.scroll_viewer.is_some() {
+                ScrollViewer::bring_into_view(ui, self.scroll_viewer, item);
+            }
+        }
+    }
+
+    /// Returns amount of items that fit into visible area of list.
+    fn items_per_page(&self, ui: &UserInterface) -> usize {
+        let item_height = self.items
+            .first()
+            .map_or(0.0, |item| ui.node(*item).widget().actual_size().y);
+        let viewport_height = if self.scroll_viewer.is_some() {
+            ui.node(self.scroll_viewer).widget().actual_size().y
+        } else {
+            self.widget.actual_size().y
+        };
+        if item_height > 0.0 {
+            ((viewport_height / item_height) as usize).max(1)
+        } else {
+            1
+        }
+    }
+
+    /// Moves selection by keyboard, returns true if key was used.
+    fn navigate(&mut self, ui: &mut UserInterface, action: WidgetAction) -> bool {
+        if self.items.is_empty() {
+            return false;
+        }
+        let last = self.items.len() - 1;
+        let page = self.items_per_page(ui);
+        let index = match (action, self.selected_index) {
+            (WidgetAction::CaretUp, Some(selected)) => selected.saturating_sub(1),
+            (WidgetAction::CaretDown, Some(selected)) => (selected + 1).min(last),
+            (WidgetAction::PageUp, Some(selected)) => selected.saturating_sub(page),
+            (WidgetAction::PageDown, Some(selected)) => (selected + page).min(last),
+            (WidgetAction::CaretUp, None) | (WidgetAction::PageUp, None) => last,
+            (WidgetAction::CaretDown, None) | (WidgetAction::PageDown, None) => 0,
+            (WidgetAction::SelectFirst, _) => 0,
+            (WidgetAction::SelectLast, _) => last,
+            (WidgetAction::SelectAll, _) if self.multi_selection => {
+                self.select_all();
+                return true;
+            }
+            _ => return false,
+        };
+        self.select_and_show(ui, index);
+        true
+    }
+
+    /// Returns text of first text node in item, lowercase.
+    fn item_text(&self, ui: &UserInterface, item: Handle<UINode>) -> Option<String> {
+        let text = ui.find_by_criteria_down(item, &|node| node.downcast_ref::<Text>().is_some());
+        ui.node(text)
+            .downcast_ref::<Text>()
+            .map(|text| text.text().to_lowercase())
+    }
+
+    /// Adds typed character to search buffer and selects next item which text starts
+    /// with buffer. Typing same character repeatedly cycles through items that start
+    /// with it. Returns true if character was used.
+    fn type_ahead(&mut self, self_handle: Handle<UINode>, ui: &mut UserInterface, symbol: char) -> bool {
+        if symbol.is_control() || (symbol == ' ' && self.search_buffer.is_empty()) || self.items.is_empty() {
+            return false;
+        }
+
+        if let Some(timer) = self.search_timer.take() {
+            ui.kill_timer(timer);
+        }
+        self.search_timer = Some(ui.set_timer(self_handle, Self::TYPE_AHEAD_TIMEOUT, false));
+        self.search_buffer.extend(symbol.to_lowercase());
+
+        let first = self.search_buffer.chars().next().unwrap();
+        let repeated = self.search_buffer.chars().all(|c| c == first);
+        let prefix = if repeated { first.to_string() } else { self.search_buffer.clone() };
+        // New search starts from item after selected one, longer prefix may still match
+        // selected item.
+        let start = match self.selected_index {
+            Some(selected) if repeated => selected + 1,
+            Some(selected) => selected,
+            None => 0,
+        };
+        let count = self.items.len();
+        let found = (0..count)
+            .map(|i| (start + i) % count)
+            .find(|i| self.item_text(ui, self.items[*i]).map_or(false, |text| text.starts_with(&prefix)));
+        if let Some(index) = found {
+            self.select_and_show(ui, index);
+        }
+        true
+    }
+}
+
+/// Updates indices which item containers use to find their state in selection.
+fn update_item_indices(ui: &mut UserInterface, items: &[Handle<UINode>]) {
+    for (index, item) in items.iter().enumerate() {
+        if let Some(item) = ui.node_mut(*item).downcast_mut::<ListBoxItem>() {
+            item.index = index;
+        }
+    }
+}
+
+/// Wraps content into item container which has selection behaviour, returns container
+/// and drag handle (NONE if `drag_handle` is not set).
+fn make_item_container(
+    ui: &mut dyn UINodeContainer,
+    item: Handle<UINode>,
+    index: usize,
+    drag_handle: bool,
+    item_style: &Rc<Style>,
+) -> (Handle<UINode>, Handle<UINode>) {
+    let (content, drag_handle) = if drag_handle {
+        let drag_handle = BorderBuilder::new(WidgetBuilder::new()
+            .with_width(8.0)
+            .with_margin(Thickness::right(2.0))
+            .with_background(Color::opaque(60, 60, 60))
+            .with_foreground(Color::opaque(110, 110, 110)))
+            .with_stroke_thickness(Thickness::uniform(1.0))
+            .build(ui);
+        ui.node_mut(item).widget_mut().set_column(1);
+        let content = GridBuilder::new(WidgetBuilder::new()
+            .with_child(drag_handle)
+            .with_child(item))
+            .add_column(Column::auto())
+            .add_column(Column::stretch())
+            .add_row(Row::stretch())
+            .build(ui);
+        (content, drag_handle)
+    } else {
+        (item, Handle::NONE)
+    };
+
+    let body = BorderBuilder::new(WidgetBuilder::new()
+        .with_foreground(Color::opaque(60, 60, 60))
+        .with_background(Color::opaque(80, 80, 80))
+        .with_child(content))
+        .with_stroke_thickness(Thickness::uniform(1.0))
+        .build(ui);
+    ui.node_mut(body).apply_style(item_style.clone());
+
+    let container = ListBoxItem {
+        widget: WidgetBuilder::new()
+            .with_child(body)
+            .build(),
+        body,
+        content: item,
+        index,
+    };
+
+    (ui.add_node(Box::new(container)), drag_handle)
 }
 
 pub struct ListBoxItem {
     widget: Widget,
     body: Handle<UINode>,
+    content: Handle<UINode>,
     index: usize,
 }
 
+impl ListBoxItem {
+    /// Returns node which was given to list box as item, it is wrapped by this container.
+    pub fn content(&self) -> Handle<UINode> {
+        self.content
+    }
+}
+
+/// Keys of items of list box, allows to update list box from snapshots of data without
+/// rebuilding items that are still present. Useful for lists that are refreshed from game
+/// state every frame.
+pub struct KeyedItems<K> {
+    list_box: Handle<UINode>,
+    /// Key of each item of list box, in order of items.
+    keys: Vec<K>,
+}
+
+impl<K: Clone + Eq + Hash> KeyedItems<K> {
+    /// Creates keys for list box which has no items.
+    pub fn new(list_box: Handle<UINode>) -> Self {
+        Self {
+            list_box,
+            keys: Vec::new(),
+        }
+    }
+
+    pub fn list_box(&self) -> Handle<UINode> {
+        self.list_box
+    }
+
+    pub fn keys(&self) -> &[K] {
+        &self.keys
+    }
+
+    pub fn position(&self, key: &K) -> Option<usize> {
+        self.keys.iter().position(|item_key| item_key == key)
+    }
+
+    /// Removes item with given key from list box.
+    pub fn remove(&mut self, ui: &mut UserInterface, key: &K) -> bool {
+        match self.position(key) {
+            Some(index) => {
+                ListBox::remove_item(ui, self.list_box, index);
+                self.keys.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Makes list box show items of snapshot in its order, keys of snapshot must be unique.
+    /// Items with keys that are no longer present are removed, items with new keys are
+    /// created by `create`, existing items are passed to `update` with their content node.
+    /// Items are moved only if they cannot stay in place: order of the longest sequence of
+    /// items which kept their relative order is not touched.
+    pub fn sync<T, KF, CF, UF>(&mut self, ui: &mut UserInterface, snapshot: &[T], key_of: KF, mut create: CF, mut update: UF)
+        where KF: Fn(&T) -> K,
+              CF: FnMut(&mut UserInterface, &T) -> Handle<UINode>,
+              UF: FnMut(&mut UserInterface, Handle<UINode>, &T) {
+        let new_keys = snapshot.iter().map(&key_of).collect::<Vec<_>>();
+        let new_positions = new_keys
+            .iter()
+            .enumerate()
+            .map(|(i, key)| (key.clone(), i))
+            .collect::<HashMap<_, _>>();
+        // Duplicates would collapse into one item and list would be shorter than snapshot.
+        debug_assert_eq!(new_positions.len(), new_keys.len(), "keys of snapshot must be unique");
+
+        for index in (0..self.keys.len()).rev() {
+            if !new_positions.contains_key(&self.keys[index]) {
+                ListBox::remove_item(ui, self.list_box, index);
+                self.keys.remove(index);
+            }
+        }
+
+        let old_positions = self.keys
+            .iter()
+            .enumerate()
+            .map(|(i, key)| (key.clone(), i))
+            .collect::<HashMap<_, _>>();
+        let old_indices = new_keys.iter().map(|key| old_positions.get(key).cloned()).collect::<Vec<_>>();
+        let stable = longest_increasing(&old_indices);
+
+        // Items are placed from the end, each one right before its successor, so stable
+        // items never move.
+        for i in (0..new_keys.len()).rev() {
+            if stable[i] {
+                continue;
+            }
+            let current = self.position(&new_keys[i]);
+            if let Some(current) = current {
+                self.keys.remove(current);
+            }
+            let target = match new_keys.get(i + 1) {
+                Some(next) => self.position(next).unwrap_or(self.keys.len()),
+                None => self.keys.len(),
+            };
+            match current {
+                Some(current) => ListBox::move_item(ui, self.list_box, current, target),
+                None => {
+                    let content = create(ui, &snapshot[i]);
+                    ListBox::insert_item(ui, self.list_box, target, content);
+                }
+            }
+            self.keys.insert(target, new_keys[i].clone());
+        }
+
+        let items = match ui.node(self.list_box).downcast_ref::<ListBox>() {
+            Some(list_box) => list_box.get_items().to_vec(),
+            None => return,
+        };
+        for (i, item) in snapshot.iter().enumerate() {
+            if old_indices[i].is_some() {
+                let content = ui.node(items[i])
+                    .downcast_ref::<ListBoxItem>()
+                    .map_or(Handle::NONE, |container| container.content);
+                update(ui, content, item);
+            }
+        }
+    }
+}
+
+/// Marks elements which form the longest strictly increasing subsequence of present
+/// indices, other elements (including missing ones) are not marked.
+fn longest_increasing(indices: &[Option<usize>]) -> Vec<bool> {
+    // Position of last element of best subsequence of each length, and predecessor of each
+    // element in its subsequence.
+    let mut tails: Vec<usize> = Vec::new();
+    let mut previous = vec![None; indices.len()];
+    for (i, index) in indices.iter().enumerate() {
+        let index = match index {
+            Some(index) => *index,
+            None => continue,
+        };
+        let length = tails
+            .binary_search_by(|tail| indices[*tail].cmp(&Some(index)))
+            .unwrap_or_else(|length| length);
+        if length > 0 {
+            previous[i] = Some(tails[length - 1]);
+        }
+        if length == tails.len() {
+            tails.push(i);
+        } else {
+            tails[length] = i;
+        }
+    }
+    let mut stable = vec![false; indices.len()];
+    let mut current = tails.last().cloned();
+    while let Some(i) = current {
+        stable[i] = true;
+        current = previous[i];
+    }
+    stable
+}
+
 impl Control for ListBoxItem {
     fn widget(&self) -> &Widget {
         &self.widget
@@ -83,12 +757,14 @@ impl Control for ListBoxItem {
         Box::new(Self {
             widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
             body: self.body,
+            content: self.content,
             index: self.index
         })
     }
 
     fn resolve(&mut self, _: &ControlTemplate, node_map: &HashMap<Handle<UINode>, Handle<UINode>>) {
         self.body = *node_map.get(&self.body).unwrap();
+        self.content = *node_map.get(&self.content).unwrap();
     }
 
     fn handle_event(&mut self, self_handle: Handle<UINode>, ui: &mut UserInterface, evt: &mut UIEvent) {
@@ -105,28 +781,22 @@ impl Control for ListBoxItem {
                 UIEventKind::MouseDown { .. } => {
                     // Explicitly set selection on parent list box. This will send
                     // SelectionChanged event and all items will react.
+                    let modifiers = ui.keyboard_modifiers();
                     ui.node_mut(list_box)
                         .downcast_mut::<ListBox>()
                         .unwrap()
-                        .set_selected(Some(self.index))
+                        .click_item(self.index, modifiers)
                 }
                 _ => ()
             }
         } else if evt.source == list_box {
-            if let Some(border) = ui.node_mut(self.body).downcast_mut::<Border>() {
-                if let UIEventKind::SelectionChanged(new_value) = evt.kind {
-                    // We know now that selection has changed in parent list box,
-                    // check at which index and keep visual state according to it.
-                    if let Some(new_value) = new_value {
-                        if new_value == self.index {
-                            border.widget_mut().set_foreground(Color::opaque(0, 0, 0));
-                            border.set_stroke_thickness(Thickness::uniform(2.0));
-                            return;
-                        }
-                    }
-                    border.widget_mut().set_foreground(Color::opaque(80, 80, 80));
-                    border.set_stroke_thickness(Thickness::uniform(1.0));
-                }
+            if let UIEventKind::SelectionChanged(selection) = &evt.kind {
+                // We know now that selection has changed in parent list box,
+                // check at which index and keep visual state according to it.
+                // Actual look of selected item is defined by style of body.
+                let selected = selection.contains(&self.index);
+                ui.set_node_state(self.body, Widget::SELECTED_STATE, selected);
+                ui.set_node_state(self.content, Widget::SELECTED_STATE, selected);
             }
         }
     }
@@ -145,7 +815,20 @@ impl Control for ListBox {
         Box::new(Self {
             widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
             selected_index: self.selected_index,
+            selection: self.selection.clone(),
+            anchor: self.anchor,
+            multi_selection: self.multi_selection,
             items: self.items.clone(),
+            panel: self.panel,
+            scroll_viewer: self.scroll_viewer,
+            reorderable: self.reorderable,
+            drag_handles: self.drag_handles.clone(),
+            has_drag_handles: self.has_drag_handles,
+            item_style: self.item_style.clone(),
+            drop_indicator: self.drop_indicator,
+            drag: None,
+            search_buffer: String::new(),
+            search_timer: None,
         })
     }
 
@@ -153,12 +836,137 @@ impl Control for ListBox {
         for item in self.items.iter_mut() {
             *item = *node_map.get(item).unwrap();
         }
+        for handle in self.drag_handles.iter_mut() {
+            if handle.is_some() {
+                *handle = *node_map.get(handle).unwrap();
+            }
+        }
+        if self.panel.is_some() {
+            self.panel = *node_map.get(&self.panel).unwrap();
+        }
+        if self.scroll_viewer.is_some() {
+            self.scroll_viewer = *node_map.get(&self.scroll_viewer).unwrap();
+        }
+        if self.drop_indicator.is_some() {
+            self.drop_indicator = *node_map.get(&self.drop_indicator).unwrap();
+        }
+    }
+
+    fn handle_event(&mut self, self_handle: Handle<UINode>, ui: &mut UserInterface, evt: &mut UIEvent) {
+        match evt.kind {
+            UIEventKind::KeyDown { code } => {
+                if !evt.handled && (evt.source == self_handle || self.widget.has_descendant(evt.source, ui)) {
+                    if let Some(action) = ui.key_action(code) {
+                        evt.handled |= self.navigate(ui, action);
+                    }
+                }
+                return;
+            }
+            UIEventKind::Text { symbol } => {
+                // Text typed into nested text boxes must not move selection.
+                if !evt.handled && evt.source == self_handle {
+                    evt.handled |= self.type_ahead(self_handle, ui, symbol);
+                }
+                return;
+            }
+            UIEventKind::Timer(id) if self.search_timer == Some(id) => {
+                self.search_timer = None;
+                self.search_buffer.clear();
+                return;
+            }
+            _ => ()
+        }
+
+        if !self.reorderable {
+            return;
+        }
+
+        match evt.kind {
+            UIEventKind::MouseDown { pos, button: MouseButton::Left } => {
+                if let Some(index) = self.item_index_of(evt.source, ui) {
+                    if self.is_drag_handle(index, evt.source, ui) {
+                        if ui.capture_mouse(self_handle) {
+                            self.drag = Some(ItemDrag { index, start: pos, active: false, long_press_timer: None });
+                        }
+                    } else {
+                        let timer = ui.set_timer(self_handle, Self::LONG_PRESS_TIME, false);
+                        self.drag = Some(ItemDrag { index, start: pos, active: false, long_press_timer: Some(timer) });
+                    }
+                }
+            }
+            UIEventKind::Timer(id) if evt.source == self_handle => {
+                let mut start = None;
+                if let Some(drag) = self.drag.as_mut() {
+                    if drag.long_press_timer == Some(id) {
+                        drag.long_press_timer = None;
+                        start = Some(drag.start);
+                    }
+                }
+                if let Some(start) = start {
+                    if ui.capture_mouse(self_handle) {
+                        if let Some(drag) = self.drag.as_mut() {
+                            drag.active = true;
+                        }
+                        self.update_drop_indicator(ui, start);
+                    } else {
+                        self.cancel_drag(ui);
+                    }
+                }
+            }
+            UIEventKind::MouseMove { pos } => {
+                let (active, waits_long_press, start) = match self.drag.as_ref() {
+                    Some(drag) => (drag.active, drag.long_press_timer.is_some(), drag.start),
+                    None => return,
+                };
+                let moved = (pos - start).len() > ui.drag_threshold();
+                if waits_long_press {
+                    // Moving pressed item without handle is not a drag.
+                    if moved && (evt.source == self_handle || self.widget.has_descendant(evt.source, ui)) {
+                        self.cancel_drag(ui);
+                    }
+                } else if evt.source == self_handle && (active || moved) {
+                    if let Some(drag) = self.drag.as_mut() {
+                        drag.active = true;
+                    }
+                    self.update_drop_indicator(ui, pos);
+                }
+            }
+            UIEventKind::MouseUp { pos, button: MouseButton::Left } => {
+                if let Some(drag) = self.drag.as_ref() {
+                    let from = drag.index;
+                    let captured = drag.long_press_timer.is_none();
+                    let active = drag.active;
+                    if captured {
+                        if evt.source != self_handle {
+                            return;
+                        }
+                        ui.release_mouse_capture();
+                    }
+                    if active {
+                        let (slot, _) = self.insertion_slot(ui, pos);
+                        let to = if slot > from { slot - 1 } else { slot };
+                        self.reorder_item(ui, from, to);
+                    }
+                    self.cancel_drag(ui);
+                }
+            }
+            UIEventKind::CaptureLost => {
+                if evt.source == self_handle {
+                    self.cancel_drag(ui);
+                }
+            }
+            _ => ()
+        }
     }
 }
 
 pub struct ListBoxBuilder {
     widget_builder: WidgetBuilder,
     items: Vec<Handle<UINode>>,
+    reorderable: bool,
+    drag_handles: bool,
+    multi_selection: bool,
+    item_style: Option<Rc<Style>>,
 }
 
 impl ListBoxBuilder {
@@ -166,6 +974,10 @@ impl ListBoxBuilder {
         Self {
             widget_builder,
             items: Vec::new(),
+            reorderable: false,
+            drag_handles: false,
+            multi_selection: false,
+            item_style: None,
         }
     }
 
@@ -173,28 +985,47 @@ impl ListBoxBuilder {
         self.items = items;
         self
     }
+
+    /// Allows user to reorder items by long-press and drag, `ItemsReordered` event is
+    /// emitted when item is dropped at new position.
+    pub fn with_reorderable(mut self, reorderable: bool) -> Self {
+        self.reorderable = reorderable;
+        self
+    }
+
+    /// Adds grab handle at the left side of each item, dragging by handle starts
+    /// immediately without long-press. Makes list box reorderable.
+    pub fn with_drag_handles(mut self, drag_handles: bool) -> Self {
+        self.drag_handles = drag_handles;
+        self
+    }
+
+    /// Allows selection of multiple items: Ctrl+Click toggles item, Shift+Click selects
+    /// range and Ctrl+A selects all items.
+    pub fn with_multi_selection(mut self, multi_selection: bool) -> Self {
+        self.multi_selection = multi_selection;
+        self
+    }
+
+    /// Sets style of item containers, style should use `Widget::SELECTED_STATE` setters
+    /// to highlight selected item. `ListBox::default_item_style` is used if not set.
+    pub fn with_item_style(mut self, style: Rc<Style>) -> Self {
+        self.item_style = Some(style);
+        self
+    }
 }
 
 impl Builder for ListBoxBuilder {
     fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
-        // Wrap each item into container which will have selection behaviour
-        let items: Vec<Handle<UINode>> = self.items.iter().enumerate().map(|(index, item)| {
-            let body = BorderBuilder::new(WidgetBuilder::new()
-                .with_foreground(Color::opaque(60, 60, 60))
-                .with_background(Color::opaque(80, 80, 80))
-                .with_child(*item))
-                .with_stroke_thickness(Thickness::uniform(1.0))
-                .build(ui);
-
-            let item = ListBoxItem {
-                widget: WidgetBuilder::new()
-                    .with_child(body)
-                    .build(),
-                body,
-                index
-            };
+        let item_style = self.item_style.clone().unwrap_or_else(ListBox::default_item_style);
 
-            ui.add_node(Box::new(item))
+        let mut drag_handles = Vec::new();
+        let items: Vec<Handle<UINode>> = self.items.iter().enumerate().map(|(index, item)| {
+            let (container, drag_handle) = make_item_container(ui, *item, index, self.drag_handles, &item_style);
+            if self.drag_handles {
+                drag_handles.push(drag_handle);
+            }
+            container
         }).collect();
 
         let panel = StackPanelBuilder::new(WidgetBuilder::new()
@@ -206,17 +1037,174 @@ impl Builder for ListBoxBuilder {
             .with_content(panel)
             .build(ui);
 
+        // Line that shows where dragged item will be inserted.
+        let drop_indicator = BorderBuilder::new(WidgetBuilder::new()
+            .with_height(2.0)
+            .with_vertical_alignment(VerticalAlignment::Top)
+            .with_visibility(Visibility::Collapsed)
+            .with_hit_test_visibility(HitTestVisibility::SubtreeInvisible)
+            .with_background(Color::opaque(200, 200, 200)))
+            .with_stroke_thickness(Thickness::uniform(0.0))
+            .build(ui);
+
         let list_box = ListBox {
             widget: self.widget_builder
+                .with_focusable(true)
                 .with_child(BorderBuilder::new(WidgetBuilder::new()
                     .with_background(Color::opaque(100, 100, 100))
                     .with_child(scroll_viewer))
                     .build(ui))
+                .with_child(drop_indicator)
                 .build(),
             selected_index: None,
+            selection: Vec::new(),
+            anchor: None,
+            multi_selection: self.multi_selection,
             items,
+            panel,
+            scroll_viewer,
+            reorderable: self.reorderable || self.drag_handles,
+            drag_handles,
+            has_drag_handles: self.drag_handles,
+            item_style,
+            drop_indicator,
+            drag: None,
+            search_buffer: String::new(),
+            search_timer: None,
         };
 
         ui.add_node(Box::new(list_box))
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::{
+            pool::Handle,
+            math::vec2::Vec2,
+        },
+        list_box::{
+            ListBox,
+            ListBoxBuilder,
+            KeyedItems,
+            longest_increasing,
+        },
+        border::BorderBuilder,
+        widget::WidgetBuilder,
+        event::{
+            OsEvent,
+            ButtonState,
+            MouseButton,
+            KeyCode,
+        },
+        UserInterface,
+        UINode,
+        UINodeContainer,
+        Builder,
+    };
+
+    fn set_key(ui: &mut UserInterface, button: KeyCode, state: ButtonState) {
+        ui.process_input_event(&OsEvent::KeyboardInput { button, state });
+    }
+
+    fn click(ui: &mut UserInterface, node: Handle<UINode>) {
+        let bounds = ui.node(node).widget().get_screen_bounds();
+        let position = Vec2::new(bounds.x + bounds.w * 0.5, bounds.y + bounds.h * 0.5);
+        ui.process_input_event(&OsEvent::CursorMoved { position });
+        ui.process_input_event(&OsEvent::MouseInput { button: MouseButton::Left, state: ButtonState::Pressed });
+        ui.process_input_event(&OsEvent::MouseInput { button: MouseButton::Left, state: ButtonState::Released });
+        while ui.poll_ui_event().is_some() {}
+    }
+
+    fn list_box(ui: &UserInterface, handle: Handle<UINode>) -> &ListBox {
+        ui.node(handle).downcast_ref::<ListBox>().unwrap()
+    }
+
+    #[test]
+    fn multi_selection_test() {
+        let mut ui = UserInterface::headless();
+        let items = (0..4)
+            .map(|_| BorderBuilder::new(WidgetBuilder::new().with_height(20.0)).build(&mut ui))
+            .collect::<Vec<_>>();
+        let handle = ListBoxBuilder::new(WidgetBuilder::new()
+            .with_width(100.0)
+            .with_height(200.0))
+            .with_items(items.clone())
+            .with_multi_selection(true)
+            .build(&mut ui);
+        ui.update(Vec2::new(800.0, 600.0), 0.0);
+
+        click(&mut ui, items[1]);
+        assert_eq!(list_box(&ui, handle).selection(), &[1]);
+
+        set_key(&mut ui, KeyCode::LControl, ButtonState::Pressed);
+        click(&mut ui, items[3]);
+        assert_eq!(list_box(&ui, handle).selection(), &[1, 3]);
+        assert_eq!(list_box(&ui, handle).get_selected(), Some(3));
+
+        // Deselected item must not stay selected one.
+        click(&mut ui, items[3]);
+        assert_eq!(list_box(&ui, handle).selection(), &[1]);
+        assert_eq!(list_box(&ui, handle).get_selected(), Some(1));
+        set_key(&mut ui, KeyCode::LControl, ButtonState::Released);
+
+        // Range starts from last remaining selected item.
+        set_key(&mut ui, KeyCode::LShift, ButtonState::Pressed);
+        click(&mut ui, items[3]);
+        assert_eq!(list_box(&ui, handle).selection(), &[1, 2, 3]);
+        click(&mut ui, items[0]);
+        assert_eq!(list_box(&ui, handle).selection(), &[0, 1]);
+        set_key(&mut ui, KeyCode::LShift, ButtonState::Released);
+
+        click(&mut ui, items[2]);
+        assert_eq!(list_box(&ui, handle).selection(), &[2]);
+    }
+
+    #[test]
+    fn longest_increasing_test() {
+        assert!(longest_increasing(&[]).is_empty());
+        assert_eq!(longest_increasing(&[None, None]), vec![false, false]);
+        assert_eq!(longest_increasing(&[Some(0), Some(1), Some(2)]), vec![true, true, true]);
+        // Item moved from the end to the beginning is the only one to move.
+        assert_eq!(longest_increasing(&[Some(3), Some(0), Some(1), Some(2)]), vec![false, true, true, true]);
+        // New items are never stable, reversed order keeps only one item in place.
+        assert_eq!(longest_increasing(&[Some(2), None, Some(1), Some(0)]).iter().filter(|s| **s).count(), 1);
+        assert_eq!(longest_increasing(&[Some(1), None, Some(0), Some(2), Some(3)]), vec![false, false, true, true, true]);
+    }
+
+    #[test]
+    fn keyed_sync_test() {
+        let mut ui = UserInterface::headless();
+        let handle = ListBoxBuilder::new(WidgetBuilder::new()).build(&mut ui);
+        let mut keyed = KeyedItems::new(handle);
+        let make_item = |ui: &mut UserInterface| BorderBuilder::new(WidgetBuilder::new()).build(ui);
+
+        let mut created = Vec::new();
+        keyed.sync(&mut ui, &[1, 2, 3], |key| *key,
+                   |ui, key| {
+                       created.push(*key);
+                       make_item(ui)
+                   },
+                   |_, _, _| ());
+        created.sort();
+        assert_eq!(created, vec![1, 2, 3]);
+        let items = list_box(&ui, handle).get_items().to_vec();
+
+        let mut created = Vec::new();
+        let mut updated = Vec::new();
+        keyed.sync(&mut ui, &[3, 1, 4], |key| *key,
+                   |ui, key| {
+                       created.push(*key);
+                       make_item(ui)
+                   },
+                   |_, _, key| updated.push(*key));
+        assert_eq!(created, vec![4]);
+        assert_eq!(updated, vec![3, 1]);
+        assert_eq!(keyed.keys(), &[3, 1, 4]);
+        // Items of kept keys are reused.
+        let new_items = list_box(&ui, handle).get_items().to_vec();
+        assert_eq!(new_items.len(), 3);
+        assert!(new_items[0] == items[2] && new_items[1] == items[0]);
+    }
+}
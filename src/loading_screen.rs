@@ -0,0 +1,382 @@
+use crate::{
+    core::{
+        color::Color,
+        pool::Handle,
+        math::{
+            vec2::Vec2,
+            Rect,
+        },
+    },
+    widget::{
+        Widget,
+        WidgetBuilder,
+    },
+    image::ImageBuilder,
+    draw::{
+        DrawingContext,
+        CommandKind,
+        CommandTexture,
+        GhostStyle,
+        Texture,
+    },
+    formatted_text::{
+        FormattedText,
+        FormattedTextBuilder,
+    },
+    color_ext::palette,
+    event::{
+        UIEvent,
+        UIEventKind,
+    },
+    UINode,
+    HorizontalAlignment,
+    VerticalAlignment,
+    Visibility,
+    Control,
+    ControlTemplate,
+    UINodeContainer,
+    Builder,
+};
+use std::{
+    cell::Cell,
+    collections::HashMap,
+    sync::Arc,
+};
+
+/// Named step of loading, weight defines its share of progress bar.
+#[derive(Clone, Debug)]
+struct Stage {
+    name: String,
+    weight: f32,
+    progress: f32,
+}
+
+/// Full-screen splash shown while game or editor loads: background image, progress bar
+/// split into named stages with caption of current stage, and tips which change every few
+/// seconds. When every stage is complete (or `finish` is called) screen fades out and
+/// collapses itself.
+///
+/// Loading code reports progress of stages by name, for example
+/// `set_stage_progress("Textures", loaded as f32 / total as f32)`, stages may be completed
+/// in any order. Screen uses safe area, so it covers whole screen when it is a child of
+/// root.
+///
+/// # Events
+///
+/// [`LoadingFinished`] - sent when fade-out has ended and screen was collapsed.
+pub struct LoadingScreen {
+    widget: Widget,
+    stages: Vec<Stage>,
+    tips: Vec<String>,
+    current_tip: usize,
+    tip_interval: f32,
+    tip_time: f32,
+    fade_duration: f32,
+    /// Opacity during fade-out, None while loading.
+    fade: Option<f32>,
+    bar_color: Color,
+    caption: FormattedText,
+    tip: FormattedText,
+    /// Size of screen for which texts were laid out.
+    text_size: Vec2,
+    /// Index of first drawing command of screen, whole subtree is faded from it.
+    first_command: Cell<usize>,
+}
+
+impl LoadingScreen {
+    const BAR_HEIGHT: f32 = 8.0;
+    /// Width of progress bar relative to width of screen.
+    const BAR_WIDTH: f32 = 0.6;
+    /// Distance from bottom edge of screen to progress bar.
+    const BAR_OFFSET: f32 = 80.0;
+    const TEXT_HEIGHT: f32 = 30.0;
+
+    /// Sets progress of stage in [0; 1], unknown stages are ignored.
+    pub fn set_stage_progress(&mut self, stage: &str, progress: f32) -> &mut Self {
+        if let Some(stage) = self.stages.iter_mut().find(|s| s.name == stage) {
+            stage.progress = progress.max(0.0).min(1.0);
+        }
+        self.on_progress_changed();
+        self
+    }
+
+    pub fn complete_stage(&mut self, stage: &str) -> &mut Self {
+        self.set_stage_progress(stage, 1.0)
+    }
+
+    pub fn stage_progress(&self, stage: &str) -> Option<f32> {
+        self.stages.iter().find(|s| s.name == stage).map(|s| s.progress)
+    }
+
+    /// Returns first incomplete stage.
+    pub fn current_stage(&self) -> Option<&str> {
+        self.stages
+            .iter()
+            .find(|stage| stage.progress < 1.0)
+            .map(|stage| stage.name.as_str())
+    }
+
+    /// Returns overall progress in [0; 1], stages contribute to it by their weights.
+    pub fn progress(&self) -> f32 {
+        let total = self.stages.iter().map(|stage| stage.weight).sum::<f32>();
+        if total <= 0.0 {
+            return if self.fade.is_some() { 1.0 } else { 0.0 };
+        }
+        self.stages.iter().map(|stage| stage.weight * stage.progress).sum::<f32>() / total
+    }
+
+    /// Starts fade-out regardless of progress of stages.
+    pub fn finish(&mut self) {
+        if self.fade.is_none() {
+            self.fade = Some(1.0);
+        }
+    }
+
+    /// Returns true if fade-out is started or ended.
+    pub fn is_finished(&self) -> bool {
+        self.fade.is_some()
+    }
+
+    pub fn set_tips(&mut self, tips: Vec<String>) -> &mut Self {
+        self.tips = tips;
+        self.current_tip = 0;
+        self.tip_time = 0.0;
+        self.update_tip();
+        self
+    }
+
+    pub fn tips(&self) -> &[String] {
+        &self.tips
+    }
+
+    fn on_progress_changed(&mut self) {
+        if !self.stages.is_empty() && self.stages.iter().all(|stage| stage.progress >= 1.0) {
+            self.finish();
+        }
+        self.update_caption();
+    }
+
+    fn update_caption(&mut self) {
+        let text = match self.current_stage() {
+            Some(stage) => format!("{}... {}%", stage, (self.progress() * 100.0).floor()),
+            None => String::new(),
+        };
+        self.caption.set_text(&text);
+        self.caption.set_size(Vec2::new(self.text_size.x, Self::TEXT_HEIGHT));
+        self.caption.build();
+    }
+
+    fn update_tip(&mut self) {
+        let text = self.tips.get(self.current_tip).map_or("", |tip| tip.as_str()).to_owned();
+        self.tip.set_text(&text);
+        self.tip.set_size(Vec2::new(self.text_size.x, Self::TEXT_HEIGHT));
+        self.tip.build();
+    }
+
+    fn bar_rect(&self, bounds: &Rect<f32>) -> Rect<f32> {
+        let width = bounds.w * Self::BAR_WIDTH;
+        Rect::new(
+            bounds.x + (bounds.w - width) * 0.5,
+            bounds.y + bounds.h - Self::BAR_OFFSET,
+            width,
+            Self::BAR_HEIGHT,
+        )
+    }
+}
+
+impl Control for LoadingScreen {
+    fn widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    fn widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    fn raw_copy(&self) -> Box<dyn Control> {
+        let mut copy = Self {
+            widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
+            stages: self.stages.clone(),
+            tips: self.tips.clone(),
+            current_tip: self.current_tip,
+            tip_interval: self.tip_interval,
+            tip_time: self.tip_time,
+            fade_duration: self.fade_duration,
+            fade: self.fade,
+            bar_color: self.bar_color,
+            caption: make_text(),
+            tip: make_text(),
+            text_size: self.text_size,
+            first_command: Cell::new(0),
+        };
+        copy.update_caption();
+        copy.update_tip();
+        Box::new(copy)
+    }
+
+    fn resolve(&mut self, _: &ControlTemplate, _: &HashMap<Handle<UINode>, Handle<UINode>>) {}
+
+    fn draw(&self, drawing_context: &mut DrawingContext) {
+        self.first_command.set(drawing_context.get_commands().len());
+        // Background also blocks input for everything under the screen.
+        drawing_context.push_rect_filled(&self.widget.get_screen_bounds(), None, self.widget.background());
+        drawing_context.commit(CommandKind::Geometry, CommandTexture::None);
+    }
+
+    fn post_draw(&self, drawing_context: &mut DrawingContext) {
+        // Bar and texts go over background image, which is a child.
+        let bounds = self.widget.get_screen_bounds();
+        let bar = self.bar_rect(&bounds);
+        drawing_context.push_rect_filled(&bar, None, palette::current().field);
+        drawing_context.push_rect_filled(&Rect::new(bar.x, bar.y, bar.w * self.progress(), bar.h), None, self.bar_color);
+        drawing_context.push_rect(&bar, 1.0, palette::current().frame);
+        drawing_context.commit(CommandKind::Geometry, CommandTexture::None);
+
+        drawing_context.draw_text(Vec2::new(bounds.x, bar.y - Self::TEXT_HEIGHT), &self.caption);
+        drawing_context.draw_text(Vec2::new(bounds.x, bar.y + Self::BAR_HEIGHT + 8.0), &self.tip);
+
+        if let Some(fade) = self.fade {
+            drawing_context.ghost_commands(self.first_command.get(), GhostStyle {
+                opacity: fade,
+                desaturation: 0.0,
+            });
+        }
+    }
+
+    fn update(&mut self, dt: f32) {
+        if self.widget.actual_size() != self.text_size {
+            self.text_size = self.widget.actual_size();
+            self.update_caption();
+            self.update_tip();
+        }
+
+        if self.tips.len() > 1 {
+            self.tip_time += dt;
+            if self.tip_time >= self.tip_interval {
+                self.tip_time = 0.0;
+                self.current_tip = (self.current_tip + 1) % self.tips.len();
+                self.update_tip();
+            }
+        }
+
+        if let Some(fade) = self.fade {
+            if self.widget.get_visibility() != Visibility::Collapsed {
+                let fade = if self.fade_duration > 0.0 { fade - dt / self.fade_duration } else { 0.0 };
+                if fade <= 0.0 {
+                    self.fade = Some(0.0);
+                    self.widget.set_visibility(Visibility::Collapsed);
+                    self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::LoadingFinished));
+                } else {
+                    self.fade = Some(fade);
+                }
+            }
+        }
+    }
+
+    fn needs_update(&self) -> bool {
+        self.widget.get_visibility() != Visibility::Collapsed
+    }
+}
+
+fn make_text() -> FormattedText {
+    FormattedTextBuilder::new()
+        .with_font(crate::DEFAULT_FONT.clone())
+        .with_color(palette::current().text)
+        .with_horizontal_alignment(HorizontalAlignment::Center)
+        .with_vertical_alignment(VerticalAlignment::Center)
+        .build()
+}
+
+pub struct LoadingScreenBuilder {
+    widget_builder: WidgetBuilder,
+    background: Option<Arc<Texture>>,
+    stages: Vec<Stage>,
+    tips: Vec<String>,
+    tip_interval: f32,
+    fade_duration: f32,
+    bar_color: Option<Color>,
+}
+
+impl LoadingScreenBuilder {
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            background: None,
+            stages: Vec::new(),
+            tips: Vec::new(),
+            tip_interval: 5.0,
+            fade_duration: 0.5,
+            bar_color: None,
+        }
+    }
+
+    /// Sets image which is stretched over whole screen.
+    pub fn with_background(mut self, texture: Arc<Texture>) -> Self {
+        self.background = Some(texture);
+        self
+    }
+
+    /// Adds stage of loading, stages are shown in order they were added.
+    pub fn with_stage(mut self, name: &str, weight: f32) -> Self {
+        self.stages.push(Stage {
+            name: name.to_owned(),
+            weight: weight.max(0.0),
+            progress: 0.0,
+        });
+        self
+    }
+
+    pub fn with_tips(mut self, tips: Vec<String>) -> Self {
+        self.tips = tips;
+        self
+    }
+
+    /// Sets time in seconds each tip is shown.
+    pub fn with_tip_interval(mut self, interval: f32) -> Self {
+        self.tip_interval = interval;
+        self
+    }
+
+    /// Sets duration of fade-out in seconds.
+    pub fn with_fade_duration(mut self, duration: f32) -> Self {
+        self.fade_duration = duration;
+        self
+    }
+
+    pub fn with_bar_color(mut self, color: Color) -> Self {
+        self.bar_color = Some(color);
+        self
+    }
+}
+
+impl Builder for LoadingScreenBuilder {
+    fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
+        let background = ImageBuilder::new(WidgetBuilder::new())
+            .with_opt_texture(self.background)
+            .build(ui);
+
+        let mut loading_screen = LoadingScreen {
+            widget: self.widget_builder
+                .with_safe_area(true)
+                .with_background(Color::BLACK)
+                .with_child(background)
+                .build(),
+            stages: self.stages,
+            tips: self.tips,
+            current_tip: 0,
+            tip_interval: self.tip_interval,
+            tip_time: 0.0,
+            fade_duration: self.fade_duration,
+            fade: None,
+            bar_color: self.bar_color.unwrap_or(palette::current().accent),
+            caption: make_text(),
+            tip: make_text(),
+            text_size: Vec2::ZERO,
+            first_command: Cell::new(0),
+        };
+        loading_screen.update_caption();
+        loading_screen.update_tip();
+
+        ui.add_node(Box::new(loading_screen))
+    }
+}
@@ -0,0 +1,264 @@
+use crate::{
+    core::pool::Handle,
+    widget::{
+        Widget,
+        WidgetBuilder,
+    },
+    text_box::{
+        TextBox,
+        TextBoxBuilder,
+    },
+    event::{
+        UIEvent,
+        UIEventKind,
+    },
+    UserInterface,
+    UINode,
+    Control,
+    ControlTemplate,
+    UINodeContainer,
+    Builder,
+};
+use std::collections::HashMap;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum MaskChar {
+    /// `0` - required digit.
+    Digit,
+    /// `9` - optional digit.
+    OptionalDigit,
+    /// `a` - required letter.
+    Letter,
+    /// `*` - required letter or digit.
+    Alphanumeric,
+    /// Any other character, `\` makes next character literal.
+    Literal(char),
+}
+
+impl MaskChar {
+    fn accepts(self, c: char) -> bool {
+        match self {
+            MaskChar::Digit | MaskChar::OptionalDigit => c.is_ascii_digit(),
+            MaskChar::Letter => c.is_alphabetic(),
+            MaskChar::Alphanumeric => c.is_alphanumeric(),
+            MaskChar::Literal(literal) => c == literal,
+        }
+    }
+
+    fn is_required(self) -> bool {
+        match self {
+            MaskChar::Digit | MaskChar::Letter | MaskChar::Alphanumeric => true,
+            MaskChar::OptionalDigit | MaskChar::Literal(_) => false,
+        }
+    }
+}
+
+fn parse_mask(mask: &str) -> Vec<MaskChar> {
+    let mut result = Vec::new();
+    let mut chars = mask.chars();
+    while let Some(c) = chars.next() {
+        result.push(match c {
+            '0' => MaskChar::Digit,
+            '9' => MaskChar::OptionalDigit,
+            'a' => MaskChar::Letter,
+            '*' => MaskChar::Alphanumeric,
+            '\\' => MaskChar::Literal(chars.next().unwrap_or('\\')),
+            _ => MaskChar::Literal(c),
+        });
+    }
+    result
+}
+
+/// Feeds characters through mask, invalid characters are dropped and literals are
+/// inserted automatically. Returns accepted text and index of next mask character.
+fn apply_mask(mask: &[MaskChar], input: impl Iterator<Item=char>) -> (String, usize) {
+    let mut text = String::new();
+    let mut i = 0;
+    'input: for c in input {
+        while i < mask.len() {
+            match mask[i] {
+                MaskChar::Literal(literal) => {
+                    text.push(literal);
+                    i += 1;
+                    if c == literal {
+                        continue 'input;
+                    }
+                }
+                MaskChar::OptionalDigit if !c.is_ascii_digit() => {
+                    // Character may close group of optional digits (like dot in IP address).
+                    let next_literal = mask[i..]
+                        .iter()
+                        .position(|m| *m != MaskChar::OptionalDigit)
+                        .map(|offset| i + offset);
+                    match next_literal {
+                        Some(index) if mask[index] == MaskChar::Literal(c) => {
+                            i = index;
+                        }
+                        _ => continue 'input,
+                    }
+                }
+                slot => {
+                    if slot.accepts(c) {
+                        text.push(c);
+                        i += 1;
+                    }
+                    continue 'input;
+                }
+            }
+        }
+        break;
+    }
+    (text, i)
+}
+
+/// Single line text box which accepts input that matches a mask, for example `00/00/0000`
+/// for dates or `099.099.099.099` for IP addresses. Mask characters:
+///
+/// - `0` - required digit,
+/// - `9` - optional digit,
+/// - `a` - required letter,
+/// - `*` - required letter or digit,
+/// - `\` - makes next character literal,
+/// - any other character is literal which is inserted automatically.
+///
+/// Invalid characters are rejected, literals typed by user are skipped.
+///
+/// # Events
+///
+/// [`TextChanged`] - sent by inner text box with already masked text.
+/// [`MaskCompletenessChanged`] - sent when all required characters were entered or when
+/// text became incomplete again.
+pub struct MaskedTextBox {
+    widget: Widget,
+    field: Handle<UINode>,
+    mask: Vec<MaskChar>,
+    is_complete: bool,
+}
+
+impl MaskedTextBox {
+    pub fn field(&self) -> Handle<UINode> {
+        self.field
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.is_complete
+    }
+
+    /// Sets new mask, text of the field is masked again.
+    pub fn set_mask(&mut self, ui: &mut UserInterface, mask: &str) {
+        self.mask = parse_mask(mask);
+        self.apply(ui);
+    }
+
+    /// Returns text of the field, it always satisfies mask but can be incomplete.
+    pub fn text(&self, ui: &UserInterface) -> String {
+        ui.node(self.field)
+            .downcast_ref::<TextBox>()
+            .map_or(String::new(), |text_box| text_box.text())
+    }
+
+    fn is_text_complete(&self, next_mask_index: usize) -> bool {
+        self.mask[next_mask_index..].iter().all(|m| !m.is_required())
+    }
+
+    fn apply(&mut self, ui: &mut UserInterface) {
+        let text_box = match ui.node_mut(self.field).downcast_mut::<TextBox>() {
+            Some(text_box) => text_box,
+            None => return,
+        };
+        let text = text_box.text();
+        let (masked, next) = apply_mask(&self.mask, text.chars());
+        if masked != text {
+            let caret = text_box.get_absolute_position().unwrap_or(0);
+            let (masked_prefix, _) = apply_mask(&self.mask, text.chars().take(caret));
+            // Setting text sends TextChanged again, but masked text stays the same.
+            text_box.set_text(&masked);
+            text_box.set_caret_position(0, masked_prefix.chars().count());
+        }
+        let is_complete = self.is_text_complete(next);
+        if is_complete != self.is_complete {
+            self.is_complete = is_complete;
+            self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::MaskCompletenessChanged(is_complete)));
+        }
+    }
+}
+
+impl Control for MaskedTextBox {
+    fn widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    fn widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    fn raw_copy(&self) -> Box<dyn Control> {
+        Box::new(Self {
+            widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
+            field: self.field,
+            mask: self.mask.clone(),
+            is_complete: self.is_complete,
+        })
+    }
+
+    fn resolve(&mut self, _: &ControlTemplate, node_map: &HashMap<Handle<UINode>, Handle<UINode>>) {
+        self.field = *node_map.get(&self.field).unwrap();
+    }
+
+    fn handle_event(&mut self, _self_handle: Handle<UINode>, ui: &mut UserInterface, evt: &mut UIEvent) {
+        if evt.source == self.field {
+            if let UIEventKind::TextChanged = evt.kind {
+                self.apply(ui);
+            }
+        }
+    }
+}
+
+pub struct MaskedTextBoxBuilder {
+    widget_builder: WidgetBuilder,
+    mask: String,
+    text: String,
+}
+
+impl MaskedTextBoxBuilder {
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            mask: String::new(),
+            text: String::new(),
+        }
+    }
+
+    pub fn with_mask(mut self, mask: &str) -> Self {
+        self.mask = mask.to_owned();
+        self
+    }
+
+    /// Sets initial text, it will be masked.
+    pub fn with_text(mut self, text: &str) -> Self {
+        self.text = text.to_owned();
+        self
+    }
+}
+
+impl Builder for MaskedTextBoxBuilder {
+    fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
+        let mask = parse_mask(&self.mask);
+        let (text, next) = apply_mask(&mask, self.text.chars());
+
+        let field = TextBoxBuilder::new(WidgetBuilder::new())
+            .with_text(text)
+            .build(ui);
+
+        let masked_text_box = MaskedTextBox {
+            widget: self.widget_builder
+                .with_child(field)
+                .build(),
+            field,
+            is_complete: mask[next..].iter().all(|m| !m.is_required()),
+            mask,
+        };
+
+        ui.add_node(Box::new(masked_text_box))
+    }
+}
@@ -0,0 +1,380 @@
+use crate::{
+    core::{
+        math::{
+            self,
+            vec2::Vec2,
+        },
+        pool::Handle,
+    },
+    widget::{
+        Widget,
+        WidgetBuilder,
+    },
+    text_box::{
+        TextBox,
+        TextBoxBuilder,
+    },
+    event::{
+        UIEvent,
+        UIEventKind,
+        MouseButton,
+    },
+    keymap::WidgetAction,
+    UserInterface,
+    AnnouncementPriority,
+    UINode,
+    Control,
+    ControlTemplate,
+    UINodeContainer,
+    Builder,
+};
+use std::collections::HashMap;
+
+/// Text box that always shows formatted number. Entered text is parsed when user
+/// presses Enter or when field loses keyboard focus, invalid input is reverted to
+/// last valid value. Value can also be changed by dragging mouse horizontally over
+/// the field.
+///
+/// # Events
+///
+/// [`NumericValueChanged`] - spawned when value changes by any method.
+pub struct NumericTextBox {
+    widget: Widget,
+    field: Handle<UINode>,
+    value: f32,
+    min: f32,
+    max: f32,
+    precision: usize,
+    /// How much value changes per one pixel of horizontal mouse movement while dragging.
+    drag_sensitivity: f32,
+    drag_origin: Option<Vec2>,
+    drag_start_value: f32,
+    is_dragging: bool,
+}
+
+impl Control for NumericTextBox {
+    fn widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    fn widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    fn raw_copy(&self) -> Box<dyn Control> {
+        Box::new(Self {
+            widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
+            field: self.field,
+            value: self.value,
+            min: self.min,
+            max: self.max,
+            precision: self.precision,
+            drag_sensitivity: self.drag_sensitivity,
+            drag_origin: None,
+            drag_start_value: self.drag_start_value,
+            is_dragging: false,
+        })
+    }
+
+    fn resolve(&mut self, _: &ControlTemplate, node_map: &HashMap<Handle<UINode>, Handle<UINode>>) {
+        self.field = *node_map.get(&self.field).unwrap();
+    }
+
+    fn handle_event(&mut self, self_handle: Handle<UINode>, ui: &mut UserInterface, evt: &mut UIEvent) {
+        if evt.source == self.field {
+            match evt.kind {
+                UIEventKind::KeyDown { code } => {
+                    match ui.key_action(code) {
+                        Some(WidgetAction::Commit) => self.commit(ui),
+                        Some(WidgetAction::Cancel) => self.sync_field(ui),
+                        _ => ()
+                    }
+                }
+                UIEventKind::LostFocus => {
+                    self.commit(ui);
+                }
+                UIEventKind::MouseDown { pos, button } => {
+                    if button == MouseButton::Left {
+                        self.drag_origin = Some(pos);
+                        self.drag_start_value = self.value;
+                    }
+                }
+                UIEventKind::MouseMove { pos } => {
+                    if let Some(origin) = self.drag_origin {
+                        let dx = pos.x - origin.x;
+                        if !self.is_dragging && dx.abs() > Self::DRAG_THRESHOLD {
+                            self.is_dragging = true;
+                        }
+                        if self.is_dragging {
+                            self.set_value(self.drag_start_value + dx * self.drag_sensitivity);
+                            // Dragging must not select text in the field.
+                            if let Some(text_box) = ui.node_mut(self.field).downcast_mut::<TextBox>() {
+                                text_box.clear_selection();
+                            }
+                            evt.handled = true;
+                        }
+                    }
+                }
+                UIEventKind::MouseUp { .. } => {
+                    self.drag_origin = None;
+                    self.is_dragging = false;
+                }
+                _ => ()
+            }
+        }
+
+        if evt.source == self_handle {
+            if let UIEventKind::NumericValueChanged { .. } = evt.kind {
+                self.sync_field(ui);
+            }
+        }
+    }
+}
+
+impl NumericTextBox {
+    /// Distance in pixels mouse must travel before dragging starts to change value.
+    pub const DRAG_THRESHOLD: f32 = 3.0;
+
+    pub fn new(widget: Widget, field: Handle<UINode>) -> Self {
+        Self {
+            widget,
+            field,
+            value: 0.0,
+            min: -std::f32::MAX,
+            max: std::f32::MAX,
+            precision: 3,
+            drag_sensitivity: 0.1,
+            drag_origin: None,
+            drag_start_value: 0.0,
+            is_dragging: false,
+        }
+    }
+
+    pub fn set_value(&mut self, value: f32) -> &mut Self {
+        let old_value = self.value;
+        let new_value = math::clampf(value, self.min, self.max);
+        if (new_value - old_value).abs() > std::f32::EPSILON {
+            self.value = new_value;
+            self.widget.events.borrow_mut().push_back(
+                UIEvent::new(UIEventKind::NumericValueChanged {
+                    old_value,
+                    new_value,
+                }));
+        }
+        self
+    }
+
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    /// Sets lower bound of value, bounds are swapped if min is greater than max.
+    pub fn set_min_value(&mut self, min: f32) -> &mut Self {
+        debug_assert!(min <= self.max, "min {} is greater than max {}", min, self.max);
+        self.min = min;
+        self.fix_bounds();
+        self.set_value(self.value)
+    }
+
+    pub fn min_value(&self) -> f32 {
+        self.min
+    }
+
+    /// Sets upper bound of value, bounds are swapped if max is less than min.
+    pub fn set_max_value(&mut self, max: f32) -> &mut Self {
+        debug_assert!(self.min <= max, "max {} is less than min {}", max, self.min);
+        self.max = max;
+        self.fix_bounds();
+        self.set_value(self.value)
+    }
+
+    fn fix_bounds(&mut self) {
+        if self.min > self.max {
+            std::mem::swap(&mut self.min, &mut self.max);
+        }
+    }
+
+    pub fn max_value(&self) -> f32 {
+        self.max
+    }
+
+    /// Sets amount of digits after decimal point, text of field is formatted again.
+    pub fn set_precision(ui: &mut UserInterface, numeric_text_box: Handle<UINode>, precision: usize) {
+        let (field, text) = match ui.node_mut(numeric_text_box).downcast_mut::<NumericTextBox>() {
+            Some(numeric_text_box) => {
+                numeric_text_box.precision = precision;
+                (numeric_text_box.field, numeric_text_box.formatted_value())
+            }
+            None => return,
+        };
+        set_field_text(ui, field, text);
+    }
+
+    pub fn precision(&self) -> usize {
+        self.precision
+    }
+
+    pub fn set_drag_sensitivity(&mut self, sensitivity: f32) -> &mut Self {
+        self.drag_sensitivity = sensitivity;
+        self
+    }
+
+    pub fn drag_sensitivity(&self) -> f32 {
+        self.drag_sensitivity
+    }
+
+    pub fn field(&self) -> Handle<UINode> {
+        self.field
+    }
+
+    pub fn formatted_value(&self) -> String {
+        format!("{:.*}", self.precision, self.value)
+    }
+
+    fn sync_field(&self, ui: &mut UserInterface) {
+        set_field_text(ui, self.field, self.formatted_value());
+    }
+
+    /// Parses text of the field, if it is not a number then text will be reverted
+    /// to current value.
+    fn commit(&mut self, ui: &mut UserInterface) {
+        let text = ui.node(self.field)
+            .downcast_ref::<TextBox>()
+            .map(|text_box| text_box.text());
+        match text.map(|text| text.trim().parse::<f32>()) {
+            Some(Ok(value)) => {
+                self.set_value(value);
+            }
+            Some(Err(_)) => {
+                // Let user know why entered text was discarded.
+                ui.announce("Invalid number", AnnouncementPriority::Assertive);
+            }
+            None => ()
+        }
+        self.sync_field(ui);
+    }
+}
+
+fn set_field_text(ui: &mut UserInterface, field: Handle<UINode>, text: String) {
+    if let Some(text_box) = ui.node_mut(field).downcast_mut::<TextBox>() {
+        if text_box.text() != text {
+            text_box.set_text(text);
+        }
+    }
+}
+
+pub struct NumericTextBoxBuilder {
+    widget_builder: WidgetBuilder,
+    value: Option<f32>,
+    min: Option<f32>,
+    max: Option<f32>,
+    precision: Option<usize>,
+    drag_sensitivity: Option<f32>,
+}
+
+impl NumericTextBoxBuilder {
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            value: None,
+            min: None,
+            max: None,
+            precision: None,
+            drag_sensitivity: None,
+        }
+    }
+
+    pub fn with_value(mut self, value: f32) -> Self {
+        self.value = Some(value);
+        self
+    }
+
+    pub fn with_min(mut self, min: f32) -> Self {
+        self.min = Some(min);
+        self
+    }
+
+    pub fn with_max(mut self, max: f32) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    pub fn with_precision(mut self, precision: usize) -> Self {
+        self.precision = Some(precision);
+        self
+    }
+
+    pub fn with_drag_sensitivity(mut self, sensitivity: f32) -> Self {
+        self.drag_sensitivity = Some(sensitivity);
+        self
+    }
+}
+
+impl Builder for NumericTextBoxBuilder {
+    fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
+        let mut min = self.min.unwrap_or(-std::f32::MAX);
+        let mut max = self.max.unwrap_or(std::f32::MAX);
+        debug_assert!(min <= max, "min {} is greater than max {}", min, max);
+        if min > max {
+            std::mem::swap(&mut min, &mut max);
+        }
+        let value = math::clampf(self.value.unwrap_or(0.0), min, max);
+        let precision = self.precision.unwrap_or(3);
+
+        let field = TextBoxBuilder::new(WidgetBuilder::new())
+            .with_text(format!("{:.*}", precision, value))
+            .build(ui);
+
+        let numeric_text_box = NumericTextBox {
+            widget: self.widget_builder
+                .with_child(field)
+                .build(),
+            field,
+            value,
+            min,
+            max,
+            precision,
+            drag_sensitivity: self.drag_sensitivity.unwrap_or(0.1),
+            drag_origin: None,
+            drag_start_value: value,
+            is_dragging: false,
+        };
+
+        ui.add_node(Box::new(numeric_text_box))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::pool::Handle,
+        numeric_text_box::{
+            NumericTextBox,
+            NumericTextBoxBuilder,
+        },
+        text_box::TextBox,
+        widget::WidgetBuilder,
+        UserInterface,
+        UINode,
+        UINodeContainer,
+        Builder,
+    };
+
+    fn field_text(ui: &UserInterface, handle: Handle<UINode>) -> String {
+        let field = ui.node(handle).downcast_ref::<NumericTextBox>().unwrap().field();
+        ui.node(field).downcast_ref::<TextBox>().unwrap().text()
+    }
+
+    #[test]
+    fn precision_test() {
+        let mut ui = UserInterface::headless();
+        let handle = NumericTextBoxBuilder::new(WidgetBuilder::new())
+            .with_value(1.3)
+            .with_precision(3)
+            .build(&mut ui);
+        assert_eq!(field_text(&ui, handle), "1.300");
+
+        NumericTextBox::set_precision(&mut ui, handle, 1);
+        assert_eq!(field_text(&ui, handle), "1.3");
+    }
+}
@@ -0,0 +1,241 @@
+use crate::{
+    core::{
+        color::Color,
+        math::vec2::Vec2,
+    },
+    draw::{
+        DrawingContext,
+        CommandKind,
+        CommandTexture,
+        Vertex,
+    },
+};
+
+/// RGBA image with 8 bits per channel, rows go from top to bottom. Can be used as texture
+/// of texture brushes, rasterizer samples such textures, other textures are replaced by
+/// vertex color.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CpuImage {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl CpuImage {
+    pub fn new(width: usize, height: usize, color: Color) -> Self {
+        let mut pixels = Vec::with_capacity(width * height * 4);
+        for _ in 0..width * height {
+            pixels.extend_from_slice(&[color.r, color.g, color.b, color.a]);
+        }
+        Self { width, height, pixels }
+    }
+
+    /// Creates image from raw RGBA pixels, returns None if size of data does not match
+    /// size of image.
+    pub fn from_rgba(width: usize, height: usize, pixels: Vec<u8>) -> Option<Self> {
+        if pixels.len() == width * height * 4 {
+            Some(Self { width, height, pixels })
+        } else {
+            None
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Raw RGBA pixels, can be saved by any image library.
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> Color {
+        let i = (y * self.width + x) * 4;
+        Color::from_rgba(self.pixels[i], self.pixels[i + 1], self.pixels[i + 2], self.pixels[i + 3])
+    }
+
+    pub fn set_pixel(&mut self, x: usize, y: usize, color: Color) {
+        let i = (y * self.width + x) * 4;
+        self.pixels[i..i + 4].copy_from_slice(&[color.r, color.g, color.b, color.a]);
+    }
+
+    /// Compares image with reference image, channels that differ by no more than
+    /// `tolerance` are considered equal. Images of different size differ in every pixel.
+    pub fn compare(&self, reference: &CpuImage, tolerance: u8) -> ImageDiff {
+        if self.width != reference.width || self.height != reference.height {
+            return ImageDiff {
+                different_pixels: self.width.max(reference.width) * self.height.max(reference.height),
+                max_difference: 255,
+                mask: None,
+            };
+        }
+        let mut different_pixels = 0;
+        let mut max_difference = 0;
+        let mut mask = CpuImage::new(self.width, self.height, Color::BLACK);
+        for (i, (a, b)) in self.pixels.chunks(4).zip(reference.pixels.chunks(4)).enumerate() {
+            let difference = a.iter()
+                .zip(b.iter())
+                .map(|(a, b)| (*a as i32 - *b as i32).abs() as u8)
+                .max()
+                .unwrap_or(0);
+            max_difference = max_difference.max(difference);
+            if difference > tolerance {
+                different_pixels += 1;
+                mask.set_pixel(i % self.width, i / self.width, Color::WHITE);
+            }
+        }
+        ImageDiff {
+            different_pixels,
+            max_difference,
+            mask: Some(mask),
+        }
+    }
+
+    fn sample(&self, uv: Vec2) -> Color {
+        if self.width == 0 || self.height == 0 {
+            return Color::WHITE;
+        }
+        let x = ((uv.x * self.width as f32) as isize).max(0).min(self.width as isize - 1) as usize;
+        let y = ((uv.y * self.height as f32) as isize).max(0).min(self.height as isize - 1) as usize;
+        self.pixel(x, y)
+    }
+
+    /// Blends color over pixel using alpha of color.
+    fn blend(&mut self, x: usize, y: usize, color: Color) {
+        let i = (y * self.width + x) * 4;
+        let alpha = color.a as f32 / 255.0;
+        let dst = &mut self.pixels[i..i + 4];
+        let mix = |src: u8, dst: u8| (src as f32 * alpha + dst as f32 * (1.0 - alpha)).round() as u8;
+        dst[0] = mix(color.r, dst[0]);
+        dst[1] = mix(color.g, dst[1]);
+        dst[2] = mix(color.b, dst[2]);
+        dst[3] = (color.a as f32 + dst[3] as f32 * (1.0 - alpha)).round() as u8;
+    }
+}
+
+/// Result of `CpuImage::compare`.
+#[derive(Clone, Debug)]
+pub struct ImageDiff {
+    /// Number of pixels that differ by more than tolerance.
+    pub different_pixels: usize,
+    /// Largest difference of a channel among all pixels.
+    pub max_difference: u8,
+    /// White pixels mark differences, None if images have different sizes.
+    pub mask: Option<CpuImage>,
+}
+
+impl ImageDiff {
+    pub fn is_same(&self) -> bool {
+        self.different_pixels == 0
+    }
+}
+
+fn multiply(a: Color, b: Color) -> Color {
+    let m = |a: u8, b: u8| ((a as u32 * b as u32 + 127) / 255) as u8;
+    Color::from_rgba(m(a.r, b.r), m(a.g, b.g), m(a.b, b.b), m(a.a, b.a))
+}
+
+fn lerp_color(a: Color, b: Color, c: Color, w: [f32; 3]) -> Color {
+    let m = |a: u8, b: u8, c: u8| (a as f32 * w[0] + b as f32 * w[1] + c as f32 * w[2]).round().max(0.0).min(255.0) as u8;
+    Color::from_rgba(m(a.r, b.r, c.r), m(a.g, b.g, c.g), m(a.b, b.b, c.b), m(a.a, b.a, c.a))
+}
+
+fn edge(a: Vec2, b: Vec2, p: Vec2) -> f32 {
+    (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x)
+}
+
+/// Calls given function for every pixel which center is inside of triangle, passes
+/// barycentric weights of pixel center.
+fn for_each_pixel<F: FnMut(usize, usize, [f32; 3])>(vertices: [&Vertex; 3], width: usize, height: usize, mut func: F) {
+    let [a, b, c] = [vertices[0].pos, vertices[1].pos, vertices[2].pos];
+    let area = edge(a, b, c);
+    if area.abs() <= std::f32::EPSILON {
+        return;
+    }
+    let min_x = a.x.min(b.x).min(c.x).floor().max(0.0) as usize;
+    let min_y = a.y.min(b.y).min(c.y).floor().max(0.0) as usize;
+    let max_x = (a.x.max(b.x).max(c.x).ceil().max(0.0) as usize).min(width);
+    let max_y = (a.y.max(b.y).max(c.y).ceil().max(0.0) as usize).min(height);
+    for y in min_y..max_y {
+        for x in min_x..max_x {
+            let p = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+            let w0 = edge(b, c, p) / area;
+            let w1 = edge(c, a, p) / area;
+            let w2 = edge(a, b, p) / area;
+            if w0 >= 0.0 && w1 >= 0.0 && w2 >= 0.0 {
+                func(x, y, [w0, w1, w2]);
+            }
+        }
+    }
+}
+
+/// Renders commands of drawing context into image of given size filled with background
+/// color, so visual regression tests can compare interface with golden images on machines
+/// without GPU. Clipping commands are emulated with stencil buffer: geometry is drawn only
+/// where stencil value equals nesting of its command. Output is close to output of GPU
+/// renderer, but not exact: pixels are sampled at their centers without antialiasing and
+/// textures are sampled by nearest texel.
+pub fn rasterize(drawing_context: &DrawingContext, width: usize, height: usize, background: Color) -> CpuImage {
+    let mut image = CpuImage::new(width, height, background);
+    let mut stencil = vec![0u8; width * height];
+    let vertices = drawing_context.get_vertices();
+    let triangles = drawing_context.get_triangles();
+
+    for command in drawing_context.get_commands() {
+        let nesting = command.get_nesting();
+        let end = command.get_start_triangle() + command.get_triangle_count();
+        let font = match command.get_texture() {
+            CommandTexture::Font(font) => Some(font.lock().unwrap()),
+            _ => None,
+        };
+        let texture = match command.get_texture() {
+            CommandTexture::Texture(texture) => texture.downcast_ref::<CpuImage>(),
+            _ => None,
+        };
+
+        for triangle in triangles[command.get_start_triangle()..end].iter() {
+            let [ia, ib, ic] = triangle.indices;
+            let tri = [&vertices[ia as usize], &vertices[ib as usize], &vertices[ic as usize]];
+            match command.get_kind() {
+                CommandKind::Clip => {
+                    // Clip rect of a node narrows clip rect of its parent, so only pixels
+                    // inside of parent's clip are affected. Re-committed clip of parent
+                    // resets stencil of its former children.
+                    for_each_pixel(tri, width, height, |x, y, _| {
+                        let value = &mut stencil[y * width + x];
+                        if value.saturating_add(1) >= nesting {
+                            *value = nesting;
+                        }
+                    });
+                }
+                CommandKind::Geometry => {
+                    for_each_pixel(tri, width, height, |x, y, w| {
+                        if nesting != 0 && stencil[y * width + x] != nesting {
+                            return;
+                        }
+                        let mut color = lerp_color(tri[0].color, tri[1].color, tri[2].color, w);
+                        let uv = tri[0].tex_coord.scale(w[0]) + tri[1].tex_coord.scale(w[1]) + tri[2].tex_coord.scale(w[2]);
+                        if let Some(font) = font.as_ref() {
+                            let size = font.get_atlas_size() as usize;
+                            if size > 0 {
+                                let tx = ((uv.x * size as f32) as usize).min(size - 1);
+                                let ty = ((uv.y * size as f32) as usize).min(size - 1);
+                                let coverage = font.get_atlas_pixels()[ty * size + tx];
+                                color.a = ((color.a as u32 * coverage as u32 + 127) / 255) as u8;
+                            }
+                        } else if let Some(texture) = texture {
+                            color = multiply(color, texture.sample(uv));
+                        }
+                        image.blend(x, y, color);
+                    });
+                }
+            }
+        }
+    }
+
+    image
+}
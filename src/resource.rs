@@ -0,0 +1,107 @@
+use crate::{
+    core::color::Color,
+    draw::Texture,
+};
+use std::sync::{
+    Arc,
+    Mutex,
+};
+
+/// State of resource which is loaded by host application.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ResourceState<T> {
+    Pending,
+    Loaded(T),
+    Failed(String),
+}
+
+/// Shared slot for resource which is loaded asynchronously. Widget keeps one copy of request
+/// and polls its state every frame, host application moves another copy to loader thread
+/// and calls [`complete`](Self::complete) or [`fail`](Self::fail) when loading is done.
+pub struct ResourceRequest<T> {
+    state: Arc<Mutex<ResourceState<T>>>,
+}
+
+impl<T> Clone for ResourceRequest<T> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<T> Default for ResourceRequest<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> ResourceRequest<T> {
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(ResourceState::Pending)),
+        }
+    }
+
+    /// Creates request that is already completed, useful when resource was cached.
+    pub fn loaded(value: T) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(ResourceState::Loaded(value))),
+        }
+    }
+
+    pub fn complete(&self, value: T) {
+        *self.state.lock().unwrap() = ResourceState::Loaded(value);
+    }
+
+    pub fn fail(&self, reason: &str) {
+        *self.state.lock().unwrap() = ResourceState::Failed(reason.to_owned());
+    }
+
+    /// Moves result out of finished request, request becomes pending again. Returns None
+    /// if request is still pending. Unlike `state` it does not require result to be `Clone`.
+    pub fn take(&self) -> Option<Result<T, String>> {
+        let mut state = self.state.lock().unwrap();
+        match std::mem::replace(&mut *state, ResourceState::Pending) {
+            ResourceState::Pending => None,
+            ResourceState::Loaded(value) => Some(Ok(value)),
+            ResourceState::Failed(reason) => Some(Err(reason)),
+        }
+    }
+
+    pub fn is_pending(&self) -> bool {
+        match *self.state.lock().unwrap() {
+            ResourceState::Pending => true,
+            _ => false,
+        }
+    }
+}
+
+impl<T: Clone> ResourceRequest<T> {
+    pub fn state(&self) -> ResourceState<T> {
+        self.state.lock().unwrap().clone()
+    }
+}
+
+pub type TextureRequest = ResourceRequest<Arc<Texture>>;
+
+/// What is shown by widget while its resource is being loaded or when loading has failed.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Placeholder {
+    /// Bounds are filled with color.
+    Color(Color),
+    /// Rotating arc of given color, speed is in turns per second.
+    Spinner {
+        color: Color,
+        speed: f32,
+    },
+}
+
+impl Default for Placeholder {
+    fn default() -> Self {
+        Placeholder::Spinner {
+            color: Color::opaque(200, 200, 200),
+            speed: 1.0,
+        }
+    }
+}
@@ -0,0 +1,278 @@
+use crate::{
+    core::{
+        pool::Handle,
+        math::vec2::Vec2,
+    },
+    widget::{
+        Widget,
+        WidgetBuilder,
+    },
+    draw::{
+        DrawingContext,
+        CommandKind,
+        CommandTexture,
+    },
+    UINode,
+    HitTestVisibility,
+    Control,
+    ControlTemplate,
+    UINodeContainer,
+    Builder,
+};
+use std::collections::HashMap;
+
+/// Crosshair drawn at the center of widget: four lines separated by a gap and optional
+/// center dot. Gap is extended by spread which gameplay code changes on shots or movement,
+/// spread smoothly follows its target so changes are animated. Reticle is excluded from
+/// hit testing by default, so it never steals clicks from widgets beneath it.
+pub struct Reticle {
+    widget: Widget,
+    /// Distance from center to inner ends of lines when spread is zero.
+    gap: f32,
+    line_length: f32,
+    thickness: f32,
+    /// Radius of center dot, zero hides dot.
+    dot_radius: f32,
+    show_lines: bool,
+    spread: f32,
+    target_spread: f32,
+    /// How fast spread follows its target, fraction of difference per second.
+    spread_smoothing: f32,
+    /// How fast target spread returns to zero, units per second.
+    spread_recovery: f32,
+}
+
+impl Reticle {
+    const DOT_SEGMENTS: usize = 12;
+
+    /// Sets spread that reticle will animate to.
+    pub fn set_spread(&mut self, spread: f32) -> &mut Self {
+        self.target_spread = spread.max(0.0);
+        self
+    }
+
+    /// Adds impulse to spread, for example on each shot. Spread recovers back to zero
+    /// with speed set by `set_spread_recovery`.
+    pub fn add_spread(&mut self, amount: f32) -> &mut Self {
+        self.target_spread = (self.target_spread + amount).max(0.0);
+        self
+    }
+
+    /// Sets spread immediately without animation.
+    pub fn reset_spread(&mut self, spread: f32) -> &mut Self {
+        self.target_spread = spread.max(0.0);
+        self.spread = self.target_spread;
+        self
+    }
+
+    /// Returns current (animated) spread.
+    pub fn spread(&self) -> f32 {
+        self.spread
+    }
+
+    pub fn target_spread(&self) -> f32 {
+        self.target_spread
+    }
+
+    pub fn set_spread_smoothing(&mut self, smoothing: f32) -> &mut Self {
+        self.spread_smoothing = smoothing.max(0.0);
+        self
+    }
+
+    pub fn spread_smoothing(&self) -> f32 {
+        self.spread_smoothing
+    }
+
+    pub fn set_spread_recovery(&mut self, recovery: f32) -> &mut Self {
+        self.spread_recovery = recovery.max(0.0);
+        self
+    }
+
+    pub fn spread_recovery(&self) -> f32 {
+        self.spread_recovery
+    }
+
+    pub fn set_gap(&mut self, gap: f32) -> &mut Self {
+        self.gap = gap;
+        self
+    }
+
+    pub fn gap(&self) -> f32 {
+        self.gap
+    }
+
+    pub fn set_line_length(&mut self, length: f32) -> &mut Self {
+        self.line_length = length;
+        self
+    }
+
+    pub fn line_length(&self) -> f32 {
+        self.line_length
+    }
+
+    pub fn set_thickness(&mut self, thickness: f32) -> &mut Self {
+        self.thickness = thickness;
+        self
+    }
+
+    pub fn thickness(&self) -> f32 {
+        self.thickness
+    }
+
+    pub fn set_dot_radius(&mut self, radius: f32) -> &mut Self {
+        self.dot_radius = radius;
+        self
+    }
+
+    pub fn dot_radius(&self) -> f32 {
+        self.dot_radius
+    }
+
+    pub fn set_show_lines(&mut self, show_lines: bool) -> &mut Self {
+        self.show_lines = show_lines;
+        self
+    }
+
+    pub fn is_show_lines(&self) -> bool {
+        self.show_lines
+    }
+}
+
+impl Control for Reticle {
+    fn widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    fn widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    fn raw_copy(&self) -> Box<dyn Control> {
+        Box::new(Self {
+            widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
+            gap: self.gap,
+            line_length: self.line_length,
+            thickness: self.thickness,
+            dot_radius: self.dot_radius,
+            show_lines: self.show_lines,
+            spread: self.spread,
+            target_spread: self.target_spread,
+            spread_smoothing: self.spread_smoothing,
+            spread_recovery: self.spread_recovery,
+        })
+    }
+
+    fn resolve(&mut self, _: &ControlTemplate, _: &HashMap<Handle<UINode>, Handle<UINode>>) {}
+
+    fn draw(&self, drawing_context: &mut DrawingContext) {
+        let bounds = self.widget.get_screen_bounds();
+        let center = Vec2::new(bounds.x + bounds.w * 0.5, bounds.y + bounds.h * 0.5);
+        let color = self.widget.foreground();
+        if self.show_lines {
+            let inner = self.gap + self.spread;
+            let outer = inner + self.line_length;
+            for direction in [Vec2::new(1.0, 0.0), Vec2::new(-1.0, 0.0), Vec2::new(0.0, 1.0), Vec2::new(0.0, -1.0)].iter() {
+                drawing_context.push_line(center + direction.scale(inner), center + direction.scale(outer), self.thickness, color);
+            }
+        }
+        if self.dot_radius > 0.0 {
+            drawing_context.push_sector(center, self.dot_radius, 0.0, 2.0 * std::f32::consts::PI, Self::DOT_SEGMENTS, color);
+        }
+        drawing_context.commit(CommandKind::Geometry, CommandTexture::None);
+    }
+
+    fn update(&mut self, dt: f32) {
+        self.target_spread = (self.target_spread - self.spread_recovery * dt).max(0.0);
+        if self.widget.reduced_motion() {
+            self.spread = self.target_spread;
+        } else {
+            let t = (self.spread_smoothing * dt).min(1.0);
+            self.spread += (self.target_spread - self.spread) * t;
+        }
+    }
+
+    fn needs_update(&self) -> bool {
+        true
+    }
+}
+
+pub struct ReticleBuilder {
+    widget_builder: WidgetBuilder,
+    gap: f32,
+    line_length: f32,
+    thickness: f32,
+    dot_radius: f32,
+    show_lines: bool,
+    spread_smoothing: f32,
+    spread_recovery: f32,
+}
+
+impl ReticleBuilder {
+    /// Hit testing of given widget builder is disabled.
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder: widget_builder.with_hit_test_visibility(HitTestVisibility::SubtreeInvisible),
+            gap: 4.0,
+            line_length: 8.0,
+            thickness: 2.0,
+            dot_radius: 0.0,
+            show_lines: true,
+            spread_smoothing: 15.0,
+            spread_recovery: 20.0,
+        }
+    }
+
+    pub fn with_gap(mut self, gap: f32) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    pub fn with_line_length(mut self, length: f32) -> Self {
+        self.line_length = length;
+        self
+    }
+
+    pub fn with_thickness(mut self, thickness: f32) -> Self {
+        self.thickness = thickness;
+        self
+    }
+
+    pub fn with_dot_radius(mut self, radius: f32) -> Self {
+        self.dot_radius = radius;
+        self
+    }
+
+    pub fn with_show_lines(mut self, show_lines: bool) -> Self {
+        self.show_lines = show_lines;
+        self
+    }
+
+    pub fn with_spread_smoothing(mut self, smoothing: f32) -> Self {
+        self.spread_smoothing = smoothing;
+        self
+    }
+
+    pub fn with_spread_recovery(mut self, recovery: f32) -> Self {
+        self.spread_recovery = recovery;
+        self
+    }
+}
+
+impl Builder for ReticleBuilder {
+    fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
+        let reticle = Reticle {
+            widget: self.widget_builder.build(),
+            gap: self.gap,
+            line_length: self.line_length,
+            thickness: self.thickness,
+            dot_radius: self.dot_radius,
+            show_lines: self.show_lines,
+            spread: 0.0,
+            target_spread: 0.0,
+            spread_smoothing: self.spread_smoothing,
+            spread_recovery: self.spread_recovery,
+        };
+
+        ui.add_node(Box::new(reticle))
+    }
+}
@@ -0,0 +1,237 @@
+use crate::{
+    core::{
+        color::Color,
+        pool::Handle,
+        math::{
+            vec2::Vec2,
+            Rect,
+        },
+    },
+    draw::{
+        CommandKind,
+        CommandTexture,
+        DrawingContext,
+    },
+    event::{
+        UIEvent,
+        UIEventKind,
+        MouseButton,
+    },
+    widget::{
+        Widget,
+        WidgetBuilder,
+    },
+    color_ext::{
+        ColorExt,
+        palette,
+    },
+    UserInterface,
+    UINode,
+    HitTestVisibility,
+    Control,
+    ControlTemplate,
+    UINodeContainer,
+    Builder,
+};
+use std::collections::HashMap;
+
+/// Marquee selection helper. Rubber band watches its target panel and when user presses
+/// left mouse button on empty space of the panel and drags the mouse, it draws selection
+/// rectangle and reports children of the target that intersect it. `SelectionRectChanged`
+/// is sent on each mouse move, `SelectionRectFinished` is sent when button is released.
+///
+/// Rubber band should be placed above target (for example as a last child of the target
+/// canvas or in the same grid cell after it), so selection rectangle will be drawn on top
+/// of items. Rubber band is not hit test visible, so it does not block input for items.
+pub struct RubberBand {
+    widget: Widget,
+    target: Handle<UINode>,
+    /// Point in screen coordinates where dragging was started.
+    origin: Option<Vec2>,
+    current: Vec2,
+    fill_brush: Color,
+    stroke_brush: Color,
+}
+
+impl RubberBand {
+    pub fn target(&self) -> Handle<UINode> {
+        self.target
+    }
+
+    pub fn set_target(&mut self, target: Handle<UINode>) -> &mut Self {
+        self.target = target;
+        self
+    }
+
+    pub fn is_selecting(&self) -> bool {
+        self.origin.is_some()
+    }
+
+    /// Returns current selection rectangle in screen coordinates.
+    pub fn selection_rect(&self) -> Option<Rect<f32>> {
+        self.origin.map(|origin| make_rect(origin, self.current))
+    }
+
+    pub fn set_fill_brush(&mut self, brush: Color) -> &mut Self {
+        self.fill_brush = brush;
+        self
+    }
+
+    pub fn set_stroke_brush(&mut self, brush: Color) -> &mut Self {
+        self.stroke_brush = brush;
+        self
+    }
+
+    fn intersecting_items(&self, self_handle: Handle<UINode>, ui: &UserInterface, rect: &Rect<f32>) -> Vec<Handle<UINode>> {
+        ui.node(self.target)
+            .widget()
+            .children()
+            .iter()
+            .filter(|child| **child != self_handle)
+            .filter(|child| {
+                let widget = ui.node(**child).widget();
+                widget.global_visibility && rects_intersect(&widget.get_screen_bounds(), rect)
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+fn make_rect(a: Vec2, b: Vec2) -> Rect<f32> {
+    let x = a.x.min(b.x);
+    let y = a.y.min(b.y);
+    Rect::new(x, y, (a.x - b.x).abs(), (a.y - b.y).abs())
+}
+
+fn rects_intersect(a: &Rect<f32>, b: &Rect<f32>) -> bool {
+    a.x <= b.x + b.w && b.x <= a.x + a.w && a.y <= b.y + b.h && b.y <= a.y + a.h
+}
+
+impl Control for RubberBand {
+    fn widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    fn widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    fn raw_copy(&self) -> Box<dyn Control> {
+        Box::new(Self {
+            widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
+            target: self.target,
+            origin: None,
+            current: Vec2::ZERO,
+            fill_brush: self.fill_brush,
+            stroke_brush: self.stroke_brush,
+        })
+    }
+
+    fn resolve(&mut self, _: &ControlTemplate, node_map: &HashMap<Handle<UINode>, Handle<UINode>>) {
+        if self.target.is_some() {
+            self.target = *node_map.get(&self.target).unwrap();
+        }
+    }
+
+    fn draw(&self, drawing_context: &mut DrawingContext) {
+        if let Some(rect) = self.selection_rect() {
+            drawing_context.push_rect_filled(&rect, None, self.fill_brush);
+            drawing_context.push_rect(&rect, 1.0, self.stroke_brush);
+            drawing_context.commit(CommandKind::Geometry, CommandTexture::None);
+        }
+    }
+
+    fn handle_event(&mut self, self_handle: Handle<UINode>, ui: &mut UserInterface, evt: &mut UIEvent) {
+        if self.target.is_none() {
+            return;
+        }
+
+        match evt.kind {
+            UIEventKind::MouseDown { pos, button } => {
+                // Only clicks on empty space of target start selection, clicks on items
+                // are handled by items themselves.
+                if evt.source == self.target && button == MouseButton::Left && ui.capture_mouse(self_handle) {
+                    self.origin = Some(pos);
+                    self.current = pos;
+                }
+            }
+            UIEventKind::MouseMove { pos } => {
+                if evt.source == self_handle {
+                    if let Some(origin) = self.origin {
+                        self.current = pos;
+                        let rect = make_rect(origin, pos);
+                        let items = self.intersecting_items(self_handle, ui, &rect);
+                        self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::SelectionRectChanged(items)));
+                    }
+                }
+            }
+            UIEventKind::MouseUp { pos, button } => {
+                if evt.source == self_handle && button == MouseButton::Left {
+                    if let Some(origin) = self.origin.take() {
+                        ui.release_mouse_capture();
+                        let rect = make_rect(origin, pos);
+                        let items = self.intersecting_items(self_handle, ui, &rect);
+                        self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::SelectionRectFinished { rect, items }));
+                    }
+                }
+            }
+            UIEventKind::CaptureLost => {
+                if evt.source == self_handle {
+                    self.origin = None;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+pub struct RubberBandBuilder {
+    widget_builder: WidgetBuilder,
+    target: Handle<UINode>,
+    fill_brush: Option<Color>,
+    stroke_brush: Option<Color>,
+}
+
+impl RubberBandBuilder {
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            target: Handle::NONE,
+            fill_brush: None,
+            stroke_brush: None,
+        }
+    }
+
+    /// Sets panel which children can be selected, for example canvas of a node editor.
+    pub fn with_target(mut self, target: Handle<UINode>) -> Self {
+        self.target = target;
+        self
+    }
+
+    pub fn with_fill_brush(mut self, brush: Color) -> Self {
+        self.fill_brush = Some(brush);
+        self
+    }
+
+    pub fn with_stroke_brush(mut self, brush: Color) -> Self {
+        self.stroke_brush = Some(brush);
+        self
+    }
+}
+
+impl Builder for RubberBandBuilder {
+    fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
+        let rubber_band = RubberBand {
+            widget: self.widget_builder
+                .with_hit_test_visibility(HitTestVisibility::SubtreeInvisible)
+                .build(),
+            target: self.target,
+            origin: None,
+            current: Vec2::ZERO,
+            fill_brush: self.fill_brush.unwrap_or_else(|| palette::current().accent.with_alpha(60)),
+            stroke_brush: self.stroke_brush.unwrap_or_else(|| palette::current().accent),
+        };
+
+        ui.add_node(Box::new(rubber_band))
+    }
+}
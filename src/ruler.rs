@@ -0,0 +1,551 @@
+use crate::{
+    core::{
+        color::Color,
+        pool::Handle,
+        math::vec2::Vec2,
+    },
+    widget::{
+        Widget,
+        WidgetBuilder,
+    },
+    draw::{
+        DrawingContext,
+        CommandKind,
+        CommandTexture,
+    },
+    event::{
+        UIEvent,
+        UIEventKind,
+        MouseButton,
+    },
+    formatted_text::{
+        FormattedText,
+        FormattedTextBuilder,
+    },
+    scroll_bar::Orientation,
+    zoom_panel::ZoomPanel,
+    color_ext::palette,
+    ttf::Font,
+    UserInterface,
+    UINode,
+    HitTestVisibility,
+    HorizontalAlignment,
+    VerticalAlignment,
+    Control,
+    ControlTemplate,
+    UINodeContainer,
+    Builder,
+};
+use std::{
+    cell::{
+        Cell,
+        RefCell,
+    },
+    collections::HashMap,
+    sync::{
+        Arc,
+        Mutex,
+    },
+};
+
+/// Guide line in coordinates of content of zoom panel. Vertical guide is a line with
+/// constant X and it is dragged out of horizontal ruler, horizontal guide is dragged out
+/// of vertical ruler.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Guide {
+    pub orientation: Orientation,
+    pub position: f32,
+}
+
+/// Screen position of origin of content and zoom of zoom panel, None if panel is not set.
+fn view_of(ui: &UserInterface, zoom_panel: Handle<UINode>) -> Option<(Vec2, f32)> {
+    if zoom_panel.is_none() {
+        return None;
+    }
+    ui.node(zoom_panel)
+        .downcast_ref::<ZoomPanel>()
+        .map(|panel| (panel.content_to_screen(Vec2::ZERO), panel.zoom()))
+}
+
+/// Returns distance between major ticks in content units, it is 1, 2 or 5 multiplied by
+/// power of ten, so major ticks are at least `min_spacing` pixels apart on screen.
+fn major_step(zoom: f32, min_spacing: f32) -> f32 {
+    let raw = min_spacing / zoom.max(std::f32::EPSILON);
+    let magnitude = 10.0f32.powf(raw.log10().floor());
+    for multiplier in [1.0, 2.0, 5.0, 10.0].iter() {
+        if magnitude * multiplier >= raw {
+            return magnitude * multiplier;
+        }
+    }
+    magnitude * 10.0
+}
+
+/// Horizontal or vertical ruler for editor canvases. Ruler shows coordinates of content of
+/// a zoom panel and follows its zoom and pan. Ruler should be placed along the edge of zoom
+/// panel, it reads transform of panel on every layout.
+///
+/// When guide layer is set, user can press left mouse button on ruler and drag a guide
+/// line out of it onto the canvas, guide is added to layer when button is released outside
+/// of ruler. Releasing button over ruler cancels the guide.
+pub struct Ruler {
+    widget: Widget,
+    orientation: Orientation,
+    zoom_panel: Handle<UINode>,
+    guide_layer: Handle<UINode>,
+    /// Multiplier of content units for labels, for example to show meters instead of pixels.
+    unit_scale: f32,
+    /// Minimal distance between major ticks in pixels.
+    min_tick_spacing: f32,
+    /// Screen position of origin of content and zoom, updated on every arrange.
+    view: Cell<(Vec2, f32)>,
+    font: Option<Arc<Mutex<Font>>>,
+    /// Reused texts of labels of major ticks.
+    labels: RefCell<Vec<FormattedText>>,
+    dragging_guide: bool,
+}
+
+impl Ruler {
+    const LABEL_SIZE: Vec2 = Vec2 { x: 48.0, y: 14.0 };
+    const MINOR_TICKS: usize = 5;
+
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    pub fn zoom_panel(&self) -> Handle<UINode> {
+        self.zoom_panel
+    }
+
+    pub fn set_zoom_panel(&mut self, zoom_panel: Handle<UINode>) -> &mut Self {
+        self.zoom_panel = zoom_panel;
+        self
+    }
+
+    pub fn guide_layer(&self) -> Handle<UINode> {
+        self.guide_layer
+    }
+
+    pub fn set_guide_layer(&mut self, guide_layer: Handle<UINode>) -> &mut Self {
+        self.guide_layer = guide_layer;
+        self
+    }
+
+    pub fn set_unit_scale(&mut self, scale: f32) -> &mut Self {
+        self.unit_scale = scale;
+        self
+    }
+
+    pub fn unit_scale(&self) -> f32 {
+        self.unit_scale
+    }
+
+    /// Orientation of guides dragged out of this ruler.
+    fn guide_orientation(&self) -> Orientation {
+        match self.orientation {
+            Orientation::Horizontal => Orientation::Vertical,
+            Orientation::Vertical => Orientation::Horizontal,
+        }
+    }
+
+    /// Converts screen point to position along the ruler in content units.
+    fn content_position(&self, screen_point: Vec2) -> f32 {
+        let (origin, zoom) = self.view.get();
+        let zoom = zoom.max(std::f32::EPSILON);
+        match self.orientation {
+            Orientation::Horizontal => (screen_point.x - origin.x) / zoom,
+            Orientation::Vertical => (screen_point.y - origin.y) / zoom,
+        }
+    }
+
+    fn set_preview(&self, ui: &mut UserInterface, preview: Option<Guide>) {
+        if let Some(layer) = ui.node_mut(self.guide_layer).downcast_mut::<GuideLayer>() {
+            layer.preview = preview;
+        }
+    }
+}
+
+impl Control for Ruler {
+    fn widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    fn widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    fn raw_copy(&self) -> Box<dyn Control> {
+        Box::new(Self {
+            widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
+            orientation: self.orientation,
+            zoom_panel: self.zoom_panel,
+            guide_layer: self.guide_layer,
+            unit_scale: self.unit_scale,
+            min_tick_spacing: self.min_tick_spacing,
+            view: self.view.clone(),
+            font: self.font.clone(),
+            labels: Default::default(),
+            dragging_guide: false,
+        })
+    }
+
+    fn resolve(&mut self, _: &ControlTemplate, node_map: &HashMap<Handle<UINode>, Handle<UINode>>) {
+        if self.zoom_panel.is_some() {
+            self.zoom_panel = *node_map.get(&self.zoom_panel).unwrap();
+        }
+        if self.guide_layer.is_some() {
+            self.guide_layer = *node_map.get(&self.guide_layer).unwrap();
+        }
+    }
+
+    fn arrange_override(&self, ui: &UserInterface, final_size: Vec2) -> Vec2 {
+        if let Some(view) = view_of(ui, self.zoom_panel) {
+            self.view.set(view);
+        }
+        self.widget.arrange_override(ui, final_size)
+    }
+
+    fn draw(&self, drawing_context: &mut DrawingContext) {
+        let bounds = self.widget.get_screen_bounds();
+        let color = self.widget.foreground();
+        drawing_context.push_rect_filled(&bounds, None, self.widget.background());
+
+        let (origin, zoom) = self.view.get();
+        let step = major_step(zoom, self.min_tick_spacing);
+        let (begin, length, thickness) = match self.orientation {
+            Orientation::Horizontal => (bounds.x, bounds.w, bounds.h),
+            Orientation::Vertical => (bounds.y, bounds.h, bounds.w),
+        };
+        let origin = match self.orientation {
+            Orientation::Horizontal => origin.x,
+            Orientation::Vertical => origin.y,
+        };
+        let first = ((begin - origin) / zoom / step).floor() as i32;
+        let last = ((begin + length - origin) / zoom / step).ceil() as i32;
+        let tick = |offset: f32, size: f32| match self.orientation {
+            Orientation::Horizontal => (Vec2::new(offset, bounds.y + bounds.h - size), Vec2::new(offset, bounds.y + bounds.h)),
+            Orientation::Vertical => (Vec2::new(bounds.x + bounds.w - size, offset), Vec2::new(bounds.x + bounds.w, offset)),
+        };
+        for i in first..=last {
+            for j in 0..Self::MINOR_TICKS {
+                let value = (i as f32 + j as f32 / Self::MINOR_TICKS as f32) * step;
+                let size = if j == 0 { thickness } else { thickness * 0.3 };
+                let (a, b) = tick(origin + value * zoom, size);
+                drawing_context.push_line(a, b, 1.0, color);
+            }
+        }
+        drawing_context.commit(CommandKind::Geometry, CommandTexture::None);
+
+        let mut labels = self.labels.borrow_mut();
+        for (n, i) in (first..=last).enumerate() {
+            let value = i as f32 * step;
+            if labels.len() <= n {
+                labels.push(FormattedTextBuilder::new()
+                    .with_opt_font(self.font.clone())
+                    .with_size(Self::LABEL_SIZE)
+                    .with_color(palette::current().text)
+                    .with_horizontal_alignment(HorizontalAlignment::Left)
+                    .with_vertical_alignment(VerticalAlignment::Top)
+                    .build());
+            }
+            let label = &mut labels[n];
+            label.set_text(&format!("{}", value * self.unit_scale));
+            label.set_color(color);
+            label.build();
+            let offset = origin + value * zoom + 2.0;
+            let position = match self.orientation {
+                Orientation::Horizontal => Vec2::new(offset, bounds.y),
+                Orientation::Vertical => Vec2::new(bounds.x, offset),
+            };
+            drawing_context.draw_text(position, label);
+        }
+    }
+
+    fn handle_event(&mut self, self_handle: Handle<UINode>, ui: &mut UserInterface, evt: &mut UIEvent) {
+        if evt.source != self_handle || self.guide_layer.is_none() {
+            return;
+        }
+
+        match evt.kind {
+            UIEventKind::MouseDown { pos, button } => {
+                if button == MouseButton::Left && ui.capture_mouse(self_handle) {
+                    self.dragging_guide = true;
+                    let guide = Guide { orientation: self.guide_orientation(), position: self.content_position(pos) };
+                    self.set_preview(ui, Some(guide));
+                    evt.handled = true;
+                }
+            }
+            UIEventKind::MouseMove { pos } => {
+                if self.dragging_guide {
+                    let guide = Guide { orientation: self.guide_orientation(), position: self.content_position(pos) };
+                    self.set_preview(ui, Some(guide));
+                }
+            }
+            UIEventKind::MouseUp { pos, button } => {
+                if button == MouseButton::Left && self.dragging_guide {
+                    self.dragging_guide = false;
+                    ui.release_mouse_capture();
+                    self.set_preview(ui, None);
+                    if !self.widget.get_screen_bounds().contains(pos.x, pos.y) {
+                        let guide = Guide { orientation: self.guide_orientation(), position: self.content_position(pos) };
+                        if let Some(layer) = ui.node_mut(self.guide_layer).downcast_mut::<GuideLayer>() {
+                            layer.add_guide(guide);
+                        }
+                    }
+                }
+            }
+            UIEventKind::CaptureLost => {
+                if self.dragging_guide {
+                    self.dragging_guide = false;
+                    self.set_preview(ui, None);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+pub struct RulerBuilder {
+    widget_builder: WidgetBuilder,
+    orientation: Orientation,
+    zoom_panel: Handle<UINode>,
+    guide_layer: Handle<UINode>,
+    unit_scale: f32,
+    min_tick_spacing: f32,
+}
+
+impl RulerBuilder {
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            orientation: Orientation::Horizontal,
+            zoom_panel: Handle::NONE,
+            guide_layer: Handle::NONE,
+            unit_scale: 1.0,
+            min_tick_spacing: 50.0,
+        }
+    }
+
+    pub fn with_orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    pub fn with_zoom_panel(mut self, zoom_panel: Handle<UINode>) -> Self {
+        self.zoom_panel = zoom_panel;
+        self
+    }
+
+    /// Sets layer which receives guides dragged out of ruler.
+    pub fn with_guide_layer(mut self, guide_layer: Handle<UINode>) -> Self {
+        self.guide_layer = guide_layer;
+        self
+    }
+
+    pub fn with_unit_scale(mut self, scale: f32) -> Self {
+        self.unit_scale = scale;
+        self
+    }
+
+    /// Sets minimal distance between major ticks in pixels, default is 50.
+    pub fn with_min_tick_spacing(mut self, spacing: f32) -> Self {
+        self.min_tick_spacing = spacing.max(1.0);
+        self
+    }
+}
+
+impl Builder for RulerBuilder {
+    fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
+        let ruler = Ruler {
+            widget: self.widget_builder
+                .with_background(palette::current().panel)
+                .with_foreground(palette::current().text)
+                .build(),
+            orientation: self.orientation,
+            zoom_panel: self.zoom_panel,
+            guide_layer: self.guide_layer,
+            unit_scale: self.unit_scale,
+            min_tick_spacing: self.min_tick_spacing,
+            view: Cell::new((Vec2::ZERO, 1.0)),
+            font: ui.default_font(),
+            labels: Default::default(),
+            dragging_guide: false,
+        };
+
+        ui.add_node(Box::new(ruler))
+    }
+}
+
+/// Transparent overlay which draws guide lines over zoom panel, it should be placed above
+/// zoom panel and cover it (for example in the same grid cell after the panel). Layer does
+/// not take part in hit testing. Guides are stored in content coordinates, so they follow
+/// zoom and pan of the panel.
+///
+/// Other widgets can use `snap` to attract points to guides, for example when dragging
+/// nodes on canvas.
+///
+/// # Events
+///
+/// [`GuidesChanged`] - sent when guide was added or removed.
+pub struct GuideLayer {
+    widget: Widget,
+    zoom_panel: Handle<UINode>,
+    guides: Vec<Guide>,
+    /// Guide which is being dragged out of ruler.
+    preview: Option<Guide>,
+    /// Screen position of origin of content and zoom, updated on every arrange.
+    view: Cell<(Vec2, f32)>,
+    guide_color: Color,
+}
+
+impl GuideLayer {
+    pub fn guides(&self) -> &[Guide] {
+        &self.guides
+    }
+
+    pub fn add_guide(&mut self, guide: Guide) {
+        self.guides.push(guide);
+        self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::GuidesChanged));
+    }
+
+    pub fn remove_guide(&mut self, index: usize) {
+        if index < self.guides.len() {
+            self.guides.remove(index);
+            self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::GuidesChanged));
+        }
+    }
+
+    pub fn clear_guides(&mut self) {
+        if !self.guides.is_empty() {
+            self.guides.clear();
+            self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::GuidesChanged));
+        }
+    }
+
+    pub fn preview(&self) -> Option<Guide> {
+        self.preview
+    }
+
+    /// Moves each coordinate of point (in content units) to the nearest guide of matching
+    /// orientation if guide is closer than `threshold` screen pixels. Returns snapped point
+    /// and flags telling which coordinates were snapped.
+    pub fn snap(&self, point: Vec2, threshold: f32) -> (Vec2, bool, bool) {
+        let threshold = threshold / self.view.get().1.max(std::f32::EPSILON);
+        let nearest = |orientation: Orientation, value: f32| {
+            self.guides
+                .iter()
+                .filter(|guide| guide.orientation == orientation)
+                .map(|guide| guide.position)
+                .filter(|position| (position - value).abs() <= threshold)
+                .min_by(|a, b| (a - value).abs().partial_cmp(&(b - value).abs()).unwrap())
+        };
+        let x = nearest(Orientation::Vertical, point.x);
+        let y = nearest(Orientation::Horizontal, point.y);
+        (Vec2::new(x.unwrap_or(point.x), y.unwrap_or(point.y)), x.is_some(), y.is_some())
+    }
+}
+
+impl Control for GuideLayer {
+    fn widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    fn widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    fn raw_copy(&self) -> Box<dyn Control> {
+        Box::new(Self {
+            widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
+            zoom_panel: self.zoom_panel,
+            guides: self.guides.clone(),
+            preview: None,
+            view: self.view.clone(),
+            guide_color: self.guide_color,
+        })
+    }
+
+    fn resolve(&mut self, _: &ControlTemplate, node_map: &HashMap<Handle<UINode>, Handle<UINode>>) {
+        if self.zoom_panel.is_some() {
+            self.zoom_panel = *node_map.get(&self.zoom_panel).unwrap();
+        }
+    }
+
+    fn arrange_override(&self, ui: &UserInterface, final_size: Vec2) -> Vec2 {
+        if let Some(view) = view_of(ui, self.zoom_panel) {
+            self.view.set(view);
+        }
+        self.widget.arrange_override(ui, final_size)
+    }
+
+    fn draw(&self, drawing_context: &mut DrawingContext) {
+        let bounds = self.widget.get_screen_bounds();
+        let (origin, zoom) = self.view.get();
+        let mut push_guide = |guide: &Guide, color: Color| match guide.orientation {
+            Orientation::Vertical => {
+                let x = origin.x + guide.position * zoom;
+                drawing_context.push_line(Vec2::new(x, bounds.y), Vec2::new(x, bounds.y + bounds.h), 1.0, color);
+            }
+            Orientation::Horizontal => {
+                let y = origin.y + guide.position * zoom;
+                drawing_context.push_line(Vec2::new(bounds.x, y), Vec2::new(bounds.x + bounds.w, y), 1.0, color);
+            }
+        };
+        for guide in self.guides.iter() {
+            push_guide(guide, self.guide_color);
+        }
+        if let Some(preview) = self.preview.as_ref() {
+            push_guide(preview, palette::current().text);
+        }
+        drawing_context.commit(CommandKind::Geometry, CommandTexture::None);
+    }
+}
+
+pub struct GuideLayerBuilder {
+    widget_builder: WidgetBuilder,
+    zoom_panel: Handle<UINode>,
+    guides: Vec<Guide>,
+    guide_color: Option<Color>,
+}
+
+impl GuideLayerBuilder {
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            zoom_panel: Handle::NONE,
+            guides: Vec::new(),
+            guide_color: None,
+        }
+    }
+
+    pub fn with_zoom_panel(mut self, zoom_panel: Handle<UINode>) -> Self {
+        self.zoom_panel = zoom_panel;
+        self
+    }
+
+    pub fn with_guides(mut self, guides: Vec<Guide>) -> Self {
+        self.guides = guides;
+        self
+    }
+
+    pub fn with_guide_color(mut self, color: Color) -> Self {
+        self.guide_color = Some(color);
+        self
+    }
+}
+
+impl Builder for GuideLayerBuilder {
+    fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
+        let layer = GuideLayer {
+            widget: self.widget_builder
+                .with_hit_test_visibility(HitTestVisibility::SubtreeInvisible)
+                .build(),
+            zoom_panel: self.zoom_panel,
+            guides: self.guides,
+            preview: None,
+            view: Cell::new((Vec2::ZERO, 1.0)),
+            guide_color: self.guide_color.unwrap_or_else(|| palette::current().accent),
+        };
+
+        ui.add_node(Box::new(layer))
+    }
+}
@@ -40,6 +40,9 @@ pub struct ScrollBar {
     max: f32,
     value: f32,
     step: f32,
+    /// Size of visible part of scrolled content, if set then indicator will have size
+    /// proportional to ratio of visible part to whole content. Zero means fixed size.
+    viewport_size: f32,
     orientation: Orientation,
     is_dragging: bool,
     offset: Vec2,
@@ -47,6 +50,8 @@ pub struct ScrollBar {
     decrease: Handle<UINode>,
     indicator: Handle<UINode>,
     field: Handle<UINode>,
+    /// Scroll bars with same group share their values.
+    scroll_group: Option<String>,
 }
 
 impl Control for ScrollBar {
@@ -65,6 +70,7 @@ impl Control for ScrollBar {
             max: self.max,
             value: self.value,
             step: self.step,
+            viewport_size: self.viewport_size,
             orientation: self.orientation,
             is_dragging: self.is_dragging,
             offset: self.offset,
@@ -72,6 +78,7 @@ impl Control for ScrollBar {
             decrease: self.decrease,
             indicator: self.indicator,
             field: self.field,
+            scroll_group: self.scroll_group.clone(),
         })
     }
 
@@ -91,6 +98,20 @@ impl Control for ScrollBar {
         let field_size = ui.node(self.field).widget().actual_size.get();
 
         let indicator = ui.node(self.indicator).widget();
+
+        if self.viewport_size > 0.0 {
+            let total = (self.max - self.min) + self.viewport_size;
+            let ratio = self.viewport_size / total;
+            match self.orientation {
+                Orientation::Horizontal => {
+                    indicator.width.set(maxf(Self::MIN_INDICATOR_SIZE, ratio * field_size.x));
+                }
+                Orientation::Vertical => {
+                    indicator.height.set(maxf(Self::MIN_INDICATOR_SIZE, ratio * field_size.y));
+                }
+            }
+        }
+
         match self.orientation {
             Orientation::Horizontal => {
                 indicator.desired_local_position.set(Vec2::new(
@@ -110,7 +131,20 @@ impl Control for ScrollBar {
         size
     }
 
-    fn handle_event(&mut self, _self_handle: Handle<UINode>, ui: &mut UserInterface, evt: &mut UIEvent) {
+    fn handle_event(&mut self, self_handle: Handle<UINode>, ui: &mut UserInterface, evt: &mut UIEvent) {
+        if let UIEventKind::NumericValueChanged { new_value, .. } = evt.kind {
+            if self.scroll_group.is_some() && evt.source != self_handle {
+                // Follow value of other scroll bar of the same group. Value of this scroll bar
+                // will be equal after that, so its own event will not cause feedback loop.
+                let same_group = ui.node(evt.source)
+                    .downcast_ref::<ScrollBar>()
+                    .map_or(false, |other| other.scroll_group == self.scroll_group);
+                if same_group {
+                    self.set_value(new_value);
+                }
+            }
+        }
+
         if let UIEventKind::Click = evt.kind {
             if evt.source == self.increase {
                 self.set_value(self.value + self.step);
@@ -136,6 +170,9 @@ impl Control for ScrollBar {
                     ui.release_mouse_capture();
                     evt.handled = true;
                 }
+                UIEventKind::CaptureLost => {
+                    self.is_dragging = false;
+                }
                 UIEventKind::MouseMove { pos, .. } => {
                     let (field_pos, field_size) = {
                         let canvas = ui.borrow_by_name_up(self.indicator, ScrollBar::PART_CANVAS).widget();
@@ -177,6 +214,7 @@ impl Control for ScrollBar {
 
 impl ScrollBar {
     pub const PART_CANVAS: &'static str = "PART_Canvas";
+    pub const MIN_INDICATOR_SIZE: f32 = 10.0;
 
     pub fn new(widget: Widget, increase: Handle<UINode>, decrease: Handle<UINode>, indicator: Handle<UINode>, field: Handle<UINode>) -> Self {
         Self {
@@ -185,6 +223,7 @@ impl ScrollBar {
             max: 100.0,
             value: 0.0,
             step: 1.0,
+            viewport_size: 0.0,
             orientation: Orientation::Vertical,
             is_dragging: false,
             offset: Default::default(),
@@ -192,9 +231,21 @@ impl ScrollBar {
             decrease,
             indicator,
             field,
+            scroll_group: None,
         }
     }
 
+    /// Adds scroll bar to group of scroll bars that share their values, for example
+    /// vertical scroll bars of two side-by-side views. None removes scroll bar from group.
+    pub fn set_scroll_group(&mut self, group: Option<&str>) -> &mut Self {
+        self.scroll_group = group.map(|group| group.to_owned());
+        self
+    }
+
+    pub fn scroll_group(&self) -> Option<&str> {
+        self.scroll_group.as_ref().map(|group| group.as_str())
+    }
+
     pub fn set_value(&mut self, value: f32) -> &mut Self {
         let old_value = self.value;
         let new_value = math::clampf(value, self.min, self.max);
@@ -256,16 +307,19 @@ impl ScrollBar {
         self.step
     }
 
-    pub fn scroll(&mut self, amount: f32) {
-        let old_value = self.value;
+    /// Sets size of visible part of scrolled content, it is used to make size of indicator
+    /// proportional to visible part of content. Zero disables proportional indicator.
+    pub fn set_viewport_size(&mut self, size: f32) -> &mut Self {
+        self.viewport_size = size;
+        self
+    }
 
-        self.value += amount;
+    pub fn viewport_size(&self) -> f32 {
+        self.viewport_size
+    }
 
-        self.widget.events.borrow_mut().push_back(
-            UIEvent::new(UIEventKind::NumericValueChanged {
-                old_value,
-                new_value: self.value,
-            }));
+    pub fn scroll(&mut self, amount: f32) {
+        self.set_value(self.value + amount);
     }
 }
 
@@ -280,9 +334,10 @@ pub struct ScrollBarBuilder {
     decrease: Option<Handle<UINode>>,
     indicator: Option<Handle<UINode>>,
     body: Option<Handle<UINode>>,
+    scroll_group: Option<String>,
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Orientation {
     Vertical,
     Horizontal,
@@ -301,6 +356,7 @@ impl ScrollBarBuilder {
             decrease: None,
             indicator: None,
             body: None,
+            scroll_group: None,
         }
     }
 
@@ -348,6 +404,11 @@ impl ScrollBarBuilder {
         self.body = Some(body);
         self
     }
+
+    pub fn with_scroll_group(mut self, group: &str) -> Self {
+        self.scroll_group = Some(group.to_owned());
+        self
+    }
 }
 
 impl Builder for ScrollBarBuilder {
@@ -360,6 +421,7 @@ impl Builder for ScrollBarBuilder {
                     Orientation::Horizontal => ">",
                     Orientation::Vertical => "v"
                 })
+                .with_repeat(0.4, 0.05)
                 .build(ui)
         });
 
@@ -388,6 +450,7 @@ impl Builder for ScrollBarBuilder {
                     Orientation::Horizontal => "<",
                     Orientation::Vertical => "^"
                 })
+                .with_repeat(0.4, 0.05)
                 .build(ui)
         });
 
@@ -469,6 +532,7 @@ impl Builder for ScrollBarBuilder {
             max: self.max.unwrap_or(100.0),
             value: self.value.unwrap_or(0.0),
             step: self.step.unwrap_or(1.0),
+            viewport_size: 0.0,
             orientation,
             is_dragging: false,
             offset: Vec2::ZERO,
@@ -476,6 +540,7 @@ impl Builder for ScrollBarBuilder {
             decrease,
             indicator,
             field,
+            scroll_group: self.scroll_group,
         };
         ui.add_node(Box::new(scroll_bar))
     }
@@ -112,6 +112,10 @@ impl ScrollContentPresenter {
         self.scroll = scroll;
     }
 
+    pub fn scroll(&self) -> Vec2 {
+        self.scroll
+    }
+
     pub fn set_vertical_scroll(&mut self, scroll: f32) {
         self.scroll.y = scroll;
     }
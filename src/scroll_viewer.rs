@@ -23,6 +23,7 @@ use crate::{
         Widget,
         WidgetBuilder,
     },
+    keymap::WidgetAction,
     Visibility,
     Control,
     UINode,
@@ -42,6 +43,10 @@ pub struct ScrollViewer {
     content_presenter: Handle<UINode>,
     v_scroll_bar: Handle<UINode>,
     h_scroll_bar: Handle<UINode>,
+    /// Offsets requested by `scroll_to` that could not be reached yet because content
+    /// was not measured, they will be applied when scroll range grows.
+    requested_h_offset: Option<f32>,
+    requested_v_offset: Option<f32>,
 }
 
 impl ScrollViewer {
@@ -58,6 +63,135 @@ impl ScrollViewer {
             content_presenter,
             v_scroll_bar,
             h_scroll_bar,
+            requested_h_offset: None,
+            requested_v_offset: None,
+        }
+    }
+
+    pub fn v_scroll_bar(&self) -> Handle<UINode> {
+        self.v_scroll_bar
+    }
+
+    pub fn h_scroll_bar(&self) -> Handle<UINode> {
+        self.h_scroll_bar
+    }
+
+    fn scroll_bar_value(ui: &UserInterface, scroll_bar: Handle<UINode>) -> f32 {
+        ui.node(scroll_bar)
+            .downcast_ref::<ScrollBar>()
+            .map_or(0.0, |scroll_bar| scroll_bar.value())
+    }
+
+    pub fn horizontal_offset(ui: &UserInterface, scroll_viewer: Handle<UINode>) -> f32 {
+        match ui.node(scroll_viewer).downcast_ref::<ScrollViewer>() {
+            Some(scroll_viewer) => Self::scroll_bar_value(ui, scroll_viewer.h_scroll_bar),
+            None => 0.0,
+        }
+    }
+
+    pub fn vertical_offset(ui: &UserInterface, scroll_viewer: Handle<UINode>) -> f32 {
+        match ui.node(scroll_viewer).downcast_ref::<ScrollViewer>() {
+            Some(scroll_viewer) => Self::scroll_bar_value(ui, scroll_viewer.v_scroll_bar),
+            None => 0.0,
+        }
+    }
+
+    /// Scrolls content to given offset. Offset that is out of current scroll range is
+    /// remembered and applied when content grows, so it is possible to restore scroll
+    /// position right after creation of scroll viewer. Pass `std::f32::MAX` as vertical
+    /// offset to keep content scrolled to the bottom (chat-like behaviour) until user
+    /// scrolls it with mouse wheel.
+    pub fn scroll_to(ui: &mut UserInterface, scroll_viewer: Handle<UINode>, offset: Vec2) {
+        let (h_scroll_bar, v_scroll_bar) = match ui.node_mut(scroll_viewer).downcast_mut::<ScrollViewer>() {
+            Some(scroll_viewer) => {
+                scroll_viewer.requested_h_offset = Some(offset.x);
+                scroll_viewer.requested_v_offset = Some(offset.y);
+                (scroll_viewer.h_scroll_bar, scroll_viewer.v_scroll_bar)
+            }
+            None => return,
+        };
+        if let Some(scroll_bar) = ui.node_mut(h_scroll_bar).downcast_mut::<ScrollBar>() {
+            scroll_bar.set_value(offset.x);
+        }
+        if let Some(scroll_bar) = ui.node_mut(v_scroll_bar).downcast_mut::<ScrollBar>() {
+            scroll_bar.set_value(offset.y);
+        }
+    }
+
+    /// Scrolls content by given amount of pixels on each axis.
+    pub fn scroll_by(ui: &mut UserInterface, scroll_viewer: Handle<UINode>, delta: Vec2) {
+        let offset = Vec2::new(
+            Self::horizontal_offset(ui, scroll_viewer) + delta.x,
+            Self::vertical_offset(ui, scroll_viewer) + delta.y,
+        );
+        Self::scroll_to(ui, scroll_viewer, offset);
+    }
+
+    /// Scrolls content by minimal distance which makes given descendant of content fully
+    /// visible, or aligns it to top left corner of viewport if it is larger than viewport.
+    /// Uses bounds from last layout, does nothing if node is already visible.
+    pub fn bring_into_view(ui: &mut UserInterface, scroll_viewer: Handle<UINode>, node: Handle<UINode>) {
+        let content_presenter = match ui.node(scroll_viewer).downcast_ref::<ScrollViewer>() {
+            Some(scroll_viewer) => scroll_viewer.content_presenter,
+            None => return,
+        };
+        let viewport = ui.node(content_presenter).widget().get_screen_bounds();
+        let bounds = ui.node(node).widget().get_screen_bounds();
+        let delta_for = |begin: f32, size: f32, viewport_begin: f32, viewport_size: f32| {
+            if begin < viewport_begin || size > viewport_size {
+                begin - viewport_begin
+            } else if begin + size > viewport_begin + viewport_size {
+                begin + size - (viewport_begin + viewport_size)
+            } else {
+                0.0
+            }
+        };
+        let delta = Vec2::new(
+            delta_for(bounds.x, bounds.w, viewport.x, viewport.w),
+            delta_for(bounds.y, bounds.h, viewport.y, viewport.h),
+        );
+        if delta.x != 0.0 || delta.y != 0.0 {
+            Self::scroll_by(ui, scroll_viewer, delta);
+        }
+    }
+
+    /// Links horizontal and vertical scroll bars of scroll viewer to groups of synchronized
+    /// scroll bars, so all scroll viewers in a group will share offset on that axis. For
+    /// example side-by-side diff views use same groups for both axes, while table with
+    /// frozen columns shares only vertical group. None unlinks axis from its group.
+    pub fn set_scroll_groups(ui: &mut UserInterface, scroll_viewer: Handle<UINode>, horizontal: Option<&str>, vertical: Option<&str>) {
+        let (h_scroll_bar, v_scroll_bar) = match ui.node(scroll_viewer).downcast_ref::<ScrollViewer>() {
+            Some(scroll_viewer) => (scroll_viewer.h_scroll_bar, scroll_viewer.v_scroll_bar),
+            None => return,
+        };
+        if let Some(scroll_bar) = ui.node_mut(h_scroll_bar).downcast_mut::<ScrollBar>() {
+            scroll_bar.set_scroll_group(horizontal);
+        }
+        if let Some(scroll_bar) = ui.node_mut(v_scroll_bar).downcast_mut::<ScrollBar>() {
+            scroll_bar.set_scroll_group(vertical);
+        }
+    }
+
+    /// Sets new max value of scroll bar and applies requested offset if it is now in range.
+    fn update_scroll_range(ui: &mut UserInterface, scroll_bar: Handle<UINode>, max: f32, viewport_size: f32, requested_offset: &mut Option<f32>) {
+        if let Some(scroll_bar) = ui.node_mut(scroll_bar).downcast_mut::<ScrollBar>() {
+            scroll_bar.set_max_value(max);
+            scroll_bar.set_viewport_size(viewport_size);
+
+            if (scroll_bar.max_value() - scroll_bar.min_value()).abs() <= std::f32::EPSILON {
+                scroll_bar.widget_mut()
+                    .set_visibility(Visibility::Collapsed);
+            } else {
+                scroll_bar.widget_mut()
+                    .set_visibility(Visibility::Visible);
+            }
+
+            if let Some(offset) = *requested_offset {
+                scroll_bar.set_value(offset);
+                if offset <= scroll_bar.max_value() {
+                    *requested_offset = None;
+                }
+            }
         }
     }
 }
@@ -78,6 +212,8 @@ impl Control for ScrollViewer {
             content_presenter: self.content_presenter,
             v_scroll_bar: self.v_scroll_bar,
             h_scroll_bar: self.h_scroll_bar,
+            requested_h_offset: self.requested_h_offset,
+            requested_v_offset: self.requested_v_offset,
         })
     }
 
@@ -108,50 +244,76 @@ impl Control for ScrollViewer {
     fn handle_event(&mut self, self_handle: Handle<UINode>, ui: &mut UserInterface, evt: &mut UIEvent) {
         if evt.target == self.v_scroll_bar {
             if let UIEventKind::MaxValueChanged(new_value) = evt.kind {
-                if let Some(scroll_bar) = ui.node_mut(self.v_scroll_bar).downcast_mut::<ScrollBar>() {
-                    scroll_bar.set_max_value(new_value);
-
-                    if (scroll_bar.max_value() - scroll_bar.min_value()).abs() <= std::f32::EPSILON {
-                        scroll_bar.widget_mut()
-                            .set_visibility(Visibility::Collapsed);
-                    } else {
-                        scroll_bar.widget_mut()
-                            .set_visibility(Visibility::Visible);
-                    }
-                }
+                let viewport_size = ui.node(self.content_presenter).widget().actual_size().y;
+                Self::update_scroll_range(ui, self.v_scroll_bar, new_value, viewport_size, &mut self.requested_v_offset);
             }
         }
 
         if evt.target == self.h_scroll_bar {
             if let UIEventKind::MaxValueChanged(new_value) = evt.kind {
-                if let Some(scroll_bar) = ui.node_mut(self.h_scroll_bar).downcast_mut::<ScrollBar>() {
-                    scroll_bar.set_max_value(new_value);
-
-                    if (scroll_bar.max_value() - scroll_bar.min_value()).abs() <= std::f32::EPSILON {
-                        scroll_bar.widget_mut()
-                            .set_visibility(Visibility::Collapsed);
-                    } else {
-                        scroll_bar.widget_mut()
-                            .set_visibility(Visibility::Visible);
-                    }
-                }
+                let viewport_size = ui.node(self.content_presenter).widget().actual_size().x;
+                Self::update_scroll_range(ui, self.h_scroll_bar, new_value, viewport_size, &mut self.requested_h_offset);
             }
         }
 
         match evt.kind {
             UIEventKind::NumericValueChanged { new_value, .. } => {
-                if let Some(content_presenter) = ui.node_mut(self.content_presenter).downcast_mut::<ScrollContentPresenter>() {
-                    if evt.source == self.h_scroll_bar {
-                        content_presenter.set_horizontal_scroll(new_value);
-                    } else if evt.source == self.v_scroll_bar {
-                        content_presenter.set_vertical_scroll(new_value);
+                if evt.source == self.h_scroll_bar || evt.source == self.v_scroll_bar {
+                    if let Some(content_presenter) = ui.node_mut(self.content_presenter).downcast_mut::<ScrollContentPresenter>() {
+                        if evt.source == self.h_scroll_bar {
+                            content_presenter.set_horizontal_scroll(new_value);
+                        } else {
+                            content_presenter.set_vertical_scroll(new_value);
+                        }
                     }
+                    let horizontal = Self::scroll_bar_value(ui, self.h_scroll_bar);
+                    let vertical = Self::scroll_bar_value(ui, self.v_scroll_bar);
+                    self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::ScrollChanged { horizontal, vertical }));
                 }
             }
-            UIEventKind::MouseWheel { amount, .. } => {
+            UIEventKind::KeyDown { code } => {
                 if !evt.handled && (evt.source == self_handle || self.widget().has_descendant(evt.source, ui)) {
+                    let page = ui.node(self.content_presenter).widget().actual_size().y;
+                    let action = ui.key_action(code);
                     if let Some(v_scroll_bar) = ui.node_mut(self.v_scroll_bar).downcast_mut::<ScrollBar>() {
-                        v_scroll_bar.scroll(-amount * 10.0);
+                        let handled = match action {
+                            Some(WidgetAction::PageUp) => {
+                                v_scroll_bar.scroll(-page);
+                                true
+                            }
+                            Some(WidgetAction::PageDown) => {
+                                v_scroll_bar.scroll(page);
+                                true
+                            }
+                            Some(WidgetAction::ScrollToStart) => {
+                                let min = v_scroll_bar.min_value();
+                                v_scroll_bar.set_value(min);
+                                true
+                            }
+                            Some(WidgetAction::ScrollToEnd) => {
+                                let max = v_scroll_bar.max_value();
+                                v_scroll_bar.set_value(max);
+                                true
+                            }
+                            _ => false
+                        };
+                        evt.handled |= handled;
+                    }
+                }
+            }
+            UIEventKind::MouseWheel { amount, .. } => {
+                if !evt.handled && (evt.source == self_handle || self.widget().has_descendant(evt.source, ui)) {
+                    // Wheel with Shift scrolls horizontally, as in most viewers.
+                    let scroll_bar = if ui.keyboard_modifiers().shift {
+                        self.requested_h_offset = None;
+                        self.h_scroll_bar
+                    } else {
+                        // User scrolling cancels pending scroll request.
+                        self.requested_v_offset = None;
+                        self.v_scroll_bar
+                    };
+                    if let Some(scroll_bar) = ui.node_mut(scroll_bar).downcast_mut::<ScrollBar>() {
+                        scroll_bar.scroll(-amount * 10.0);
                         evt.handled = true;
                     }
                 }
@@ -164,6 +326,10 @@ impl Control for ScrollViewer {
 pub struct ScrollViewerBuilder {
     widget_builder: WidgetBuilder,
     content: Handle<UINode>,
+    h_scroll_group: Option<String>,
+    v_scroll_group: Option<String>,
+    horizontal_scroll_allowed: bool,
+    vertical_scroll_allowed: bool,
 }
 
 impl ScrollViewerBuilder {
@@ -171,6 +337,10 @@ impl ScrollViewerBuilder {
         Self {
             widget_builder,
             content: Handle::NONE,
+            h_scroll_group: None,
+            v_scroll_group: None,
+            horizontal_scroll_allowed: false,
+            vertical_scroll_allowed: true,
         }
     }
 
@@ -178,6 +348,32 @@ impl ScrollViewerBuilder {
         self.content = content;
         self
     }
+
+    /// Content is measured with unlimited width and can be scrolled horizontally, otherwise
+    /// it is fit into width of viewport. Disabled by default.
+    pub fn with_horizontal_scroll_allowed(mut self, value: bool) -> Self {
+        self.horizontal_scroll_allowed = value;
+        self
+    }
+
+    /// Content is measured with unlimited height and can be scrolled vertically, otherwise
+    /// it is fit into height of viewport. Enabled by default.
+    pub fn with_vertical_scroll_allowed(mut self, value: bool) -> Self {
+        self.vertical_scroll_allowed = value;
+        self
+    }
+
+    /// See `ScrollViewer::set_scroll_groups`.
+    pub fn with_horizontal_scroll_group(mut self, group: &str) -> Self {
+        self.h_scroll_group = Some(group.to_owned());
+        self
+    }
+
+    /// See `ScrollViewer::set_scroll_groups`.
+    pub fn with_vertical_scroll_group(mut self, group: &str) -> Self {
+        self.v_scroll_group = Some(group.to_owned());
+        self
+    }
 }
 
 impl Builder for ScrollViewerBuilder {
@@ -186,21 +382,29 @@ impl Builder for ScrollViewerBuilder {
             .with_child(self.content)
             .on_row(0)
             .on_column(0))
+            .with_horizontal_scroll_allowed(self.horizontal_scroll_allowed)
+            .with_vertical_scroll_allowed(self.vertical_scroll_allowed)
             .build(ui);
 
-        let v_scroll_bar = ScrollBarBuilder::new(WidgetBuilder::new()
+        let mut v_scroll_bar_builder = ScrollBarBuilder::new(WidgetBuilder::new()
             .on_row(0)
             .on_column(1)
             .with_width(20.0))
-            .with_orientation(Orientation::Vertical)
-            .build(ui);
+            .with_orientation(Orientation::Vertical);
+        if let Some(group) = self.v_scroll_group.as_ref() {
+            v_scroll_bar_builder = v_scroll_bar_builder.with_scroll_group(group);
+        }
+        let v_scroll_bar = v_scroll_bar_builder.build(ui);
 
-        let h_scroll_bar = ScrollBarBuilder::new(WidgetBuilder::new()
+        let mut h_scroll_bar_builder = ScrollBarBuilder::new(WidgetBuilder::new()
             .on_row(1)
             .on_column(0)
             .with_height(20.0))
-            .with_orientation(Orientation::Horizontal)
-            .build(ui);
+            .with_orientation(Orientation::Horizontal);
+        if let Some(group) = self.h_scroll_group.as_ref() {
+            h_scroll_bar_builder = h_scroll_bar_builder.with_scroll_group(group);
+        }
+        let h_scroll_bar = h_scroll_bar_builder.build(ui);
 
         let scroll_viewer = ScrollViewer {
             widget: self.widget_builder
@@ -218,6 +422,8 @@ impl Builder for ScrollViewerBuilder {
             v_scroll_bar,
             h_scroll_bar,
             content_presenter,
+            requested_h_offset: None,
+            requested_v_offset: None,
         };
         ui.add_node(Box::new(scroll_viewer))
     }
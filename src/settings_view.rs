@@ -0,0 +1,551 @@
+use crate::{
+    core::pool::Handle,
+    widget::{
+        Widget,
+        WidgetBuilder,
+    },
+    grid::{
+        GridBuilder,
+        Column,
+        Row,
+    },
+    text::{
+        Text,
+        TextBuilder,
+    },
+    text_box::{
+        TextBox,
+        TextBoxBuilder,
+    },
+    list_box::{
+        ListBox,
+        ListBoxBuilder,
+    },
+    check_box::{
+        CheckBox,
+        CheckBoxBuilder,
+    },
+    numeric_text_box::{
+        NumericTextBox,
+        NumericTextBoxBuilder,
+    },
+    hotkey_field::{
+        HotkeyField,
+        HotkeyFieldBuilder,
+    },
+    button::{
+        Button,
+        ButtonBuilder,
+    },
+    stack_panel::StackPanelBuilder,
+    scroll_viewer::ScrollViewerBuilder,
+    event::{
+        UIEvent,
+        UIEventKind,
+    },
+    keymap::Hotkey,
+    color_ext::{
+        ColorExt,
+        palette,
+    },
+    UserInterface,
+    UINode,
+    Thickness,
+    VerticalAlignment,
+    Visibility,
+    Control,
+    ControlTemplate,
+    UINodeContainer,
+    Builder,
+};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    rc::Rc,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SettingValue {
+    Bool(bool),
+    Int(i32),
+    Float(f32),
+    /// Index of option of enumeration.
+    Enum(usize),
+    Keybind(Option<Hotkey>),
+}
+
+/// Kind of setting, defines editor that is created for it.
+#[derive(Clone, Debug)]
+pub enum SettingKind {
+    Bool,
+    Int {
+        min: i32,
+        max: i32,
+    },
+    Float {
+        min: f32,
+        max: f32,
+        precision: usize,
+    },
+    /// Names of options, editor cycles through them on click.
+    Enum(Vec<String>),
+    Keybind,
+}
+
+/// Declarative description of a single setting.
+#[derive(Clone, Debug)]
+pub struct SettingDesc {
+    /// Key of setting in store.
+    pub key: String,
+    pub label: String,
+    pub description: String,
+    pub kind: SettingKind,
+}
+
+impl SettingDesc {
+    pub fn new(key: &str, label: &str, kind: SettingKind) -> Self {
+        Self {
+            key: key.to_owned(),
+            label: label.to_owned(),
+            description: String::new(),
+            kind,
+        }
+    }
+
+    pub fn bool(key: &str, label: &str) -> Self {
+        Self::new(key, label, SettingKind::Bool)
+    }
+
+    pub fn int(key: &str, label: &str, min: i32, max: i32) -> Self {
+        Self::new(key, label, SettingKind::Int { min, max })
+    }
+
+    pub fn float(key: &str, label: &str, min: f32, max: f32, precision: usize) -> Self {
+        Self::new(key, label, SettingKind::Float { min, max, precision })
+    }
+
+    pub fn enumeration(key: &str, label: &str, options: &[&str]) -> Self {
+        Self::new(key, label, SettingKind::Enum(options.iter().map(|option| (*option).to_owned()).collect()))
+    }
+
+    pub fn keybind(key: &str, label: &str) -> Self {
+        Self::new(key, label, SettingKind::Keybind)
+    }
+
+    /// Sets hint shown under label, it is also matched by search.
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = description.to_owned();
+        self
+    }
+
+    fn matches(&self, query: &str) -> bool {
+        self.label.to_lowercase().contains(query)
+            || self.description.to_lowercase().contains(query)
+            || self.key.to_lowercase().contains(query)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SettingsCategory {
+    pub name: String,
+    pub settings: Vec<SettingDesc>,
+}
+
+impl SettingsCategory {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            settings: Vec::new(),
+        }
+    }
+
+    pub fn with_setting(mut self, setting: SettingDesc) -> Self {
+        self.settings.push(setting);
+        self
+    }
+}
+
+/// Application-provided storage of setting values, for example config file of an editor or
+/// options of a game. Settings view reads values from it when it is shown or refreshed and
+/// writes values which were changed by user.
+pub trait SettingsStore {
+    /// Returns value of setting, None leaves editor with its default value.
+    fn value(&self, key: &str) -> Option<SettingValue>;
+
+    fn set_value(&mut self, key: &str, value: SettingValue);
+}
+
+#[derive(Clone)]
+struct Entry {
+    category: usize,
+    desc: SettingDesc,
+    /// Row with label and editor, hidden when filtered out.
+    row: Handle<UINode>,
+    editor: Handle<UINode>,
+}
+
+/// Options page generated from a schema: list of categories on the left, settings of
+/// selected category on the right, each setting is a row with label and editor matching
+/// kind of setting (check box, numeric field, option button or hotkey field).
+///
+/// Non-empty search text shows settings of all categories whose label, description or key
+/// contains the text, ignoring selected category.
+///
+/// Values are bound to [`SettingsStore`] in both directions: editors are filled from store
+/// when view is attached to user interface and on `SettingsView::refresh`, every change
+/// made by user is written to store immediately.
+///
+/// # Events
+///
+/// [`SettingChanged`] - sent when user has changed a setting, after it was written to store.
+pub struct SettingsView {
+    widget: Widget,
+    store: Rc<RefCell<dyn SettingsStore>>,
+    categories: Handle<UINode>,
+    search: Handle<UINode>,
+    entries: Vec<Entry>,
+    selected_category: usize,
+    query: String,
+}
+
+impl SettingsView {
+    pub fn store(&self) -> Rc<RefCell<dyn SettingsStore>> {
+        self.store.clone()
+    }
+
+    pub fn selected_category(&self) -> usize {
+        self.selected_category
+    }
+
+    /// Returns editor of setting with given key.
+    pub fn editor(&self, key: &str) -> Handle<UINode> {
+        self.entries
+            .iter()
+            .find(|entry| entry.desc.key == key)
+            .map_or(Handle::NONE, |entry| entry.editor)
+    }
+
+    /// Fills editors with values from store, should be called when store was changed by
+    /// application.
+    pub fn refresh(ui: &mut UserInterface, settings_view: Handle<UINode>) {
+        let (store, entries) = match ui.node(settings_view).downcast_ref::<SettingsView>() {
+            Some(view) => (view.store.clone(), view.entries.clone()),
+            None => return,
+        };
+        sync_editors(ui, &*store.borrow(), &entries);
+    }
+
+    /// Shows rows of settings that pass current category and search filter.
+    fn apply_filter(&self, ui: &mut UserInterface) {
+        let query = self.query.trim().to_lowercase();
+        for entry in self.entries.iter() {
+            let visible = if query.is_empty() {
+                entry.category == self.selected_category
+            } else {
+                entry.desc.matches(&query)
+            };
+            ui.node_mut(entry.row)
+                .widget_mut()
+                .set_visibility(if visible { Visibility::Visible } else { Visibility::Collapsed });
+        }
+    }
+
+    /// Converts event of editor to new value of its setting.
+    fn value_from_event(&self, entry: &Entry, kind: &UIEventKind) -> Option<SettingValue> {
+        let current = self.store.borrow().value(&entry.desc.key);
+        match (&entry.desc.kind, kind) {
+            (SettingKind::Bool, UIEventKind::Checked(Some(checked))) => Some(SettingValue::Bool(*checked)),
+            (SettingKind::Int { .. }, UIEventKind::NumericValueChanged { new_value, .. }) => Some(SettingValue::Int(new_value.round() as i32)),
+            (SettingKind::Float { .. }, UIEventKind::NumericValueChanged { new_value, .. }) => Some(SettingValue::Float(*new_value)),
+            (SettingKind::Enum(options), UIEventKind::Click) if !options.is_empty() => {
+                let index = match current {
+                    Some(SettingValue::Enum(index)) => (index + 1) % options.len(),
+                    _ => 0,
+                };
+                Some(SettingValue::Enum(index))
+            }
+            (SettingKind::Keybind, UIEventKind::HotkeyChanged(hotkey)) => Some(SettingValue::Keybind(*hotkey)),
+            _ => None,
+        }
+        .filter(|value| current.as_ref() != Some(value))
+    }
+}
+
+fn sync_editors(ui: &mut UserInterface, store: &dyn SettingsStore, entries: &[Entry]) {
+    for entry in entries {
+        let value = match store.value(&entry.desc.key) {
+            Some(value) => value,
+            None => continue,
+        };
+        match (&entry.desc.kind, value) {
+            (SettingKind::Bool, SettingValue::Bool(checked)) => {
+                if let Some(check_box) = ui.node_mut(entry.editor).downcast_mut::<CheckBox>() {
+                    check_box.set_checked(Some(checked));
+                }
+            }
+            (SettingKind::Int { .. }, SettingValue::Int(value)) => {
+                if let Some(field) = ui.node_mut(entry.editor).downcast_mut::<NumericTextBox>() {
+                    field.set_value(value as f32);
+                }
+            }
+            (SettingKind::Float { .. }, SettingValue::Float(value)) => {
+                if let Some(field) = ui.node_mut(entry.editor).downcast_mut::<NumericTextBox>() {
+                    field.set_value(value);
+                }
+            }
+            (SettingKind::Enum(options), SettingValue::Enum(index)) if index < options.len() => {
+                let content = ui.node(entry.editor).downcast_ref::<Button>().map(|button| button.content());
+                if let Some(content) = content {
+                    if let Some(text) = ui.node_mut(content).downcast_mut::<Text>() {
+                        text.set_text(&options[index]);
+                    }
+                }
+            }
+            (SettingKind::Keybind, SettingValue::Keybind(hotkey)) => {
+                HotkeyField::set_hotkey_of(ui, entry.editor, hotkey);
+            }
+            _ => ()
+        }
+    }
+}
+
+impl Control for SettingsView {
+    fn widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    fn widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    fn raw_copy(&self) -> Box<dyn Control> {
+        Box::new(Self {
+            widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
+            store: self.store.clone(),
+            categories: self.categories,
+            search: self.search,
+            entries: self.entries.clone(),
+            selected_category: self.selected_category,
+            query: self.query.clone(),
+        })
+    }
+
+    fn resolve(&mut self, _: &ControlTemplate, node_map: &HashMap<Handle<UINode>, Handle<UINode>>) {
+        self.categories = *node_map.get(&self.categories).unwrap();
+        self.search = *node_map.get(&self.search).unwrap();
+        for entry in self.entries.iter_mut() {
+            entry.row = *node_map.get(&entry.row).unwrap();
+            entry.editor = *node_map.get(&entry.editor).unwrap();
+        }
+    }
+
+    fn handle_event(&mut self, _self_handle: Handle<UINode>, ui: &mut UserInterface, evt: &mut UIEvent) {
+        if evt.source == self.categories {
+            if let UIEventKind::SelectionChanged(selection) = &evt.kind {
+                if let Some(index) = selection.first() {
+                    self.selected_category = *index;
+                    self.apply_filter(ui);
+                }
+            }
+            return;
+        }
+
+        if evt.source == self.search {
+            if let UIEventKind::TextChanged = evt.kind {
+                self.query = ui.node(self.search)
+                    .downcast_ref::<TextBox>()
+                    .map(|search| search.text())
+                    .unwrap_or_default();
+                self.apply_filter(ui);
+            }
+            return;
+        }
+
+        if let Some(entry) = self.entries.iter().find(|entry| entry.editor == evt.source).cloned() {
+            // Values that are equal to stored ones come from `refresh` and are not echoed
+            // back to store.
+            if let Some(value) = self.value_from_event(&entry, &evt.kind) {
+                self.store.borrow_mut().set_value(&entry.desc.key, value.clone());
+                // Editor shows value as it was stored: option button gets text of next
+                // option, integer field drops fraction.
+                sync_editors(ui, &*self.store.borrow(), std::slice::from_ref(&entry));
+                self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::SettingChanged {
+                    key: entry.desc.key,
+                    value,
+                }));
+            }
+        }
+    }
+
+    fn on_attached_to_ui(&mut self, _self_handle: Handle<UINode>, ui: &mut UserInterface) {
+        sync_editors(ui, &*self.store.borrow(), &self.entries);
+        if let Some(categories) = ui.node_mut(self.categories).downcast_mut::<ListBox>() {
+            categories.set_selected(Some(self.selected_category));
+        }
+        self.apply_filter(ui);
+    }
+}
+
+pub struct SettingsViewBuilder {
+    widget_builder: WidgetBuilder,
+    store: Rc<RefCell<dyn SettingsStore>>,
+    categories: Vec<SettingsCategory>,
+    category_width: f32,
+    editor_width: f32,
+}
+
+impl SettingsViewBuilder {
+    pub fn new(widget_builder: WidgetBuilder, store: Rc<RefCell<dyn SettingsStore>>) -> Self {
+        Self {
+            widget_builder,
+            store,
+            categories: Vec::new(),
+            category_width: 160.0,
+            editor_width: 180.0,
+        }
+    }
+
+    pub fn with_category(mut self, category: SettingsCategory) -> Self {
+        self.categories.push(category);
+        self
+    }
+
+    pub fn with_categories(mut self, categories: Vec<SettingsCategory>) -> Self {
+        self.categories.extend(categories);
+        self
+    }
+
+    /// Sets width of list of categories.
+    pub fn with_category_width(mut self, width: f32) -> Self {
+        self.category_width = width;
+        self
+    }
+
+    /// Sets width of column of editors.
+    pub fn with_editor_width(mut self, width: f32) -> Self {
+        self.editor_width = width;
+        self
+    }
+}
+
+fn make_editor(ui: &mut dyn UINodeContainer, kind: &SettingKind) -> Handle<UINode> {
+    let widget_builder = WidgetBuilder::new()
+        .with_margin(Thickness::uniform(2.0))
+        .with_vertical_alignment(VerticalAlignment::Center)
+        .on_column(1);
+    match kind {
+        SettingKind::Bool => CheckBoxBuilder::new(widget_builder)
+            .build(ui),
+        SettingKind::Int { min, max } => NumericTextBoxBuilder::new(widget_builder)
+            .with_min(*min as f32)
+            .with_max(*max as f32)
+            .with_precision(0)
+            .build(ui),
+        SettingKind::Float { min, max, precision } => NumericTextBoxBuilder::new(widget_builder)
+            .with_min(*min)
+            .with_max(*max)
+            .with_precision(*precision)
+            .build(ui),
+        SettingKind::Enum(options) => ButtonBuilder::new(widget_builder)
+            .with_text(options.first().map_or("", |option| option.as_str()))
+            .build(ui),
+        SettingKind::Keybind => HotkeyFieldBuilder::new(widget_builder)
+            .build(ui),
+    }
+}
+
+impl Builder for SettingsViewBuilder {
+    fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
+        let mut entries = Vec::new();
+        let mut rows = Vec::new();
+        for (category_index, category) in self.categories.iter().enumerate() {
+            for desc in category.settings.iter() {
+                let label = TextBuilder::new(WidgetBuilder::new()
+                    .with_margin(Thickness::uniform(2.0))
+                    .on_row(0)
+                    .on_column(0))
+                    .with_text(&desc.label)
+                    .with_vertical_text_alignment(VerticalAlignment::Center)
+                    .build(ui);
+                let description = TextBuilder::new(WidgetBuilder::new()
+                    .with_foreground(palette::current().text.darken(0.3))
+                    .with_visibility(if desc.description.is_empty() { Visibility::Collapsed } else { Visibility::Visible })
+                    .with_margin(Thickness::uniform(2.0))
+                    .on_row(1)
+                    .on_column(0))
+                    .with_text(&desc.description)
+                    .build(ui);
+                let editor = make_editor(ui, &desc.kind);
+                ui.node_mut(editor).widget_mut().set_row_span(2);
+                let row = GridBuilder::new(WidgetBuilder::new()
+                    .with_margin(Thickness::uniform(2.0))
+                    .with_children(&[label, description, editor]))
+                    .add_column(Column::stretch())
+                    .add_column(Column::strict(self.editor_width))
+                    .add_row(Row::auto())
+                    .add_row(Row::auto())
+                    .build(ui);
+                rows.push(row);
+                entries.push(Entry {
+                    category: category_index,
+                    desc: desc.clone(),
+                    row,
+                    editor,
+                });
+            }
+        }
+
+        let category_items = self.categories
+            .iter()
+            .map(|category| TextBuilder::new(WidgetBuilder::new()
+                .with_margin(Thickness::uniform(4.0)))
+                .with_text(&category.name)
+                .build(ui))
+            .collect::<Vec<Handle<UINode>>>();
+        let categories = ListBoxBuilder::new(WidgetBuilder::new()
+            .with_margin(Thickness::uniform(2.0))
+            .with_row_span(2)
+            .on_column(0))
+            .with_items(category_items)
+            .build(ui);
+
+        let search = TextBoxBuilder::new(WidgetBuilder::new()
+            .with_background(palette::current().field)
+            .with_height(24.0)
+            .with_margin(Thickness::uniform(2.0))
+            .on_row(0)
+            .on_column(1))
+            .build(ui);
+
+        let settings = ScrollViewerBuilder::new(WidgetBuilder::new()
+            .on_row(1)
+            .on_column(1))
+            .with_content(StackPanelBuilder::new(WidgetBuilder::new()
+                .with_children(&rows))
+                .build(ui))
+            .build(ui);
+
+        let settings_view = SettingsView {
+            widget: self.widget_builder
+                .with_background(palette::current().panel)
+                .with_child(GridBuilder::new(WidgetBuilder::new()
+                    .with_child(categories)
+                    .with_child(search)
+                    .with_child(settings))
+                    .add_column(Column::strict(self.category_width))
+                    .add_column(Column::stretch())
+                    .add_row(Row::auto())
+                    .add_row(Row::stretch())
+                    .build(ui))
+                .build(),
+            store: self.store,
+            categories,
+            search,
+            entries,
+            selected_category: 0,
+            query: String::new(),
+        };
+
+        ui.add_node(Box::new(settings_view))
+    }
+}
@@ -0,0 +1,52 @@
+use crate::{
+    core::pool::Handle,
+    event::{
+        UIEvent,
+        UIEventKind,
+    },
+    UserInterface,
+    UINode,
+};
+
+/// Standard interaction sound of stock widgets.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum UiSound {
+    /// Mouse cursor entered interactive (focusable) widget.
+    Hover,
+    /// Button was clicked or check box was toggled.
+    Click,
+    /// Item of list was selected.
+    Select,
+    /// Window or popup was opened.
+    Open,
+    Close,
+    /// Some action was rejected, sent only by `UserInterface::play_sound`.
+    Error,
+}
+
+impl UiSound {
+    /// Maps event to standard sound, returns None if event should be silent.
+    pub fn from_event(ui: &UserInterface, evt: &UIEvent) -> Option<UiSound> {
+        match evt.kind {
+            UIEventKind::MouseEnter => {
+                if ui.nodes().is_valid_handle(evt.source) && ui.node(evt.source).widget().is_focusable() {
+                    Some(UiSound::Hover)
+                } else {
+                    None
+                }
+            }
+            UIEventKind::Click | UIEventKind::Checked(_) => Some(UiSound::Click),
+            UIEventKind::SelectionChanged(_) => Some(UiSound::Select),
+            UIEventKind::Opened => Some(UiSound::Open),
+            UIEventKind::Closed => Some(UiSound::Close),
+            _ => None,
+        }
+    }
+}
+
+/// Receives interaction sounds of all stock widgets, so game can give audio feedback from
+/// one place instead of listening to dozens of event kinds. Handler is called when event
+/// is returned by `UserInterface::poll_ui_event`, see `UserInterface::set_sound_handler`.
+pub trait UiSoundHandler {
+    fn play(&mut self, sound: UiSound, source: Handle<UINode>);
+}
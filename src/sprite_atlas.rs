@@ -0,0 +1,208 @@
+use crate::{
+    core::math::Rect,
+    draw::{
+        Texture,
+        TextureBrush,
+    },
+};
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::Arc,
+};
+
+/// Error of parsing of atlas description.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AtlasError {
+    /// Line (1-based) could not be parsed.
+    InvalidLine(usize),
+    /// Page of atlas has no `size` entry.
+    MissingPageSize(String),
+    /// Texture of page was not provided by loader.
+    UnknownPage(String),
+}
+
+impl fmt::Display for AtlasError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AtlasError::InvalidLine(line) => write!(f, "invalid atlas description at line {}", line),
+            AtlasError::MissingPageSize(page) => write!(f, "atlas page {} has no size", page),
+            AtlasError::UnknownPage(page) => write!(f, "texture of atlas page {} is not available", page),
+        }
+    }
+}
+
+/// Registry of named sprites, every sprite is a region of some texture. Sprites can be
+/// used anywhere texture brush is accepted: as background of widget, by `ImageBuilder::with_brush`
+/// or as icon of button. Registry is owned by user interface, see `UserInterface::sprites_mut`.
+#[derive(Default)]
+pub struct SpriteRegistry {
+    sprites: HashMap<String, TextureBrush>,
+}
+
+impl SpriteRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, name: &str, brush: TextureBrush) {
+        self.sprites.insert(name.to_owned(), brush);
+    }
+
+    /// Registers sprite with region of texture given in pixels.
+    pub fn register_region(&mut self, name: &str, texture: Arc<Texture>, texture_size: (u32, u32), region: Rect<f32>) {
+        self.register(name, make_brush(texture, texture_size, region));
+    }
+
+    pub fn unregister(&mut self, name: &str) {
+        self.sprites.remove(name);
+    }
+
+    pub fn sprite(&self, name: &str) -> Option<TextureBrush> {
+        self.sprites.get(name).cloned()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.sprites.contains_key(name)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item=&str> {
+        self.sprites.keys().map(|name| name.as_str())
+    }
+
+    /// Loads sprites from simple text description where each line is `name x y width height`
+    /// in pixels, empty lines and lines starting with `#` are ignored. Returns number of
+    /// loaded sprites.
+    pub fn load_rects(&mut self, description: &str, texture: Arc<Texture>, texture_size: (u32, u32)) -> Result<usize, AtlasError> {
+        let mut sprites = Vec::new();
+        for (n, line) in description.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut parts = line.split_whitespace();
+            let name = parts.next().ok_or(AtlasError::InvalidLine(n + 1))?;
+            let numbers = parts
+                .map(|part| part.parse::<f32>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| AtlasError::InvalidLine(n + 1))?;
+            if numbers.len() != 4 {
+                return Err(AtlasError::InvalidLine(n + 1));
+            }
+            let region = Rect::new(numbers[0], numbers[1], numbers[2], numbers[3]);
+            sprites.push((name.to_owned(), make_brush(texture.clone(), texture_size, region)));
+        }
+        let count = sprites.len();
+        self.sprites.extend(sprites);
+        Ok(count)
+    }
+
+    /// Loads sprites from libGDX/Spine text atlas (`.atlas`). Atlas can have several pages,
+    /// texture of each page is requested from `page_texture` by file name of page. Both old
+    /// (`xy` + `size`) and new (`bounds`) region formats are supported, rotated regions are
+    /// loaded as is. Returns number of loaded sprites.
+    pub fn load_gdx_atlas<F>(&mut self, description: &str, mut page_texture: F) -> Result<usize, AtlasError>
+        where F: FnMut(&str) -> Option<Arc<Texture>> {
+        struct Page {
+            name: String,
+            texture: Arc<Texture>,
+            size: Option<(u32, u32)>,
+        }
+
+        struct Region {
+            name: String,
+            position: Option<(f32, f32)>,
+            size: Option<(f32, f32)>,
+        }
+
+        fn numbers(value: &str, line: usize) -> Result<Vec<f32>, AtlasError> {
+            value
+                .split(',')
+                .map(|part| part.trim().parse::<f32>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| AtlasError::InvalidLine(line))
+        }
+
+        fn pair(value: &str, line: usize) -> Result<(f32, f32), AtlasError> {
+            match numbers(value, line)?.as_slice() {
+                [a, b] => Ok((*a, *b)),
+                _ => Err(AtlasError::InvalidLine(line)),
+            }
+        }
+
+        fn flush(page: &Option<Page>, region: Option<Region>, sprites: &mut Vec<(String, TextureBrush)>) -> Result<(), AtlasError> {
+            if let (Some(page), Some(region)) = (page, region) {
+                let size = page.size.ok_or_else(|| AtlasError::MissingPageSize(page.name.clone()))?;
+                if let (Some((x, y)), Some((w, h))) = (region.position, region.size) {
+                    sprites.push((region.name, make_brush(page.texture.clone(), size, Rect::new(x, y, w, h))));
+                }
+            }
+            Ok(())
+        }
+
+        let mut sprites = Vec::new();
+        let mut page: Option<Page> = None;
+        let mut region: Option<Region> = None;
+        let mut expect_page = true;
+
+        for (n, line) in description.lines().enumerate() {
+            let line_number = n + 1;
+            if line.trim().is_empty() {
+                flush(&page, region.take(), &mut sprites)?;
+                expect_page = true;
+                continue;
+            }
+            match line.find(':') {
+                None => {
+                    flush(&page, region.take(), &mut sprites)?;
+                    let name = line.trim().to_owned();
+                    if expect_page {
+                        let texture = page_texture(&name).ok_or_else(|| AtlasError::UnknownPage(name.clone()))?;
+                        page = Some(Page { name, texture, size: None });
+                        expect_page = false;
+                    } else {
+                        region = Some(Region { name, position: None, size: None });
+                    }
+                }
+                Some(separator) => {
+                    let key = line[..separator].trim();
+                    let value = &line[separator + 1..];
+                    match region.as_mut() {
+                        Some(region) => match key {
+                            "xy" => region.position = Some(pair(value, line_number)?),
+                            "size" => region.size = Some(pair(value, line_number)?),
+                            "bounds" => {
+                                let v = numbers(value, line_number)?;
+                                if v.len() != 4 {
+                                    return Err(AtlasError::InvalidLine(line_number));
+                                }
+                                region.position = Some((v[0], v[1]));
+                                region.size = Some((v[2], v[3]));
+                            }
+                            _ => (),
+                        },
+                        None => {
+                            let page = page.as_mut().ok_or(AtlasError::InvalidLine(line_number))?;
+                            if key == "size" {
+                                let (width, height) = pair(value, line_number)?;
+                                page.size = Some((width as u32, height as u32));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        flush(&page, region.take(), &mut sprites)?;
+
+        let count = sprites.len();
+        self.sprites.extend(sprites);
+        Ok(count)
+    }
+}
+
+/// Makes brush for region of texture given in pixels.
+fn make_brush(texture: Arc<Texture>, texture_size: (u32, u32), region: Rect<f32>) -> TextureBrush {
+    let (width, height) = (texture_size.0.max(1) as f32, texture_size.1.max(1) as f32);
+    TextureBrush::new(texture)
+        .with_uv_rect(Rect::new(region.x / width, region.y / height, region.w / width, region.h / height))
+}
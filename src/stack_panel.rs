@@ -8,6 +8,7 @@ use crate::{
         draw::DrawingContext,
         UINode,
         scroll_bar::Orientation,
+        scroll_content_presenter::ScrollContentPresenter,
         Control    ,
     core::{
         math::{
@@ -21,21 +22,60 @@ use crate::{
         Builder
 
 };
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    cell::{
+        Cell,
+        RefCell,
+    },
+};
 
 pub struct StackPanel {
     widget: Widget,
     orientation: Orientation,
+    /// Stacks children from bottom to top for vertical orientation and from right to
+    /// left for horizontal. First child will be placed at the far edge of the panel.
+    reversed: bool,
+    /// When panel is hosted by scroll viewer, only children that intersect viewport
+    /// will be measured, arranged and drawn. Size of other children is estimated.
+    /// Virtualization is not applied to reversed panels.
+    virtualizing: bool,
+    /// Offsets of children along orientation axis calculated by virtualized measure pass.
+    /// Empty if last measure pass was not virtualized.
+    virtual_offsets: RefCell<Vec<f32>>,
+    /// Average size of realized children along orientation axis, used for children
+    /// that were never measured.
+    estimated_item_size: Cell<f32>,
 }
 
 impl StackPanel {
     pub fn new(widget: Widget) -> Self {
         Self {
             widget,
-            orientation: Orientation::Vertical
+            orientation: Orientation::Vertical,
+            reversed: false,
+            virtualizing: false,
+            virtual_offsets: Default::default(),
+            estimated_item_size: Cell::new(0.0),
         }
     }
 
+    pub fn set_virtualizing(&mut self, virtualizing: bool) {
+        self.virtualizing = virtualizing;
+    }
+
+    pub fn is_virtualizing(&self) -> bool {
+        self.virtualizing
+    }
+
+    pub fn set_reversed(&mut self, reversed: bool) {
+        self.reversed = reversed;
+    }
+
+    pub fn is_reversed(&self) -> bool {
+        self.reversed
+    }
+
     pub fn set_orientation(&mut self, orientation: Orientation) {
         self.orientation = orientation;
     }
@@ -43,6 +83,102 @@ impl StackPanel {
     pub fn orientation(&self) -> Orientation {
         self.orientation
     }
+
+    fn main_axis(&self, v: Vec2) -> f32 {
+        match self.orientation {
+            Orientation::Vertical => v.y,
+            Orientation::Horizontal => v.x,
+        }
+    }
+
+    fn cross_axis(&self, v: Vec2) -> f32 {
+        match self.orientation {
+            Orientation::Vertical => v.x,
+            Orientation::Horizontal => v.y,
+        }
+    }
+
+    /// Returns visible range of the panel along orientation axis. It is available only
+    /// if panel is hosted directly by scroll content presenter.
+    fn viewport(&self, ui: &UserInterface) -> Option<(f32, f32)> {
+        if self.widget.parent.is_none() {
+            return None;
+        }
+        let presenter = ui.node(self.widget.parent).downcast_ref::<ScrollContentPresenter>()?;
+        let begin = self.main_axis(presenter.scroll());
+        let end = begin + self.main_axis(presenter.widget().actual_size());
+        Some((begin, end))
+    }
+
+    fn measure_virtualized(&self, ui: &UserInterface, child_constraint: Vec2, viewport: (f32, f32)) -> Vec2 {
+        let mut offsets = self.virtual_offsets.borrow_mut();
+        offsets.clear();
+
+        let mut estimated_item_size = self.estimated_item_size.get();
+        if estimated_item_size <= 0.0 {
+            // Nothing was realized yet, first child is better guess than zero size.
+            if let Some(first) = self.widget.children.first() {
+                let first = ui.node(*first);
+                first.measure(ui, child_constraint);
+                estimated_item_size = self.main_axis(first.widget().desired_size.get());
+            }
+        }
+        let mut offset = 0.0;
+        let mut cross_size = 0.0;
+        let mut realized_size = 0.0;
+        let mut realized_count = 0;
+
+        for child_handle in self.widget.children.iter() {
+            let child = ui.node(*child_handle);
+            let cached_size = self.main_axis(child.widget().desired_size.get());
+            let size = if cached_size > 0.0 { cached_size } else { estimated_item_size };
+            let realized = offset < viewport.1 && offset + size >= viewport.0;
+            child.widget().culled.set(!realized);
+            offsets.push(offset);
+            offset += if realized {
+                child.measure(ui, child_constraint);
+                let desired = child.widget().desired_size.get();
+                cross_size = maxf(cross_size, self.cross_axis(desired));
+                realized_size += self.main_axis(desired);
+                realized_count += 1;
+                self.main_axis(desired)
+            } else {
+                size
+            };
+        }
+
+        if realized_count > 0 {
+            self.estimated_item_size.set(realized_size / realized_count as f32);
+        }
+
+        match self.orientation {
+            Orientation::Vertical => Vec2::new(cross_size, offset),
+            Orientation::Horizontal => Vec2::new(offset, cross_size),
+        }
+    }
+
+    fn arrange_virtualized(&self, ui: &UserInterface, final_size: Vec2) -> Vec2 {
+        let offsets = self.virtual_offsets.borrow();
+        let mut extent = 0.0;
+        for (child_handle, offset) in self.widget.children.iter().zip(offsets.iter()) {
+            let child = ui.node(*child_handle);
+            let desired = child.widget().desired_size.get();
+            extent = maxf(extent, *offset + self.main_axis(desired));
+            if child.widget().culled.get() {
+                continue;
+            }
+            let child_bounds = match self.orientation {
+                Orientation::Vertical => Rect::new(0.0, *offset, maxf(final_size.x, desired.x), desired.y),
+                Orientation::Horizontal => Rect::new(*offset, 0.0, desired.x, maxf(final_size.y, desired.y)),
+            };
+            child.arrange(ui, &child_bounds);
+        }
+
+        match self.orientation {
+            Orientation::Vertical => Vec2::new(final_size.x, maxf(extent, final_size.y)),
+            Orientation::Horizontal => Vec2::new(maxf(extent, final_size.x), final_size.y),
+        }
+    }
 }
 
 impl Control for StackPanel {
@@ -57,7 +193,11 @@ impl Control for StackPanel {
     fn raw_copy(&self) -> Box<dyn Control> {
         Box::new(Self {
             widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
-            orientation: self.orientation
+            orientation: self.orientation,
+            reversed: self.reversed,
+            virtualizing: self.virtualizing,
+            virtual_offsets: Default::default(),
+            estimated_item_size: self.estimated_item_size.clone(),
         })
     }
 
@@ -99,9 +239,19 @@ impl Control for StackPanel {
             }
         }
 
+        if self.virtualizing && !self.reversed {
+            // Presenter has no size before its first layout, everything is measured once
+            // then, so the list does not flash empty.
+            if let Some(viewport) = self.viewport(ui).filter(|(begin, end)| end > begin) {
+                return self.measure_virtualized(ui, child_constraint, viewport);
+            }
+        }
+        self.virtual_offsets.borrow_mut().clear();
+
         let mut measured_size = Vec2::ZERO;
 
         for child_handle in self.widget.children.iter() {
+            ui.node(*child_handle).widget().culled.set(false);
             ui.node(*child_handle).measure(ui, child_constraint);
 
             let child = ui.node(*child_handle).widget();
@@ -126,6 +276,10 @@ impl Control for StackPanel {
     }
 
     fn arrange_override(&self, ui: &UserInterface, final_size: Vec2) -> Vec2 {
+        if !self.virtual_offsets.borrow().is_empty() {
+            return self.arrange_virtualized(ui, final_size);
+        }
+
         let mut width = final_size.x;
         let mut height = final_size.y;
 
@@ -134,13 +288,37 @@ impl Control for StackPanel {
             Orientation::Horizontal => width = 0.0,
         }
 
+        // Reversed panel places children starting from far edge, so we have to know
+        // total extent of children first.
+        let extent = if self.reversed {
+            let mut total = 0.0;
+            for child_handle in self.widget.children.iter() {
+                let desired = ui.node(*child_handle).widget().desired_size.get();
+                total += match self.orientation {
+                    Orientation::Vertical => desired.y,
+                    Orientation::Horizontal => desired.x,
+                };
+            }
+            match self.orientation {
+                Orientation::Vertical => maxf(total, final_size.y),
+                Orientation::Horizontal => maxf(total, final_size.x),
+            }
+        } else {
+            0.0
+        };
+
         for child_handle in self.widget.children.iter() {
             let child = ui.node(*child_handle).widget();
             match self.orientation {
                 Orientation::Vertical => {
+                    let y = if self.reversed {
+                        extent - height - child.desired_size.get().y
+                    } else {
+                        height
+                    };
                     let child_bounds = Rect::new(
                         0.0,
-                        height,
+                        y,
                         maxf(width, child.desired_size.get().x),
                         child.desired_size.get().y,
                     );
@@ -149,8 +327,13 @@ impl Control for StackPanel {
                     height += child.desired_size.get().y;
                 }
                 Orientation::Horizontal => {
+                    let x = if self.reversed {
+                        extent - width - child.desired_size.get().x
+                    } else {
+                        width
+                    };
                     let child_bounds = Rect::new(
-                        width,
+                        x,
                         0.0,
                         child.desired_size.get().x,
                         maxf(height, child.desired_size.get().y),
@@ -186,6 +369,8 @@ impl Control for StackPanel {
 pub struct StackPanelBuilder {
     widget_builder: WidgetBuilder,
     orientation: Option<Orientation>,
+    reversed: bool,
+    virtualizing: bool,
 }
 
 impl StackPanelBuilder {
@@ -193,6 +378,8 @@ impl StackPanelBuilder {
         Self {
             widget_builder,
             orientation: None,
+            reversed: false,
+            virtualizing: false,
         }
     }
 
@@ -200,6 +387,16 @@ impl StackPanelBuilder {
         self.orientation = Some(orientation);
         self
     }
+
+    pub fn with_reversed(mut self, reversed: bool) -> Self {
+        self.reversed = reversed;
+        self
+    }
+
+    pub fn with_virtualizing(mut self, virtualizing: bool) -> Self {
+        self.virtualizing = virtualizing;
+        self
+    }
 }
 
 impl Builder for StackPanelBuilder {
@@ -207,8 +404,54 @@ impl Builder for StackPanelBuilder {
         let stack_panel = StackPanel {
             widget: self.widget_builder.build(),
             orientation: self.orientation.unwrap_or(Orientation::Vertical),
+            reversed: self.reversed,
+            virtualizing: self.virtualizing,
+            virtual_offsets: Default::default(),
+            estimated_item_size: Cell::new(0.0),
         };
 
         ui.add_node(Box::new(stack_panel))
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::math::vec2::Vec2,
+        stack_panel::StackPanelBuilder,
+        scroll_viewer::ScrollViewerBuilder,
+        border::BorderBuilder,
+        widget::WidgetBuilder,
+        UserInterface,
+        UINodeContainer,
+        Builder,
+    };
+
+    #[test]
+    fn virtualization_test() {
+        let mut ui = UserInterface::headless();
+        let items = (0..50)
+            .map(|_| BorderBuilder::new(WidgetBuilder::new().with_height(20.0)).build(&mut ui))
+            .collect::<Vec<_>>();
+        let panel = StackPanelBuilder::new(WidgetBuilder::new()
+            .with_children(&items))
+            .with_virtualizing(true)
+            .build(&mut ui);
+        ScrollViewerBuilder::new(WidgetBuilder::new()
+            .with_width(100.0)
+            .with_height(100.0))
+            .with_content(panel)
+            .build(&mut ui);
+
+        // Viewport is unknown on first frame, list must not be empty.
+        ui.update(Vec2::new(800.0, 600.0), 0.0);
+        assert_eq!(ui.node(panel).widget().desired_size.get().y, 1000.0);
+        assert!(items.iter().all(|item| !ui.node(*item).widget().culled.get()));
+
+        // Then only visible items are realized, extent stays the same.
+        ui.update(Vec2::new(800.0, 600.0), 0.0);
+        assert_eq!(ui.node(panel).widget().desired_size.get().y, 1000.0);
+        assert!(!ui.node(items[0]).widget().culled.get());
+        assert!(ui.node(items[49]).widget().culled.get());
+    }
+}
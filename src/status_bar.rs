@@ -0,0 +1,214 @@
+use crate::{
+    core::{
+        color::Color,
+        pool::Handle,
+    },
+    widget::{
+        Widget,
+        WidgetBuilder,
+    },
+    grid::{
+        GridBuilder,
+        Column,
+        Row,
+    },
+    border::BorderBuilder,
+    dock_panel::{
+        Dock,
+        DockPanel,
+    },
+    text::{
+        Text,
+        TextBuilder,
+    },
+    UserInterface,
+    UINode,
+    Thickness,
+    VerticalAlignment,
+    Control,
+    ControlTemplate,
+    UINodeContainer,
+    Builder,
+};
+use std::collections::HashMap;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum SectionAlignment {
+    /// Section is placed at left side and takes as much space as its content needs.
+    Left,
+    /// Section takes all space that is left after other sections.
+    Stretch,
+    /// Section is placed at right side and takes as much space as its content needs.
+    Right,
+}
+
+pub struct StatusBarSection {
+    alignment: SectionAlignment,
+    text: String,
+    content: Handle<UINode>,
+}
+
+impl StatusBarSection {
+    pub fn new(alignment: SectionAlignment) -> Self {
+        Self {
+            alignment,
+            text: String::new(),
+            content: Handle::NONE,
+        }
+    }
+
+    pub fn with_text(mut self, text: &str) -> Self {
+        self.text = text.to_owned();
+        self
+    }
+
+    /// Sets arbitrary content of section, text will not be created in this case.
+    pub fn with_content(mut self, content: Handle<UINode>) -> Self {
+        self.content = content;
+        self
+    }
+}
+
+/// Horizontal strip at the bottom of a window, split into sections that show text or
+/// any other content. Status bar is docked to the bottom edge when it is a child of
+/// `DockPanel`, unless other dock was set explicitly. Sections keep order in which they
+/// were added, but all left-aligned sections go first, then stretch sections and
+/// right-aligned ones at the end.
+pub struct StatusBar {
+    widget: Widget,
+    /// Content of each section in order of addition.
+    sections: Vec<Handle<UINode>>,
+}
+
+impl StatusBar {
+    pub fn sections(&self) -> &[Handle<UINode>] {
+        &self.sections
+    }
+
+    /// Returns content of section, it is `Text` for sections created without custom content.
+    pub fn section(&self, index: usize) -> Handle<UINode> {
+        self.sections.get(index).cloned().unwrap_or(Handle::NONE)
+    }
+
+    /// Sets text of section with given index, does nothing if section has custom content.
+    pub fn set_section_text(ui: &mut UserInterface, status_bar: Handle<UINode>, index: usize, text: &str) {
+        let section = match ui.node(status_bar).downcast_ref::<StatusBar>() {
+            Some(status_bar) => status_bar.section(index),
+            None => return,
+        };
+        if section.is_some() {
+            if let Some(text_node) = ui.node_mut(section).downcast_mut::<Text>() {
+                text_node.set_text(text);
+            }
+        }
+    }
+}
+
+impl Control for StatusBar {
+    fn widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    fn widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    fn raw_copy(&self) -> Box<dyn Control> {
+        Box::new(Self {
+            widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
+            sections: self.sections.clone(),
+        })
+    }
+
+    fn resolve(&mut self, _: &ControlTemplate, node_map: &HashMap<Handle<UINode>, Handle<UINode>>) {
+        for section in self.sections.iter_mut() {
+            *section = *node_map.get(section).unwrap();
+        }
+    }
+}
+
+pub struct StatusBarBuilder {
+    widget_builder: WidgetBuilder,
+    sections: Vec<StatusBarSection>,
+}
+
+impl StatusBarBuilder {
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            sections: Vec::new(),
+        }
+    }
+
+    pub fn with_section(mut self, section: StatusBarSection) -> Self {
+        self.sections.push(section);
+        self
+    }
+}
+
+impl Builder for StatusBarBuilder {
+    fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
+        let mut sections = Vec::new();
+        let mut containers = Vec::new();
+        for section in self.sections {
+            let content = if section.content.is_some() {
+                section.content
+            } else {
+                TextBuilder::new(WidgetBuilder::new())
+                    .with_text(&section.text)
+                    .with_vertical_text_alignment(VerticalAlignment::Center)
+                    .build(ui)
+            };
+            let container = BorderBuilder::new(WidgetBuilder::new()
+                .with_margin(Thickness::uniform(1.0))
+                .with_background(Color::opaque(50, 50, 50))
+                .with_foreground(Color::opaque(80, 80, 80))
+                .with_child(content))
+                .with_stroke_thickness(Thickness::uniform(1.0))
+                .build(ui);
+            sections.push(content);
+            containers.push((section.alignment, container));
+        }
+
+        let has_stretch = containers.iter().any(|(alignment, _)| *alignment == SectionAlignment::Stretch);
+
+        let mut columns = Vec::new();
+        for order in &[SectionAlignment::Left, SectionAlignment::Stretch, SectionAlignment::Right] {
+            // Empty stretch column pushes right-aligned sections to the right edge.
+            if *order == SectionAlignment::Right && !has_stretch {
+                columns.push(Column::stretch());
+            }
+            for (alignment, container) in containers.iter().filter(|(alignment, _)| alignment == order) {
+                ui.node_mut(*container).widget_mut().set_column(columns.len());
+                columns.push(match alignment {
+                    SectionAlignment::Stretch => Column::stretch(),
+                    _ => Column::auto(),
+                });
+            }
+        }
+
+        let children = containers
+            .iter()
+            .map(|(_, container)| *container)
+            .collect::<Vec<_>>();
+        let grid = GridBuilder::new(WidgetBuilder::new()
+            .with_children(&children))
+            .add_row(Row::stretch())
+            .add_columns(columns)
+            .build(ui);
+
+        let mut widget = self.widget_builder
+            .with_child(grid)
+            .build();
+        if !widget.has_attached_property(DockPanel::DOCK) {
+            widget.set_attached_property(DockPanel::DOCK, Dock::Bottom);
+        }
+
+        let status_bar = StatusBar {
+            widget,
+            sections,
+        };
+
+        ui.add_node(Box::new(status_bar))
+    }
+}
@@ -18,10 +18,17 @@ impl PropertySetter {
     }
 }
 
+/// Setter which is applied only when widget is in specific state.
+pub struct StateSetter {
+    state: String,
+    setter: PropertySetter,
+}
+
 #[derive(Default)]
 pub struct Style {
     base_style: Option<Rc<Style>>,
     setters: Vec<PropertySetter>,
+    state_setters: Vec<StateSetter>,
 }
 
 impl Style {
@@ -36,11 +43,21 @@ impl Style {
     pub fn base_style(&self) -> Option<Rc<Style>> {
         self.base_style.clone()
     }
+
+    /// Returns setters that must be applied when widget is in given state, for example
+    /// in `Widget::SELECTED_STATE`.
+    pub fn state_setters<'a>(&'a self, state: &'a str) -> impl Iterator<Item=&'a PropertySetter> {
+        self.state_setters
+            .iter()
+            .filter(move |state_setter| state_setter.state == state)
+            .map(|state_setter| &state_setter.setter)
+    }
 }
 
 pub struct StyleBuilder {
     base_style: Option<Rc<Style>>,
     setters: Vec<PropertySetter>,
+    state_setters: Vec<StateSetter>,
 }
 
 impl Default for StyleBuilder {
@@ -54,6 +71,7 @@ impl StyleBuilder {
         Self {
             base_style: None,
             setters: Default::default(),
+            state_setters: Default::default(),
         }
     }
 
@@ -67,10 +85,22 @@ impl StyleBuilder {
         self
     }
 
+    /// Adds setter which will be applied on top of usual setters when widget is in given
+    /// state. Style should have usual setter for the same property, otherwise property
+    /// will keep its value when widget leaves the state.
+    pub fn with_state_setter(mut self, state: &str, name: &str, value: Box<dyn Any>) -> Self {
+        self.state_setters.push(StateSetter {
+            state: state.to_owned(),
+            setter: PropertySetter { name: name.to_owned(), value },
+        });
+        self
+    }
+
     pub fn build(self) -> Style {
         Style {
             base_style: self.base_style,
-            setters: self.setters
+            setters: self.setters,
+            state_setters: self.state_setters,
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,148 @@
+use crate::{
+    core::{
+        color::Color,
+        pool::Handle,
+    },
+    color_ext::palette::{
+        self,
+        Palette,
+    },
+    border::BorderBuilder,
+    button::ButtonBuilder,
+    check_box::CheckBoxBuilder,
+    list_box::ListBoxBuilder,
+    scroll_bar::{
+        ScrollBarBuilder,
+        Orientation,
+    },
+    stack_panel::StackPanelBuilder,
+    text::TextBuilder,
+    text_box::TextBoxBuilder,
+    widget::WidgetBuilder,
+    UINode,
+    UINodeContainer,
+    Builder,
+    Thickness,
+};
+
+/// Builds sheet with swatches of all colors of palette followed by stock widgets in their
+/// typical states, so palette can be verified at a glance. Widgets are built with given
+/// palette, current palette is restored after build.
+pub struct SwatchSheetBuilder {
+    widget_builder: WidgetBuilder,
+    palette: Palette,
+}
+
+impl SwatchSheetBuilder {
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            palette: palette::current(),
+        }
+    }
+
+    pub fn with_palette(mut self, palette: Palette) -> Self {
+        self.palette = palette;
+        self
+    }
+}
+
+fn make_label(ui: &mut dyn UINodeContainer, text: &str, color: Color) -> Handle<UINode> {
+    TextBuilder::new(WidgetBuilder::new()
+        .with_foreground(color)
+        .with_margin(Thickness::uniform(2.0)))
+        .with_text(text)
+        .build(ui)
+}
+
+fn make_swatch(ui: &mut dyn UINodeContainer, name: &str, color: Color, p: &Palette) -> Handle<UINode> {
+    let label = make_label(ui, name, p.text);
+    let sample = BorderBuilder::new(WidgetBuilder::new()
+        .with_width(24.0)
+        .with_height(24.0)
+        .with_margin(Thickness::uniform(2.0))
+        .with_background(color)
+        .with_foreground(p.frame))
+        .with_stroke_thickness(Thickness::uniform(1.0))
+        .build(ui);
+    StackPanelBuilder::new(WidgetBuilder::new()
+        .with_child(sample)
+        .with_child(label))
+        .with_orientation(Orientation::Horizontal)
+        .build(ui)
+}
+
+impl Builder for SwatchSheetBuilder {
+    fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
+        let previous = palette::current();
+        palette::set_current(self.palette);
+        let p = self.palette;
+
+        let swatches = [
+            ("Panel", p.panel),
+            ("Field", p.field),
+            ("Frame", p.frame),
+            ("Control", p.control),
+            ("Control Hover", p.control_hover),
+            ("Text", p.text),
+            ("Text Selection", p.text_selection),
+            ("Text Selection Multiline", p.text_selection_multiline),
+            ("Accent", p.accent),
+        ]
+            .iter()
+            .map(|(name, color)| make_swatch(ui, name, *color, &p))
+            .collect::<Vec<_>>();
+
+        let button = ButtonBuilder::new(WidgetBuilder::new()
+            .with_margin(Thickness::uniform(2.0)))
+            .with_text("Button")
+            .build(ui);
+        let toggled = ButtonBuilder::new(WidgetBuilder::new()
+            .with_margin(Thickness::uniform(2.0)))
+            .with_text("Toggled Button")
+            .with_toggle_mode(true)
+            .with_toggled(true)
+            .build(ui);
+        let check_box_label = make_label(ui, "Check Box", p.text);
+        let check_box = CheckBoxBuilder::new(WidgetBuilder::new()
+            .with_margin(Thickness::uniform(2.0)))
+            .with_content(check_box_label)
+            .build(ui);
+        let text_box = TextBoxBuilder::new(WidgetBuilder::new()
+            .with_height(26.0)
+            .with_margin(Thickness::uniform(2.0))
+            .with_foreground(p.text))
+            .with_text("Text Box".to_owned())
+            .build(ui);
+        let scroll_bar = ScrollBarBuilder::new(WidgetBuilder::new()
+            .with_height(20.0)
+            .with_margin(Thickness::uniform(2.0)))
+            .with_orientation(Orientation::Horizontal)
+            .with_value(30.0)
+            .build(ui);
+        let items = (1..=3)
+            .map(|i| make_label(ui, &format!("List Item {}", i), p.text))
+            .collect();
+        let list_box = ListBoxBuilder::new(WidgetBuilder::new()
+            .with_margin(Thickness::uniform(2.0)))
+            .with_items(items)
+            .build(ui);
+
+        let mut widget_builder = self.widget_builder
+            .with_background(p.panel);
+        for swatch in swatches {
+            widget_builder = widget_builder.with_child(swatch);
+        }
+        let sheet = StackPanelBuilder::new(widget_builder
+            .with_child(button)
+            .with_child(toggled)
+            .with_child(check_box)
+            .with_child(text_box)
+            .with_child(scroll_bar)
+            .with_child(list_box))
+            .build(ui);
+
+        palette::set_current(previous);
+        sheet
+    }
+}
@@ -0,0 +1,262 @@
+use crate::{
+    core::{
+        pool::Handle,
+        math::vec2::Vec2,
+    },
+    draw::DrawingContext,
+    formatted_text::{
+        FormattedText,
+        FormattedTextBuilder,
+    },
+    widget::{
+        Widget,
+        WidgetBuilder,
+    },
+    ttf::Font,
+    UINode,
+    Control,
+    ControlTemplate,
+    UINodeContainer,
+    UserInterface,
+    Builder,
+    HorizontalAlignment,
+    VerticalAlignment,
+};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    ops::Range,
+    sync::{
+        Arc,
+        Mutex,
+    },
+};
+
+/// Glyph of icon font, font is rasterized for specific size.
+#[derive(Clone)]
+pub struct Icon {
+    pub font: Arc<Mutex<Font>>,
+    pub codepoint: u32,
+}
+
+/// Icon font registered in icon registry. Font is kept as raw data and rasterized
+/// on demand for every requested size, so icons stay crisp at any size.
+struct IconFont {
+    data: Vec<u8>,
+    char_set: Vec<Range<u32>>,
+    /// Rasterized fonts by pixel height.
+    sizes: HashMap<u32, Arc<Mutex<Font>>>,
+}
+
+/// Registry of icon fonts and named icons, for example "save" -> codepoint 0xE161 of
+/// "material" font. Registry is owned by user interface, see `UserInterface::icons_mut`,
+/// so icons can be referenced by name instead of by codepoints across whole application.
+#[derive(Default)]
+pub struct IconRegistry {
+    fonts: HashMap<String, IconFont>,
+    /// Name of icon -> name of font and codepoint.
+    icons: HashMap<String, (String, u32)>,
+}
+
+impl IconRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers icon font from memory. Char set must contain codepoints of all icons
+    /// of the font, icon fonts usually put glyphs into private use area 0xE000..0xF8FF.
+    /// Returns false if data is not a valid font.
+    pub fn register_font(&mut self, name: &str, data: Vec<u8>, char_set: &[Range<u32>]) -> bool {
+        // Rasterize once to check that font is valid.
+        if Font::from_memory(data.clone(), 16.0, char_set).is_err() {
+            return false;
+        }
+        self.fonts.insert(name.to_owned(), IconFont {
+            data,
+            char_set: char_set.to_vec(),
+            sizes: Default::default(),
+        });
+        true
+    }
+
+    pub fn has_font(&self, name: &str) -> bool {
+        self.fonts.contains_key(name)
+    }
+
+    /// Registers named icon, font does not have to be registered yet.
+    pub fn register_icon(&mut self, name: &str, font: &str, codepoint: u32) {
+        self.icons.insert(name.to_owned(), (font.to_owned(), codepoint));
+    }
+
+    pub fn unregister_icon(&mut self, name: &str) {
+        self.icons.remove(name);
+    }
+
+    /// Returns font and codepoint of named icon.
+    pub fn lookup(&self, name: &str) -> Option<(&str, u32)> {
+        self.icons
+            .get(name)
+            .map(|(font, codepoint)| (font.as_str(), *codepoint))
+    }
+
+    /// Returns registered font rasterized for given size, fonts are cached so every size
+    /// is rasterized only once.
+    pub fn font(&mut self, name: &str, size: f32) -> Option<Arc<Mutex<Font>>> {
+        let icon_font = self.fonts.get_mut(name)?;
+        let height = size.round().max(1.0) as u32;
+        if let Some(font) = icon_font.sizes.get(&height) {
+            return Some(font.clone());
+        }
+        let font = Font::from_memory(icon_font.data.clone(), height as f32, &icon_font.char_set).ok()?;
+        let font = Arc::new(Mutex::new(font));
+        icon_font.sizes.insert(height, font.clone());
+        Some(font)
+    }
+
+    /// Returns named icon rasterized for given size, None if there is no such icon or its
+    /// font is not registered.
+    pub fn icon(&mut self, name: &str, size: f32) -> Option<Icon> {
+        let (font, codepoint) = self.icons.get(name).cloned()?;
+        Some(Icon {
+            font: self.font(&font, size)?,
+            codepoint,
+        })
+    }
+}
+
+/// Single glyph of icon font, centered in bounds of widget. Glyph is drawn with foreground
+/// brush of widget.
+pub struct SymbolIcon {
+    widget: Widget,
+    icon: Option<Icon>,
+    size: f32,
+    formatted_text: RefCell<Option<FormattedText>>,
+}
+
+impl SymbolIcon {
+    pub fn icon(&self) -> Option<&Icon> {
+        self.icon.as_ref()
+    }
+
+    pub fn set_icon(&mut self, icon: Option<Icon>) {
+        self.icon = icon;
+        self.formatted_text.replace(None);
+    }
+
+    pub fn size(&self) -> f32 {
+        self.size
+    }
+
+    /// Sets size of icon in pixels. Font of icon is not changed, so named icons should be
+    /// fetched again from registry for new size, see [`set_named_icon`](Self::set_named_icon).
+    pub fn set_size(&mut self, size: f32) {
+        self.size = size;
+    }
+
+    /// Sets named icon from icon registry of user interface with given size.
+    pub fn set_named_icon(ui: &mut UserInterface, icon: Handle<UINode>, name: &str, size: f32) {
+        let glyph = ui.icons_mut().icon(name, size);
+        if let Some(symbol_icon) = ui.node_mut(icon).downcast_mut::<SymbolIcon>() {
+            symbol_icon.set_icon(glyph);
+            symbol_icon.set_size(size);
+        }
+    }
+}
+
+impl Control for SymbolIcon {
+    fn widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    fn widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    fn raw_copy(&self) -> Box<dyn Control> {
+        Box::new(Self {
+            widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
+            icon: self.icon.clone(),
+            size: self.size,
+            formatted_text: RefCell::new(None),
+        })
+    }
+
+    fn resolve(&mut self, _: &ControlTemplate, _: &HashMap<Handle<UINode>, Handle<UINode>>) {}
+
+    fn measure_override(&self, _: &UserInterface, _: Vec2) -> Vec2 {
+        Vec2::new(self.size, self.size)
+    }
+
+    fn draw(&self, drawing_context: &mut DrawingContext) {
+        let icon = match self.icon.as_ref() {
+            Some(icon) => icon,
+            None => return,
+        };
+        let bounds = self.widget.get_screen_bounds();
+        let mut formatted_text = self.formatted_text.borrow_mut();
+        let text = formatted_text.get_or_insert_with(|| {
+            let mut text = FormattedTextBuilder::new()
+                .with_font(icon.font.clone())
+                .with_horizontal_alignment(HorizontalAlignment::Center)
+                .with_vertical_alignment(VerticalAlignment::Center)
+                .build();
+            text.set_text(&std::char::from_u32(icon.codepoint).unwrap_or(' ').to_string());
+            text
+        });
+        text.set_size(Vec2::new(bounds.w, bounds.h));
+        text.set_color(self.widget.foreground());
+        text.build();
+        drawing_context.draw_text(Vec2::new(bounds.x, bounds.y), text);
+    }
+}
+
+pub struct SymbolIconBuilder {
+    widget_builder: WidgetBuilder,
+    icon: Option<Icon>,
+    size: f32,
+}
+
+impl SymbolIconBuilder {
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            icon: None,
+            size: 16.0,
+        }
+    }
+
+    /// Sets glyph of font, font should be rasterized for size of icon.
+    pub fn with_glyph(mut self, font: Arc<Mutex<Font>>, codepoint: u32) -> Self {
+        self.icon = Some(Icon { font, codepoint });
+        self
+    }
+
+    /// Sets icon, usually taken from registry by `IconRegistry::icon`.
+    pub fn with_icon(mut self, icon: Icon) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    pub fn with_opt_icon(mut self, icon: Option<Icon>) -> Self {
+        self.icon = icon;
+        self
+    }
+
+    pub fn with_size(mut self, size: f32) -> Self {
+        self.size = size;
+        self
+    }
+}
+
+impl Builder for SymbolIconBuilder {
+    fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
+        let symbol_icon = SymbolIcon {
+            widget: self.widget_builder.build(),
+            icon: self.icon,
+            size: self.size,
+            formatted_text: RefCell::new(None),
+        };
+
+        ui.add_node(Box::new(symbol_icon))
+    }
+}
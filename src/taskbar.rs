@@ -0,0 +1,175 @@
+use crate::{
+    core::pool::Handle,
+    widget::{
+        Widget,
+        WidgetBuilder,
+    },
+    button::{
+        Button,
+        ButtonBuilder,
+    },
+    stack_panel::StackPanelBuilder,
+    scroll_bar::Orientation,
+    window::Window,
+    event::{
+        UIEvent,
+        UIEventKind,
+    },
+    UserInterface,
+    UINode,
+    Thickness,
+    Control,
+    ControlTemplate,
+    UINodeContainer,
+    Builder,
+};
+use std::collections::HashMap;
+
+/// Strip of buttons, one for each open window of user interface. Click on a button
+/// activates its window, click on a button of active window minimizes it. Windows that
+/// are built `with_minimize_to_taskbar` are hidden completely when minimized and can be
+/// restored only from taskbar or by `UserInterface::activate_window`.
+///
+/// Taskbar follows events of windows (opened, closed, minimized) so buttons are added
+/// and removed automatically.
+pub struct Taskbar {
+    widget: Widget,
+    panel: Handle<UINode>,
+    /// Pairs of window and its button.
+    entries: Vec<(Handle<UINode>, Handle<UINode>)>,
+}
+
+impl Taskbar {
+    /// Returns button of given window, or NONE if window has no button.
+    pub fn button_of(&self, window: Handle<UINode>) -> Handle<UINode> {
+        self.entries
+            .iter()
+            .find(|(w, _)| *w == window)
+            .map_or(Handle::NONE, |(_, button)| *button)
+    }
+
+    pub fn windows(&self) -> Vec<Handle<UINode>> {
+        self.entries.iter().map(|(window, _)| *window).collect()
+    }
+
+    /// Adds, updates or removes button of a window according to its current state.
+    fn sync_window(&mut self, ui: &mut UserInterface, window: Handle<UINode>) {
+        let (open, minimized, title) = match ui.node(window).downcast_ref::<Window>() {
+            Some(window) => (window.is_open(), window.is_minimized(), window.title().to_owned()),
+            None => return,
+        };
+        let button = self.button_of(window);
+        if open {
+            let button = if button.is_none() {
+                let button = ButtonBuilder::new(WidgetBuilder::new()
+                    .with_margin(Thickness::uniform(1.0)))
+                    .with_toggle_mode(true)
+                    .with_text(if title.is_empty() { "Window" } else { title.as_str() })
+                    .build(ui);
+                ui.link_nodes(button, self.panel);
+                self.entries.push((window, button));
+                button
+            } else {
+                button
+            };
+            if let Some(button) = ui.node_mut(button).downcast_mut::<Button>() {
+                button.set_toggled(!minimized);
+            }
+        } else if button.is_some() {
+            self.entries.retain(|(w, _)| *w != window);
+            ui.remove_node(button);
+        }
+    }
+
+    /// Removes buttons of windows that were removed from user interface.
+    fn remove_dead_entries(&mut self, ui: &mut UserInterface) {
+        let (alive, dead): (Vec<_>, Vec<_>) = self.entries
+            .iter()
+            .cloned()
+            .partition(|(window, _)| ui.nodes.is_valid_handle(*window));
+        self.entries = alive;
+        for (_, button) in dead {
+            ui.remove_node(button);
+        }
+    }
+}
+
+impl Control for Taskbar {
+    fn widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    fn widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    fn raw_copy(&self) -> Box<dyn Control> {
+        Box::new(Self {
+            widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
+            panel: self.panel,
+            entries: Vec::new(),
+        })
+    }
+
+    fn resolve(&mut self, _: &ControlTemplate, node_map: &HashMap<Handle<UINode>, Handle<UINode>>) {
+        self.panel = *node_map.get(&self.panel).unwrap();
+    }
+
+    fn handle_event(&mut self, self_handle: Handle<UINode>, ui: &mut UserInterface, evt: &mut UIEvent) {
+        if let UIEventKind::Click = evt.kind {
+            if let Some((window, _)) = self.entries.iter().find(|(_, button)| *button == evt.source).cloned() {
+                let minimized = ui.node(window).downcast_ref::<Window>().map_or(false, |w| w.is_minimized());
+                if ui.active_window() == window && !minimized {
+                    if let Some(window) = ui.node_mut(window).downcast_mut::<Window>() {
+                        window.minimize(true);
+                    }
+                } else {
+                    ui.activate_window(window);
+                }
+                return;
+            }
+        }
+
+        if evt.source != self_handle && ui.nodes.is_valid_handle(evt.source) &&
+            ui.node(evt.source).downcast_ref::<Window>().is_some() {
+            self.remove_dead_entries(ui);
+            self.sync_window(ui, evt.source);
+        }
+    }
+
+    fn on_attached_to_ui(&mut self, _self_handle: Handle<UINode>, ui: &mut UserInterface) {
+        for window in ui.open_windows() {
+            self.sync_window(ui, window);
+        }
+    }
+}
+
+pub struct TaskbarBuilder {
+    widget_builder: WidgetBuilder,
+}
+
+impl TaskbarBuilder {
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+        }
+    }
+}
+
+impl Builder for TaskbarBuilder {
+    fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
+        let panel = StackPanelBuilder::new(WidgetBuilder::new())
+            .with_orientation(Orientation::Horizontal)
+            .build(ui);
+
+        let taskbar = Taskbar {
+            widget: self.widget_builder
+                .with_child(panel)
+                .build(),
+            panel,
+            entries: Vec::new(),
+        };
+
+        ui.add_node(Box::new(taskbar))
+    }
+}
@@ -1,11 +1,16 @@
 use crate::{
     core::{
+        color::Color,
         pool::Handle,
         math::vec2::Vec2,
     },
         VerticalAlignment,
         HorizontalAlignment,
-        draw::DrawingContext,
+        draw::{
+            DrawingContext,
+            RetainedGeometry,
+        },
+        resource::ResourceRequest,
         formatted_text::{
             FormattedText,
             FormattedTextBuilder,
@@ -34,10 +39,19 @@ pub struct Text {
     widget: Widget,
     need_update: Cell<bool>,
     text: String,
-    font: Arc<Mutex<Font>>,
+    /// Text without font is laid out as empty, see `UserInterface::headless`.
+    font: Option<Arc<Mutex<Font>>>,
     vertical_alignment: VerticalAlignment,
     horizontal_alignment: HorizontalAlignment,
     formatted_text: RefCell<FormattedText>,
+    /// Static text bakes its glyph quads once and reuses them until layout changes.
+    is_static: bool,
+    /// Baked glyphs with build key and color of formatted text they were made from.
+    retained: RefCell<Option<(u64, Color, RetainedGeometry)>>,
+    /// Layout is done on worker thread, previous layout is shown until new one is ready.
+    background_layout: bool,
+    /// Layout key and request of text which is being built on worker thread.
+    pending_layout: RefCell<Option<(u64, ResourceRequest<FormattedText>)>>,
 }
 
 impl Control for Text {
@@ -58,8 +72,12 @@ impl Control for Text {
             vertical_alignment: self.vertical_alignment,
             horizontal_alignment: self.horizontal_alignment,
             formatted_text: RefCell::new(FormattedTextBuilder::new()
-                .with_font(self.font.clone())
+                .with_opt_font(self.font.clone())
                 .build()),
+            is_static: self.is_static,
+            retained: RefCell::new(None),
+            background_layout: self.background_layout,
+            pending_layout: RefCell::new(None),
         })
     }
 
@@ -67,7 +85,9 @@ impl Control for Text {
 
     fn draw(&self, drawing_context: &mut DrawingContext) {
         let bounds = self.widget.get_screen_bounds();
-        if self.need_update.get() {
+        if self.background_layout {
+            self.update_background_layout(Vec2::new(bounds.w, bounds.h));
+        } else if self.need_update.get() {
             let mut text = self.formatted_text.borrow_mut();
             text.set_size(Vec2::new(bounds.w, bounds.h));
             text.set_text(self.text.as_str());
@@ -77,7 +97,22 @@ impl Control for Text {
             text.build();
             self.need_update.set(true); // TODO
         }
-        drawing_context.draw_text(Vec2::new(bounds.x, bounds.y), &self.formatted_text.borrow());
+        let position = Vec2::new(bounds.x, bounds.y);
+        if self.is_static {
+            let text = self.formatted_text.borrow();
+            if let Some(key) = text.build_key() {
+                let color = self.widget.foreground();
+                let mut retained = self.retained.borrow_mut();
+                if retained.as_ref().map_or(true, |(retained_key, retained_color, _)| *retained_key != key || *retained_color != color) {
+                    *retained = RetainedGeometry::from_text(&text).map(|geometry| (key, color, geometry));
+                }
+                if let Some((_, _, geometry)) = retained.as_ref() {
+                    drawing_context.push_retained(geometry, position);
+                    return;
+                }
+            }
+        }
+        drawing_context.draw_text(position, &self.formatted_text.borrow());
     }
 }
 
@@ -90,9 +125,13 @@ impl Text {
             formatted_text: RefCell::new(FormattedTextBuilder::new()
                 .with_font(crate::DEFAULT_FONT.clone())
                 .build()),
-            font: crate::DEFAULT_FONT.clone(),
+            font: Some(crate::DEFAULT_FONT.clone()),
             vertical_alignment: VerticalAlignment::Stretch,
             horizontal_alignment: HorizontalAlignment::Stretch,
+            is_static: false,
+            retained: RefCell::new(None),
+            background_layout: false,
+            pending_layout: RefCell::new(None),
         }
     }
 
@@ -108,12 +147,12 @@ impl Text {
     }
 
     pub fn set_font(&mut self, font: Arc<Mutex<Font>>) -> &mut Self {
-        self.font = font;
+        self.font = Some(font);
         self.need_update.set(true);
         self
     }
 
-    pub fn font(&self) -> Arc<Mutex<Font>> {
+    pub fn font(&self) -> Option<Arc<Mutex<Font>>> {
         self.font.clone()
     }
 
@@ -134,6 +173,64 @@ impl Text {
     pub fn horizontal_alignment(&self) -> HorizontalAlignment {
         self.horizontal_alignment
     }
+
+    /// Marks text as static, glyph geometry of static text is generated once and then
+    /// reused while text and its layout stay the same. Useful for labels which rarely
+    /// change, especially when there are hundreds of them.
+    pub fn set_static(&mut self, is_static: bool) -> &mut Self {
+        self.is_static = is_static;
+        if !is_static {
+            self.retained.borrow_mut().take();
+        }
+        self
+    }
+
+    pub fn is_static(&self) -> bool {
+        self.is_static
+    }
+
+    /// Enables layout of text on worker thread, useful for large texts like logs or consoles.
+    /// While new layout is being built, previous one is shown.
+    pub fn set_background_layout(&mut self, background_layout: bool) -> &mut Self {
+        self.background_layout = background_layout;
+        self.pending_layout.borrow_mut().take();
+        self
+    }
+
+    pub fn is_background_layout(&self) -> bool {
+        self.background_layout
+    }
+
+    fn update_background_layout(&self, size: Vec2) {
+        let mut pending = self.pending_layout.borrow_mut();
+        if let Some((_, request)) = pending.as_ref() {
+            match request.take() {
+                Some(Ok(built)) => {
+                    *self.formatted_text.borrow_mut() = built;
+                    *pending = None;
+                }
+                Some(Err(_)) => *pending = None,
+                None => (),
+            }
+        }
+
+        let mut text = self.formatted_text.borrow_mut();
+        let mut desired = text.unbuilt_copy();
+        desired.set_size(size);
+        desired.set_text(self.text.as_str());
+        desired.set_horizontal_alignment(self.horizontal_alignment);
+        desired.set_vertical_alignment(self.vertical_alignment);
+        let key = desired.layout_key();
+        let is_built = text.build_key() == Some(key);
+        let is_building = pending.as_ref().map_or(false, |(pending_key, _)| *pending_key == key);
+        if !is_built && !is_building {
+            *pending = Some((key, desired.build_async()));
+        }
+
+        // Only color may change here, so build just recolors current glyphs.
+        text.set_color(self.widget.foreground());
+        text.build();
+    }
 }
 
 pub struct TextBuilder {
@@ -142,6 +239,8 @@ pub struct TextBuilder {
     font: Option<Arc<Mutex<Font>>>,
     vertical_text_alignment: Option<VerticalAlignment>,
     horizontal_text_alignment: Option<HorizontalAlignment>,
+    is_static: bool,
+    background_layout: bool,
 }
 
 impl TextBuilder {
@@ -152,6 +251,8 @@ impl TextBuilder {
             font: None,
             vertical_text_alignment: None,
             horizontal_text_alignment: None,
+            is_static: false,
+            background_layout: false,
         }
     }
 
@@ -179,15 +280,23 @@ impl TextBuilder {
         self.horizontal_text_alignment = Some(halign);
         self
     }
+
+    /// See `Text::set_static`.
+    pub fn with_static(mut self, is_static: bool) -> Self {
+        self.is_static = is_static;
+        self
+    }
+
+    /// See `Text::set_background_layout`.
+    pub fn with_background_layout(mut self, background_layout: bool) -> Self {
+        self.background_layout = background_layout;
+        self
+    }
 }
 
 impl Builder for TextBuilder {
     fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
-        let font = if let Some(font) = self.font {
-            font
-        } else {
-            crate::DEFAULT_FONT.clone()
-        };
+        let font = self.font.or_else(|| ui.default_font());
 
         ui.add_node(Box::new(Text {
             widget: self.widget_builder.build(),
@@ -195,8 +304,12 @@ impl Builder for TextBuilder {
             need_update: Cell::new(true),
             vertical_alignment: self.vertical_text_alignment.unwrap_or(VerticalAlignment::Top),
             horizontal_alignment: self.horizontal_text_alignment.unwrap_or(HorizontalAlignment::Left),
-            formatted_text: RefCell::new(FormattedTextBuilder::new().with_font(font.clone()).build()),
+            formatted_text: RefCell::new(FormattedTextBuilder::new().with_opt_font(font.clone()).build()),
             font,
+            is_static: self.is_static,
+            retained: RefCell::new(None),
+            background_layout: self.background_layout,
+            pending_layout: RefCell::new(None),
         }))
     }
 }
\ No newline at end of file
@@ -6,7 +6,9 @@ use crate::{
         },
         pool::Handle,
         color::Color,
+        math,
     },
+    color_ext::palette,
     widget::{
         WidgetBuilder,
         Widget,
@@ -27,16 +29,18 @@ use crate::{
         UIEvent,
         UIEventKind,
         MouseButton,
-        KeyCode
     },
+    keymap::WidgetAction,
     ControlTemplate,
     UINodeContainer,
     Builder,
     ttf::Font,
+    maxf,
 };
 use std::{
     collections::HashMap,
     cmp,
+    ops::Range,
     sync::{Mutex, Arc},
     cell::RefCell
 };
@@ -53,18 +57,113 @@ pub enum VerticalDirection {
     Up,
 }
 
-#[derive(Copy, Clone, PartialEq, Eq)]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Position {
     line: usize,
     offset: usize,
 }
 
+impl Position {
+    pub fn new(line: usize, offset: usize) -> Self {
+        Self { line, offset }
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
 #[derive(Copy, Clone, PartialEq, Eq)]
 pub struct SelectionRange {
     begin: Position,
     end: Position,
 }
 
+impl SelectionRange {
+    pub fn begin(&self) -> Position {
+        self.begin
+    }
+
+    pub fn end(&self) -> Position {
+        self.end
+    }
+
+    /// Returns range where `begin` is always before or at `end`, selection made by
+    /// dragging mouse backwards has them swapped.
+    pub fn normalized(&self) -> Self {
+        if self.begin > self.end {
+            Self { begin: self.end, end: self.begin }
+        } else {
+            *self
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.begin == self.end
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum UnderlineStyle {
+    None,
+    Solid,
+    /// Wavy line, usually used for spelling errors.
+    Squiggle,
+}
+
+/// Decoration of a range of text, range is given in character indices and is adjusted
+/// automatically when text is edited. Decoration is removed when its range becomes empty.
+#[derive(Clone, Debug)]
+pub struct TextDecoration {
+    pub range: Range<usize>,
+    pub underline: UnderlineStyle,
+    pub underline_color: Color,
+    pub background: Option<Color>,
+}
+
+impl TextDecoration {
+    pub fn squiggle(range: Range<usize>, color: Color) -> Self {
+        Self {
+            range,
+            underline: UnderlineStyle::Squiggle,
+            underline_color: color,
+            background: None,
+        }
+    }
+
+    pub fn highlight(range: Range<usize>, color: Color) -> Self {
+        Self {
+            range,
+            underline: UnderlineStyle::None,
+            underline_color: color,
+            background: Some(color),
+        }
+    }
+}
+
+pub type DecorationId = usize;
+
+/// Moves range after text edit at `at` where `removed` characters were removed and then
+/// `inserted` characters were inserted. Text inserted at boundaries of range is not
+/// included in it.
+fn shift_range(range: &Range<usize>, at: usize, removed: usize, inserted: usize) -> Range<usize> {
+    let edit_end = at + removed;
+    let map = |p: usize, inclusive: bool| {
+        if p < at || (inclusive && p == at) {
+            p
+        } else if p >= edit_end {
+            p - removed + inserted
+        } else {
+            at
+        }
+    };
+    map(range.start, false).min(map(range.end, true))..map(range.end, true)
+}
+
 pub struct TextBox {
     widget: Widget,
     caret_line: usize,
@@ -75,9 +174,24 @@ pub struct TextBox {
     formatted_text: RefCell<FormattedText>,
     selection_range: Option<SelectionRange>,
     selecting: bool,
+    caret_width: f32,
+    caret_color: Color,
+    /// When enabled, typed characters replace character under caret instead of
+    /// being inserted. Toggled by Insert key, caret is drawn as a block in this mode.
+    overwrite_mode: bool,
+    /// Offset of text relative to widget bounds, allows to show text that does not fit.
+    scroll: Vec2,
+    /// Last known position of mouse cursor while selecting text.
+    selection_cursor_pos: Vec2,
+    decorations: Vec<(DecorationId, TextDecoration)>,
+    next_decoration_id: DecorationId,
 }
 
 impl TextBox {
+    /// Scroll speed multiplier used when mouse cursor goes beyond widget bounds while
+    /// selecting text, the further cursor goes - the faster text scrolls.
+    pub const AUTO_SCROLL_SPEED: f32 = 8.0;
+
     pub fn new(widget: Widget) -> Self {
         Self {
             widget,
@@ -91,9 +205,123 @@ impl TextBox {
                 .build()),
             selection_range: None,
             selecting: false,
+            caret_width: 2.0,
+            caret_color: Color::WHITE,
+            overwrite_mode: false,
+            scroll: Vec2::ZERO,
+            selection_cursor_pos: Vec2::ZERO,
+            decorations: Vec::new(),
+            next_decoration_id: 0,
         }
     }
 
+    /// Adds decoration of range of text, for example to underline spelling errors found by
+    /// spell-checker. Returned id can be used to remove decoration.
+    pub fn add_decoration(&mut self, decoration: TextDecoration) -> DecorationId {
+        let id = self.next_decoration_id;
+        self.next_decoration_id += 1;
+        self.decorations.push((id, decoration));
+        id
+    }
+
+    pub fn remove_decoration(&mut self, id: DecorationId) -> bool {
+        let count = self.decorations.len();
+        self.decorations.retain(|(decoration_id, _)| *decoration_id != id);
+        count != self.decorations.len()
+    }
+
+    pub fn clear_decorations(&mut self) {
+        self.decorations.clear();
+    }
+
+    /// Returns current decorations with their ranges adjusted to edits.
+    pub fn decorations(&self) -> impl Iterator<Item=(DecorationId, &TextDecoration)> {
+        self.decorations.iter().map(|(id, decoration)| (*id, decoration))
+    }
+
+    fn shift_decorations(&mut self, at: usize, removed: usize, inserted: usize) {
+        for (_, decoration) in self.decorations.iter_mut() {
+            decoration.range = shift_range(&decoration.range, at, removed, inserted);
+        }
+        self.decorations.retain(|(_, decoration)| decoration.range.start < decoration.range.end);
+    }
+
+    /// Returns rectangles (one per line) covered by given range of characters.
+    fn range_rects(&self, range: &Range<usize>, origin: Vec2) -> Vec<Rect<f32>> {
+        let text = self.formatted_text.borrow();
+        text.get_lines()
+            .iter()
+            .filter(|line| range.start < line.end && range.end > line.begin)
+            .map(|line| {
+                let begin = range.start.max(line.begin);
+                let end = range.end.min(line.end);
+                Rect::new(origin.x + line.x_offset + text.get_range_width(line.begin..begin),
+                          origin.y + line.y_offset,
+                          text.get_range_width(begin..end),
+                          line.height)
+            })
+            .collect()
+    }
+
+    fn draw_underline(drawing_context: &mut DrawingContext, rect: &Rect<f32>, style: UnderlineStyle, color: Color) {
+        let y = rect.y + rect.h;
+        match style {
+            UnderlineStyle::None => (),
+            UnderlineStyle::Solid => {
+                drawing_context.push_line(Vec2::new(rect.x, y), Vec2::new(rect.x + rect.w, y), 1.0, color);
+            }
+            UnderlineStyle::Squiggle => {
+                let step = 2.0;
+                let mut x = rect.x;
+                let mut up = true;
+                while x < rect.x + rect.w {
+                    let next = (x + step).min(rect.x + rect.w);
+                    let (a, b) = if up { (y, y - step) } else { (y - step, y) };
+                    drawing_context.push_line(Vec2::new(x, a), Vec2::new(next, b), 1.0, color);
+                    x = next;
+                    up = !up;
+                }
+            }
+        }
+    }
+
+    pub fn set_caret_width(&mut self, width: f32) -> &mut Self {
+        self.caret_width = width;
+        self
+    }
+
+    pub fn caret_width(&self) -> f32 {
+        self.caret_width
+    }
+
+    pub fn set_caret_color(&mut self, color: Color) -> &mut Self {
+        self.caret_color = color;
+        self
+    }
+
+    pub fn caret_color(&self) -> Color {
+        self.caret_color
+    }
+
+    pub fn set_blink_interval(&mut self, interval: f32) -> &mut Self {
+        self.blink_interval = interval;
+        self
+    }
+
+    pub fn blink_interval(&self) -> f32 {
+        self.blink_interval
+    }
+
+    pub fn set_overwrite_mode(&mut self, state: bool) -> &mut Self {
+        self.overwrite_mode = state;
+        self.reset_blink();
+        self
+    }
+
+    pub fn is_overwrite_mode(&self) -> bool {
+        self.overwrite_mode
+    }
+
     pub fn reset_blink(&mut self) {
         self.caret_visible = true;
         self.blink_timer = 0.0;
@@ -143,6 +371,44 @@ impl TextBox {
         }
     }
 
+    /// Moves caret to the beginning of previous or next word, words are separated by
+    /// whitespace.
+    pub fn move_caret_word(&mut self, direction: HorizontalDirection) {
+        let index = match self.get_absolute_position() {
+            Some(index) => index,
+            None => return,
+        };
+
+        let new_index = {
+            let text = self.formatted_text.borrow();
+            let chars = text.get_raw_text();
+            let is_space = |i: usize| std::char::from_u32(chars[i]).map_or(false, |c| c.is_whitespace());
+            let mut i = index;
+            match direction {
+                HorizontalDirection::Left => {
+                    while i > 0 && is_space(i - 1) {
+                        i -= 1;
+                    }
+                    while i > 0 && !is_space(i - 1) {
+                        i -= 1;
+                    }
+                }
+                HorizontalDirection::Right => {
+                    while i < chars.len() && !is_space(i) {
+                        i += 1;
+                    }
+                    while i < chars.len() && is_space(i) {
+                        i += 1;
+                    }
+                }
+            }
+            i
+        };
+
+        self.selection_range = None;
+        self.set_caret_char_index(new_index);
+    }
+
     pub fn move_caret_y(&mut self, offset: usize, direction: VerticalDirection) {
         let text = self.formatted_text.borrow();
         let lines = text.get_lines();
@@ -179,21 +445,315 @@ impl TextBox {
         }
     }
 
-    /// Inserts given character at current caret position.
+    fn position_to_char_index(&self, position: Position) -> Option<usize> {
+        self.formatted_text
+            .borrow()
+            .position_to_char(position.line, position.offset)
+    }
+
+    fn char_index_to_position(&self, index: usize) -> Position {
+        let (line, offset) = self.formatted_text.borrow().char_to_position(index);
+        Position { line, offset }
+    }
+
+    fn set_caret_char_index(&mut self, index: usize) {
+        let position = self.char_index_to_position(index);
+        self.caret_line = position.line;
+        self.caret_offset = position.offset;
+        self.reset_blink();
+    }
+
+    fn on_text_changed(&self) {
+        self.widget
+            .events
+            .borrow_mut()
+            .push_back(UIEvent::new(UIEventKind::TextChanged));
+    }
+
+    /// Removes selected text (if any) and puts caret at the place where selection
+    /// was started. Returns `true` if something was removed.
+    pub fn remove_selection(&mut self) -> bool {
+        let removed = self.remove_selected_text();
+        if removed {
+            self.on_text_changed();
+        }
+        removed
+    }
+
+    fn remove_selected_text(&mut self) -> bool {
+        if let Some(selection_range) = self.selection_range.take() {
+            let range = selection_range.normalized();
+            if let (Some(begin), Some(end)) = (self.position_to_char_index(range.begin), self.position_to_char_index(range.end)) {
+                if begin < end {
+                    self.formatted_text.borrow_mut().remove_range(begin..end);
+                    self.formatted_text.borrow_mut().build();
+                    self.shift_decorations(begin, end - begin, 0);
+                    self.set_caret_char_index(begin);
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Inserts given string at current caret position replacing selected text if any. Can be
+    /// used to paste text from clipboard.
+    pub fn insert_str(&mut self, str: &str) {
+        self.remove_selected_text();
+        let str = str.chars()
+            .filter(|c| !c.is_control() || *c == '\n')
+            .collect::<String>();
+        let position = self.get_absolute_position().unwrap_or(0);
+        self.formatted_text.borrow_mut().insert_str(&str, position);
+        self.formatted_text.borrow_mut().build();
+        self.shift_decorations(position, 0, str.chars().count());
+        self.set_caret_char_index(position + str.chars().count());
+        self.on_text_changed();
+    }
+
+    /// Inserts given character at current caret position. In overwrite mode character
+    /// under caret will be replaced, unless caret is at the end of a line. Selected text
+    /// will be replaced by the character.
     pub fn insert_char(&mut self, c: char) {
         if !c.is_control() {
+            let replaced_selection = self.remove_selected_text();
             let position = self.get_absolute_position().unwrap_or(0);
+            let mut removed = 0;
+            if self.overwrite_mode && !replaced_selection && self.is_caret_over_char() {
+                self.formatted_text.borrow_mut().remove_at(position);
+                removed = 1;
+            }
             self.formatted_text.borrow_mut().insert_char(c, position);
             self.formatted_text.borrow_mut().build();
+            self.shift_decorations(position, removed, 1);
             self.move_caret_x(1, HorizontalDirection::Right);
+            self.on_text_changed();
+        }
+    }
+
+    fn is_caret_over_char(&self) -> bool {
+        self.formatted_text
+            .borrow()
+            .get_lines()
+            .get(self.caret_line)
+            .map_or(false, |line| self.caret_offset < line.len())
+    }
+
+    /// Returns screen-space bounds of caret. In overwrite mode caret covers
+    /// character under it.
+    fn caret_bounds(&self, origin: Vec2) -> Option<Rect<f32>> {
+        let text = self.formatted_text.borrow();
+        let line = text.get_lines().get(self.caret_line)?;
+        let offset = cmp::min(self.caret_offset, line.len());
+        let position = line.begin + offset;
+        let x = origin.x + line.x_offset + text.get_range_width(line.begin..position);
+        let y = origin.y + line.y_offset;
+        let width = if self.overwrite_mode {
+            if offset < line.len() {
+                text.get_range_width(position..(position + 1))
+            } else {
+                line.height * 0.5
+            }
+        } else {
+            self.caret_width
+        };
+        Some(Rect::new(x, y, width, line.height))
+    }
+
+    fn text_origin(&self) -> Vec2 {
+        self.widget.screen_position - self.scroll
+    }
+
+    fn content_size(&self) -> Vec2 {
+        let mut size = Vec2::ZERO;
+        for line in self.formatted_text.borrow().get_lines() {
+            size.x = maxf(size.x, line.x_offset + line.width);
+            size.y = maxf(size.y, line.y_offset + line.height);
+        }
+        size
+    }
+
+    /// Sets new scroll offset of text, it will be clamped so text will not go out
+    /// of widget bounds further than needed.
+    pub fn set_scroll(&mut self, scroll: Vec2) -> &mut Self {
+        let content_size = self.content_size();
+        let size = self.widget.actual_size();
+        self.scroll = Vec2::new(
+            math::clampf(scroll.x, 0.0, maxf(0.0, content_size.x - size.x)),
+            math::clampf(scroll.y, 0.0, maxf(0.0, content_size.y - size.y)),
+        );
+        self
+    }
+
+    pub fn scroll(&self) -> Vec2 {
+        self.scroll
+    }
+
+    /// Returns text position which is closest to given screen position. Unlike
+    /// `screen_pos_to_text_pos` it always returns position if there is any text,
+    /// which makes it suitable for selection by dragging.
+    pub fn nearest_text_pos(&self, screen_pos: Vec2) -> Option<Position> {
+        let local_pos = screen_pos - self.text_origin();
+        let text = self.formatted_text.borrow();
+        let lines = text.get_lines();
+        if lines.is_empty() {
+            return None;
+        }
+        let line_index = lines
+            .iter()
+            .position(|line| local_pos.y < line.y_offset + line.height)
+            .unwrap_or(lines.len() - 1);
+        let line = lines[line_index];
+        let font = text.get_font()?;
+        let font = font.lock().unwrap();
+        let mut x = line.x_offset;
+        for (offset, index) in (line.begin..line.end).enumerate() {
+            let advance = font.get_glyph_advance(text.get_raw_text()[index]) + text.letter_spacing();
+            if local_pos.x < x + advance * 0.5 {
+                return Some(Position { line: line_index, offset });
+            }
+            x += advance;
+        }
+        Some(Position { line: line_index, offset: line.len() })
+    }
+
+    fn extend_selection(&mut self, screen_pos: Vec2) {
+        if let Some(position) = self.nearest_text_pos(screen_pos) {
+            if let Some(ref mut selection_range) = self.selection_range {
+                selection_range.end = position;
+            }
+            self.caret_line = position.line;
+            self.caret_offset = position.offset;
+        }
+    }
+
+    /// Replaces whole text of text box. Selection will be reset and caret will be moved
+    /// to the beginning of the text.
+    pub fn set_text<P: AsRef<str>>(&mut self, text: P) -> &mut Self {
+        self.selection_range = None;
+        // Ranges make no sense for completely new text.
+        self.decorations.clear();
+        self.formatted_text.borrow_mut().set_text(text.as_ref());
+        self.formatted_text.borrow_mut().build();
+        self.caret_line = 0;
+        self.caret_offset = 0;
+        self.scroll = Vec2::ZERO;
+        self.reset_blink();
+        self.on_text_changed();
+        self
+    }
+
+    pub fn text(&self) -> String {
+        self.formatted_text.borrow().text()
+    }
+
+    /// Clamps position to existing lines and offsets, None if there are no lines.
+    fn clamp_position(&self, position: Position) -> Option<Position> {
+        let text = self.formatted_text.borrow();
+        let lines = text.get_lines();
+        if lines.is_empty() {
+            None
+        } else {
+            let line = cmp::min(position.line, lines.len() - 1);
+            Some(Position::new(line, cmp::min(position.offset, lines[line].len())))
         }
     }
 
+    /// Moves caret to given line and offset in it, both values will be clamped to
+    /// actual text bounds.
+    pub fn set_caret_position(&mut self, line: usize, offset: usize) -> &mut Self {
+        let position = self.clamp_position(Position::new(line, offset))
+            .unwrap_or_else(|| Position::new(0, 0));
+        if position != self.caret_position() {
+            self.caret_line = position.line;
+            self.caret_offset = position.offset;
+            self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::CaretMoved(position)));
+        }
+        self.reset_blink();
+        self
+    }
+
+    fn set_selection_range(&mut self, selection_range: Option<SelectionRange>) {
+        if selection_range != self.selection_range {
+            self.selection_range = selection_range;
+            self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::TextSelectionChanged(selection_range)));
+        }
+    }
+
+    pub fn caret_position(&self) -> Position {
+        Position::new(self.caret_line, self.caret_offset)
+    }
+
+    /// Selects text between given positions and moves caret to the end of selection.
+    /// Positions are clamped to text and swapped if begin is after end.
+    pub fn select_range(&mut self, begin: Position, end: Position) -> &mut Self {
+        match (self.clamp_position(begin), self.clamp_position(end)) {
+            (Some(begin), Some(end)) => {
+                let range = SelectionRange { begin, end }.normalized();
+                self.set_selection_range(Some(range));
+                self.set_caret_position(range.end.line, range.end.offset)
+            }
+            _ => {
+                self.set_selection_range(None);
+                self.set_caret_position(0, 0)
+            }
+        }
+    }
+
+    pub fn clear_selection(&mut self) -> &mut Self {
+        self.set_selection_range(None);
+        self
+    }
+
+    pub fn selection_range(&self) -> Option<SelectionRange> {
+        self.selection_range
+    }
+
+    pub fn selected_text(&self) -> String {
+        if let Some(range) = self.selection_range.map(|range| range.normalized()) {
+            if let (Some(begin), Some(end)) = (self.position_to_char_index(range.begin), self.position_to_char_index(range.end)) {
+                return self.formatted_text
+                    .borrow()
+                    .get_raw_text()[begin..end]
+                    .iter()
+                    .filter_map(|c| std::char::from_u32(*c))
+                    .collect();
+            }
+        }
+        String::new()
+    }
+
+    /// Adjusts scroll so caret will be visible.
+    pub fn scroll_to_caret(&mut self) -> &mut Self {
+        if let Some(caret_bounds) = self.caret_bounds(Vec2::ZERO) {
+            let size = self.widget.actual_size();
+            let mut scroll = self.scroll;
+            if caret_bounds.x < scroll.x {
+                scroll.x = caret_bounds.x;
+            } else if caret_bounds.x + caret_bounds.w > scroll.x + size.x {
+                scroll.x = caret_bounds.x + caret_bounds.w - size.x;
+            }
+            if caret_bounds.y < scroll.y {
+                scroll.y = caret_bounds.y;
+            } else if caret_bounds.y + caret_bounds.h > scroll.y + size.y {
+                scroll.y = caret_bounds.y + caret_bounds.h - size.y;
+            }
+            self.set_scroll(scroll);
+        }
+        self
+    }
+
     pub fn get_text_len(&self) -> usize {
         self.formatted_text.borrow_mut().get_raw_text().len()
     }
 
+    /// Removes character before or after caret. If there is selected text, then
+    /// it will be removed instead.
     pub fn remove_char(&mut self, direction: HorizontalDirection) {
+        if self.remove_selection() {
+            return;
+        }
+
         if let Some(position) = self.get_absolute_position() {
             let text_len = self.get_text_len();
             if text_len != 0 {
@@ -213,41 +773,42 @@ impl TextBox {
                 };
                 self.formatted_text.borrow_mut().remove_at(position);
                 self.formatted_text.borrow_mut().build();
+                self.shift_decorations(position, 1, 0);
 
                 if direction == HorizontalDirection::Left {
                     self.move_caret_x(1, direction);
                 }
+
+                self.on_text_changed();
             }
         }
     }
 
     pub fn screen_pos_to_text_pos(&self, screen_pos: Vec2) -> Option<Position> {
-        let mut caret_pos = self.widget.screen_position;
-        if let Some(font) = self.formatted_text.borrow().get_font() {
+        let position = self.text_origin();
+        let text = self.formatted_text.borrow();
+        if let Some(font) = text.get_font() {
             let font = font.lock().unwrap();
-            for (line_index, line) in self.formatted_text.borrow().get_lines().iter().enumerate() {
-                let line_bounds =
-                    Rect::new(caret_pos.x + line.x_offset, caret_pos.y, line.width, font.get_ascender());
+            for (line_index, line) in text.get_lines().iter().enumerate() {
+                let line_bounds = Rect::new(
+                    position.x + line.x_offset,
+                    position.y + line.y_offset,
+                    line.width,
+                    line.height,
+                );
                 if line_bounds.contains(screen_pos.x, screen_pos.y) {
                     let mut x = line_bounds.x;
                     // Check each character in line.
                     for (offset, index) in (line.begin..line.end).enumerate() {
-                        let symbol = self.formatted_text.borrow().get_raw_text()[index];
-                        let (width, height, advance) = if let Some(glyph) = font.get_glyph(symbol) {
-                            (glyph.get_bitmap_width(), glyph.get_bitmap_height(), glyph.get_advance())
-                        } else {
-                            // Stub
-                            let h = font.get_height();
-                            (h, h, h)
-                        };
-                        let char_bounds = Rect::new(x, line_bounds.y, width, height);
+                        let symbol = text.get_raw_text()[index];
+                        let advance = font.get_glyph_advance(symbol) + text.letter_spacing();
+                        let char_bounds = Rect::new(x, line_bounds.y, advance, line_bounds.h);
                         if char_bounds.contains(screen_pos.x, screen_pos.y) {
                             return Some(Position { line: line_index, offset });
                         }
                         x += advance;
                     }
                 }
-                caret_pos.y += line_bounds.h;
             }
         }
         None
@@ -272,9 +833,20 @@ impl Control for TextBox {
             blink_timer: self.blink_timer,
             blink_interval: self.blink_interval,
             formatted_text: RefCell::new(FormattedTextBuilder::new()
-                .with_font(self.formatted_text.borrow().get_font().unwrap()).build()),
+                .with_opt_font(self.formatted_text.borrow().get_font())
+                .with_line_height_scale(self.formatted_text.borrow().line_height_scale())
+                .with_letter_spacing(self.formatted_text.borrow().letter_spacing())
+                .with_paragraph_spacing(self.formatted_text.borrow().paragraph_spacing())
+                .build()),
             selection_range: self.selection_range,
             selecting: self.selecting,
+            caret_width: self.caret_width,
+            caret_color: self.caret_color,
+            overwrite_mode: self.overwrite_mode,
+            scroll: self.scroll,
+            selection_cursor_pos: self.selection_cursor_pos,
+            decorations: self.decorations.clone(),
+            next_decoration_id: self.next_decoration_id,
         })
     }
 
@@ -284,14 +856,25 @@ impl Control for TextBox {
         self.widget.draw(drawing_context);
 
         let bounds = self.widget.get_screen_bounds();
-        drawing_context.push_rect_filled(&bounds, None, Color::opaque(80, 80, 80));
+        drawing_context.push_rect_filled(&bounds, None, palette::current().field);
         drawing_context.commit(CommandKind::Geometry, CommandTexture::None);
 
         self.formatted_text.borrow_mut().set_size(Vec2::new(bounds.w, bounds.h));
         self.formatted_text.borrow_mut().set_color(self.widget.background());
         self.formatted_text.borrow_mut().build();
 
-        if let Some(ref selection_range) = self.selection_range {
+        let origin = self.text_origin();
+
+        // Range is backwards when selected to the left and could be out of text that has
+        // shrunk since selection was made.
+        let selection_range = self.selection_range.and_then(|range| {
+            let range = range.normalized();
+            Some(SelectionRange {
+                begin: self.clamp_position(range.begin)?,
+                end: self.clamp_position(range.end)?,
+            })
+        });
+        if let Some(selection_range) = selection_range {
             let text = self.formatted_text.borrow();
             let lines = text.get_lines();
             if selection_range.begin.line == selection_range.end.line {
@@ -301,11 +884,11 @@ impl Control for TextBox {
                 // Begin line
                 let offset = text.get_range_width(line.begin..(line.begin + begin));
                 let width = text.get_range_width((line.begin + begin)..(line.begin + end));
-                let bounds = Rect::new(bounds.x + line.x_offset + offset,
-                                       bounds.y + line.y_offset,
+                let bounds = Rect::new(origin.x + line.x_offset + offset,
+                                       origin.y + line.y_offset,
                                        width,
                                        line.height);
-                drawing_context.push_rect_filled(&bounds, None, Color::opaque(65, 65, 90));
+                drawing_context.push_rect_filled(&bounds, None, palette::current().text_selection);
             } else {
                 for (i, line) in text.get_lines().iter().enumerate() {
                     if i >= selection_range.begin.line && i <= selection_range.end.line {
@@ -313,69 +896,98 @@ impl Control for TextBox {
                             // Begin line
                             let offset = text.get_range_width(line.begin..(line.begin + selection_range.begin.offset));
                             let width = text.get_range_width((line.begin + selection_range.begin.offset)..line.end);
-                            Rect::new(bounds.x + line.x_offset + offset,
-                                      bounds.y + line.y_offset,
+                            Rect::new(origin.x + line.x_offset + offset,
+                                      origin.y + line.y_offset,
                                       width,
                                       line.height)
                         } else if i == selection_range.end.line {
                             // End line
                             let width = text.get_range_width(line.begin..(line.begin + selection_range.end.offset));
-                            Rect::new(bounds.x + line.x_offset,
-                                      bounds.y + line.y_offset,
+                            Rect::new(origin.x + line.x_offset,
+                                      origin.y + line.y_offset,
                                       width,
                                       line.height)
                         } else {
                             // Everything between
-                            Rect::new(bounds.x + line.x_offset,
-                                      bounds.y + line.y_offset,
+                            Rect::new(origin.x + line.x_offset,
+                                      origin.y + line.y_offset,
                                       line.width,
                                       line.height)
                         };
-                        drawing_context.push_rect_filled(&bounds, None, Color::opaque(90, 90, 120));
+                        drawing_context.push_rect_filled(&bounds, None, palette::current().text_selection_multiline);
                     }
                 }
             }
         }
+        for (_, decoration) in self.decorations.iter() {
+            if let Some(background) = decoration.background {
+                for rect in self.range_rects(&decoration.range, origin) {
+                    drawing_context.push_rect_filled(&rect, None, background);
+                }
+            }
+        }
         drawing_context.commit(CommandKind::Geometry, CommandTexture::None);
 
-        let screen_position = Vec2::new(bounds.x, bounds.y);
-        drawing_context.draw_text(screen_position, &self.formatted_text.borrow());
+        // Block caret must be drawn under text, otherwise it will hide the character.
+        if self.caret_visible && self.overwrite_mode {
+            if let Some(caret_bounds) = self.caret_bounds(origin) {
+                drawing_context.push_rect_filled(&caret_bounds, None, self.caret_color);
+                drawing_context.commit(CommandKind::Geometry, CommandTexture::None);
+            }
+        }
 
-        if self.caret_visible {
-            let text = self.formatted_text.borrow();
-            if let Some(font) = text.get_font() {
-                let font = font.lock().unwrap();
-                if let Some(line) = text.get_lines().get(self.caret_line) {
-                    let text = text.get_raw_text();
-                    let mut caret_pos = Vec2::new(
-                        screen_position.x,
-                        screen_position.y + self.caret_line as f32 * font.get_ascender(),
-                    );
-                    for (offset, char_index) in (line.begin..line.end).enumerate() {
-                        if offset >= self.caret_offset {
-                            break;
-                        }
-                        if let Some(glyph) = font.get_glyph(text[char_index]) {
-                            caret_pos.x += glyph.get_advance();
-                        } else {
-                            caret_pos.x += font.get_height();
-                        }
-                    }
+        drawing_context.draw_text(origin, &self.formatted_text.borrow());
 
-                    let caret_bounds = Rect::new(caret_pos.x, caret_pos.y, 2.0, font.get_height());
-                    drawing_context.push_rect_filled(&caret_bounds, None, Color::WHITE);
-                    drawing_context.commit(CommandKind::Geometry, CommandTexture::None);
-                }
+        for (_, decoration) in self.decorations.iter() {
+            for rect in self.range_rects(&decoration.range, origin) {
+                Self::draw_underline(drawing_context, &rect, decoration.underline, decoration.underline_color);
+            }
+        }
+        drawing_context.commit(CommandKind::Geometry, CommandTexture::None);
+
+        if self.caret_visible && !self.overwrite_mode {
+            if let Some(caret_bounds) = self.caret_bounds(origin) {
+                drawing_context.push_rect_filled(&caret_bounds, None, self.caret_color);
+                drawing_context.commit(CommandKind::Geometry, CommandTexture::None);
             }
         }
     }
 
+    fn needs_update(&self) -> bool {
+        true
+    }
+
     fn update(&mut self, dt: f32) {
         self.widget.update(dt);
-        self.blink_timer += dt;
-        if self.blink_timer >= self.blink_interval {
-            self.blink_timer = 0.0;
-            self.caret_visible = !self.caret_visible;
+        if self.widget.reduced_motion() {
+            self.caret_visible = true;
+        } else {
+            self.blink_timer += dt;
+            if self.blink_timer >= self.blink_interval {
+                self.blink_timer = 0.0;
+                self.caret_visible = !self.caret_visible;
+            }
+        }
+
+        if self.selecting {
+            // Scroll text when mouse cursor is outside of widget bounds.
+            let bounds = self.widget.get_screen_bounds();
+            let pos = self.selection_cursor_pos;
+            let mut velocity = Vec2::ZERO;
+            if pos.x < bounds.x {
+                velocity.x = pos.x - bounds.x;
+            } else if pos.x > bounds.x + bounds.w {
+                velocity.x = pos.x - (bounds.x + bounds.w);
+            }
+            if pos.y < bounds.y {
+                velocity.y = pos.y - bounds.y;
+            } else if pos.y > bounds.y + bounds.h {
+                velocity.y = pos.y - (bounds.y + bounds.h);
+            }
+            if velocity.x != 0.0 || velocity.y != 0.0 {
+                self.set_scroll(self.scroll + velocity.scale(Self::AUTO_SCROLL_SPEED * dt));
+                self.extend_selection(pos);
+            }
         }
     }
 
@@ -384,36 +996,48 @@ impl Control for TextBox {
             match evt.kind {
                 UIEventKind::Text { symbol } => {
                     self.insert_char(symbol);
+                    self.scroll_to_caret();
                 }
                 UIEventKind::KeyDown { code } => {
-                    match code {
-                        KeyCode::Up => {
+                    match ui.key_action(code) {
+                        Some(WidgetAction::CaretUp) => {
                             self.move_caret_y(1, VerticalDirection::Up);
                         }
-                        KeyCode::Down => {
+                        Some(WidgetAction::CaretDown) => {
                             self.move_caret_y(1, VerticalDirection::Down);
                         }
-                        KeyCode::Right => {
+                        Some(WidgetAction::CaretRight) => {
                             self.move_caret_x(1, HorizontalDirection::Right);
                         }
-                        KeyCode::Left => {
+                        Some(WidgetAction::CaretLeft) => {
                             self.move_caret_x(1, HorizontalDirection::Left);
                         }
-                        KeyCode::Delete => {
+                        Some(WidgetAction::CaretWordRight) => {
+                            self.move_caret_word(HorizontalDirection::Right);
+                        }
+                        Some(WidgetAction::CaretWordLeft) => {
+                            self.move_caret_word(HorizontalDirection::Left);
+                        }
+                        Some(WidgetAction::DeleteForward) => {
                             self.remove_char(HorizontalDirection::Right);
                         }
-                        KeyCode::Backspace => {
+                        Some(WidgetAction::DeleteBackward) => {
                             self.remove_char(HorizontalDirection::Left);
                         }
+                        Some(WidgetAction::ToggleOverwrite) => {
+                            self.set_overwrite_mode(!self.overwrite_mode);
+                        }
                         _ => ()
                     }
+                    self.scroll_to_caret();
                 }
                 UIEventKind::MouseDown { pos, button } => {
                     if button == MouseButton::Left {
                         self.selection_range = None;
                         self.selecting = true;
+                        self.selection_cursor_pos = pos;
 
-                        if let Some(position) = self.screen_pos_to_text_pos(pos) {
+                        if let Some(position) = self.nearest_text_pos(pos) {
                             self.caret_line = position.line;
                             self.caret_offset = position.offset;
 
@@ -428,11 +1052,23 @@ impl Control for TextBox {
                 }
                 UIEventKind::MouseMove { pos } => {
                     if self.selecting {
-                        if let Some(position) = self.screen_pos_to_text_pos(pos) {
-                            if let Some(ref mut sel_range) = self.selection_range {
-                                sel_range.end = position;
-                            }
+                        self.selection_cursor_pos = pos;
+                        self.extend_selection(pos);
+                    }
+                }
+                UIEventKind::MouseWheel { amount, .. } => {
+                    // Scroll only if text does not fit, otherwise let parent handle the event.
+                    if self.content_size().y > self.widget.actual_size().y {
+                        let line_height = self.formatted_text
+                            .borrow()
+                            .get_lines()
+                            .first()
+                            .map_or(0.0, |line| line.height);
+                        self.set_scroll(Vec2::new(self.scroll.x, self.scroll.y - amount * line_height));
+                        if self.selecting {
+                            self.extend_selection(self.selection_cursor_pos);
                         }
+                        evt.handled = true;
                     }
                 }
                 UIEventKind::MouseUp { .. } => {
@@ -450,6 +1086,12 @@ pub struct TextBoxBuilder {
     widget_builder: WidgetBuilder,
     font: Option<Arc<Mutex<Font>>>,
     text: String,
+    line_height_scale: f32,
+    letter_spacing: f32,
+    paragraph_spacing: f32,
+    caret_width: f32,
+    caret_color: Color,
+    blink_interval: f32,
 }
 
 impl TextBoxBuilder {
@@ -458,6 +1100,12 @@ impl TextBoxBuilder {
             widget_builder,
             font: None,
             text: "".to_owned(),
+            line_height_scale: 1.0,
+            letter_spacing: 0.0,
+            paragraph_spacing: 0.0,
+            caret_width: 2.0,
+            caret_color: Color::WHITE,
+            blink_interval: 0.5,
         }
     }
 
@@ -470,25 +1118,131 @@ impl TextBoxBuilder {
         self.text = text;
         self
     }
+
+    pub fn with_line_height_scale(mut self, line_height_scale: f32) -> Self {
+        self.line_height_scale = line_height_scale;
+        self
+    }
+
+    pub fn with_letter_spacing(mut self, letter_spacing: f32) -> Self {
+        self.letter_spacing = letter_spacing;
+        self
+    }
+
+    pub fn with_paragraph_spacing(mut self, paragraph_spacing: f32) -> Self {
+        self.paragraph_spacing = paragraph_spacing;
+        self
+    }
+
+    pub fn with_caret_width(mut self, width: f32) -> Self {
+        self.caret_width = width;
+        self
+    }
+
+    pub fn with_caret_color(mut self, color: Color) -> Self {
+        self.caret_color = color;
+        self
+    }
+
+    pub fn with_blink_interval(mut self, interval: f32) -> Self {
+        self.blink_interval = interval;
+        self
+    }
 }
 
 impl Builder for TextBoxBuilder {
     fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
         let text_box = TextBox {
-            widget: self.widget_builder.build(),
+            widget: self.widget_builder.with_focusable(true).build(),
             caret_line: 0,
             caret_offset: 0,
             caret_visible: true,
             blink_timer: 0.0,
-            blink_interval: 0.5,
+            blink_interval: self.blink_interval,
             formatted_text: RefCell::new(FormattedTextBuilder::new()
                 .with_text(self.text)
-                .with_font(self.font.unwrap_or(crate::DEFAULT_FONT.clone()))
+                .with_opt_font(self.font.or_else(|| ui.default_font()))
+                .with_line_height_scale(self.line_height_scale)
+                .with_letter_spacing(self.letter_spacing)
+                .with_paragraph_spacing(self.paragraph_spacing)
                 .build()),
             selection_range: None,
             selecting: false,
+            caret_width: self.caret_width,
+            caret_color: self.caret_color,
+            overwrite_mode: false,
+            scroll: Vec2::ZERO,
+            selection_cursor_pos: Vec2::ZERO,
+            decorations: Vec::new(),
+            next_decoration_id: 0,
         };
 
         ui.add_node(Box::new(text_box))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        core::math::vec2::Vec2,
+        text_box::{
+            TextBox,
+            TextBoxBuilder,
+            Position,
+        },
+        widget::WidgetBuilder,
+        event::UIEventKind,
+        UserInterface,
+        UINodeContainer,
+        Builder,
+    };
+
+    #[test]
+    fn caret_after_trailing_new_line_test() {
+        let mut ui = UserInterface::headless();
+        let handle = TextBoxBuilder::new(WidgetBuilder::new()
+            .with_width(200.0)
+            .with_height(100.0))
+            .with_font(crate::DEFAULT_FONT.clone())
+            .build(&mut ui);
+        ui.update(Vec2::new(800.0, 600.0), 0.0);
+
+        let text_box = ui.node_mut(handle).downcast_mut::<TextBox>().unwrap();
+        text_box.set_text("ab\n");
+        text_box.set_caret_position(1, 0);
+        assert!(text_box.caret_position() == Position::new(1, 0));
+
+        // Positions past the end are clamped to empty last line.
+        text_box.select_range(Position::new(5, 5), Position::new(0, 1));
+        assert!(text_box.caret_position() == Position::new(1, 0));
+    }
+
+    #[test]
+    fn programmatic_changes_events_test() {
+        let mut ui = UserInterface::headless();
+        let handle = TextBoxBuilder::new(WidgetBuilder::new())
+            .with_font(crate::DEFAULT_FONT.clone())
+            .with_text("abc".to_owned())
+            .build(&mut ui);
+        let text_box = ui.node_mut(handle).downcast_mut::<TextBox>().unwrap();
+        text_box.set_text("abc");
+        text_box.set_caret_position(0, 2);
+        // Same position again is not a change.
+        text_box.set_caret_position(0, 2);
+        text_box.select_range(Position::new(0, 1), Position::new(0, 3));
+        text_box.clear_selection();
+        text_box.clear_selection();
+
+        let mut caret_moves = 0;
+        let mut selection_changes = Vec::new();
+        while let Some(event) = ui.poll_ui_event() {
+            match event.kind {
+                UIEventKind::CaretMoved(_) => caret_moves += 1,
+                UIEventKind::TextSelectionChanged(range) => selection_changes.push(range.is_some()),
+                _ => (),
+            }
+        }
+        assert_eq!(caret_moves, 2);
+        assert_eq!(selection_changes, vec![true, false]);
+    }
+}
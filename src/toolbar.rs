@@ -0,0 +1,418 @@
+use crate::{
+    core::{
+        color::Color,
+        pool::Handle,
+        math::{
+            vec2::Vec2,
+            Rect,
+        },
+    },
+    widget::{
+        Widget,
+        WidgetBuilder,
+    },
+    button::{
+        Button,
+        ButtonBuilder,
+    },
+    border::BorderBuilder,
+    image::ImageBuilder,
+    stack_panel::StackPanelBuilder,
+    scroll_bar::Orientation,
+    event::{
+        UIEvent,
+        UIEventKind,
+    },
+    draw::Texture,
+    UserInterface,
+    UINode,
+    Thickness,
+    Visibility,
+    VerticalAlignment,
+    Control,
+    ControlTemplate,
+    UINodeContainer,
+    Builder,
+    maxf,
+};
+use std::{
+    cell::Cell,
+    collections::HashMap,
+    sync::Arc,
+};
+
+/// Lays out tool buttons and separators horizontally. Items that do not fit into toolbar
+/// are hidden and become available in overflow dropdown which is opened by a button at
+/// the right edge of toolbar.
+///
+/// Toggle buttons can be joined into groups by `Toolbar::TOGGLE_GROUP` attached property,
+/// only one button in a group can be toggled at a time.
+pub struct Toolbar {
+    widget: Widget,
+    items: Vec<Handle<UINode>>,
+    overflow_button: Handle<UINode>,
+    /// Root-level node which hosts overflowed items while dropdown is open.
+    overflow_popup: Handle<UINode>,
+    overflow_panel: Handle<UINode>,
+    /// Index of first item that did not fit into toolbar on last arrange pass.
+    overflow_index: Cell<usize>,
+    overflow_open: bool,
+}
+
+impl Toolbar {
+    /// Name of attached property of type `String` which defines toggle group of an item.
+    pub const TOGGLE_GROUP: &'static str = "ToggleGroup";
+
+    pub fn items(&self) -> &[Handle<UINode>] {
+        &self.items
+    }
+
+    pub fn is_overflow_open(&self) -> bool {
+        self.overflow_open
+    }
+
+    fn open_overflow(&mut self, ui: &mut UserInterface) {
+        if self.overflow_open {
+            return;
+        }
+        self.overflow_open = true;
+
+        // Toolbar node is out of pool while it handles an event, so items are detached
+        // from it manually instead of `unlink_node`.
+        for item in self.items[self.overflow_index.get()..].iter() {
+            self.widget.children.retain(|child| child != item);
+            ui.node_mut(*item).widget_mut().parent = Handle::NONE;
+            ui.link_nodes(*item, self.overflow_panel);
+            ui.node(*item).widget().culled.set(false);
+        }
+
+        let button = ui.node(self.overflow_button).widget();
+        let position = Vec2::new(
+            button.screen_position.x,
+            self.widget.screen_position.y + self.widget.actual_size().y,
+        );
+
+        // Popup must be drawn on top of everything else.
+        let root = ui.root();
+        ui.node_mut(root).widget_mut().move_child(self.overflow_popup, std::usize::MAX);
+        ui.node_mut(self.overflow_popup)
+            .widget_mut()
+            .set_desired_local_position(position)
+            .set_visibility(Visibility::Visible);
+    }
+
+    /// Moves overflowed items back, same as in `open_overflow` they are attached manually.
+    fn close_overflow(&mut self, self_handle: Handle<UINode>, ui: &mut UserInterface) {
+        if !self.overflow_open {
+            return;
+        }
+        self.overflow_open = false;
+
+        for item in self.items[self.overflow_index.get()..].iter() {
+            ui.unlink_node(*item);
+            ui.node_mut(*item).widget_mut().parent = self_handle;
+            self.widget.children.push(*item);
+        }
+
+        ui.node_mut(self.overflow_popup)
+            .widget_mut()
+            .set_visibility(Visibility::Collapsed);
+    }
+
+    fn is_item_event(&self, ui: &UserInterface, source: Handle<UINode>) -> Option<Handle<UINode>> {
+        self.items
+            .iter()
+            .find(|item| **item == source || ui.node(**item).widget().has_descendant(source, ui))
+            .cloned()
+    }
+
+    /// Untoggles every button that is in the same toggle group as given item.
+    fn update_toggle_group(&self, ui: &mut UserInterface, toggled_item: Handle<UINode>) {
+        let group = match ui.node(toggled_item).widget().attached_property::<String>(Self::TOGGLE_GROUP) {
+            Some(group) => group.clone(),
+            None => return,
+        };
+        for item in self.items.iter().filter(|item| **item != toggled_item) {
+            let node = ui.node_mut(*item);
+            if node.widget().attached_property::<String>(Self::TOGGLE_GROUP) == Some(&group) {
+                if let Some(button) = node.downcast_mut::<Button>() {
+                    button.set_toggled(false);
+                }
+            }
+        }
+    }
+}
+
+impl Control for Toolbar {
+    fn widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    fn widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    fn raw_copy(&self) -> Box<dyn Control> {
+        Box::new(Self {
+            widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
+            items: self.items.clone(),
+            overflow_button: self.overflow_button,
+            overflow_popup: self.overflow_popup,
+            overflow_panel: self.overflow_panel,
+            overflow_index: Cell::new(self.items.len()),
+            overflow_open: false,
+        })
+    }
+
+    fn resolve(&mut self, _: &ControlTemplate, node_map: &HashMap<Handle<UINode>, Handle<UINode>>) {
+        for item in self.items.iter_mut() {
+            *item = *node_map.get(item).unwrap();
+        }
+        self.overflow_button = *node_map.get(&self.overflow_button).unwrap();
+        self.overflow_popup = *node_map.get(&self.overflow_popup).unwrap();
+        self.overflow_panel = *node_map.get(&self.overflow_panel).unwrap();
+    }
+
+    fn measure_override(&self, ui: &UserInterface, _available_size: Vec2) -> Vec2 {
+        let size_for_child = Vec2::new(std::f32::INFINITY, std::f32::INFINITY);
+        let mut desired_size = Vec2::ZERO;
+
+        for child_handle in self.widget.children.iter() {
+            ui.node(*child_handle).measure(ui, size_for_child);
+            if *child_handle != self.overflow_button {
+                let child_size = ui.node(*child_handle).widget().desired_size.get();
+                desired_size.x += child_size.x;
+                desired_size.y = maxf(desired_size.y, child_size.y);
+            }
+        }
+
+        desired_size
+    }
+
+    fn arrange_override(&self, ui: &UserInterface, final_size: Vec2) -> Vec2 {
+        let overflow_button = ui.node(self.overflow_button);
+        let overflow_button_width = overflow_button.widget().desired_size.get().x;
+
+        // Overflowed items are in the dropdown while it is open, so split point must stay the same.
+        if !self.overflow_open {
+            let total_width = self.items
+                .iter()
+                .fold(0.0, |acc, item| acc + ui.node(*item).widget().desired_size.get().x);
+            let limit = if total_width > final_size.x {
+                final_size.x - overflow_button_width
+            } else {
+                final_size.x
+            };
+
+            let mut x = 0.0;
+            let mut overflow_index = self.items.len();
+            for (i, item) in self.items.iter().enumerate() {
+                x += ui.node(*item).widget().desired_size.get().x;
+                if x > limit {
+                    overflow_index = i;
+                    break;
+                }
+            }
+            self.overflow_index.set(overflow_index);
+
+            for (i, item) in self.items.iter().enumerate() {
+                ui.node(*item).widget().culled.set(i >= overflow_index);
+            }
+        }
+
+        let mut x = 0.0;
+        for item in self.items[..self.overflow_index.get()].iter() {
+            let width = ui.node(*item).widget().desired_size.get().x;
+            ui.node(*item).arrange(ui, &Rect::new(x, 0.0, width, final_size.y));
+            x += width;
+        }
+
+        let has_overflow = self.overflow_index.get() < self.items.len();
+        overflow_button.widget().culled.set(!has_overflow);
+        overflow_button.arrange(ui, &Rect::new(
+            final_size.x - overflow_button_width, 0.0, overflow_button_width, final_size.y));
+
+        final_size
+    }
+
+    fn handle_event(&mut self, self_handle: Handle<UINode>, ui: &mut UserInterface, evt: &mut UIEvent) {
+        match evt.kind {
+            UIEventKind::Click => {
+                if evt.source == self.overflow_button {
+                    if self.overflow_open {
+                        self.close_overflow(self_handle, ui);
+                    } else {
+                        self.open_overflow(ui);
+                    }
+                } else if self.overflow_open && self.is_item_event(ui, evt.source).is_some() {
+                    self.close_overflow(self_handle, ui);
+                }
+            }
+            UIEventKind::Checked(Some(true)) => {
+                if let Some(item) = self.is_item_event(ui, evt.source) {
+                    self.update_toggle_group(ui, item);
+                }
+            }
+            _ => ()
+        }
+    }
+
+    fn on_removed(&mut self, _self_handle: Handle<UINode>, ui: &mut UserInterface) {
+        ui.remove_node(self.overflow_popup);
+    }
+}
+
+pub struct ToolbarBuilder {
+    widget_builder: WidgetBuilder,
+    items: Vec<Handle<UINode>>,
+}
+
+impl ToolbarBuilder {
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            items: Vec::new(),
+        }
+    }
+
+    pub fn with_item(mut self, item: Handle<UINode>) -> Self {
+        self.items.push(item);
+        self
+    }
+
+    pub fn with_items(mut self, items: &[Handle<UINode>]) -> Self {
+        self.items.extend_from_slice(items);
+        self
+    }
+
+    /// Adds thin vertical line between items.
+    pub fn with_separator(mut self, ui: &mut dyn UINodeContainer) -> Self {
+        let separator = BorderBuilder::new(WidgetBuilder::new()
+            .with_width(1.0)
+            .with_margin(Thickness { left: 3.0, top: 2.0, right: 3.0, bottom: 2.0 })
+            .with_background(Color::opaque(80, 80, 80)))
+            .with_stroke_thickness(Thickness::zero())
+            .build(ui);
+        self.items.push(separator);
+        self
+    }
+}
+
+impl Builder for ToolbarBuilder {
+    fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
+        let overflow_button = ButtonBuilder::new(WidgetBuilder::new()
+            .with_width(16.0))
+            .with_text(">>")
+            .build(ui);
+
+        let overflow_panel = StackPanelBuilder::new(WidgetBuilder::new())
+            .with_orientation(Orientation::Vertical)
+            .build(ui);
+
+        let overflow_popup = BorderBuilder::new(WidgetBuilder::new()
+            .with_visibility(Visibility::Collapsed)
+            .with_background(Color::opaque(60, 60, 60))
+            .with_foreground(Color::opaque(100, 100, 100))
+            .with_child(overflow_panel))
+            .with_stroke_thickness(Thickness::uniform(1.0))
+            .build(ui);
+
+        let toolbar = Toolbar {
+            widget: self.widget_builder
+                .with_children(&self.items)
+                .with_child(overflow_button)
+                .build(),
+            overflow_index: Cell::new(self.items.len()),
+            items: self.items,
+            overflow_button,
+            overflow_popup,
+            overflow_panel,
+            overflow_open: false,
+        };
+
+        ui.add_node(Box::new(toolbar))
+    }
+}
+
+/// Creates buttons with toolbar look: fixed square size with icon or short text,
+/// optionally in toggle mode and in a toggle group.
+pub struct ToolButtonBuilder {
+    widget_builder: WidgetBuilder,
+    icon: Option<Arc<Texture>>,
+    text: Option<String>,
+    toggle_mode: bool,
+    toggled: bool,
+    toggle_group: Option<String>,
+}
+
+impl ToolButtonBuilder {
+    /// Default size of tool button.
+    pub const SIZE: f32 = 24.0;
+
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            icon: None,
+            text: None,
+            toggle_mode: false,
+            toggled: false,
+            toggle_group: None,
+        }
+    }
+
+    pub fn with_icon(mut self, icon: Arc<Texture>) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    pub fn with_text(mut self, text: &str) -> Self {
+        self.text = Some(text.to_owned());
+        self
+    }
+
+    pub fn with_toggle_mode(mut self, toggle_mode: bool) -> Self {
+        self.toggle_mode = toggle_mode;
+        self
+    }
+
+    pub fn with_toggled(mut self, toggled: bool) -> Self {
+        self.toggled = toggled;
+        self
+    }
+
+    /// Puts button into toggle group, this also enables toggle mode.
+    pub fn with_toggle_group(mut self, group: &str) -> Self {
+        self.toggle_group = Some(group.to_owned());
+        self.toggle_mode = true;
+        self
+    }
+}
+
+impl Builder for ToolButtonBuilder {
+    fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
+        let mut widget_builder = self.widget_builder
+            .with_min_size(Vec2::new(ToolButtonBuilder::SIZE, ToolButtonBuilder::SIZE))
+            .with_vertical_alignment(VerticalAlignment::Center);
+        if let Some(group) = self.toggle_group {
+            widget_builder = widget_builder.with_attached_property(Toolbar::TOGGLE_GROUP, group);
+        }
+
+        let mut builder = ButtonBuilder::new(widget_builder)
+            .with_toggle_mode(self.toggle_mode)
+            .with_toggled(self.toggled);
+        builder = match (self.icon, self.text) {
+            (Some(icon), Some(text)) => builder.with_icon_and_text(icon, &text),
+            (Some(icon), None) => {
+                let image = ImageBuilder::new(WidgetBuilder::new()
+                    .with_width(Button::ICON_SIZE)
+                    .with_height(Button::ICON_SIZE))
+                    .with_texture(icon)
+                    .build(ui);
+                builder.with_content(image)
+            }
+            (None, Some(text)) => builder.with_text(&text),
+            (None, None) => builder,
+        };
+        builder.build(ui)
+    }
+}
@@ -0,0 +1,515 @@
+use crate::{
+    core::pool::Handle,
+    widget::{
+        Widget,
+        WidgetBuilder,
+    },
+    text::{
+        Text,
+        TextBuilder,
+    },
+    check_box::{
+        CheckBox,
+        CheckBoxBuilder,
+    },
+    scroll_viewer::ScrollViewerBuilder,
+    stack_panel::StackPanelBuilder,
+    grid::{
+        GridBuilder,
+        Column,
+        Row,
+    },
+    event::{
+        UIEvent,
+        UIEventKind,
+    },
+    UserInterface,
+    UINode,
+    Thickness,
+    VerticalAlignment,
+    HorizontalAlignment,
+    bool_to_visibility,
+    Control,
+    ControlTemplate,
+    UINodeContainer,
+    Builder,
+};
+use std::collections::HashMap;
+
+/// Whether children of tree item exist as nodes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ChildrenState {
+    /// Children (if any) are created.
+    Created,
+    /// Item declares that it has children, but they are not created yet.
+    NotLoaded,
+    /// `Expanding` was sent, item waits until application adds children.
+    Loading,
+}
+
+/// Node of tree view: header with expander, optional check box and content, and panel
+/// with child items below it.
+///
+/// Check box of checkable item reflects state of its checkable children: checked or
+/// unchecked if all children are in the same state, undefined otherwise. Checking item
+/// by user checks all its children.
+///
+/// Item with lazy children shows expander without having children. When it is expanded
+/// first time, it shows loading placeholder and asks application to create children, which
+/// are added by `TreeItem::add_item`. `TreeItem::finish_loading` should be called if
+/// there are no children to add.
+///
+/// # Events
+///
+/// [`Checked`] - sent by item when state of its check box has changed.
+/// [`Expanding`] - sent by item with lazy children when it is expanded first time.
+pub struct TreeItem {
+    widget: Widget,
+    content: Handle<UINode>,
+    expander: Handle<UINode>,
+    check_box: Handle<UINode>,
+    panel: Handle<UINode>,
+    items: Vec<Handle<UINode>>,
+    is_expanded: bool,
+    children_state: ChildrenState,
+    /// Placeholder shown while lazy children are loading.
+    placeholder: Handle<UINode>,
+}
+
+impl TreeItem {
+    /// Indentation of child items relative to parent.
+    pub const INDENT: f32 = 16.0;
+
+    pub fn content(&self) -> Handle<UINode> {
+        self.content
+    }
+
+    pub fn items(&self) -> &[Handle<UINode>] {
+        &self.items
+    }
+
+    pub fn is_expanded(&self) -> bool {
+        self.is_expanded
+    }
+
+    pub fn children_state(&self) -> ChildrenState {
+        self.children_state
+    }
+
+    pub fn is_checkable(&self) -> bool {
+        self.check_box.is_some()
+    }
+
+    /// Returns state of check box of item, None for items which are not checkable.
+    pub fn checked(&self, ui: &UserInterface) -> Option<Option<bool>> {
+        if self.check_box.is_none() {
+            return None;
+        }
+        ui.node(self.check_box).downcast_ref::<CheckBox>().map(|check_box| check_box.checked())
+    }
+
+    /// Sets state of check box of item, all children are set to the same state if
+    /// value is not undefined.
+    pub fn set_checked(ui: &mut UserInterface, item: Handle<UINode>, value: Option<bool>) {
+        let check_box = match ui.node(item).downcast_ref::<TreeItem>() {
+            Some(item) if item.check_box.is_some() => item.check_box,
+            _ => return,
+        };
+        if let Some(check_box) = ui.node_mut(check_box).downcast_mut::<CheckBox>() {
+            check_box.set_checked(value);
+        }
+    }
+
+    pub fn set_expanded(ui: &mut UserInterface, item: Handle<UINode>, expanded: bool) {
+        let (panel, expander) = match ui.node_mut(item).downcast_mut::<TreeItem>() {
+            Some(item) => {
+                item.is_expanded = expanded;
+                item.request_children();
+                (item.panel, item.expander)
+            }
+            None => return,
+        };
+        apply_expanded(ui, panel, expander, expanded);
+    }
+
+    /// Adds child item to the end of children list. Loading placeholder is removed.
+    pub fn add_item(ui: &mut UserInterface, item: Handle<UINode>, child: Handle<UINode>) {
+        let (panel, expander, placeholder) = match ui.node_mut(item).downcast_mut::<TreeItem>() {
+            Some(item) => {
+                item.items.push(child);
+                item.children_state = ChildrenState::Created;
+                (item.panel, item.expander, std::mem::replace(&mut item.placeholder, Handle::NONE))
+            }
+            None => return,
+        };
+        if placeholder.is_some() {
+            ui.remove_node(placeholder);
+        }
+        ui.link_nodes(child, panel);
+        ui.node_mut(expander).widget_mut().set_visibility(bool_to_visibility(true));
+    }
+
+    /// Marks lazy children as loaded and removes loading placeholder. Expander is hidden
+    /// if no children were added.
+    pub fn finish_loading(ui: &mut UserInterface, item: Handle<UINode>) {
+        let (expander, placeholder, has_items) = match ui.node_mut(item).downcast_mut::<TreeItem>() {
+            Some(item) => {
+                item.children_state = ChildrenState::Created;
+                (item.expander, std::mem::replace(&mut item.placeholder, Handle::NONE), !item.items.is_empty())
+            }
+            None => return,
+        };
+        if placeholder.is_some() {
+            ui.remove_node(placeholder);
+        }
+        ui.node_mut(expander).widget_mut().set_visibility(bool_to_visibility(has_items));
+    }
+
+    /// Sends `Expanding` if item is expanded and its lazy children were not requested yet.
+    fn request_children(&mut self) {
+        if self.is_expanded && self.children_state == ChildrenState::NotLoaded {
+            self.children_state = ChildrenState::Loading;
+            self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::Expanding));
+        }
+    }
+
+    /// Returns state which check box should have according to states of checkable
+    /// children, or None if item has no such children.
+    fn aggregate_children_state(&self, ui: &UserInterface) -> Option<Option<bool>> {
+        let mut result = None;
+        for state in self.items
+            .iter()
+            .filter_map(|child| ui.node(*child).downcast_ref::<TreeItem>())
+            .filter_map(|child| child.checked(ui)) {
+            result = match result {
+                None => Some(state),
+                Some(current) if current == state => Some(current),
+                Some(_) => return Some(None),
+            }
+        }
+        result
+    }
+
+    fn on_check_box_changed(&mut self, ui: &mut UserInterface, value: Option<bool>) {
+        // Defined state goes down to children. If change came from children, they
+        // already have this state and nothing happens.
+        if value.is_some() {
+            for child in self.items.iter() {
+                Self::set_checked(ui, *child, value);
+            }
+        }
+        self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::Checked(value)));
+    }
+}
+
+fn expander_symbol(expanded: bool) -> &'static str {
+    if expanded { "-" } else { "+" }
+}
+
+fn apply_expanded(ui: &mut UserInterface, panel: Handle<UINode>, expander: Handle<UINode>, expanded: bool) {
+    ui.node_mut(panel).widget_mut().set_visibility(bool_to_visibility(expanded));
+    if let Some(expander) = ui.node_mut(expander).downcast_mut::<Text>() {
+        expander.set_text(expander_symbol(expanded));
+    }
+}
+
+impl Control for TreeItem {
+    fn widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    fn widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    fn raw_copy(&self) -> Box<dyn Control> {
+        Box::new(Self {
+            widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
+            content: self.content,
+            expander: self.expander,
+            check_box: self.check_box,
+            panel: self.panel,
+            items: self.items.clone(),
+            is_expanded: self.is_expanded,
+            children_state: self.children_state,
+            placeholder: self.placeholder,
+        })
+    }
+
+    fn resolve(&mut self, _: &ControlTemplate, node_map: &HashMap<Handle<UINode>, Handle<UINode>>) {
+        if self.content.is_some() {
+            self.content = *node_map.get(&self.content).unwrap();
+        }
+        if self.check_box.is_some() {
+            self.check_box = *node_map.get(&self.check_box).unwrap();
+        }
+        self.expander = *node_map.get(&self.expander).unwrap();
+        self.panel = *node_map.get(&self.panel).unwrap();
+        for item in self.items.iter_mut() {
+            *item = *node_map.get(item).unwrap();
+        }
+        if self.placeholder.is_some() {
+            self.placeholder = *node_map.get(&self.placeholder).unwrap();
+        }
+    }
+
+    fn handle_event(&mut self, _self_handle: Handle<UINode>, ui: &mut UserInterface, evt: &mut UIEvent) {
+        match evt.kind {
+            UIEventKind::MouseDown { .. } if evt.source == self.expander => {
+                self.is_expanded = !self.is_expanded;
+                self.request_children();
+                apply_expanded(ui, self.panel, self.expander, self.is_expanded);
+                evt.handled = true;
+            }
+            UIEventKind::Checked(value) if evt.source == self.check_box && self.check_box.is_some() => {
+                self.on_check_box_changed(ui, value);
+            }
+            UIEventKind::Checked(_) if self.check_box.is_some() && self.items.contains(&evt.source) => {
+                if let Some(state) = self.aggregate_children_state(ui) {
+                    if let Some(check_box) = ui.node_mut(self.check_box).downcast_mut::<CheckBox>() {
+                        check_box.set_checked(state);
+                    }
+                }
+            }
+            _ => ()
+        }
+    }
+}
+
+pub struct TreeItemBuilder {
+    widget_builder: WidgetBuilder,
+    content: Handle<UINode>,
+    items: Vec<Handle<UINode>>,
+    is_expanded: bool,
+    checkable: bool,
+    checked: Option<bool>,
+    lazy_children: bool,
+}
+
+impl TreeItemBuilder {
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            content: Handle::NONE,
+            items: Vec::new(),
+            is_expanded: true,
+            checkable: false,
+            checked: Some(false),
+            lazy_children: false,
+        }
+    }
+
+    pub fn with_content(mut self, content: Handle<UINode>) -> Self {
+        self.content = content;
+        self
+    }
+
+    pub fn with_items(mut self, items: Vec<Handle<UINode>>) -> Self {
+        self.items = items;
+        self
+    }
+
+    pub fn with_expanded(mut self, expanded: bool) -> Self {
+        self.is_expanded = expanded;
+        self
+    }
+
+    /// Adds check box in front of content.
+    pub fn with_checkable(mut self, checkable: bool) -> Self {
+        self.checkable = checkable;
+        self
+    }
+
+    /// Sets initial state of check box, state of checkable children is not changed.
+    pub fn with_checked(mut self, checked: Option<bool>) -> Self {
+        self.checked = checked;
+        self
+    }
+
+    /// Declares that item has children which will be created by application when item
+    /// is expanded first time. Item with lazy children is initially collapsed. Has no
+    /// effect if children were given by `with_items`.
+    pub fn with_lazy_children(mut self, lazy_children: bool) -> Self {
+        self.lazy_children = lazy_children;
+        self
+    }
+}
+
+impl Builder for TreeItemBuilder {
+    fn build(mut self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
+        let placeholder = if self.lazy_children && self.items.is_empty() {
+            self.is_expanded = false;
+            let placeholder = TextBuilder::new(WidgetBuilder::new())
+                .with_text("Loading...")
+                .build(ui);
+            self.items = vec![placeholder];
+            placeholder
+        } else {
+            Handle::NONE
+        };
+
+        let expander = TextBuilder::new(WidgetBuilder::new()
+            .with_width(TreeItem::INDENT)
+            .with_visibility(bool_to_visibility(!self.items.is_empty())))
+            .with_text(expander_symbol(self.is_expanded))
+            .with_horizontal_text_alignment(HorizontalAlignment::Center)
+            .with_vertical_text_alignment(VerticalAlignment::Center)
+            .build(ui);
+
+        let check_box = if self.checkable {
+            // Undefined state is reachable only through children, click on it checks item.
+            CheckBoxBuilder::new(WidgetBuilder::new()
+                .on_column(1)
+                .with_width(CheckBox::BOX_SIZE)
+                .with_height(CheckBox::BOX_SIZE)
+                .with_margin(Thickness::right(4.0))
+                .with_vertical_alignment(VerticalAlignment::Center))
+                .checked(self.checked)
+                .with_cycle(vec![Some(true), Some(false)])
+                .build(ui)
+        } else {
+            Handle::NONE
+        };
+
+        if self.content.is_some() {
+            ui.node_mut(self.content).widget_mut().set_column(2);
+        }
+
+        let mut header_builder = WidgetBuilder::new()
+            .with_child(expander);
+        if check_box.is_some() {
+            header_builder = header_builder.with_child(check_box);
+        }
+        if self.content.is_some() {
+            header_builder = header_builder.with_child(self.content);
+        }
+        let header = GridBuilder::new(header_builder)
+            .add_column(Column::auto())
+            .add_column(Column::auto())
+            .add_column(Column::stretch())
+            .add_row(Row::stretch())
+            .build(ui);
+
+        let mut panel_builder = WidgetBuilder::new()
+            .with_margin(Thickness::left(TreeItem::INDENT))
+            .with_visibility(bool_to_visibility(self.is_expanded));
+        for item in self.items.iter() {
+            panel_builder = panel_builder.with_child(*item);
+        }
+        let panel = StackPanelBuilder::new(panel_builder)
+            .build(ui);
+
+        let tree_item = TreeItem {
+            widget: self.widget_builder
+                .with_child(StackPanelBuilder::new(WidgetBuilder::new()
+                    .with_child(header)
+                    .with_child(panel))
+                    .build(ui))
+                .build(),
+            content: self.content,
+            expander,
+            check_box,
+            panel,
+            // Placeholder is child of panel, but it is not an item.
+            items: if placeholder.is_some() { Vec::new() } else { self.items },
+            is_expanded: self.is_expanded,
+            children_state: if placeholder.is_some() { ChildrenState::NotLoaded } else { ChildrenState::Created },
+            placeholder,
+        };
+
+        ui.add_node(Box::new(tree_item))
+    }
+}
+
+/// Hierarchical list of tree items in scroll viewer.
+pub struct TreeView {
+    widget: Widget,
+    panel: Handle<UINode>,
+    items: Vec<Handle<UINode>>,
+}
+
+impl TreeView {
+    /// Returns root items.
+    pub fn items(&self) -> &[Handle<UINode>] {
+        &self.items
+    }
+
+    /// Adds root item to the end of tree.
+    pub fn add_item(ui: &mut UserInterface, tree_view: Handle<UINode>, item: Handle<UINode>) {
+        let panel = match ui.node_mut(tree_view).downcast_mut::<TreeView>() {
+            Some(tree_view) => {
+                tree_view.items.push(item);
+                tree_view.panel
+            }
+            None => return,
+        };
+        ui.link_nodes(item, panel);
+    }
+}
+
+impl Control for TreeView {
+    fn widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    fn widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    fn raw_copy(&self) -> Box<dyn Control> {
+        Box::new(Self {
+            widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
+            panel: self.panel,
+            items: self.items.clone(),
+        })
+    }
+
+    fn resolve(&mut self, _: &ControlTemplate, node_map: &HashMap<Handle<UINode>, Handle<UINode>>) {
+        self.panel = *node_map.get(&self.panel).unwrap();
+        for item in self.items.iter_mut() {
+            *item = *node_map.get(item).unwrap();
+        }
+    }
+}
+
+pub struct TreeViewBuilder {
+    widget_builder: WidgetBuilder,
+    items: Vec<Handle<UINode>>,
+}
+
+impl TreeViewBuilder {
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            items: Vec::new(),
+        }
+    }
+
+    pub fn with_items(mut self, items: Vec<Handle<UINode>>) -> Self {
+        self.items = items;
+        self
+    }
+}
+
+impl Builder for TreeViewBuilder {
+    fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
+        let mut panel_builder = WidgetBuilder::new();
+        for item in self.items.iter() {
+            panel_builder = panel_builder.with_child(*item);
+        }
+        let panel = StackPanelBuilder::new(panel_builder)
+            .build(ui);
+
+        let tree_view = TreeView {
+            widget: self.widget_builder
+                .with_child(ScrollViewerBuilder::new(WidgetBuilder::new())
+                    .with_content(panel)
+                    .build(ui))
+                .build(),
+            panel,
+            items: self.items,
+        };
+
+        ui.add_node(Box::new(tree_view))
+    }
+}
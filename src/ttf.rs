@@ -12,6 +12,8 @@ use crate::{
         },
         pool::{Pool, Handle},
     },
+    resource::ResourceRequest,
+    worker::WorkerPool,
 };
 use std::{
     cmp::Ordering,
@@ -19,7 +21,11 @@ use std::{
     path::Path,
     fs::File,
     io::Read,
-    ops::Range
+    ops::Range,
+    sync::{
+        Arc,
+        Mutex,
+    },
 };
 use crate::draw::Texture;
 
@@ -902,6 +908,21 @@ impl Font {
         Ok(font)
     }
 
+    /// Rasterizes font on thread of text worker pool, so loading of large char sets does
+    /// not stall UI thread. Request fails if data is not a valid font.
+    pub fn from_memory_async(data: Vec<u8>, height: f32, char_set: &[Range<u32>]) -> ResourceRequest<Arc<Mutex<Font>>> {
+        let request = ResourceRequest::new();
+        let result = request.clone();
+        let char_set = char_set.to_vec();
+        WorkerPool::text().execute(move || {
+            match Font::from_memory(data, height, &char_set) {
+                Ok(font) => request.complete(Arc::new(Mutex::new(font))),
+                Err(_) => request.fail("invalid font data"),
+            }
+        });
+        result
+    }
+
     pub fn from_file(path: &Path, height: f32, char_set: &[Range<u32>]) -> Result<Self, ()> {
         if let Ok(ref mut file) = File::open(path) {
             let mut file_content: Vec<u8> = Vec::with_capacity(file.metadata().unwrap().len() as usize);
@@ -0,0 +1,375 @@
+use crate::{
+    core::{
+        color::Color,
+        pool::Handle,
+        math::{
+            vec2::Vec2,
+            Rect,
+        },
+    },
+    widget::{
+        Widget,
+        WidgetBuilder,
+    },
+    draw::{
+        DrawingContext,
+        CommandKind,
+        CommandTexture,
+    },
+    event::{
+        UIEvent,
+        UIEventKind,
+        MouseButton,
+    },
+    color_ext::{
+        ColorExt,
+        palette,
+    },
+    UserInterface,
+    UINode,
+    Control,
+    ControlTemplate,
+    UINodeContainer,
+    Builder,
+};
+use std::collections::HashMap;
+
+/// Splits samples into blocks of given size and returns minimum and maximum of each block,
+/// this is usual precomputed representation of audio clip for drawing.
+pub fn compute_peaks(samples: &[f32], block_size: usize) -> Vec<(f32, f32)> {
+    samples
+        .chunks(block_size.max(1))
+        .map(|block| block.iter().fold((std::f32::MAX, std::f32::MIN), |(min, max), s| (min.min(*s), max.max(*s))))
+        .collect()
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum DragMode {
+    Playhead,
+    /// Selection is being made, contains time where drag started.
+    Selection(f32),
+    /// View is scrolled, contains last mouse position.
+    Scroll(Vec2),
+}
+
+/// Shows peaks of audio clip, a playhead and a selected range. Peaks are pairs of minimum
+/// and maximum of equal blocks of samples in [-1; 1] (see `compute_peaks`), they cover
+/// whole clip of given duration. All times are in seconds.
+///
+/// Mouse wheel zooms around cursor, middle mouse button scrolls view. Left mouse button
+/// drags playhead when pressed near it, otherwise it selects range; click without drag
+/// clears selection and moves playhead.
+///
+/// # Events
+///
+/// [`PlayheadMoved`] - sent when user moved playhead.
+/// [`RangeSelected`] - sent when user finished selection or cleared it.
+pub struct Waveform {
+    widget: Widget,
+    peaks: Vec<(f32, f32)>,
+    duration: f32,
+    /// Time at left edge of widget.
+    view_start: f32,
+    /// Amount of seconds visible in widget.
+    view_length: f32,
+    playhead: f32,
+    selection: Option<(f32, f32)>,
+    drag: Option<DragMode>,
+    wave_color: Color,
+    playhead_color: Color,
+    selection_color: Color,
+}
+
+impl Waveform {
+    /// Distance in pixels at which playhead can be grabbed.
+    const PLAYHEAD_GRAB_DISTANCE: f32 = 4.0;
+    const ZOOM_STEP: f32 = 0.2;
+    /// Shortest visible fragment in seconds.
+    const MIN_VIEW_LENGTH: f32 = 0.001;
+
+    pub fn set_peaks(&mut self, peaks: Vec<(f32, f32)>, duration: f32) -> &mut Self {
+        self.peaks = peaks;
+        self.duration = duration.max(0.0);
+        self.playhead = self.playhead.min(self.duration);
+        self.selection = None;
+        self.set_view(0.0, self.duration);
+        self
+    }
+
+    pub fn peaks(&self) -> &[(f32, f32)] {
+        &self.peaks
+    }
+
+    pub fn duration(&self) -> f32 {
+        self.duration
+    }
+
+    /// Sets visible fragment of clip, it is clamped to clip.
+    pub fn set_view(&mut self, start: f32, length: f32) -> &mut Self {
+        self.view_length = length.max(Self::MIN_VIEW_LENGTH).min(self.duration.max(Self::MIN_VIEW_LENGTH));
+        self.view_start = start.max(0.0).min((self.duration - self.view_length).max(0.0));
+        self
+    }
+
+    /// Returns start and length of visible fragment.
+    pub fn view(&self) -> (f32, f32) {
+        (self.view_start, self.view_length)
+    }
+
+    /// Sets playhead without sending event, for example to follow playback.
+    pub fn set_playhead(&mut self, time: f32) -> &mut Self {
+        self.playhead = time.max(0.0).min(self.duration);
+        self
+    }
+
+    pub fn playhead(&self) -> f32 {
+        self.playhead
+    }
+
+    pub fn set_selection(&mut self, selection: Option<(f32, f32)>) -> &mut Self {
+        self.selection = selection.map(|(a, b)| (a.min(b).max(0.0), a.max(b).min(self.duration)));
+        self
+    }
+
+    pub fn selection(&self) -> Option<(f32, f32)> {
+        self.selection
+    }
+
+    fn time_to_x(&self, bounds: &Rect<f32>, time: f32) -> f32 {
+        bounds.x + (time - self.view_start) / self.view_length * bounds.w
+    }
+
+    fn x_to_time(&self, bounds: &Rect<f32>, x: f32) -> f32 {
+        let time = self.view_start + (x - bounds.x) / bounds.w.max(1.0) * self.view_length;
+        time.max(0.0).min(self.duration)
+    }
+
+    /// Returns minimum and maximum of peaks which cover given time span.
+    fn peak_of(&self, from: f32, to: f32) -> Option<(f32, f32)> {
+        if self.peaks.is_empty() || self.duration <= 0.0 {
+            return None;
+        }
+        let count = self.peaks.len();
+        let first = ((from / self.duration * count as f32) as usize).min(count - 1);
+        let last = ((to / self.duration * count as f32).ceil() as usize).max(first + 1).min(count);
+        Some(self.peaks[first..last]
+            .iter()
+            .fold((std::f32::MAX, std::f32::MIN), |(min, max), (a, b)| (min.min(*a), max.max(*b))))
+    }
+
+    fn move_playhead(&mut self, time: f32) {
+        self.playhead = time;
+        self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::PlayheadMoved(time)));
+    }
+
+    fn select(&mut self, selection: Option<(f32, f32)>) {
+        self.set_selection(selection);
+        self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::RangeSelected(self.selection)));
+    }
+}
+
+impl Control for Waveform {
+    fn widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    fn widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    fn raw_copy(&self) -> Box<dyn Control> {
+        Box::new(Self {
+            widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
+            peaks: self.peaks.clone(),
+            duration: self.duration,
+            view_start: self.view_start,
+            view_length: self.view_length,
+            playhead: self.playhead,
+            selection: self.selection,
+            drag: None,
+            wave_color: self.wave_color,
+            playhead_color: self.playhead_color,
+            selection_color: self.selection_color,
+        })
+    }
+
+    fn resolve(&mut self, _: &ControlTemplate, _: &HashMap<Handle<UINode>, Handle<UINode>>) {}
+
+    fn draw(&self, drawing_context: &mut DrawingContext) {
+        let bounds = self.widget.get_screen_bounds();
+        drawing_context.push_rect_filled(&bounds, None, self.widget.background());
+
+        if let Some((begin, end)) = self.selection {
+            let left = self.time_to_x(&bounds, begin).max(bounds.x);
+            let right = self.time_to_x(&bounds, end).min(bounds.x + bounds.w);
+            if right > left {
+                drawing_context.push_rect_filled(&Rect::new(left, bounds.y, right - left, bounds.h), None, self.selection_color);
+            }
+        }
+
+        let center = bounds.y + bounds.h * 0.5;
+        let half_height = bounds.h * 0.5;
+        drawing_context.push_line(Vec2::new(bounds.x, center), Vec2::new(bounds.x + bounds.w, center), 1.0, self.wave_color.with_alpha(80));
+
+        // One column of peaks per pixel.
+        let columns = bounds.w.max(0.0) as usize;
+        let column_length = self.view_length / bounds.w.max(1.0);
+        for column in 0..columns {
+            let from = self.view_start + column as f32 * column_length;
+            if from >= self.duration {
+                break;
+            }
+            if let Some((min, max)) = self.peak_of(from, from + column_length) {
+                let top = center - max.max(-1.0).min(1.0) * half_height;
+                let bottom = center - min.max(-1.0).min(1.0) * half_height;
+                drawing_context.push_rect_filled(&Rect::new(bounds.x + column as f32, top, 1.0, (bottom - top).max(1.0)), None, self.wave_color);
+            }
+        }
+
+        let x = self.time_to_x(&bounds, self.playhead);
+        if x >= bounds.x && x <= bounds.x + bounds.w {
+            drawing_context.push_line(Vec2::new(x, bounds.y), Vec2::new(x, bounds.y + bounds.h), 2.0, self.playhead_color);
+        }
+        drawing_context.commit(CommandKind::Geometry, CommandTexture::None);
+    }
+
+    fn handle_event(&mut self, self_handle: Handle<UINode>, ui: &mut UserInterface, evt: &mut UIEvent) {
+        if evt.source != self_handle {
+            return;
+        }
+
+        let bounds = self.widget.get_screen_bounds();
+        match evt.kind {
+            UIEventKind::MouseWheel { pos, amount } => {
+                // Time under cursor stays in place.
+                let anchor = self.x_to_time(&bounds, pos.x);
+                let length = self.view_length * (1.0 - Self::ZOOM_STEP).powf(amount);
+                let k = (pos.x - bounds.x) / bounds.w.max(1.0);
+                self.set_view(anchor - k * length, length);
+                evt.handled = true;
+            }
+            UIEventKind::MouseDown { pos, button } => {
+                let drag = match button {
+                    MouseButton::Left => {
+                        if (self.time_to_x(&bounds, self.playhead) - pos.x).abs() <= Self::PLAYHEAD_GRAB_DISTANCE {
+                            DragMode::Playhead
+                        } else {
+                            DragMode::Selection(self.x_to_time(&bounds, pos.x))
+                        }
+                    }
+                    MouseButton::Middle => DragMode::Scroll(pos),
+                    _ => return,
+                };
+                if ui.capture_mouse(self_handle) {
+                    self.drag = Some(drag);
+                    evt.handled = true;
+                }
+            }
+            UIEventKind::MouseMove { pos } => {
+                let time = self.x_to_time(&bounds, pos.x);
+                match self.drag {
+                    Some(DragMode::Playhead) => self.move_playhead(time),
+                    Some(DragMode::Selection(start)) => {
+                        self.set_selection(Some((start, time)));
+                    }
+                    Some(DragMode::Scroll(last)) => {
+                        let offset = (pos.x - last.x) / bounds.w.max(1.0) * self.view_length;
+                        self.set_view(self.view_start - offset, self.view_length);
+                        self.drag = Some(DragMode::Scroll(pos));
+                    }
+                    None => (),
+                }
+            }
+            UIEventKind::MouseUp { pos, .. } => {
+                if let Some(drag) = self.drag.take() {
+                    ui.release_mouse_capture();
+                    if let DragMode::Selection(start) = drag {
+                        let end = self.x_to_time(&bounds, pos.x);
+                        // Click without noticeable drag is a click on timeline.
+                        if (self.time_to_x(&bounds, end) - self.time_to_x(&bounds, start)).abs() < 2.0 {
+                            self.select(None);
+                            self.move_playhead(end);
+                        } else {
+                            self.select(Some((start, end)));
+                        }
+                    }
+                }
+            }
+            UIEventKind::CaptureLost => {
+                // Keep range selected so far.
+                if let Some(DragMode::Selection(_)) = self.drag.take() {
+                    self.select(self.selection);
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+pub struct WaveformBuilder {
+    widget_builder: WidgetBuilder,
+    peaks: Vec<(f32, f32)>,
+    duration: f32,
+    wave_color: Option<Color>,
+    playhead_color: Option<Color>,
+    selection_color: Option<Color>,
+}
+
+impl WaveformBuilder {
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            peaks: Vec::new(),
+            duration: 0.0,
+            wave_color: None,
+            playhead_color: None,
+            selection_color: None,
+        }
+    }
+
+    /// Sets peaks of clip of given duration in seconds.
+    pub fn with_peaks(mut self, peaks: Vec<(f32, f32)>, duration: f32) -> Self {
+        self.peaks = peaks;
+        self.duration = duration;
+        self
+    }
+
+    pub fn with_wave_color(mut self, color: Color) -> Self {
+        self.wave_color = Some(color);
+        self
+    }
+
+    pub fn with_playhead_color(mut self, color: Color) -> Self {
+        self.playhead_color = Some(color);
+        self
+    }
+
+    pub fn with_selection_color(mut self, color: Color) -> Self {
+        self.selection_color = Some(color);
+        self
+    }
+}
+
+impl Builder for WaveformBuilder {
+    fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
+        let p = palette::current();
+        let mut waveform = Waveform {
+            widget: self.widget_builder
+                .with_background(p.field)
+                .build(),
+            peaks: Vec::new(),
+            duration: 0.0,
+            view_start: 0.0,
+            view_length: 0.0,
+            playhead: 0.0,
+            selection: None,
+            drag: None,
+            wave_color: self.wave_color.unwrap_or(p.accent),
+            playhead_color: self.playhead_color.unwrap_or(p.text),
+            selection_color: self.selection_color.unwrap_or_else(|| p.accent.with_alpha(60)),
+        };
+        waveform.set_peaks(self.peaks, self.duration);
+
+        ui.add_node(Box::new(waveform))
+    }
+}
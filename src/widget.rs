@@ -11,10 +11,18 @@ use crate::{
     HorizontalAlignment,
     Thickness,
     Visibility,
+    LayoutTransform,
+    RenderTransform,
+    HitTestShape,
+    HitTestVisibility,
     UserInterface,
     UINode,
     event::UIEvent,
     style::Style,
+    draw::{
+        TextureBrush,
+        AnimatedSpriteBrush,
+    },
     Control,
     ControlTemplate,
 };
@@ -29,8 +37,32 @@ use std::{
     },
     any::Any,
     rc::Rc,
+    sync::Mutex,
 };
 
+/// Makes storable copy of value of attached property, None if value has other type.
+pub type AttachedPropertyCloner = fn(&dyn Any) -> Option<Rc<dyn Any>>;
+
+pub(in crate) fn clone_attached_property<T: Any + Clone>(value: &dyn Any) -> Option<Rc<dyn Any>> {
+    value.downcast_ref::<T>().map(|value| Rc::new(value.clone()) as Rc<dyn Any>)
+}
+
+lazy_static! {
+    /// Types of attached properties by name, `set_property` uses them to store values of
+    /// properties defined by panels.
+    static ref ATTACHED_PROPERTY_TYPES: Mutex<HashMap<String, AttachedPropertyCloner>> = {
+        Mutex::new(crate::builtin_attached_properties()
+            .into_iter()
+            .map(|(name, cloner)| (name.to_owned(), cloner))
+            .collect())
+    };
+}
+
+/// Copies value to target if value has the same type.
+fn assign<T: Any + Clone>(target: &mut T, value: &dyn Any) -> bool {
+    value.downcast_ref::<T>().map(|value| *target = value.clone()).is_some()
+}
+
 pub struct Widget {
     pub(in crate) name: String,
     /// Desired position relative to parent node
@@ -52,11 +84,13 @@ pub struct Widget {
     /// Maximum width and height
     pub(in crate) max_size: Vec2,
     background: Color,
+    background_texture: Option<TextureBrush>,
+    pub(in crate) background_animation: Option<AnimatedSpriteBrush>,
     foreground: Color,
-    /// Index of row to which this node belongs
-    row: usize,
-    /// Index of column to which this node belongs
-    column: usize,
+    /// Properties that are defined by parent panels, for example row and column of grid.
+    /// Every panel reads its own properties by name, so new panels do not require new
+    /// fields in widget.
+    attached_properties: HashMap<String, Rc<dyn Any>>,
     /// Vertical alignment
     pub(in crate) vertical_alignment: VerticalAlignment,
     /// Horizontal alignment
@@ -66,6 +100,24 @@ pub struct Widget {
     /// Current visibility state
     pub(in crate) visibility: Visibility,
     pub(in crate) global_visibility: bool,
+    /// Set by virtualizing panels for children that are outside of viewport, such
+    /// children (and their descendants) are neither drawn nor hit-tested.
+    pub(in crate) culled: Cell<bool>,
+    /// Transform applied before layout, parent reserves space for transformed bounds.
+    pub(in crate) layout_transform: LayoutTransform,
+    /// Transform applied after layout to widget and its descendants.
+    pub(in crate) render_transform: RenderTransform,
+    /// If set, root-level widget will be laid out inside safe area of user interface
+    /// instead of whole screen.
+    pub(in crate) use_safe_area: bool,
+    /// Whether widget can receive keyboard focus by Tab or directional navigation.
+    pub(in crate) is_focusable: bool,
+    /// Whether `ActualBoundsChanged` event is sent when screen bounds have changed.
+    pub(in crate) bounds_notifications: bool,
+    /// Copy of reduced motion setting of user interface, see `UserInterface::set_reduced_motion`.
+    pub(in crate) reduced_motion: bool,
+    /// Focus scope limits Tab and directional navigation to its descendants.
+    pub(in crate) is_focus_scope: bool,
     pub(in crate) children: Vec<Handle<UINode>>,
     pub(in crate) parent: Handle<UINode>,
     /// Indices of commands in command buffer emitted by the node.
@@ -74,8 +126,14 @@ pub struct Widget {
     pub(in crate) measure_valid: Cell<bool>,
     pub(in crate) arrange_valid: Cell<bool>,
     pub(in crate) events: RefCell<VecDeque<UIEvent>>,
-    pub(in crate) is_hit_test_visible: bool,
+    pub(in crate) hit_test_visibility: HitTestVisibility,
+    pub(in crate) hit_test_shape: HitTestShape,
     pub(in crate) style: Option<Rc<Style>>,
+    /// Names of states in which widget is, styles use them to choose state setters.
+    pub(in crate) states: Vec<String>,
+    /// Arbitrary application data associated with widget, for example id of an entity
+    /// which is shown by the widget.
+    user_data: Option<Rc<dyn Any>>,
 }
 
 impl Default for Widget {
@@ -106,14 +164,23 @@ impl Control for Widget {
             min_size: self.min_size,
             max_size: self.max_size,
             background: self.background,
+            background_texture: self.background_texture.clone(),
+            background_animation: self.background_animation.clone(),
             foreground: self.foreground,
-            row: self.row,
-            column: self.column,
+            attached_properties: self.attached_properties.clone(),
             vertical_alignment: self.vertical_alignment,
             horizontal_alignment: self.horizontal_alignment,
             margin: self.margin,
             visibility: self.visibility,
             global_visibility: self.global_visibility,
+            culled: Cell::new(false),
+            layout_transform: self.layout_transform,
+            render_transform: self.render_transform,
+            use_safe_area: self.use_safe_area,
+            is_focusable: self.is_focusable,
+            bounds_notifications: self.bounds_notifications,
+            reduced_motion: self.reduced_motion,
+            is_focus_scope: self.is_focus_scope,
             children: self.children.clone(),
             parent: self.parent,
             command_indices: Default::default(),
@@ -121,58 +188,63 @@ impl Control for Widget {
             measure_valid: Cell::new(false),
             arrange_valid: Cell::new(false),
             events: Default::default(),
-            is_hit_test_visible: self.is_hit_test_visible,
+            hit_test_visibility: self.hit_test_visibility,
+            hit_test_shape: self.hit_test_shape.clone(),
             style: self.style.clone(),
+            states: self.states.clone(),
+            user_data: self.user_data.clone(),
         })
     }
 
     fn resolve(&mut self, _: &ControlTemplate, _: &HashMap<Handle<UINode>, Handle<UINode>>) {}
 
     fn set_property(&mut self, name: &str, value: &dyn Any) {
-        match name {
-            Self::HORIZONTAL_ALIGNMENT => if let Some(value) = value.downcast_ref() {
-                self.horizontal_alignment = *value
-            },
-            Self::VERTICAL_ALIGNMENT => if let Some(value) = value.downcast_ref() {
-                self.vertical_alignment = *value
-            },
-            Self::WIDTH =>
-                if let Some(value) = value.downcast_ref() {
-                    self.width.set(*value)
-                } else if let Some(value) = value.downcast_ref::<f64>() {
-                    self.width.set(*value as f32)
-                },
-            Self::HEIGHT =>
-                if let Some(value) = value.downcast_ref() {
-                    self.height.set(*value)
-                } else if let Some(value) = value.downcast_ref::<f64>() {
-                    self.height.set(*value as f32)
-                },
-            Self::MARGIN => if let Some(value) = value.downcast_ref() {
-                self.margin = *value
-            },
-            Self::ROW => if let Some(value) = value.downcast_ref() {
-                self.row = *value
+        let assigned = match name {
+            Self::HORIZONTAL_ALIGNMENT => assign(&mut self.horizontal_alignment, value),
+            Self::VERTICAL_ALIGNMENT => assign(&mut self.vertical_alignment, value),
+            Self::WIDTH => match (value.downcast_ref::<f32>(), value.downcast_ref::<f64>()) {
+                (Some(width), _) => { self.width.set(*width); true }
+                (_, Some(width)) => { self.width.set(*width as f32); true }
+                _ => false,
             },
-            Self::COLUMN => if let Some(value) = value.downcast_ref() {
-                self.column = *value
+            Self::HEIGHT => match (value.downcast_ref::<f32>(), value.downcast_ref::<f64>()) {
+                (Some(height), _) => { self.height.set(*height); true }
+                (_, Some(height)) => { self.height.set(*height as f32); true }
+                _ => false,
             },
-            Self::BACKGROUND => if let Some(value) = value.downcast_ref() {
-                self.background = *value
-            },
-            Self::FOREGROUND => if let Some(value) = value.downcast_ref() {
-                self.foreground = *value;
+            Self::MARGIN => assign(&mut self.margin, value),
+            Self::ROW => value.downcast_ref::<usize>().map(|row| { self.set_row(*row); }).is_some(),
+            Self::COLUMN => value.downcast_ref::<usize>().map(|column| { self.set_column(*column); }).is_some(),
+            Self::ROW_SPAN => value.downcast_ref::<usize>().map(|span| { self.set_row_span(*span); }).is_some(),
+            Self::COLUMN_SPAN => value.downcast_ref::<usize>().map(|span| { self.set_column_span(*span); }).is_some(),
+            Self::BACKGROUND => assign(&mut self.background, value),
+            Self::FOREGROUND => assign(&mut self.foreground, value),
+            Self::BACKGROUND_TEXTURE => assign(&mut self.background_texture, value),
+            Self::VISIBILITY => assign(&mut self.visibility, value),
+            Self::MIN_SIZE => assign(&mut self.min_size, value),
+            Self::MAX_SIZE => assign(&mut self.max_size, value),
+            Self::RENDER_TRANSFORM => assign(&mut self.render_transform, value),
+            Self::LAYOUT_TRANSFORM => assign(&mut self.layout_transform, value),
+            // Any other name is attached property of some panel.
+            _ => {
+                let cloner = ATTACHED_PROPERTY_TYPES.lock().unwrap().get(name).cloned();
+                match cloner {
+                    Some(cloner) => match cloner(value) {
+                        Some(value) => {
+                            self.attached_properties.insert(name.to_owned(), value);
+                            true
+                        }
+                        None => false,
+                    },
+                    None => {
+                        log::warn!("Unknown property {} of widget {}", name, self.name);
+                        return;
+                    }
+                }
             }
-            Self::VISIBILITY => if let Some(value) = value.downcast_ref() {
-                self.visibility = *value
-            },
-            Self::MIN_SIZE => if let Some(value) = value.downcast_ref() {
-                self.min_size = *value
-            },
-            Self::MAX_SIZE => if let Some(value) = value.downcast_ref() {
-                self.max_size = *value
-            },
-            _ => ()
+        };
+        if !assigned {
+            log::warn!("Value of property {} of widget {} has wrong type", name, self.name);
         }
     }
 
@@ -183,14 +255,15 @@ impl Control for Widget {
             Self::WIDTH => Some(&self.width),
             Self::HEIGHT => Some(&self.height),
             Self::MARGIN => Some(&self.margin),
-            Self::ROW => Some(&self.row),
-            Self::COLUMN => Some(&self.column),
             Self::VISIBILITY => Some(&self.visibility),
             Self::BACKGROUND => Some(&self.background),
             Self::FOREGROUND => Some(&self.foreground),
+            Self::BACKGROUND_TEXTURE => Some(&self.background_texture),
             Self::MIN_SIZE => Some(&self.min_size),
             Self::MAX_SIZE => Some(&self.max_size),
-            _ => None,
+            Self::LAYOUT_TRANSFORM => Some(&self.layout_transform),
+            Self::RENDER_TRANSFORM => Some(&self.render_transform),
+            _ => self.attached_properties.get(name).map(|v| &**v),
         }
     }
 }
@@ -203,11 +276,19 @@ impl Widget {
     pub const MARGIN: &'static str = "Margin";
     pub const ROW: &'static str = "Row";
     pub const COLUMN: &'static str = "Column";
+    pub const ROW_SPAN: &'static str = "RowSpan";
+    pub const COLUMN_SPAN: &'static str = "ColumnSpan";
     pub const BACKGROUND: &'static str = "Background";
     pub const FOREGROUND: &'static str = "Foreground";
+    pub const BACKGROUND_TEXTURE: &'static str = "BackgroundTexture";
     pub const VISIBILITY: &'static str = "Visibility";
     pub const MIN_SIZE: &'static str = "MinSize";
     pub const MAX_SIZE: &'static str = "MaxSize";
+    pub const LAYOUT_TRANSFORM: &'static str = "LayoutTransform";
+    pub const RENDER_TRANSFORM: &'static str = "RenderTransform";
+
+    /// Standard state which item containers set on their content when item is selected.
+    pub const SELECTED_STATE: &'static str = "Selected";
 
     #[inline]
     pub fn set_name<P: AsRef<str>>(&mut self, name: P) -> &mut Self {
@@ -258,6 +339,40 @@ impl Widget {
         self.background
     }
 
+    /// Sets texture that is drawn as background, background color is used as tint.
+    #[inline]
+    pub fn set_background_texture(&mut self, brush: Option<TextureBrush>) -> &mut Self {
+        self.background_texture = brush;
+        self
+    }
+
+    #[inline]
+    pub fn background_texture(&self) -> Option<&TextureBrush> {
+        self.background_texture.as_ref()
+    }
+
+    /// Sets sprite animation which frames are used as background texture, animation is
+    /// advanced by user interface. Use `UserInterface::set_background_animation` for nodes
+    /// that are already added to user interface, otherwise animation won't be advanced.
+    #[inline]
+    pub fn set_background_animation(&mut self, animation: Option<AnimatedSpriteBrush>) -> &mut Self {
+        if let Some(animation) = animation.as_ref() {
+            self.background_texture = Some(animation.current_brush());
+        }
+        self.background_animation = animation;
+        self
+    }
+
+    #[inline]
+    pub fn background_animation(&self) -> Option<&AnimatedSpriteBrush> {
+        self.background_animation.as_ref()
+    }
+
+    #[inline]
+    pub fn background_animation_mut(&mut self) -> Option<&mut AnimatedSpriteBrush> {
+        self.background_animation.as_mut()
+    }
+
     #[inline]
     pub fn set_foreground(&mut self, color: Color) -> &mut Self {
         self.foreground = color;
@@ -316,10 +431,39 @@ impl Widget {
 
     #[inline]
     pub fn set_column(&mut self, column: usize) -> &mut Self {
-        self.column = column;
+        self.set_attached_property(Self::COLUMN, column)
+    }
+
+    /// Registers type of attached property, so its value can be set by name through
+    /// `set_property` (from styles and templates). Properties of built-in panels are
+    /// registered already.
+    pub fn register_attached_property<T: Any + Clone>(name: &str) {
+        ATTACHED_PROPERTY_TYPES.lock().unwrap().insert(name.to_owned(), clone_attached_property::<T>);
+    }
+
+    /// Sets value of attached property, previous value of any type will be replaced.
+    pub fn set_attached_property<T: Any>(&mut self, name: &str, value: T) -> &mut Self {
+        self.attached_properties.insert(name.to_owned(), Rc::new(value));
         self
     }
 
+    /// Returns value of attached property, `None` if there is no such property or
+    /// it has different type.
+    pub fn attached_property<T: Any>(&self, name: &str) -> Option<&T> {
+        self.attached_properties
+            .get(name)
+            .and_then(|value| value.downcast_ref::<T>())
+    }
+
+    pub fn remove_attached_property(&mut self, name: &str) -> &mut Self {
+        self.attached_properties.remove(name);
+        self
+    }
+
+    pub fn has_attached_property(&self, name: &str) -> bool {
+        self.attached_properties.contains_key(name)
+    }
+
     #[inline]
     pub fn set_margin(&mut self, margin: Thickness) -> &mut Self {
         self.margin = margin;
@@ -336,6 +480,26 @@ impl Widget {
         &self.children
     }
 
+    /// Returns index of given direct child in children list of this widget.
+    #[inline]
+    pub fn child_index(&self, child: Handle<UINode>) -> Option<usize> {
+        self.children.iter().position(|c| *c == child)
+    }
+
+    /// Moves given direct child to specified position in children list, index will be clamped
+    /// to children count. Order of children defines order of layout in panels and order of
+    /// drawing. Returns `false` if node is not a child of this widget.
+    pub fn move_child(&mut self, child: Handle<UINode>, index: usize) -> bool {
+        if let Some(current) = self.child_index(child) {
+            self.children.remove(current);
+            let index = std::cmp::min(index, self.children.len());
+            self.children.insert(index, child);
+            true
+        } else {
+            false
+        }
+    }
+
     #[inline]
     pub fn parent(&self) -> Handle<UINode> {
         self.parent
@@ -343,18 +507,41 @@ impl Widget {
 
     #[inline]
     pub fn column(&self) -> usize {
-        self.column
+        self.attached_property::<usize>(Self::COLUMN).cloned().unwrap_or(0)
     }
 
     #[inline]
     pub fn set_row(&mut self, row: usize) -> &mut Self {
-        self.row = row;
-        self
+        self.set_attached_property(Self::ROW, row)
     }
 
     #[inline]
     pub fn row(&self) -> usize {
-        self.row
+        self.attached_property::<usize>(Self::ROW).cloned().unwrap_or(0)
+    }
+
+    /// Sets count of grid rows occupied by widget starting from its row, zero is treated
+    /// as one.
+    #[inline]
+    pub fn set_row_span(&mut self, row_span: usize) -> &mut Self {
+        self.set_attached_property(Self::ROW_SPAN, row_span)
+    }
+
+    #[inline]
+    pub fn row_span(&self) -> usize {
+        self.attached_property::<usize>(Self::ROW_SPAN).cloned().unwrap_or(1).max(1)
+    }
+
+    /// Sets count of grid columns occupied by widget starting from its column, zero is
+    /// treated as one.
+    #[inline]
+    pub fn set_column_span(&mut self, column_span: usize) -> &mut Self {
+        self.set_attached_property(Self::COLUMN_SPAN, column_span)
+    }
+
+    #[inline]
+    pub fn column_span(&self) -> usize {
+        self.attached_property::<usize>(Self::COLUMN_SPAN).cloned().unwrap_or(1).max(1)
     }
 
     #[inline]
@@ -378,6 +565,105 @@ impl Widget {
         self.visibility
     }
 
+    #[inline]
+    pub fn set_layout_transform(&mut self, transform: LayoutTransform) -> &mut Self {
+        self.layout_transform = transform;
+        self
+    }
+
+    #[inline]
+    pub fn layout_transform(&self) -> LayoutTransform {
+        self.layout_transform
+    }
+
+    #[inline]
+    pub fn set_hit_test_visibility(&mut self, visibility: HitTestVisibility) -> &mut Self {
+        self.hit_test_visibility = visibility;
+        self
+    }
+
+    #[inline]
+    pub fn hit_test_visibility(&self) -> HitTestVisibility {
+        self.hit_test_visibility
+    }
+
+    /// Sets shape which is used for hit testing instead of drawn geometry, for example
+    /// `HitTestShape::Ellipse` for round buttons.
+    #[inline]
+    pub fn set_hit_test_shape(&mut self, shape: HitTestShape) -> &mut Self {
+        self.hit_test_shape = shape;
+        self
+    }
+
+    #[inline]
+    pub fn hit_test_shape(&self) -> &HitTestShape {
+        &self.hit_test_shape
+    }
+
+    #[inline]
+    pub fn set_render_transform(&mut self, transform: RenderTransform) -> &mut Self {
+        self.render_transform = transform;
+        self
+    }
+
+    #[inline]
+    pub fn render_transform(&self) -> RenderTransform {
+        self.render_transform
+    }
+
+    #[inline]
+    pub fn set_use_safe_area(&mut self, use_safe_area: bool) -> &mut Self {
+        self.use_safe_area = use_safe_area;
+        self
+    }
+
+    #[inline]
+    pub fn is_using_safe_area(&self) -> bool {
+        self.use_safe_area
+    }
+
+    #[inline]
+    pub fn set_focusable(&mut self, focusable: bool) -> &mut Self {
+        self.is_focusable = focusable;
+        self
+    }
+
+    #[inline]
+    pub fn is_focusable(&self) -> bool {
+        self.is_focusable
+    }
+
+    /// Enables `ActualBoundsChanged` events, they are off by default because layout
+    /// changes bounds of whole subtrees (on scroll or resize for example).
+    #[inline]
+    pub fn set_bounds_notifications(&mut self, enabled: bool) -> &mut Self {
+        self.bounds_notifications = enabled;
+        self
+    }
+
+    #[inline]
+    pub fn is_bounds_notifications(&self) -> bool {
+        self.bounds_notifications
+    }
+
+    /// Returns true if widget should replace its animations with instant state changes,
+    /// this is set by user interface for all widgets.
+    #[inline]
+    pub fn reduced_motion(&self) -> bool {
+        self.reduced_motion
+    }
+
+    #[inline]
+    pub fn set_focus_scope(&mut self, focus_scope: bool) -> &mut Self {
+        self.is_focus_scope = focus_scope;
+        self
+    }
+
+    #[inline]
+    pub fn is_focus_scope(&self) -> bool {
+        self.is_focus_scope
+    }
+
     #[inline]
     pub fn set_style(&mut self, style: Rc<Style>) -> &mut Self {
         self.style = Some(style);
@@ -389,6 +675,51 @@ impl Widget {
         self.style.clone()
     }
 
+    /// Adds or removes state, returns true if state has changed. Use
+    /// `UserInterface::set_node_state` to also re-apply style of the node.
+    pub fn set_state(&mut self, state: &str, active: bool) -> bool {
+        match self.states.iter().position(|s| s == state) {
+            Some(i) if !active => {
+                self.states.remove(i);
+                true
+            }
+            None if active => {
+                self.states.push(state.to_owned());
+                true
+            }
+            _ => false
+        }
+    }
+
+    #[inline]
+    pub fn has_state(&self, state: &str) -> bool {
+        self.states.iter().any(|s| s == state)
+    }
+
+    #[inline]
+    pub fn states(&self) -> &[String] {
+        &self.states
+    }
+
+    #[inline]
+    pub fn set_user_data(&mut self, user_data: Option<Rc<dyn Any>>) -> &mut Self {
+        self.user_data = user_data;
+        self
+    }
+
+    #[inline]
+    pub fn user_data(&self) -> Option<Rc<dyn Any>> {
+        self.user_data.clone()
+    }
+
+    /// Returns reference to user data if it has given type.
+    #[inline]
+    pub fn user_data_ref<T: Any>(&self) -> Option<&T> {
+        self.user_data
+            .as_ref()
+            .and_then(|data| data.downcast_ref::<T>())
+    }
+
     pub fn has_descendant(&self, node_handle: Handle<UINode>, ui: &UserInterface) -> bool {
         for child_handle in self.children.iter() {
             if *child_handle == node_handle {
@@ -428,14 +759,23 @@ pub struct WidgetBuilder {
     max_size: Option<Vec2>,
     min_size: Option<Vec2>,
     background: Option<Color>,
+    background_texture: Option<TextureBrush>,
+    background_animation: Option<AnimatedSpriteBrush>,
     foreground: Option<Color>,
-    row: Option<usize>,
-    column: Option<usize>,
+    attached_properties: HashMap<String, Rc<dyn Any>>,
     margin: Option<Thickness>,
     children: Vec<Handle<UINode>>,
-    is_hit_test_visible: bool,
+    hit_test_visibility: HitTestVisibility,
+    hit_test_shape: HitTestShape,
     visibility: Visibility,
+    layout_transform: LayoutTransform,
+    render_transform: RenderTransform,
+    use_safe_area: bool,
+    is_focusable: bool,
+    bounds_notifications: bool,
+    is_focus_scope: bool,
     pub(in crate) style: Option<Rc<Style>>,
+    user_data: Option<Rc<dyn Any>>,
 }
 
 impl Default for WidgetBuilder {
@@ -455,15 +795,24 @@ impl WidgetBuilder {
             max_size: None,
             min_size: None,
             background: None,
+            background_texture: None,
+            background_animation: None,
             foreground: None,
-            row: None,
-            column: None,
+            attached_properties: Default::default(),
             margin: None,
             desired_position: None,
             children: Vec::new(),
-            is_hit_test_visible: true,
+            hit_test_visibility: HitTestVisibility::Visible,
+            hit_test_shape: HitTestShape::Geometry,
             visibility: Visibility::Visible,
+            layout_transform: LayoutTransform::identity(),
+            render_transform: RenderTransform::identity(),
+            use_safe_area: false,
+            is_focusable: false,
+            bounds_notifications: false,
+            is_focus_scope: false,
             style: None,
+            user_data: None,
         }
     }
 
@@ -502,18 +851,42 @@ impl WidgetBuilder {
         self
     }
 
+    /// Sets texture that is drawn as background by widgets that draw background (border,
+    /// image, etc.), background color is used as tint.
+    pub fn with_background_texture(mut self, brush: TextureBrush) -> Self {
+        self.background_texture = Some(brush);
+        self
+    }
+
+    /// Sets animated sprite as background, for animated icons and loading indicators.
+    pub fn with_background_animation(mut self, animation: AnimatedSpriteBrush) -> Self {
+        self.background_animation = Some(animation);
+        self
+    }
+
     pub fn with_foreground(mut self, color: Color) -> Self {
         self.foreground = Some(color);
         self
     }
 
-    pub fn on_row(mut self, row: usize) -> Self {
-        self.row = Some(row);
-        self
+    pub fn on_row(self, row: usize) -> Self {
+        self.with_attached_property(Widget::ROW, row)
     }
 
-    pub fn on_column(mut self, column: usize) -> Self {
-        self.column = Some(column);
+    pub fn on_column(self, column: usize) -> Self {
+        self.with_attached_property(Widget::COLUMN, column)
+    }
+
+    pub fn with_row_span(self, row_span: usize) -> Self {
+        self.with_attached_property(Widget::ROW_SPAN, row_span)
+    }
+
+    pub fn with_column_span(self, column_span: usize) -> Self {
+        self.with_attached_property(Widget::COLUMN_SPAN, column_span)
+    }
+
+    pub fn with_attached_property<T: Any>(mut self, name: &str, value: T) -> Self {
+        self.attached_properties.insert(name.to_owned(), Rc::new(value));
         self
     }
 
@@ -551,8 +924,13 @@ impl WidgetBuilder {
         self
     }
 
-    pub fn with_hit_test_visibility(mut self, state: bool) -> Self {
-        self.is_hit_test_visible = state;
+    pub fn with_hit_test_visibility(mut self, visibility: HitTestVisibility) -> Self {
+        self.hit_test_visibility = visibility;
+        self
+    }
+
+    pub fn with_hit_test_shape(mut self, shape: HitTestShape) -> Self {
+        self.hit_test_shape = shape;
         self
     }
 
@@ -561,6 +939,43 @@ impl WidgetBuilder {
         self
     }
 
+    pub fn with_layout_transform(mut self, transform: LayoutTransform) -> Self {
+        self.layout_transform = transform;
+        self
+    }
+
+    pub fn with_render_transform(mut self, transform: RenderTransform) -> Self {
+        self.render_transform = transform;
+        self
+    }
+
+    /// Root-level widgets with this flag are laid out inside safe area of user interface.
+    pub fn with_safe_area(mut self, use_safe_area: bool) -> Self {
+        self.use_safe_area = use_safe_area;
+        self
+    }
+
+    pub fn with_focusable(mut self, focusable: bool) -> Self {
+        self.is_focusable = focusable;
+        self
+    }
+
+    /// Enables `ActualBoundsChanged` events of widget.
+    pub fn with_bounds_notifications(mut self, enabled: bool) -> Self {
+        self.bounds_notifications = enabled;
+        self
+    }
+
+    pub fn with_focus_scope(mut self, focus_scope: bool) -> Self {
+        self.is_focus_scope = focus_scope;
+        self
+    }
+
+    pub fn with_user_data(mut self, user_data: Rc<dyn Any>) -> Self {
+        self.user_data = Some(user_data);
+        self
+    }
+
     pub fn build(self) -> Widget {
         let mut widget = Widget {
             name: self.name.unwrap_or_default(),
@@ -574,14 +989,26 @@ impl WidgetBuilder {
             min_size: self.min_size.unwrap_or(Vec2::ZERO),
             max_size: self.max_size.unwrap_or_else(|| Vec2::new(std::f32::INFINITY, std::f32::INFINITY)),
             background: self.background.unwrap_or(Color::WHITE),
+            background_texture: self.background_animation
+                .as_ref()
+                .map(|animation| animation.current_brush())
+                .or(self.background_texture),
+            background_animation: self.background_animation,
             foreground: self.foreground.unwrap_or(Color::WHITE),
-            row: self.row.unwrap_or(0),
-            column: self.column.unwrap_or(0),
+            attached_properties: self.attached_properties,
             vertical_alignment: self.vertical_alignment.unwrap_or(VerticalAlignment::Stretch),
             horizontal_alignment: self.horizontal_alignment.unwrap_or(HorizontalAlignment::Stretch),
             margin: self.margin.unwrap_or_else(Thickness::zero),
             visibility: self.visibility,
             global_visibility: true,
+            culled: Cell::new(false),
+            layout_transform: self.layout_transform,
+            render_transform: self.render_transform,
+            use_safe_area: self.use_safe_area,
+            is_focusable: self.is_focusable,
+            bounds_notifications: self.bounds_notifications,
+            reduced_motion: false,
+            is_focus_scope: self.is_focus_scope,
             children: self.children,
             parent: Handle::NONE,
             command_indices: Default::default(),
@@ -589,8 +1016,11 @@ impl WidgetBuilder {
             measure_valid: Cell::new(false),
             arrange_valid: Cell::new(false),
             events: RefCell::new(VecDeque::new()),
-            is_hit_test_visible: self.is_hit_test_visible,
+            hit_test_visibility: self.hit_test_visibility,
+            hit_test_shape: self.hit_test_shape.clone(),
             style: None,
+            states: Vec::new(),
+            user_data: self.user_data,
         };
 
         if let Some(style) = self.style {
@@ -24,19 +24,44 @@ use crate::{
         WidgetBuilder,
     },
     Visibility,
+    keymap::WidgetAction,
     bool_to_visibility,
     Control,
     ControlTemplate,
     UINodeContainer,
     Builder,
+    color_ext::palette,
     core::{
-        color::Color,
         pool::Handle,
         math::vec2::Vec2,
     },
 };
 use std::collections::HashMap;
 
+/// Saved state of a window, window is identified by its node name or title text.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WindowLayout {
+    pub name: String,
+    pub position: Vec2,
+    pub size: Vec2,
+    pub open: bool,
+    pub minimized: bool,
+}
+
+/// Saved arrangement of windows, can be stored by application between sessions and
+/// then restored by `UserInterface::apply_layout`. Order of windows is their order on
+/// screen from bottom to top.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WorkspaceLayout {
+    pub windows: Vec<WindowLayout>,
+}
+
+impl WorkspaceLayout {
+    pub fn find(&self, name: &str) -> Option<&WindowLayout> {
+        self.windows.iter().find(|window| window.name == name)
+    }
+}
+
 /// Represents a widget looking as window in Windows - with title, minimize and close buttons.
 /// It has scrollable region for content, content can be any desired node or even other window.
 /// Window can be dragged by its title.
@@ -48,6 +73,10 @@ pub struct Window {
     minimized: bool,
     can_minimize: bool,
     can_close: bool,
+    open: bool,
+    /// Minimized window is hidden completely and can be restored from taskbar.
+    minimize_to_taskbar: bool,
+    title: String,
     header: Handle<UINode>,
     minimize_button: Handle<UINode>,
     close_button: Handle<UINode>,
@@ -72,6 +101,9 @@ impl Control for Window {
             minimized: self.minimized,
             can_minimize: self.can_minimize,
             can_close: self.can_close,
+            open: self.open,
+            minimize_to_taskbar: self.minimize_to_taskbar,
+            title: self.title.clone(),
             header: self.header,
             minimize_button: self.minimize_button,
             close_button: self.close_button,
@@ -108,10 +140,21 @@ impl Control for Window {
                     }
                     evt.handled = true;
                 }
+                UIEventKind::CaptureLost => {
+                    self.is_dragged = false;
+                }
                 _ => ()
             }
         }
 
+        if let UIEventKind::KeyDown { code } = evt.kind {
+            if !evt.handled && (evt.source == self_handle || self.widget.has_descendant(evt.source, ui)) &&
+                ui.key_action(code) == Some(WidgetAction::WindowClose) {
+                self.close();
+                evt.handled = true;
+            }
+        }
+
         if evt.source == self.minimize_button {
             if let UIEventKind::Click = evt.kind {
                 self.minimize(!self.minimized);
@@ -127,16 +170,26 @@ impl Control for Window {
         if evt.source == self_handle || evt.target == self_handle {
             match evt.kind {
                 UIEventKind::Opened => {
-                    self.widget.set_visibility(Visibility::Visible);
+                    self.open = true;
+                    if !(self.minimized && self.minimize_to_taskbar) {
+                        self.widget.set_visibility(Visibility::Visible);
+                    }
                 }
                 UIEventKind::Closed => {
+                    self.open = false;
                     self.widget.set_visibility(Visibility::Collapsed);
                 }
                 UIEventKind::Minimized(minimized) => {
                     self.minimized = minimized;
-                    let scroll_viewer = ui.node_mut(self.scroll_viewer).downcast_mut::<ScrollViewer>().unwrap();
-                    let visibility = if !minimized { Visibility::Visible } else { Visibility::Collapsed };
-                    scroll_viewer.widget_mut().set_visibility(visibility);
+                    if self.minimize_to_taskbar {
+                        if self.open {
+                            self.widget.set_visibility(bool_to_visibility(!minimized));
+                        }
+                    } else {
+                        let scroll_viewer = ui.node_mut(self.scroll_viewer).downcast_mut::<ScrollViewer>().unwrap();
+                        let visibility = if !minimized { Visibility::Visible } else { Visibility::Collapsed };
+                        scroll_viewer.widget_mut().set_visibility(visibility);
+                    }
                 }
                 UIEventKind::CanMinimizeChanged(value) => {
                     self.can_minimize = value;
@@ -172,6 +225,9 @@ impl Window {
             minimized: false,
             can_minimize: true,
             can_close: true,
+            open: true,
+            minimize_to_taskbar: false,
+            title: String::new(),
             header,
             minimize_button,
             close_button,
@@ -179,6 +235,33 @@ impl Window {
         }
     }
 
+    /// Returns text of title, empty if title is a custom node.
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn set_title_text(&mut self, title: &str) -> &mut Self {
+        self.title = title.to_owned();
+        self
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn is_minimized(&self) -> bool {
+        self.minimized
+    }
+
+    pub fn is_minimize_to_taskbar(&self) -> bool {
+        self.minimize_to_taskbar
+    }
+
+    pub fn set_minimize_to_taskbar(&mut self, value: bool) -> &mut Self {
+        self.minimize_to_taskbar = value;
+        self
+    }
+
     pub fn close(&mut self) {
         self.widget
             .events
@@ -222,6 +305,7 @@ pub struct WindowBuilder<'a> {
     can_close: bool,
     can_minimize: bool,
     open: bool,
+    minimize_to_taskbar: bool,
 }
 
 /// Window title can be either text or node.
@@ -245,6 +329,7 @@ impl<'a> WindowBuilder<'a> {
             can_close: true,
             can_minimize: true,
             open: true,
+            minimize_to_taskbar: false,
         }
     }
 
@@ -272,15 +357,26 @@ impl<'a> WindowBuilder<'a> {
         self.open = open;
         self
     }
+
+    /// Minimized window will be hidden completely, it can be restored by `Taskbar` or
+    /// `UserInterface::activate_window`.
+    pub fn with_minimize_to_taskbar(mut self, value: bool) -> Self {
+        self.minimize_to_taskbar = value;
+        self
+    }
 }
 
 impl Builder for WindowBuilder<'_> {
     fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
         let minimize_button;
         let close_button;
+        let title = match self.title {
+            Some(WindowTitle::Text(text)) => text.to_owned(),
+            _ => String::new(),
+        };
 
         let header = BorderBuilder::new(WidgetBuilder::new()
-            .with_background(Color::opaque(120, 120, 120))
+            .with_background(palette::current().control)
             .with_horizontal_alignment(HorizontalAlignment::Stretch)
             .with_height(30.0)
             .with_child(GridBuilder::new(WidgetBuilder::new()
@@ -338,6 +434,7 @@ impl Builder for WindowBuilder<'_> {
 
         let window = Window {
             widget: self.widget_builder
+                .with_focus_scope(true)
                 .with_visibility(if self.open { Visibility::Visible } else { Visibility::Collapsed })
                 .with_child(BorderBuilder::new(WidgetBuilder::new()
                     .with_child(GridBuilder::new(WidgetBuilder::new()
@@ -347,7 +444,7 @@ impl Builder for WindowBuilder<'_> {
                         .add_row(Row::auto())
                         .add_row(Row::stretch())
                         .build(ui))
-                    .with_background(Color::opaque(100, 100, 100)))
+                    .with_background(palette::current().frame))
                     .build(ui))
                 .build(),
             mouse_click_pos: Vec2::ZERO,
@@ -356,6 +453,9 @@ impl Builder for WindowBuilder<'_> {
             minimized: false,
             can_minimize: self.can_minimize,
             can_close: self.can_close,
+            open: self.open,
+            minimize_to_taskbar: self.minimize_to_taskbar,
+            title,
             header,
             minimize_button,
             close_button,
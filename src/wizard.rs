@@ -0,0 +1,374 @@
+use crate::{
+    core::{
+        color::Color,
+        pool::Handle,
+        math::Rect,
+    },
+    widget::{
+        Widget,
+        WidgetBuilder,
+    },
+    grid::{
+        GridBuilder,
+        Column,
+        Row,
+    },
+    text::{
+        Text,
+        TextBuilder,
+    },
+    button::ButtonBuilder,
+    stack_panel::StackPanelBuilder,
+    scroll_bar::Orientation,
+    draw::{
+        DrawingContext,
+        CommandKind,
+        CommandTexture,
+    },
+    event::{
+        UIEvent,
+        UIEventKind,
+    },
+    color_ext::palette,
+    UserInterface,
+    UINode,
+    HorizontalAlignment,
+    Thickness,
+    Visibility,
+    Control,
+    ControlTemplate,
+    UINodeContainer,
+    Builder,
+};
+use std::{
+    collections::HashMap,
+    rc::Rc,
+};
+
+/// Checks input of a page before user leaves it forward, error message is shown in wizard
+/// and navigation is blocked.
+pub type PageValidator = Rc<dyn Fn(&UserInterface) -> Result<(), String>>;
+
+pub struct WizardPage {
+    pub title: String,
+    pub content: Handle<UINode>,
+    pub validator: Option<PageValidator>,
+}
+
+impl WizardPage {
+    pub fn new(title: &str, content: Handle<UINode>) -> Self {
+        Self {
+            title: title.to_owned(),
+            content,
+            validator: None,
+        }
+    }
+
+    pub fn with_validator<F>(mut self, validator: F) -> Self
+        where F: Fn(&UserInterface) -> Result<(), String> + 'static {
+        self.validator = Some(Rc::new(validator));
+        self
+    }
+}
+
+#[derive(Clone)]
+struct Page {
+    title: String,
+    content: Handle<UINode>,
+    validator: Option<PageValidator>,
+}
+
+/// Nodes of wizard which change with current page.
+#[derive(Copy, Clone)]
+struct Parts {
+    caption: Handle<UINode>,
+    error: Handle<UINode>,
+    back: Handle<UINode>,
+    next: Handle<UINode>,
+    finish: Handle<UINode>,
+    cancel: Handle<UINode>,
+}
+
+impl Parts {
+    /// Updates visibility of pages and buttons, caption and error for given page.
+    fn show_page(&self, ui: &mut UserInterface, pages: &[Page], index: usize) {
+        for (i, page) in pages.iter().enumerate() {
+            ui.node_mut(page.content)
+                .widget_mut()
+                .set_visibility(if i == index { Visibility::Visible } else { Visibility::Collapsed });
+        }
+        let last = index + 1 >= pages.len();
+        let visibility = |visible: bool| if visible { Visibility::Visible } else { Visibility::Collapsed };
+        ui.node_mut(self.back).widget_mut().set_visibility(visibility(index > 0));
+        ui.node_mut(self.next).widget_mut().set_visibility(visibility(!last));
+        ui.node_mut(self.finish).widget_mut().set_visibility(visibility(last));
+        let caption = match pages.get(index) {
+            Some(page) => format!("Step {} of {}: {}", index + 1, pages.len(), page.title),
+            None => String::new(),
+        };
+        set_text(ui, self.caption, &caption);
+        set_text(ui, self.error, "");
+    }
+}
+
+fn set_text(ui: &mut UserInterface, node: Handle<UINode>, text: &str) {
+    if let Some(text_ref) = ui.node_mut(node).downcast_mut::<Text>() {
+        text_ref.set_text(text);
+    }
+}
+
+/// Sequence of pages shown one at a time with Back, Next, Finish and Cancel buttons, for
+/// project creation, export and similar dialogs. Strip on top shows progress, caption shows
+/// number and title of current page.
+///
+/// Next and Finish run validator of current page first, if it fails its message is shown
+/// under the page and navigation does not happen. Back never validates. Wizard does not
+/// close itself, application should react on `WizardFinished` and `WizardCancelled`.
+///
+/// # Events
+///
+/// [`WizardPageChanged`] - sent when other page was shown, contains previous and new index.
+/// [`WizardFinished`] - sent when Finish was clicked on last page and its input is valid.
+/// [`WizardCancelled`] - sent when Cancel was clicked.
+pub struct Wizard {
+    widget: Widget,
+    pages: Vec<Page>,
+    current: usize,
+    parts: Parts,
+}
+
+impl Wizard {
+    const PROGRESS_HEIGHT: f32 = 4.0;
+    const ERROR_COLOR: Color = Color { r: 220, g: 80, b: 80, a: 255 };
+
+    pub fn current_page(&self) -> usize {
+        self.current
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    pub fn page_content(&self, index: usize) -> Handle<UINode> {
+        self.pages.get(index).map_or(Handle::NONE, |page| page.content)
+    }
+
+    pub fn is_last_page(&self) -> bool {
+        self.current + 1 >= self.pages.len()
+    }
+
+    /// Shows page with given index without validation, for example to return user to a
+    /// page with invalid input.
+    pub fn go_to(ui: &mut UserInterface, wizard: Handle<UINode>, index: usize) {
+        let (from, pages, parts) = match ui.node_mut(wizard).downcast_mut::<Wizard>() {
+            Some(wizard_ref) if index < wizard_ref.pages.len() => {
+                let from = wizard_ref.current;
+                wizard_ref.current = index;
+                if from != index {
+                    wizard_ref.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::WizardPageChanged { from, to: index }));
+                }
+                (from, wizard_ref.pages.clone(), wizard_ref.parts)
+            }
+            _ => return,
+        };
+        if from != index {
+            parts.show_page(ui, &pages, index);
+        }
+    }
+
+    /// Runs validator of current page, shows its error if it fails.
+    fn validate(&self, ui: &mut UserInterface) -> bool {
+        let result = match self.pages.get(self.current).and_then(|page| page.validator.clone()) {
+            Some(validator) => validator(&*ui),
+            None => Ok(()),
+        };
+        match result {
+            Ok(()) => true,
+            Err(message) => {
+                set_text(ui, self.parts.error, &message);
+                false
+            }
+        }
+    }
+
+    fn navigate(&mut self, ui: &mut UserInterface, to: usize) {
+        let from = self.current;
+        if to != from && to < self.pages.len() {
+            self.current = to;
+            self.parts.show_page(ui, &self.pages, to);
+            self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::WizardPageChanged { from, to }));
+        }
+    }
+}
+
+impl Control for Wizard {
+    fn widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    fn widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    fn raw_copy(&self) -> Box<dyn Control> {
+        Box::new(Self {
+            widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
+            pages: self.pages.clone(),
+            current: self.current,
+            parts: self.parts,
+        })
+    }
+
+    fn resolve(&mut self, _: &ControlTemplate, node_map: &HashMap<Handle<UINode>, Handle<UINode>>) {
+        for page in self.pages.iter_mut() {
+            page.content = *node_map.get(&page.content).unwrap();
+        }
+        let parts = &mut self.parts;
+        parts.caption = *node_map.get(&parts.caption).unwrap();
+        parts.error = *node_map.get(&parts.error).unwrap();
+        parts.back = *node_map.get(&parts.back).unwrap();
+        parts.next = *node_map.get(&parts.next).unwrap();
+        parts.finish = *node_map.get(&parts.finish).unwrap();
+        parts.cancel = *node_map.get(&parts.cancel).unwrap();
+    }
+
+    fn draw(&self, drawing_context: &mut DrawingContext) {
+        let bounds = self.widget.get_screen_bounds();
+        drawing_context.push_rect_filled(&bounds, None, self.widget.background());
+        let strip = Rect::new(bounds.x, bounds.y, bounds.w, Self::PROGRESS_HEIGHT);
+        drawing_context.push_rect_filled(&strip, None, palette::current().field);
+        if !self.pages.is_empty() {
+            let progress = (self.current + 1) as f32 / self.pages.len() as f32;
+            drawing_context.push_rect_filled(&Rect::new(strip.x, strip.y, strip.w * progress, strip.h), None, palette::current().accent);
+        }
+        drawing_context.commit(CommandKind::Geometry, CommandTexture::None);
+    }
+
+    fn handle_event(&mut self, _self_handle: Handle<UINode>, ui: &mut UserInterface, evt: &mut UIEvent) {
+        if let UIEventKind::Click = evt.kind {
+            if evt.source == self.parts.back && self.current > 0 {
+                self.navigate(ui, self.current - 1);
+            } else if evt.source == self.parts.next && !self.is_last_page() {
+                if self.validate(ui) {
+                    self.navigate(ui, self.current + 1);
+                }
+            } else if evt.source == self.parts.finish && self.is_last_page() {
+                if self.validate(ui) {
+                    self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::WizardFinished));
+                }
+            } else if evt.source == self.parts.cancel {
+                self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::WizardCancelled));
+            }
+        }
+    }
+
+    fn on_attached_to_ui(&mut self, _self_handle: Handle<UINode>, ui: &mut UserInterface) {
+        self.parts.show_page(ui, &self.pages, self.current);
+    }
+}
+
+pub struct WizardBuilder {
+    widget_builder: WidgetBuilder,
+    pages: Vec<WizardPage>,
+}
+
+impl WizardBuilder {
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            pages: Vec::new(),
+        }
+    }
+
+    pub fn with_page(mut self, page: WizardPage) -> Self {
+        self.pages.push(page);
+        self
+    }
+
+    pub fn with_pages(mut self, pages: Vec<WizardPage>) -> Self {
+        self.pages.extend(pages);
+        self
+    }
+}
+
+fn make_button(ui: &mut dyn UINodeContainer, text: &str) -> Handle<UINode> {
+    ButtonBuilder::new(WidgetBuilder::new()
+        .with_width(80.0)
+        .with_margin(Thickness::uniform(2.0)))
+        .with_text(text)
+        .build(ui)
+}
+
+impl Builder for WizardBuilder {
+    fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
+        let caption = TextBuilder::new(WidgetBuilder::new()
+            .with_margin(Thickness::uniform(4.0))
+            .on_row(1))
+            .build(ui);
+
+        let pages_grid = GridBuilder::new(WidgetBuilder::new()
+            .with_children(&self.pages
+                .iter()
+                .map(|page| page.content)
+                .collect::<Vec<Handle<UINode>>>())
+            .with_margin(Thickness::uniform(4.0))
+            .on_row(2))
+            .build(ui);
+
+        let error = TextBuilder::new(WidgetBuilder::new()
+            .with_foreground(Wizard::ERROR_COLOR)
+            .with_margin(Thickness::uniform(4.0))
+            .on_row(3))
+            .build(ui);
+
+        let back = make_button(ui, "Back");
+        let next = make_button(ui, "Next");
+        let finish = make_button(ui, "Finish");
+        let cancel = make_button(ui, "Cancel");
+        let buttons = StackPanelBuilder::new(WidgetBuilder::new()
+            .with_horizontal_alignment(HorizontalAlignment::Right)
+            .with_children(&[back, next, finish, cancel])
+            .on_row(4))
+            .with_orientation(Orientation::Horizontal)
+            .build(ui);
+
+        let grid = GridBuilder::new(WidgetBuilder::new()
+            .with_child(caption)
+            .with_child(pages_grid)
+            .with_child(error)
+            .with_child(buttons))
+            .add_column(Column::stretch())
+            .add_row(Row::strict(Wizard::PROGRESS_HEIGHT))
+            .add_row(Row::auto())
+            .add_row(Row::stretch())
+            .add_row(Row::auto())
+            .add_row(Row::auto())
+            .build(ui);
+
+        let wizard = Wizard {
+            widget: self.widget_builder
+                .with_background(palette::current().panel)
+                .with_child(grid)
+                .build(),
+            pages: self.pages
+                .into_iter()
+                .map(|page| Page {
+                    title: page.title,
+                    content: page.content,
+                    validator: page.validator,
+                })
+                .collect(),
+            current: 0,
+            parts: Parts {
+                caption,
+                error,
+                back,
+                next,
+                finish,
+                cancel,
+            },
+        };
+
+        ui.add_node(Box::new(wizard))
+    }
+}
@@ -0,0 +1,76 @@
+use std::{
+    sync::{
+        mpsc::{
+            self,
+            Sender,
+        },
+        Arc,
+        Mutex,
+    },
+    thread,
+};
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Small pool of threads for heavy text work: rasterization of fonts and layout of large
+/// texts. If no thread could be started, jobs are executed synchronously by caller.
+pub struct WorkerPool {
+    sender: Mutex<Option<Sender<Job>>>,
+    thread_count: usize,
+}
+
+lazy_static! {
+    static ref TEXT_WORKERS: WorkerPool = WorkerPool::new(2);
+}
+
+impl WorkerPool {
+    pub fn new(thread_count: usize) -> Self {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let mut started = 0;
+        for i in 0..thread_count {
+            let receiver = receiver.clone();
+            let result = thread::Builder::new()
+                .name(format!("ui-worker-{}", i))
+                .spawn(move || loop {
+                    // Lock is released before job is executed, so other workers can take
+                    // next jobs meanwhile.
+                    let job = match receiver.lock().unwrap().recv() {
+                        Ok(job) => job,
+                        Err(_) => break,
+                    };
+                    job();
+                });
+            if result.is_ok() {
+                started += 1;
+            }
+        }
+        Self {
+            sender: Mutex::new(if started > 0 { Some(sender) } else { None }),
+            thread_count: started,
+        }
+    }
+
+    /// Returns pool which is used by text widgets and font loading.
+    pub fn text() -> &'static WorkerPool {
+        &TEXT_WORKERS
+    }
+
+    /// Number of running threads, zero means that jobs are executed synchronously.
+    pub fn thread_count(&self) -> usize {
+        self.thread_count
+    }
+
+    /// Executes job on some worker thread, or right away if pool has no threads.
+    pub fn execute<F>(&self, job: F)
+        where F: FnOnce() + Send + 'static {
+        let job: Job = Box::new(job);
+        let rejected = match self.sender.lock().unwrap().as_ref() {
+            Some(sender) => sender.send(job).err().map(|err| err.0),
+            None => Some(job),
+        };
+        if let Some(job) = rejected {
+            job();
+        }
+    }
+}
@@ -0,0 +1,281 @@
+use crate::{
+    core::{
+        color::Color,
+        pool::Handle,
+        math::{
+            vec2::Vec2,
+            Rect,
+        },
+    },
+    widget::{
+        Widget,
+        WidgetBuilder,
+    },
+    draw::{
+        DrawingContext,
+        CommandKind,
+        CommandTexture,
+    },
+    color_ext::palette,
+    UserInterface,
+    UINode,
+    Visibility,
+    Control,
+    ControlTemplate,
+    UINodeContainer,
+    Builder,
+};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+};
+
+struct WorldMarker {
+    node: Handle<UINode>,
+    /// Projected position in local coordinates of layer, None if it cannot be projected.
+    position: Option<Vec2>,
+    clamp_to_edge: bool,
+}
+
+/// Layer for quest markers, nameplates and similar widgets that follow objects of 3D world.
+/// Layer knows nothing about cameras: each frame engine projects world positions to screen
+/// and passes them to `set_marker_position`, marker is centered at given position.
+///
+/// Markers added with `clamp_to_edge` stay at the edge of layer when their position is off
+/// screen, and an arrow pointing to the real position is drawn next to them. Other markers
+/// are hidden while off screen. Positions of points behind camera are usually mirrored by
+/// engine before they are passed to layer, so such markers stick to the opposite edge.
+///
+/// Layer should cover whole screen, children that are not markers are arranged like in
+/// canvas.
+pub struct WorldMarkerLayer {
+    widget: Widget,
+    markers: Vec<WorldMarker>,
+    /// Distance from edges of layer to clamped markers.
+    edge_margin: f32,
+    arrow_size: f32,
+    arrow_color: Color,
+    /// Centers and directions of arrows computed on last arrange, in local coordinates.
+    arrows: RefCell<Vec<(Vec2, Vec2)>>,
+}
+
+impl WorldMarkerLayer {
+    /// Adds node as marker and links it to layer. Marker stays hidden until its position
+    /// is set.
+    pub fn add_marker(ui: &mut UserInterface, layer: Handle<UINode>, node: Handle<UINode>, clamp_to_edge: bool) {
+        if let Some(layer_ref) = ui.node_mut(layer).downcast_mut::<WorldMarkerLayer>() {
+            layer_ref.markers.retain(|marker| marker.node != node);
+            layer_ref.markers.push(WorldMarker {
+                node,
+                position: None,
+                clamp_to_edge,
+            });
+        } else {
+            return;
+        }
+        ui.link_nodes(node, layer);
+        ui.node_mut(node).widget_mut().set_visibility(Visibility::Collapsed);
+    }
+
+    /// Removes marker node from user interface.
+    pub fn remove_marker(ui: &mut UserInterface, layer: Handle<UINode>, node: Handle<UINode>) {
+        if let Some(layer) = ui.node_mut(layer).downcast_mut::<WorldMarkerLayer>() {
+            layer.markers.retain(|marker| marker.node != node);
+        }
+        ui.remove_node(node);
+    }
+
+    /// Sets projected position of marker in screen coordinates, None hides marker (for
+    /// example when object is too far away).
+    pub fn set_marker_position(ui: &mut UserInterface, layer: Handle<UINode>, node: Handle<UINode>, position: Option<Vec2>) {
+        let visible = match ui.node_mut(layer).downcast_mut::<WorldMarkerLayer>() {
+            Some(layer) => {
+                let bounds = layer.widget.get_screen_bounds();
+                let origin = Vec2::new(bounds.x, bounds.y);
+                match layer.markers.iter_mut().find(|marker| marker.node == node) {
+                    Some(marker) => {
+                        marker.position = position.map(|position| position - origin);
+                        position.map_or(false, |position| marker.clamp_to_edge || bounds.contains(position.x, position.y))
+                    }
+                    None => return,
+                }
+            }
+            None => return,
+        };
+        ui.node_mut(node).widget_mut().set_visibility(if visible {
+            Visibility::Visible
+        } else {
+            Visibility::Collapsed
+        });
+    }
+
+    pub fn markers(&self) -> Vec<Handle<UINode>> {
+        self.markers.iter().map(|marker| marker.node).collect()
+    }
+
+    pub fn set_edge_margin(&mut self, margin: f32) -> &mut Self {
+        self.edge_margin = margin;
+        self
+    }
+
+    pub fn edge_margin(&self) -> f32 {
+        self.edge_margin
+    }
+
+    pub fn set_arrow_size(&mut self, size: f32) -> &mut Self {
+        self.arrow_size = size;
+        self
+    }
+
+    pub fn arrow_size(&self) -> f32 {
+        self.arrow_size
+    }
+
+    pub fn set_arrow_color(&mut self, color: Color) -> &mut Self {
+        self.arrow_color = color;
+        self
+    }
+
+    pub fn arrow_color(&self) -> Color {
+        self.arrow_color
+    }
+
+    /// Returns center of marker clamped to area of layer and direction of arrow if
+    /// marker was clamped.
+    fn clamp_marker(&self, position: Vec2, half_size: Vec2, final_size: Vec2) -> (Vec2, Option<Vec2>) {
+        let offset = self.edge_margin + self.arrow_size;
+        let clamp = |value: f32, half: f32, size: f32| {
+            let min = offset + half;
+            let max = size - offset - half;
+            if min > max { size * 0.5 } else { value.max(min).min(max) }
+        };
+        let center = Vec2::new(
+            clamp(position.x, half_size.x, final_size.x),
+            clamp(position.y, half_size.y, final_size.y),
+        );
+        (center, (position - center).normalized())
+    }
+}
+
+impl Control for WorldMarkerLayer {
+    fn widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    fn widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    fn raw_copy(&self) -> Box<dyn Control> {
+        Box::new(Self {
+            widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
+            markers: Vec::new(),
+            edge_margin: self.edge_margin,
+            arrow_size: self.arrow_size,
+            arrow_color: self.arrow_color,
+            arrows: Default::default(),
+        })
+    }
+
+    fn resolve(&mut self, _: &ControlTemplate, _: &HashMap<Handle<UINode>, Handle<UINode>>) {}
+
+    fn measure_override(&self, ui: &UserInterface, _available_size: Vec2) -> Vec2 {
+        let size_for_child = Vec2::new(std::f32::INFINITY, std::f32::INFINITY);
+        for child_handle in self.widget.children.iter() {
+            ui.node(*child_handle).measure(ui, size_for_child);
+        }
+        Vec2::ZERO
+    }
+
+    fn arrange_override(&self, ui: &UserInterface, final_size: Vec2) -> Vec2 {
+        let mut arrows = self.arrows.borrow_mut();
+        arrows.clear();
+        for child_handle in self.widget.children.iter() {
+            let child = ui.node(*child_handle);
+            let size = child.widget().desired_size.get();
+            let marker = self.markers.iter().find(|marker| marker.node == *child_handle);
+            let position = match marker {
+                Some(marker) => {
+                    let position = marker.position.unwrap_or(Vec2::ZERO);
+                    let half_size = size.scale(0.5);
+                    let center = if marker.clamp_to_edge {
+                        let (center, direction) = self.clamp_marker(position, half_size, final_size);
+                        if let Some(direction) = direction {
+                            // Distance from center to edge of marker along direction.
+                            let tx = if direction.x.abs() > std::f32::EPSILON { half_size.x / direction.x.abs() } else { std::f32::MAX };
+                            let ty = if direction.y.abs() > std::f32::EPSILON { half_size.y / direction.y.abs() } else { std::f32::MAX };
+                            arrows.push((center + direction.scale(tx.min(ty)), direction));
+                        }
+                        center
+                    } else {
+                        position
+                    };
+                    center - half_size
+                }
+                None => child.widget().desired_local_position.get(),
+            };
+            child.arrange(ui, &Rect::new(position.x, position.y, size.x, size.y));
+        }
+        final_size
+    }
+
+    fn draw(&self, drawing_context: &mut DrawingContext) {
+        let bounds = self.widget.get_screen_bounds();
+        let origin = Vec2::new(bounds.x, bounds.y);
+        let size = self.arrow_size;
+        for (base, direction) in self.arrows.borrow().iter() {
+            let base = origin + *base;
+            let side = Vec2::new(-direction.y, direction.x).scale(size * 0.5);
+            drawing_context.push_triangle_filled([base + direction.scale(size), base + side, base - side], self.arrow_color);
+        }
+        drawing_context.commit(CommandKind::Geometry, CommandTexture::None);
+    }
+}
+
+pub struct WorldMarkerLayerBuilder {
+    widget_builder: WidgetBuilder,
+    edge_margin: Option<f32>,
+    arrow_size: Option<f32>,
+    arrow_color: Option<Color>,
+}
+
+impl WorldMarkerLayerBuilder {
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            edge_margin: None,
+            arrow_size: None,
+            arrow_color: None,
+        }
+    }
+
+    pub fn with_edge_margin(mut self, margin: f32) -> Self {
+        self.edge_margin = Some(margin);
+        self
+    }
+
+    pub fn with_arrow_size(mut self, size: f32) -> Self {
+        self.arrow_size = Some(size);
+        self
+    }
+
+    pub fn with_arrow_color(mut self, color: Color) -> Self {
+        self.arrow_color = Some(color);
+        self
+    }
+}
+
+impl Builder for WorldMarkerLayerBuilder {
+    fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
+        let layer = WorldMarkerLayer {
+            widget: self.widget_builder.build(),
+            markers: Vec::new(),
+            edge_margin: self.edge_margin.unwrap_or(4.0),
+            arrow_size: self.arrow_size.unwrap_or(10.0),
+            arrow_color: self.arrow_color.unwrap_or_else(|| palette::current().accent),
+            arrows: Default::default(),
+        };
+
+        ui.add_node(Box::new(layer))
+    }
+}
@@ -0,0 +1,299 @@
+use crate::{
+    core::{
+        pool::Handle,
+        math::{
+            vec2::Vec2,
+            Rect,
+            clampf,
+        },
+    },
+    event::{
+        UIEvent,
+        UIEventKind,
+        MouseButton,
+    },
+    widget::{
+        Widget,
+        WidgetBuilder,
+    },
+    UserInterface,
+    UINode,
+    RenderTransform,
+    Control,
+    ControlTemplate,
+    UINodeContainer,
+    Builder,
+};
+use std::collections::HashMap;
+
+/// Container that zooms and pans its content, zoom is changed by mouse wheel (at cursor
+/// position) or pinch gesture, middle mouse button drags content. Content is laid out
+/// once at its desired size and then transformed by render transform, so zooming is
+/// cheap and does not cause layout of content. Content is clipped by bounds of panel.
+///
+/// # Events
+///
+/// [`ZoomChanged`] - sent when zoom has changed by any method.
+pub struct ZoomPanel {
+    widget: Widget,
+    content: Handle<UINode>,
+    zoom: f32,
+    min_zoom: f32,
+    max_zoom: f32,
+    /// Offset of content in panel coordinates.
+    pan: Vec2,
+    /// Last mouse position while panning.
+    pan_origin: Option<Vec2>,
+    /// Zoom factor change per one wheel step.
+    zoom_step: f32,
+}
+
+impl ZoomPanel {
+    pub fn content(&self) -> Handle<UINode> {
+        self.content
+    }
+
+    pub fn zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    pub fn pan(&self) -> Vec2 {
+        self.pan
+    }
+
+    pub fn min_zoom(&self) -> f32 {
+        self.min_zoom
+    }
+
+    pub fn max_zoom(&self) -> f32 {
+        self.max_zoom
+    }
+
+    /// Sets zoom limits, current zoom will be clamped at next change.
+    pub fn set_zoom_limits(&mut self, min: f32, max: f32) -> &mut Self {
+        self.min_zoom = min.min(max);
+        self.max_zoom = max.max(min);
+        self
+    }
+
+    pub fn set_zoom_step(&mut self, step: f32) -> &mut Self {
+        self.zoom_step = step;
+        self
+    }
+
+    /// Converts point in screen coordinates to coordinates of content.
+    pub fn screen_to_content(&self, point: Vec2) -> Vec2 {
+        self.transform().inverse_transform_point(point - self.widget.screen_position)
+    }
+
+    /// Converts point in coordinates of content to screen coordinates.
+    pub fn content_to_screen(&self, point: Vec2) -> Vec2 {
+        self.widget.screen_position + self.transform().transform_point(point)
+    }
+
+    fn transform(&self) -> RenderTransform {
+        RenderTransform::new(Vec2::new(self.zoom, self.zoom), self.pan)
+    }
+
+    /// Sets zoom keeping point of content under given point in screen coordinates at
+    /// the same place.
+    pub fn zoom_at(&mut self, ui: &mut UserInterface, zoom: f32, screen_point: Vec2) {
+        let zoom = clampf(zoom, self.min_zoom, self.max_zoom);
+        if (zoom - self.zoom).abs() <= std::f32::EPSILON {
+            return;
+        }
+        let content_point = self.screen_to_content(screen_point);
+        let local_point = screen_point - self.widget.screen_position;
+        self.zoom = zoom;
+        self.pan = local_point - content_point.scale(zoom);
+        self.apply_transform(ui);
+        self.widget.events.borrow_mut().push_back(UIEvent::new(UIEventKind::ZoomChanged(zoom)));
+    }
+
+    /// Sets zoom relative to center of panel.
+    pub fn set_zoom(&mut self, ui: &mut UserInterface, zoom: f32) {
+        let bounds = self.widget.get_screen_bounds();
+        self.zoom_at(ui, zoom, Vec2::new(bounds.x + bounds.w * 0.5, bounds.y + bounds.h * 0.5));
+    }
+
+    pub fn set_pan(&mut self, ui: &mut UserInterface, pan: Vec2) {
+        self.pan = pan;
+        self.apply_transform(ui);
+    }
+
+    fn apply_transform(&self, ui: &mut UserInterface) {
+        if self.content.is_some() {
+            ui.node_mut(self.content)
+                .widget_mut()
+                .set_render_transform(self.transform());
+        }
+    }
+}
+
+impl Control for ZoomPanel {
+    fn widget(&self) -> &Widget {
+        &self.widget
+    }
+
+    fn widget_mut(&mut self) -> &mut Widget {
+        &mut self.widget
+    }
+
+    fn raw_copy(&self) -> Box<dyn Control> {
+        Box::new(Self {
+            widget: *self.widget.raw_copy().downcast::<Widget>().unwrap_or_else(|_| panic!()),
+            content: self.content,
+            zoom: self.zoom,
+            min_zoom: self.min_zoom,
+            max_zoom: self.max_zoom,
+            pan: self.pan,
+            pan_origin: None,
+            zoom_step: self.zoom_step,
+        })
+    }
+
+    fn resolve(&mut self, _: &ControlTemplate, node_map: &HashMap<Handle<UINode>, Handle<UINode>>) {
+        if self.content.is_some() {
+            self.content = *node_map.get(&self.content).unwrap();
+        }
+    }
+
+    fn measure_override(&self, ui: &UserInterface, available_size: Vec2) -> Vec2 {
+        // Content is not limited by panel, it can be zoomed and panned anyway.
+        let infinite = Vec2::new(std::f32::INFINITY, std::f32::INFINITY);
+        for child_handle in self.widget.children.iter() {
+            ui.node(*child_handle).measure(ui, infinite);
+        }
+
+        Vec2::new(
+            if available_size.x.is_finite() { available_size.x } else { 0.0 },
+            if available_size.y.is_finite() { available_size.y } else { 0.0 },
+        )
+    }
+
+    fn arrange_override(&self, ui: &UserInterface, final_size: Vec2) -> Vec2 {
+        for child_handle in self.widget.children.iter() {
+            let child = ui.node(*child_handle);
+            let size = child.widget().desired_size.get();
+            child.arrange(ui, &Rect::new(0.0, 0.0, size.x, size.y));
+        }
+
+        final_size
+    }
+
+    fn handle_event(&mut self, self_handle: Handle<UINode>, ui: &mut UserInterface, evt: &mut UIEvent) {
+        match evt.kind {
+            UIEventKind::MouseWheel { pos, amount } => {
+                if !evt.handled && (evt.source == self_handle || self.widget.has_descendant(evt.source, ui)) {
+                    let zoom = self.zoom * (1.0 + self.zoom_step).powf(amount);
+                    self.zoom_at(ui, zoom, pos);
+                    evt.handled = true;
+                }
+            }
+            UIEventKind::Pinch { pos, delta } => {
+                if !evt.handled && (evt.source == self_handle || self.widget.has_descendant(evt.source, ui)) {
+                    let zoom = self.zoom * (1.0 + delta);
+                    self.zoom_at(ui, zoom, pos);
+                    evt.handled = true;
+                }
+            }
+            UIEventKind::MouseDown { pos, button: MouseButton::Middle } => {
+                if (evt.source == self_handle || self.widget.has_descendant(evt.source, ui)) && ui.capture_mouse(self_handle) {
+                    self.pan_origin = Some(pos);
+                    evt.handled = true;
+                }
+            }
+            UIEventKind::MouseMove { pos } => {
+                if evt.source == self_handle {
+                    if let Some(origin) = self.pan_origin {
+                        let pan = self.pan + (pos - origin);
+                        self.pan_origin = Some(pos);
+                        self.set_pan(ui, pan);
+                    }
+                }
+            }
+            UIEventKind::MouseUp { button: MouseButton::Middle, .. } => {
+                if evt.source == self_handle && self.pan_origin.take().is_some() {
+                    ui.release_mouse_capture();
+                }
+            }
+            UIEventKind::CaptureLost => {
+                if evt.source == self_handle {
+                    self.pan_origin = None;
+                }
+            }
+            _ => ()
+        }
+    }
+}
+
+pub struct ZoomPanelBuilder {
+    widget_builder: WidgetBuilder,
+    content: Handle<UINode>,
+    zoom: f32,
+    min_zoom: f32,
+    max_zoom: f32,
+    zoom_step: f32,
+}
+
+impl ZoomPanelBuilder {
+    pub fn new(widget_builder: WidgetBuilder) -> Self {
+        Self {
+            widget_builder,
+            content: Handle::NONE,
+            zoom: 1.0,
+            min_zoom: 0.1,
+            max_zoom: 10.0,
+            zoom_step: 0.1,
+        }
+    }
+
+    pub fn with_content(mut self, content: Handle<UINode>) -> Self {
+        self.content = content;
+        self
+    }
+
+    pub fn with_zoom(mut self, zoom: f32) -> Self {
+        self.zoom = zoom;
+        self
+    }
+
+    pub fn with_zoom_limits(mut self, min: f32, max: f32) -> Self {
+        self.min_zoom = min.min(max);
+        self.max_zoom = max.max(min);
+        self
+    }
+
+    /// Sets relative change of zoom per one mouse wheel step, default is 0.1.
+    pub fn with_zoom_step(mut self, step: f32) -> Self {
+        self.zoom_step = step;
+        self
+    }
+}
+
+impl Builder for ZoomPanelBuilder {
+    fn build(self, ui: &mut dyn UINodeContainer) -> Handle<UINode> {
+        let zoom = clampf(self.zoom, self.min_zoom, self.max_zoom);
+
+        let mut widget_builder = self.widget_builder;
+        if self.content.is_some() {
+            ui.node_mut(self.content)
+                .widget_mut()
+                .set_render_transform(RenderTransform::new(Vec2::new(zoom, zoom), Vec2::ZERO));
+            widget_builder = widget_builder.with_child(self.content);
+        }
+
+        let zoom_panel = ZoomPanel {
+            widget: widget_builder.build(),
+            content: self.content,
+            zoom,
+            min_zoom: self.min_zoom,
+            max_zoom: self.max_zoom,
+            pan: Vec2::ZERO,
+            pan_origin: None,
+            zoom_step: self.zoom_step,
+        };
+
+        ui.add_node(Box::new(zoom_panel))
+    }
+}